@@ -22,6 +22,8 @@ pub enum RuntimeError {
     InvalidHandlerIndex,
     #[error("Memory Instance Not Found")]
     MemoryNotFound,
+    #[error("Memory Allocation Failed")]
+    MemoryAllocationFailed,
     #[error("Invalid Wasm Binary: {0}")]
     InvalidWasm(&'static str),
     #[error("Type Mismatch")]
@@ -38,6 +40,22 @@ pub enum RuntimeError {
     InvalidConstantExpression,
     #[error("Invalid Data Segment Index")]
     InvalidDataSegmentIndex,
+    #[error("Out Of Bounds Memory Access")]
+    MemoryOutOfBounds,
+    #[error("Unreachable Executed")]
+    Unreachable,
+    #[error("Undefined Element")]
+    UndefinedElement,
+    #[error("Uninitialized Element")]
+    UninitializedElement,
+    #[error("Out Of Bounds Table Access")]
+    TableOutOfBounds,
+    #[error("Integer Divide By Zero")]
+    DivisionByZero,
+    #[error("Uncaught Exception")]
+    UncaughtException,
+    #[error("Null Exception Reference")]
+    NullExceptionReference,
 
     // Migration Errors
     #[error("Serialization Error: {0}")]
@@ -50,12 +68,30 @@ pub enum RuntimeError {
     CheckpointLoadError(String),
     #[error("Checkpoint Requested")]
     CheckpointRequested,
+    #[error("Instruction Budget Exhausted")]
+    FuelExhausted,
 }
 
 #[derive(Debug, Error, Clone, PartialEq)]
 pub enum ParserError {
     #[error("Invalid Version")]
     VersionError,
+    #[error("Invalid Text Format at Line {line}, Column {column}: {message}")]
+    WatError {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    #[error("Invalid Module at Offset {offset}: {message}")]
+    ValidationError { offset: usize, message: String },
+    #[error("Invalid Function {func_index} at Offset {offset}: {message}")]
+    FuncValidationError {
+        func_index: u32,
+        offset: usize,
+        message: String,
+    },
+    #[error("Unsupported Instruction at Offset {offset}: {op}")]
+    UnsupportedInstruction { offset: usize, op: String },
     #[error("Unsupported OP Code in Global Section Init Expr at Offset: {offset}")]
     InitExprUnsupportedOPCodeError { offset: usize },
     #[error("Unexpected Else operator found")]
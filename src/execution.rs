@@ -9,7 +9,9 @@ mod export;
 pub mod func;
 mod global;
 pub mod handlers;
+pub mod host;
 pub mod ir;
+pub mod linker;
 pub mod mem;
 pub mod migration;
 pub mod module;
@@ -19,6 +21,7 @@ pub mod runtime;
 pub mod state;
 pub mod stats;
 mod table;
+mod tag;
 #[cfg(feature = "trace")]
 pub mod trace;
 pub mod value;
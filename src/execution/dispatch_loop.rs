@@ -17,10 +17,17 @@ use crate::execution::state::VmState;
 /// `state` must have all pointer fields valid for the duration of the call.
 pub fn execute_instructions(state: &mut VmState) -> Outcome {
     loop {
-        // Per-instruction checkpoint poll (atomic flag or throttled file syscall).
-        if migration::poll_checkpoint(state) {
-            state.trap = Some(RuntimeError::CheckpointRequested);
-            return Outcome::Trap;
+        // Per-instruction checkpoint poll (atomic flag or throttled file syscall)
+        // and fuel accounting, skipped when neither is active for this run.
+        if state.interruptible {
+            if migration::poll_checkpoint(state) {
+                state.trap = Some(RuntimeError::CheckpointRequested);
+                return Outcome::Trap;
+            }
+            if state.consume_fuel() {
+                state.trap = Some(RuntimeError::FuelExhausted);
+                return Outcome::Trap;
+            }
         }
 
         // Natural pc-overflow handling: pop nested label or halt at function level.
@@ -43,6 +50,11 @@ pub fn execute_instructions(state: &mut VmState) -> Outcome {
             }
         }
 
+        #[cfg(feature = "trace")]
+        crate::execution::trace::trace_step(state);
+        #[cfg(feature = "stats")]
+        crate::execution::stats::record_step(state);
+
         let h = unsafe { *state.handlers.add(state.pc) };
         match h(state) {
             Outcome::Continue => continue,
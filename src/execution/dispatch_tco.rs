@@ -20,13 +20,24 @@ use crate::execution::state::VmState;
 /// last entry set to a sentinel handler (e.g., `halt`) so out-of-range
 /// dispatch terminates safely.
 pub fn execute_instructions(state: &mut VmState) -> Outcome {
-    if migration::poll_checkpoint(state) {
-        state.trap = Some(RuntimeError::CheckpointRequested);
-        return Outcome::Trap;
+    if state.interruptible {
+        if migration::poll_checkpoint(state) {
+            state.trap = Some(RuntimeError::CheckpointRequested);
+            return Outcome::Trap;
+        }
+        if state.consume_fuel() {
+            state.trap = Some(RuntimeError::FuelExhausted);
+            return Outcome::Trap;
+        }
     }
     if state.pc >= state.instrs_len {
         return Outcome::Halt;
     }
+    #[cfg(feature = "trace")]
+    crate::execution::trace::trace_step(state);
+    #[cfg(feature = "stats")]
+    crate::execution::stats::record_step(state);
+
     let h = unsafe { *state.handlers.add(state.pc) };
     h(state)
 }
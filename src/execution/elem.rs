@@ -1,16 +1,15 @@
 //! Element segment instances for table initialization.
 
-use super::{func::FuncAddr, module::*, value::Ref};
+use super::value::Ref;
 use crate::structure::types::*;
-use std::cell::RefCell;
+use std::cell::{self, RefCell};
 use std::rc::Rc;
 
 /// Reference-counted handle to an element segment instance.
 #[derive(Clone, Debug)]
-#[allow(dead_code)]
 pub struct ElemAddr(Rc<RefCell<ElemInst>>);
 
-/// Element segment instance holding function references.
+/// Element segment instance holding references.
 #[derive(Debug)]
 pub struct ElemInst {
     pub _type_: RefType,
@@ -18,15 +17,21 @@ pub struct ElemInst {
 }
 
 impl ElemAddr {
-    /// Creates a new element segment from function indices.
-    pub fn new(type_: &RefType, funcs: &Vec<FuncAddr>, init: &Vec<i32>) -> ElemAddr {
-        let elem: Vec<Ref> = init
-            .into_iter()
-            .map(|i| Ref::FuncAddr(funcs.get_by_idx(FuncIdx(*i as u32)).clone()))
-            .collect();
+    /// Creates a new element segment from evaluated references.
+    pub fn new(type_: &RefType, elem: Vec<Ref>) -> ElemAddr {
         ElemAddr(Rc::new(RefCell::new(ElemInst {
             _type_: *type_,
             _elem: elem,
         })))
     }
+
+    /// Borrows the references without copying them.
+    pub fn elements(&self) -> cell::Ref<'_, [Ref]> {
+        cell::Ref::map(self.0.borrow(), |inst| inst._elem.as_slice())
+    }
+
+    /// Clears the references (for elem.drop instruction).
+    pub fn drop_elem(&self) {
+        self.0.borrow_mut()._elem.clear();
+    }
 }
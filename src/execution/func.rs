@@ -1,5 +1,6 @@
 //! Function instances and addresses.
 
+use super::host::{Caller, IntoHostFunc};
 use super::module::*;
 use super::value::{Val, WasiFuncAddr};
 use crate::error::RuntimeError;
@@ -14,6 +15,10 @@ use std::rc::{Rc, Weak};
 #[derive(Clone)]
 pub struct FuncAddr(Rc<UnsafeCell<FuncInst>>);
 
+/// Type-erased host function body. Receives the calling instance and the
+/// arguments, and returns the results in order.
+pub type HostCode = Rc<dyn Fn(&Caller, Vec<Val>) -> Result<Vec<Val>, RuntimeError>>;
+
 /// Function instance variants: runtime (Wasm), host, or WASI.
 pub enum FuncInst {
     RuntimeFunc {
@@ -23,7 +28,7 @@ pub enum FuncInst {
     },
     HostFunc {
         type_: FuncType,
-        host_code: Rc<dyn Fn(Vec<Val>) -> Result<Option<Val>, RuntimeError>>,
+        host_code: HostCode,
     },
     WasiFunc {
         type_: FuncType,
@@ -100,6 +105,44 @@ impl FuncAddr {
         })))
     }
 
+    /// Allocates a host function of type `type_` from an untyped closure.
+    ///
+    /// The closure must return exactly the values described by
+    /// `type_.results`; otherwise the call fails with `TypeMismatch`.
+    pub fn alloc_host<F>(type_: FuncType, host_code: F) -> FuncAddr
+    where
+        F: Fn(&Caller, Vec<Val>) -> Result<Vec<Val>, RuntimeError> + 'static,
+    {
+        let results = type_.results.clone();
+        let host_code: HostCode = Rc::new(move |caller: &Caller, params: Vec<Val>| {
+            let values = host_code(caller, params)?;
+            let matches = values.len() == results.len()
+                && values.iter().zip(&results).all(|(val, ty)| match val {
+                    Val::Ref(_) => matches!(ty, ValueType::RefType(_)),
+                    _ => val.val_type() == *ty,
+                });
+            if !matches {
+                return Err(RuntimeError::TypeMismatch);
+            }
+            Ok(values)
+        });
+        FuncAddr(Rc::new(UnsafeCell::new(FuncInst::HostFunc {
+            type_,
+            host_code,
+        })))
+    }
+
+    /// Allocates a host function from a typed closure such as
+    /// `|a: i32, b: i64| -> f64`. The function type is derived from the
+    /// closure's signature.
+    pub fn wrap<Params, Results>(func: impl IntoHostFunc<Params, Results>) -> FuncAddr {
+        let (type_, host_code) = func.into_host_func();
+        FuncAddr(Rc::new(UnsafeCell::new(FuncInst::HostFunc {
+            type_,
+            host_code,
+        })))
+    }
+
     /// Replaces placeholder with actual function definition.
     pub fn replace(&self, func: Func, module: Weak<ModuleInst>) {
         let upgraded_module = module.upgrade().expect("Module weak ref expired");
@@ -145,12 +188,7 @@ impl FuncAddr {
     }
 
     /// Extracts host function details if this is a host function.
-    pub fn get_host_func_details(
-        &self,
-    ) -> Option<(
-        FuncType,
-        Rc<dyn Fn(Vec<Val>) -> Result<Option<Val>, RuntimeError>>,
-    )> {
+    pub fn get_host_func_details(&self) -> Option<(FuncType, HostCode)> {
         // Safety: Single-threaded access
         let inst = unsafe { &*self.0.get() };
         match inst {
@@ -185,4 +223,9 @@ impl FuncAddr {
     pub fn get_rc(&self) -> &Rc<UnsafeCell<FuncInst>> {
         &self.0
     }
+
+    /// Returns true if both handles refer to the same function instance.
+    pub fn ptr_eq(&self, other: &FuncAddr) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
 }
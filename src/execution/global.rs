@@ -31,6 +31,11 @@ impl GlobalAddr {
         }
     }
 
+    /// Returns the type of this global.
+    pub fn global_type(&self) -> GlobalType {
+        self.global_inst.borrow()._type_
+    }
+
     /// Gets the current value.
     pub fn get(&self) -> Val {
         self.global_inst.borrow().value.clone()
@@ -46,4 +51,9 @@ impl GlobalAddr {
             Err(RuntimeError::InstructionFailed)
         }
     }
+
+    /// Returns true if both handles refer to the same global instance.
+    pub fn ptr_eq(&self, other: &GlobalAddr) -> bool {
+        Rc::ptr_eq(&self.global_inst, &other.global_inst)
+    }
 }
@@ -24,20 +24,24 @@
 #![allow(unused_unsafe)]
 
 use crate::error::RuntimeError;
-use crate::execution::ir::{Handler, Outcome, ProcessedInstr, RegOrLocal};
+use crate::execution::ir::{CatchKind, Handler, Outcome, ProcessedInstr, RegOrLocal};
+use crate::execution::mem::MemAddr;
 use crate::execution::module::GetInstanceByIdx;
+use crate::execution::module::ModuleInst;
 use crate::execution::operand;
-use crate::execution::regs::Reg;
+use crate::execution::regs::{Reg, RegFile};
 use crate::execution::state::{Label, LabelStack, ModuleLevelInstr, VmState};
-use crate::execution::value::Val;
+use crate::execution::tag::ExnAddr;
+use crate::execution::value::{Ref, Val, Vec_};
 use arrayvec::ArrayVec;
 
 // ============================================================================
 // Handler index constants
 //
 // These indices identify each Wasm instruction handler. Numbered by Wasm
-// opcode where applicable, with extensions in the 0xF0-0x103 range for
-// type-specialized variants. The parser uses these to look up handlers via
+// opcode where applicable, with extensions in the 0xF0-0x108 range for
+// type-specialized variants. SIMD (0xFD-prefixed) instructions are numbered
+// `HANDLER_IDX_SIMD_BASE + sub-opcode`. The parser uses these to look up handlers via
 // `select_handler` (and to populate the `handlers` array on each Func).
 // ============================================================================
 
@@ -48,6 +52,8 @@ pub const HANDLER_IDX_BLOCK: usize = 0x02;
 pub const HANDLER_IDX_LOOP: usize = 0x03;
 pub const HANDLER_IDX_IF: usize = 0x04;
 pub const HANDLER_IDX_ELSE: usize = 0x05;
+pub const HANDLER_IDX_THROW: usize = 0x08;
+pub const HANDLER_IDX_THROW_REF: usize = 0x0A;
 pub const HANDLER_IDX_END: usize = 0x0B;
 pub const HANDLER_IDX_BR: usize = 0x0C;
 pub const HANDLER_IDX_BR_IF: usize = 0x0D;
@@ -55,6 +61,9 @@ pub const HANDLER_IDX_BR_TABLE: usize = 0x0E;
 pub const HANDLER_IDX_RETURN: usize = 0x0F;
 pub const HANDLER_IDX_CALL: usize = 0x10;
 pub const HANDLER_IDX_CALL_INDIRECT: usize = 0x11;
+pub const HANDLER_IDX_RETURN_CALL: usize = 0x12;
+pub const HANDLER_IDX_RETURN_CALL_INDIRECT: usize = 0x13;
+pub const HANDLER_IDX_TRY_TABLE: usize = 0x1F;
 
 // Variable Instructions
 pub const HANDLER_IDX_LOCAL_GET: usize = 0x20;
@@ -261,6 +270,13 @@ pub const HANDLER_IDX_I64_TRUNC_SAT_F64_U: usize = 0xCF;
 
 pub const HANDLER_IDX_DATA_DROP: usize = 0xE8;
 
+// Bulk Table Instructions
+pub const HANDLER_IDX_TABLE_INIT: usize = 0xE9;
+pub const HANDLER_IDX_ELEM_DROP: usize = 0xEA;
+pub const HANDLER_IDX_TABLE_COPY: usize = 0xEB;
+pub const HANDLER_IDX_TABLE_GROW: usize = 0xEC;
+pub const HANDLER_IDX_TABLE_SIZE: usize = 0xED;
+
 // Type-specialized select handler constants
 pub const HANDLER_IDX_SELECT_I32: usize = 0xF0;
 pub const HANDLER_IDX_SELECT_I64: usize = 0xF1;
@@ -293,6 +309,271 @@ pub const HANDLER_IDX_REF_LOCAL_SET: usize = 0x102;
 // WASI call handler constant
 pub const HANDLER_IDX_CALL_WASI: usize = 0x103;
 
+// v128 local.get / local.set, select and global handler constants
+pub const HANDLER_IDX_V128_LOCAL_GET: usize = 0x104;
+pub const HANDLER_IDX_V128_LOCAL_SET: usize = 0x105;
+pub const HANDLER_IDX_SELECT_V128: usize = 0x106;
+pub const HANDLER_IDX_GLOBAL_GET_V128: usize = 0x107;
+pub const HANDLER_IDX_GLOBAL_SET_V128: usize = 0x108;
+
+// SIMD handler constants (0xFD prefix, numbered by sub-opcode)
+pub const HANDLER_IDX_SIMD_BASE: usize = 0x200;
+
+/// Declares the SIMD handler constants together with their text-format
+/// names so trace and stats output share a single table.
+macro_rules! simd_handler_indices {
+    ($($name:ident = $subop:literal => $text:literal,)*) => {
+        $(pub const $name: usize = HANDLER_IDX_SIMD_BASE + $subop;)*
+
+        /// Returns the text-format name of a SIMD handler index.
+        pub fn simd_instruction_name(handler_index: usize) -> Option<&'static str> {
+            match handler_index {
+                $($name => Some($text),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+simd_handler_indices! {
+    HANDLER_IDX_V128_LOAD = 0x00 => "v128.load",
+    HANDLER_IDX_V128_LOAD8X8_S = 0x01 => "v128.load8x8_s",
+    HANDLER_IDX_V128_LOAD8X8_U = 0x02 => "v128.load8x8_u",
+    HANDLER_IDX_V128_LOAD16X4_S = 0x03 => "v128.load16x4_s",
+    HANDLER_IDX_V128_LOAD16X4_U = 0x04 => "v128.load16x4_u",
+    HANDLER_IDX_V128_LOAD32X2_S = 0x05 => "v128.load32x2_s",
+    HANDLER_IDX_V128_LOAD32X2_U = 0x06 => "v128.load32x2_u",
+    HANDLER_IDX_V128_LOAD8_SPLAT = 0x07 => "v128.load8_splat",
+    HANDLER_IDX_V128_LOAD16_SPLAT = 0x08 => "v128.load16_splat",
+    HANDLER_IDX_V128_LOAD32_SPLAT = 0x09 => "v128.load32_splat",
+    HANDLER_IDX_V128_LOAD64_SPLAT = 0x0A => "v128.load64_splat",
+    HANDLER_IDX_V128_STORE = 0x0B => "v128.store",
+    HANDLER_IDX_V128_CONST = 0x0C => "v128.const",
+    HANDLER_IDX_I8X16_SHUFFLE = 0x0D => "i8x16.shuffle",
+    HANDLER_IDX_I8X16_SWIZZLE = 0x0E => "i8x16.swizzle",
+    HANDLER_IDX_I8X16_SPLAT = 0x0F => "i8x16.splat",
+    HANDLER_IDX_I16X8_SPLAT = 0x10 => "i16x8.splat",
+    HANDLER_IDX_I32X4_SPLAT = 0x11 => "i32x4.splat",
+    HANDLER_IDX_I64X2_SPLAT = 0x12 => "i64x2.splat",
+    HANDLER_IDX_F32X4_SPLAT = 0x13 => "f32x4.splat",
+    HANDLER_IDX_F64X2_SPLAT = 0x14 => "f64x2.splat",
+    HANDLER_IDX_I8X16_EXTRACT_LANE_S = 0x15 => "i8x16.extract_lane_s",
+    HANDLER_IDX_I8X16_EXTRACT_LANE_U = 0x16 => "i8x16.extract_lane_u",
+    HANDLER_IDX_I8X16_REPLACE_LANE = 0x17 => "i8x16.replace_lane",
+    HANDLER_IDX_I16X8_EXTRACT_LANE_S = 0x18 => "i16x8.extract_lane_s",
+    HANDLER_IDX_I16X8_EXTRACT_LANE_U = 0x19 => "i16x8.extract_lane_u",
+    HANDLER_IDX_I16X8_REPLACE_LANE = 0x1A => "i16x8.replace_lane",
+    HANDLER_IDX_I32X4_EXTRACT_LANE = 0x1B => "i32x4.extract_lane",
+    HANDLER_IDX_I32X4_REPLACE_LANE = 0x1C => "i32x4.replace_lane",
+    HANDLER_IDX_I64X2_EXTRACT_LANE = 0x1D => "i64x2.extract_lane",
+    HANDLER_IDX_I64X2_REPLACE_LANE = 0x1E => "i64x2.replace_lane",
+    HANDLER_IDX_F32X4_EXTRACT_LANE = 0x1F => "f32x4.extract_lane",
+    HANDLER_IDX_F32X4_REPLACE_LANE = 0x20 => "f32x4.replace_lane",
+    HANDLER_IDX_F64X2_EXTRACT_LANE = 0x21 => "f64x2.extract_lane",
+    HANDLER_IDX_F64X2_REPLACE_LANE = 0x22 => "f64x2.replace_lane",
+    HANDLER_IDX_I8X16_EQ = 0x23 => "i8x16.eq",
+    HANDLER_IDX_I8X16_NE = 0x24 => "i8x16.ne",
+    HANDLER_IDX_I8X16_LT_S = 0x25 => "i8x16.lt_s",
+    HANDLER_IDX_I8X16_LT_U = 0x26 => "i8x16.lt_u",
+    HANDLER_IDX_I8X16_GT_S = 0x27 => "i8x16.gt_s",
+    HANDLER_IDX_I8X16_GT_U = 0x28 => "i8x16.gt_u",
+    HANDLER_IDX_I8X16_LE_S = 0x29 => "i8x16.le_s",
+    HANDLER_IDX_I8X16_LE_U = 0x2A => "i8x16.le_u",
+    HANDLER_IDX_I8X16_GE_S = 0x2B => "i8x16.ge_s",
+    HANDLER_IDX_I8X16_GE_U = 0x2C => "i8x16.ge_u",
+    HANDLER_IDX_I16X8_EQ = 0x2D => "i16x8.eq",
+    HANDLER_IDX_I16X8_NE = 0x2E => "i16x8.ne",
+    HANDLER_IDX_I16X8_LT_S = 0x2F => "i16x8.lt_s",
+    HANDLER_IDX_I16X8_LT_U = 0x30 => "i16x8.lt_u",
+    HANDLER_IDX_I16X8_GT_S = 0x31 => "i16x8.gt_s",
+    HANDLER_IDX_I16X8_GT_U = 0x32 => "i16x8.gt_u",
+    HANDLER_IDX_I16X8_LE_S = 0x33 => "i16x8.le_s",
+    HANDLER_IDX_I16X8_LE_U = 0x34 => "i16x8.le_u",
+    HANDLER_IDX_I16X8_GE_S = 0x35 => "i16x8.ge_s",
+    HANDLER_IDX_I16X8_GE_U = 0x36 => "i16x8.ge_u",
+    HANDLER_IDX_I32X4_EQ = 0x37 => "i32x4.eq",
+    HANDLER_IDX_I32X4_NE = 0x38 => "i32x4.ne",
+    HANDLER_IDX_I32X4_LT_S = 0x39 => "i32x4.lt_s",
+    HANDLER_IDX_I32X4_LT_U = 0x3A => "i32x4.lt_u",
+    HANDLER_IDX_I32X4_GT_S = 0x3B => "i32x4.gt_s",
+    HANDLER_IDX_I32X4_GT_U = 0x3C => "i32x4.gt_u",
+    HANDLER_IDX_I32X4_LE_S = 0x3D => "i32x4.le_s",
+    HANDLER_IDX_I32X4_LE_U = 0x3E => "i32x4.le_u",
+    HANDLER_IDX_I32X4_GE_S = 0x3F => "i32x4.ge_s",
+    HANDLER_IDX_I32X4_GE_U = 0x40 => "i32x4.ge_u",
+    HANDLER_IDX_F32X4_EQ = 0x41 => "f32x4.eq",
+    HANDLER_IDX_F32X4_NE = 0x42 => "f32x4.ne",
+    HANDLER_IDX_F32X4_LT = 0x43 => "f32x4.lt",
+    HANDLER_IDX_F32X4_GT = 0x44 => "f32x4.gt",
+    HANDLER_IDX_F32X4_LE = 0x45 => "f32x4.le",
+    HANDLER_IDX_F32X4_GE = 0x46 => "f32x4.ge",
+    HANDLER_IDX_F64X2_EQ = 0x47 => "f64x2.eq",
+    HANDLER_IDX_F64X2_NE = 0x48 => "f64x2.ne",
+    HANDLER_IDX_F64X2_LT = 0x49 => "f64x2.lt",
+    HANDLER_IDX_F64X2_GT = 0x4A => "f64x2.gt",
+    HANDLER_IDX_F64X2_LE = 0x4B => "f64x2.le",
+    HANDLER_IDX_F64X2_GE = 0x4C => "f64x2.ge",
+    HANDLER_IDX_V128_NOT = 0x4D => "v128.not",
+    HANDLER_IDX_V128_AND = 0x4E => "v128.and",
+    HANDLER_IDX_V128_ANDNOT = 0x4F => "v128.andnot",
+    HANDLER_IDX_V128_OR = 0x50 => "v128.or",
+    HANDLER_IDX_V128_XOR = 0x51 => "v128.xor",
+    HANDLER_IDX_V128_BITSELECT = 0x52 => "v128.bitselect",
+    HANDLER_IDX_V128_ANY_TRUE = 0x53 => "v128.any_true",
+    HANDLER_IDX_V128_LOAD8_LANE = 0x54 => "v128.load8_lane",
+    HANDLER_IDX_V128_LOAD16_LANE = 0x55 => "v128.load16_lane",
+    HANDLER_IDX_V128_LOAD32_LANE = 0x56 => "v128.load32_lane",
+    HANDLER_IDX_V128_LOAD64_LANE = 0x57 => "v128.load64_lane",
+    HANDLER_IDX_V128_STORE8_LANE = 0x58 => "v128.store8_lane",
+    HANDLER_IDX_V128_STORE16_LANE = 0x59 => "v128.store16_lane",
+    HANDLER_IDX_V128_STORE32_LANE = 0x5A => "v128.store32_lane",
+    HANDLER_IDX_V128_STORE64_LANE = 0x5B => "v128.store64_lane",
+    HANDLER_IDX_V128_LOAD32_ZERO = 0x5C => "v128.load32_zero",
+    HANDLER_IDX_V128_LOAD64_ZERO = 0x5D => "v128.load64_zero",
+    HANDLER_IDX_F32X4_DEMOTE_F64X2_ZERO = 0x5E => "f32x4.demote_f64x2_zero",
+    HANDLER_IDX_F64X2_PROMOTE_LOW_F32X4 = 0x5F => "f64x2.promote_low_f32x4",
+    HANDLER_IDX_I8X16_ABS = 0x60 => "i8x16.abs",
+    HANDLER_IDX_I8X16_NEG = 0x61 => "i8x16.neg",
+    HANDLER_IDX_I8X16_POPCNT = 0x62 => "i8x16.popcnt",
+    HANDLER_IDX_I8X16_ALL_TRUE = 0x63 => "i8x16.all_true",
+    HANDLER_IDX_I8X16_BITMASK = 0x64 => "i8x16.bitmask",
+    HANDLER_IDX_I8X16_NARROW_I16X8_S = 0x65 => "i8x16.narrow_i16x8_s",
+    HANDLER_IDX_I8X16_NARROW_I16X8_U = 0x66 => "i8x16.narrow_i16x8_u",
+    HANDLER_IDX_F32X4_CEIL = 0x67 => "f32x4.ceil",
+    HANDLER_IDX_F32X4_FLOOR = 0x68 => "f32x4.floor",
+    HANDLER_IDX_F32X4_TRUNC = 0x69 => "f32x4.trunc",
+    HANDLER_IDX_F32X4_NEAREST = 0x6A => "f32x4.nearest",
+    HANDLER_IDX_I8X16_SHL = 0x6B => "i8x16.shl",
+    HANDLER_IDX_I8X16_SHR_S = 0x6C => "i8x16.shr_s",
+    HANDLER_IDX_I8X16_SHR_U = 0x6D => "i8x16.shr_u",
+    HANDLER_IDX_I8X16_ADD = 0x6E => "i8x16.add",
+    HANDLER_IDX_I8X16_ADD_SAT_S = 0x6F => "i8x16.add_sat_s",
+    HANDLER_IDX_I8X16_ADD_SAT_U = 0x70 => "i8x16.add_sat_u",
+    HANDLER_IDX_I8X16_SUB = 0x71 => "i8x16.sub",
+    HANDLER_IDX_I8X16_SUB_SAT_S = 0x72 => "i8x16.sub_sat_s",
+    HANDLER_IDX_I8X16_SUB_SAT_U = 0x73 => "i8x16.sub_sat_u",
+    HANDLER_IDX_F64X2_CEIL = 0x74 => "f64x2.ceil",
+    HANDLER_IDX_F64X2_FLOOR = 0x75 => "f64x2.floor",
+    HANDLER_IDX_I8X16_MIN_S = 0x76 => "i8x16.min_s",
+    HANDLER_IDX_I8X16_MIN_U = 0x77 => "i8x16.min_u",
+    HANDLER_IDX_I8X16_MAX_S = 0x78 => "i8x16.max_s",
+    HANDLER_IDX_I8X16_MAX_U = 0x79 => "i8x16.max_u",
+    HANDLER_IDX_F64X2_TRUNC = 0x7A => "f64x2.trunc",
+    HANDLER_IDX_I8X16_AVGR_U = 0x7B => "i8x16.avgr_u",
+    HANDLER_IDX_I16X8_EXTADD_PAIRWISE_I8X16_S = 0x7C => "i16x8.extadd_pairwise_i8x16_s",
+    HANDLER_IDX_I16X8_EXTADD_PAIRWISE_I8X16_U = 0x7D => "i16x8.extadd_pairwise_i8x16_u",
+    HANDLER_IDX_I32X4_EXTADD_PAIRWISE_I16X8_S = 0x7E => "i32x4.extadd_pairwise_i16x8_s",
+    HANDLER_IDX_I32X4_EXTADD_PAIRWISE_I16X8_U = 0x7F => "i32x4.extadd_pairwise_i16x8_u",
+    HANDLER_IDX_I16X8_ABS = 0x80 => "i16x8.abs",
+    HANDLER_IDX_I16X8_NEG = 0x81 => "i16x8.neg",
+    HANDLER_IDX_I16X8_Q15MULR_SAT_S = 0x82 => "i16x8.q15mulr_sat_s",
+    HANDLER_IDX_I16X8_ALL_TRUE = 0x83 => "i16x8.all_true",
+    HANDLER_IDX_I16X8_BITMASK = 0x84 => "i16x8.bitmask",
+    HANDLER_IDX_I16X8_NARROW_I32X4_S = 0x85 => "i16x8.narrow_i32x4_s",
+    HANDLER_IDX_I16X8_NARROW_I32X4_U = 0x86 => "i16x8.narrow_i32x4_u",
+    HANDLER_IDX_I16X8_EXTEND_LOW_I8X16_S = 0x87 => "i16x8.extend_low_i8x16_s",
+    HANDLER_IDX_I16X8_EXTEND_HIGH_I8X16_S = 0x88 => "i16x8.extend_high_i8x16_s",
+    HANDLER_IDX_I16X8_EXTEND_LOW_I8X16_U = 0x89 => "i16x8.extend_low_i8x16_u",
+    HANDLER_IDX_I16X8_EXTEND_HIGH_I8X16_U = 0x8A => "i16x8.extend_high_i8x16_u",
+    HANDLER_IDX_I16X8_SHL = 0x8B => "i16x8.shl",
+    HANDLER_IDX_I16X8_SHR_S = 0x8C => "i16x8.shr_s",
+    HANDLER_IDX_I16X8_SHR_U = 0x8D => "i16x8.shr_u",
+    HANDLER_IDX_I16X8_ADD = 0x8E => "i16x8.add",
+    HANDLER_IDX_I16X8_ADD_SAT_S = 0x8F => "i16x8.add_sat_s",
+    HANDLER_IDX_I16X8_ADD_SAT_U = 0x90 => "i16x8.add_sat_u",
+    HANDLER_IDX_I16X8_SUB = 0x91 => "i16x8.sub",
+    HANDLER_IDX_I16X8_SUB_SAT_S = 0x92 => "i16x8.sub_sat_s",
+    HANDLER_IDX_I16X8_SUB_SAT_U = 0x93 => "i16x8.sub_sat_u",
+    HANDLER_IDX_F64X2_NEAREST = 0x94 => "f64x2.nearest",
+    HANDLER_IDX_I16X8_MUL = 0x95 => "i16x8.mul",
+    HANDLER_IDX_I16X8_MIN_S = 0x96 => "i16x8.min_s",
+    HANDLER_IDX_I16X8_MIN_U = 0x97 => "i16x8.min_u",
+    HANDLER_IDX_I16X8_MAX_S = 0x98 => "i16x8.max_s",
+    HANDLER_IDX_I16X8_MAX_U = 0x99 => "i16x8.max_u",
+    HANDLER_IDX_I16X8_AVGR_U = 0x9B => "i16x8.avgr_u",
+    HANDLER_IDX_I16X8_EXTMUL_LOW_I8X16_S = 0x9C => "i16x8.extmul_low_i8x16_s",
+    HANDLER_IDX_I16X8_EXTMUL_HIGH_I8X16_S = 0x9D => "i16x8.extmul_high_i8x16_s",
+    HANDLER_IDX_I16X8_EXTMUL_LOW_I8X16_U = 0x9E => "i16x8.extmul_low_i8x16_u",
+    HANDLER_IDX_I16X8_EXTMUL_HIGH_I8X16_U = 0x9F => "i16x8.extmul_high_i8x16_u",
+    HANDLER_IDX_I32X4_ABS = 0xA0 => "i32x4.abs",
+    HANDLER_IDX_I32X4_NEG = 0xA1 => "i32x4.neg",
+    HANDLER_IDX_I32X4_ALL_TRUE = 0xA3 => "i32x4.all_true",
+    HANDLER_IDX_I32X4_BITMASK = 0xA4 => "i32x4.bitmask",
+    HANDLER_IDX_I32X4_EXTEND_LOW_I16X8_S = 0xA7 => "i32x4.extend_low_i16x8_s",
+    HANDLER_IDX_I32X4_EXTEND_HIGH_I16X8_S = 0xA8 => "i32x4.extend_high_i16x8_s",
+    HANDLER_IDX_I32X4_EXTEND_LOW_I16X8_U = 0xA9 => "i32x4.extend_low_i16x8_u",
+    HANDLER_IDX_I32X4_EXTEND_HIGH_I16X8_U = 0xAA => "i32x4.extend_high_i16x8_u",
+    HANDLER_IDX_I32X4_SHL = 0xAB => "i32x4.shl",
+    HANDLER_IDX_I32X4_SHR_S = 0xAC => "i32x4.shr_s",
+    HANDLER_IDX_I32X4_SHR_U = 0xAD => "i32x4.shr_u",
+    HANDLER_IDX_I32X4_ADD = 0xAE => "i32x4.add",
+    HANDLER_IDX_I32X4_SUB = 0xB1 => "i32x4.sub",
+    HANDLER_IDX_I32X4_MUL = 0xB5 => "i32x4.mul",
+    HANDLER_IDX_I32X4_MIN_S = 0xB6 => "i32x4.min_s",
+    HANDLER_IDX_I32X4_MIN_U = 0xB7 => "i32x4.min_u",
+    HANDLER_IDX_I32X4_MAX_S = 0xB8 => "i32x4.max_s",
+    HANDLER_IDX_I32X4_MAX_U = 0xB9 => "i32x4.max_u",
+    HANDLER_IDX_I32X4_DOT_I16X8_S = 0xBA => "i32x4.dot_i16x8_s",
+    HANDLER_IDX_I32X4_EXTMUL_LOW_I16X8_S = 0xBC => "i32x4.extmul_low_i16x8_s",
+    HANDLER_IDX_I32X4_EXTMUL_HIGH_I16X8_S = 0xBD => "i32x4.extmul_high_i16x8_s",
+    HANDLER_IDX_I32X4_EXTMUL_LOW_I16X8_U = 0xBE => "i32x4.extmul_low_i16x8_u",
+    HANDLER_IDX_I32X4_EXTMUL_HIGH_I16X8_U = 0xBF => "i32x4.extmul_high_i16x8_u",
+    HANDLER_IDX_I64X2_ABS = 0xC0 => "i64x2.abs",
+    HANDLER_IDX_I64X2_NEG = 0xC1 => "i64x2.neg",
+    HANDLER_IDX_I64X2_ALL_TRUE = 0xC3 => "i64x2.all_true",
+    HANDLER_IDX_I64X2_BITMASK = 0xC4 => "i64x2.bitmask",
+    HANDLER_IDX_I64X2_EXTEND_LOW_I32X4_S = 0xC7 => "i64x2.extend_low_i32x4_s",
+    HANDLER_IDX_I64X2_EXTEND_HIGH_I32X4_S = 0xC8 => "i64x2.extend_high_i32x4_s",
+    HANDLER_IDX_I64X2_EXTEND_LOW_I32X4_U = 0xC9 => "i64x2.extend_low_i32x4_u",
+    HANDLER_IDX_I64X2_EXTEND_HIGH_I32X4_U = 0xCA => "i64x2.extend_high_i32x4_u",
+    HANDLER_IDX_I64X2_SHL = 0xCB => "i64x2.shl",
+    HANDLER_IDX_I64X2_SHR_S = 0xCC => "i64x2.shr_s",
+    HANDLER_IDX_I64X2_SHR_U = 0xCD => "i64x2.shr_u",
+    HANDLER_IDX_I64X2_ADD = 0xCE => "i64x2.add",
+    HANDLER_IDX_I64X2_SUB = 0xD1 => "i64x2.sub",
+    HANDLER_IDX_I64X2_MUL = 0xD5 => "i64x2.mul",
+    HANDLER_IDX_I64X2_EQ = 0xD6 => "i64x2.eq",
+    HANDLER_IDX_I64X2_NE = 0xD7 => "i64x2.ne",
+    HANDLER_IDX_I64X2_LT_S = 0xD8 => "i64x2.lt_s",
+    HANDLER_IDX_I64X2_GT_S = 0xD9 => "i64x2.gt_s",
+    HANDLER_IDX_I64X2_LE_S = 0xDA => "i64x2.le_s",
+    HANDLER_IDX_I64X2_GE_S = 0xDB => "i64x2.ge_s",
+    HANDLER_IDX_I64X2_EXTMUL_LOW_I32X4_S = 0xDC => "i64x2.extmul_low_i32x4_s",
+    HANDLER_IDX_I64X2_EXTMUL_HIGH_I32X4_S = 0xDD => "i64x2.extmul_high_i32x4_s",
+    HANDLER_IDX_I64X2_EXTMUL_LOW_I32X4_U = 0xDE => "i64x2.extmul_low_i32x4_u",
+    HANDLER_IDX_I64X2_EXTMUL_HIGH_I32X4_U = 0xDF => "i64x2.extmul_high_i32x4_u",
+    HANDLER_IDX_F32X4_ABS = 0xE0 => "f32x4.abs",
+    HANDLER_IDX_F32X4_NEG = 0xE1 => "f32x4.neg",
+    HANDLER_IDX_F32X4_SQRT = 0xE3 => "f32x4.sqrt",
+    HANDLER_IDX_F32X4_ADD = 0xE4 => "f32x4.add",
+    HANDLER_IDX_F32X4_SUB = 0xE5 => "f32x4.sub",
+    HANDLER_IDX_F32X4_MUL = 0xE6 => "f32x4.mul",
+    HANDLER_IDX_F32X4_DIV = 0xE7 => "f32x4.div",
+    HANDLER_IDX_F32X4_MIN = 0xE8 => "f32x4.min",
+    HANDLER_IDX_F32X4_MAX = 0xE9 => "f32x4.max",
+    HANDLER_IDX_F32X4_PMIN = 0xEA => "f32x4.pmin",
+    HANDLER_IDX_F32X4_PMAX = 0xEB => "f32x4.pmax",
+    HANDLER_IDX_F64X2_ABS = 0xEC => "f64x2.abs",
+    HANDLER_IDX_F64X2_NEG = 0xED => "f64x2.neg",
+    HANDLER_IDX_F64X2_SQRT = 0xEF => "f64x2.sqrt",
+    HANDLER_IDX_F64X2_ADD = 0xF0 => "f64x2.add",
+    HANDLER_IDX_F64X2_SUB = 0xF1 => "f64x2.sub",
+    HANDLER_IDX_F64X2_MUL = 0xF2 => "f64x2.mul",
+    HANDLER_IDX_F64X2_DIV = 0xF3 => "f64x2.div",
+    HANDLER_IDX_F64X2_MIN = 0xF4 => "f64x2.min",
+    HANDLER_IDX_F64X2_MAX = 0xF5 => "f64x2.max",
+    HANDLER_IDX_F64X2_PMIN = 0xF6 => "f64x2.pmin",
+    HANDLER_IDX_F64X2_PMAX = 0xF7 => "f64x2.pmax",
+    HANDLER_IDX_I32X4_TRUNC_SAT_F32X4_S = 0xF8 => "i32x4.trunc_sat_f32x4_s",
+    HANDLER_IDX_I32X4_TRUNC_SAT_F32X4_U = 0xF9 => "i32x4.trunc_sat_f32x4_u",
+    HANDLER_IDX_F32X4_CONVERT_I32X4_S = 0xFA => "f32x4.convert_i32x4_s",
+    HANDLER_IDX_F32X4_CONVERT_I32X4_U = 0xFB => "f32x4.convert_i32x4_u",
+    HANDLER_IDX_I32X4_TRUNC_SAT_F64X2_S_ZERO = 0xFC => "i32x4.trunc_sat_f64x2_s_zero",
+    HANDLER_IDX_I32X4_TRUNC_SAT_F64X2_U_ZERO = 0xFD => "i32x4.trunc_sat_f64x2_u_zero",
+    HANDLER_IDX_F64X2_CONVERT_LOW_I32X4_S = 0xFE => "f64x2.convert_low_i32x4_s",
+    HANDLER_IDX_F64X2_CONVERT_LOW_I32X4_U = 0xFF => "f64x2.convert_low_i32x4_u",
+}
+
 // ============================================================================
 // advance! macro — the difference between tco and non-tco mode
 // ============================================================================
@@ -335,17 +616,44 @@ pub fn checkpoint_trap(state: &mut VmState) -> Outcome {
     Outcome::Trap
 }
 
+/// Sentinel handler for an exhausted instruction budget. Tail-called from
+/// `next_handler` so that `Runtime::run_for` can pause before `state.pc`.
+#[inline(never)]
+pub fn fuel_trap(state: &mut VmState) -> Outcome {
+    state.trap = Some(crate::error::RuntimeError::FuelExhausted);
+    Outcome::Trap
+}
+
 /// Picks the next handler to dispatch. Returns the checkpoint-trap sentinel
-/// when `poll_checkpoint` signals a request, otherwise the indexed handler
-/// at `state.pc`. The returned function pointer is then tail-called from
+/// when `poll_checkpoint` signals a request and the fuel-trap sentinel when
+/// the instruction budget is exhausted, otherwise the indexed handler at
+/// `state.pc`. The returned function pointer is then tail-called from
 /// the `advance!` macro, so this helper itself must not break tail-call
 /// optimization at its call site.
 #[inline(always)]
 pub unsafe fn next_handler(state: &mut VmState) -> Handler {
+    if state.interruptible {
+        if let Some(trap) = interrupt_handler(state) {
+            return trap;
+        }
+    }
+    #[cfg(feature = "trace")]
+    crate::execution::trace::trace_step(state);
+    #[cfg(feature = "stats")]
+    crate::execution::stats::record_step(state);
+    *state.handlers.add(state.pc)
+}
+
+/// Slow path of `next_handler`, taken only while checkpointing or a fuel
+/// budget is active. Kept out of line so unbounded runs pay a single branch.
+#[inline(never)]
+fn interrupt_handler(state: &mut VmState) -> Option<Handler> {
     if crate::execution::migration::poll_checkpoint(state) {
-        checkpoint_trap
+        Some(checkpoint_trap)
+    } else if state.consume_fuel() {
+        Some(fuel_trap)
     } else {
-        *state.handlers.add(state.pc)
+        None
     }
 }
 
@@ -444,9 +752,9 @@ i32_unop!(i32_eqz, |a: i32| (a == 0) as i32);
 i32_unop!(i32_extend8_s, |a: i32| (a as i8) as i32);
 i32_unop!(i32_extend16_s, |a: i32| (a as i16) as i32);
 
-// Division / remainder: delegate divide-by-zero and signed overflow traps to
-// the host runtime. On wasm32, plain `/` and `%` emit `i32.div_s` / `i32.rem_s`
-// which trap on host; `wrapping_rem` preserves Wasm-spec `MIN % -1 = 0`.
+// Division / remainder: divide-by-zero traps with `DivisionByZero`, signed
+// `MIN / -1` with `IntegerOverflow`; `wrapping_rem` preserves Wasm-spec
+// `MIN % -1 = 0`.
 pub fn i32_div_s(state: &mut VmState) -> Outcome {
     let (dst, src1, src2) = match state.current_instr() {
         ProcessedInstr::I32Reg {
@@ -456,6 +764,14 @@ pub fn i32_div_s(state: &mut VmState) -> Outcome {
     };
     let a = operand::read_i32(state, &src1);
     let b = operand::read_i32(state, &src2);
+    if b == 0 {
+        state.trap = Some(RuntimeError::DivisionByZero);
+        return trap(state);
+    }
+    if a == i32::MIN && b == -1 {
+        state.trap = Some(RuntimeError::IntegerOverflow);
+        return trap(state);
+    }
     operand::write_i32(state, &dst, a / b);
     state.pc += 1;
     advance!(state)
@@ -470,6 +786,10 @@ pub fn i32_div_u(state: &mut VmState) -> Outcome {
     };
     let a = operand::read_i32(state, &src1);
     let b = operand::read_i32(state, &src2) as u32;
+    if b == 0 {
+        state.trap = Some(RuntimeError::DivisionByZero);
+        return trap(state);
+    }
     operand::write_i32(state, &dst, ((a as u32) / b) as i32);
     state.pc += 1;
     advance!(state)
@@ -484,6 +804,10 @@ pub fn i32_rem_s(state: &mut VmState) -> Outcome {
     };
     let a = operand::read_i32(state, &src1);
     let b = operand::read_i32(state, &src2);
+    if b == 0 {
+        state.trap = Some(RuntimeError::DivisionByZero);
+        return trap(state);
+    }
     operand::write_i32(state, &dst, a.wrapping_rem(b));
     state.pc += 1;
     advance!(state)
@@ -498,6 +822,10 @@ pub fn i32_rem_u(state: &mut VmState) -> Outcome {
     };
     let a = operand::read_i32(state, &src1);
     let b = operand::read_i32(state, &src2) as u32;
+    if b == 0 {
+        state.trap = Some(RuntimeError::DivisionByZero);
+        return trap(state);
+    }
     operand::write_i32(state, &dst, ((a as u32) % b) as i32);
     state.pc += 1;
     advance!(state)
@@ -611,7 +939,7 @@ pub fn i64_eqz(state: &mut VmState) -> Outcome {
     advance!(state)
 }
 
-// I64 division / remainder: host runtime traps divide-by-zero and div_s overflow.
+// I64 division / remainder: same trap rules as the i32 variants.
 pub fn i64_div_s(state: &mut VmState) -> Outcome {
     let (dst, src1, src2) = match state.current_instr() {
         ProcessedInstr::I64Reg {
@@ -621,6 +949,14 @@ pub fn i64_div_s(state: &mut VmState) -> Outcome {
     };
     let a = operand::read_i64(state, &src1);
     let b = operand::read_i64(state, &src2);
+    if b == 0 {
+        state.trap = Some(RuntimeError::DivisionByZero);
+        return trap(state);
+    }
+    if a == i64::MIN && b == -1 {
+        state.trap = Some(RuntimeError::IntegerOverflow);
+        return trap(state);
+    }
     operand::write_i64(state, &dst, a / b);
     state.pc += 1;
     advance!(state)
@@ -635,6 +971,10 @@ pub fn i64_div_u(state: &mut VmState) -> Outcome {
     };
     let a = operand::read_i64(state, &src1);
     let b = operand::read_i64(state, &src2) as u64;
+    if b == 0 {
+        state.trap = Some(RuntimeError::DivisionByZero);
+        return trap(state);
+    }
     operand::write_i64(state, &dst, ((a as u64) / b) as i64);
     state.pc += 1;
     advance!(state)
@@ -649,6 +989,10 @@ pub fn i64_rem_s(state: &mut VmState) -> Outcome {
     };
     let a = operand::read_i64(state, &src1);
     let b = operand::read_i64(state, &src2);
+    if b == 0 {
+        state.trap = Some(RuntimeError::DivisionByZero);
+        return trap(state);
+    }
     operand::write_i64(state, &dst, a.wrapping_rem(b));
     state.pc += 1;
     advance!(state)
@@ -663,6 +1007,10 @@ pub fn i64_rem_u(state: &mut VmState) -> Outcome {
     };
     let a = operand::read_i64(state, &src1);
     let b = operand::read_i64(state, &src2) as u64;
+    if b == 0 {
+        state.trap = Some(RuntimeError::DivisionByZero);
+        return trap(state);
+    }
     operand::write_i64(state, &dst, ((a as u64) % b) as i64);
     state.pc += 1;
     advance!(state)
@@ -1279,20 +1627,47 @@ pub fn conv_i64_trunc_f64_u(state: &mut VmState) -> Outcome {
 // Memory load handlers
 // ============================================================================
 
+/// Base pointer and length of memory `memory`. Memory 0 comes from the
+/// frame's cached pointer; other memories are looked up in the module. A
+/// missing memory has length 0, so every access to it traps.
+#[inline(always)]
+fn mem_view(state: &VmState, memory: u32) -> (*mut u8, usize) {
+    if memory == 0 {
+        (state.mem_ptr, state.mem_len)
+    } else {
+        state
+            .module()
+            .mem_addrs
+            .get(memory as usize)
+            .map_or((std::ptr::null_mut(), 0), |m| (m.data_ptr(), m.data_len()))
+    }
+}
+
 /// Macro for memory load — N-byte read from `mem_ptr + addr + offset`,
 /// extended/converted, written to RegOrLocal dst.
 macro_rules! mem_load {
     ($name:ident, $ty:ty, $cast_to:ty, $write:ident, $convert:expr) => {
         pub fn $name(state: &mut VmState) -> Outcome {
-            let (addr, dst, offset) = match state.current_instr() {
+            let (addr, dst, offset, memory) = match state.current_instr() {
                 ProcessedInstr::MemoryLoadReg {
-                    addr, dst, offset, ..
-                } => (*addr, *dst, *offset),
+                    addr,
+                    dst,
+                    offset,
+                    memory,
+                    ..
+                } => (*addr, *dst, *offset, *memory),
                 _ => unsafe { std::hint::unreachable_unchecked() },
             };
-            let p = operand::read_i32(state, &addr);
+            let p = operand::read_addr(state, &addr);
+            let (mem_ptr, mem_len) = mem_view(state, memory);
+            let Some(pos) =
+                MemAddr::check_range(mem_len, p, offset, std::mem::size_of::<$ty>() as u64)
+            else {
+                state.trap = Some(RuntimeError::MemoryOutOfBounds);
+                return trap(state);
+            };
             let v: $ty = unsafe {
-                let raw_ptr = state.mem_ptr.add((p as usize) + (offset as usize)) as *const $ty;
+                let raw_ptr = mem_ptr.add(pos) as *const $ty;
                 std::ptr::read_unaligned(raw_ptr)
             };
             let result: $cast_to = $convert(v);
@@ -1325,19 +1700,27 @@ mem_load!(mem_load_i64_32u, u32, i64, write_dst_i64, |v: u32| v as i64);
 macro_rules! mem_store {
     ($name:ident, $read:ident, $store_ty:ty, $cast:expr) => {
         pub fn $name(state: &mut VmState) -> Outcome {
-            let (addr, value, offset) = match state.current_instr() {
+            let (addr, value, offset, memory) = match state.current_instr() {
                 ProcessedInstr::MemoryStoreReg {
                     addr,
                     value,
                     offset,
+                    memory,
                     ..
-                } => (*addr, *value, *offset),
+                } => (*addr, *value, *offset, *memory),
                 _ => unsafe { std::hint::unreachable_unchecked() },
             };
-            let p = operand::read_i32(state, &addr);
+            let p = operand::read_addr(state, &addr);
             let v = operand::$read(state, &value);
+            let (mem_ptr, mem_len) = mem_view(state, memory);
+            let Some(pos) =
+                MemAddr::check_range(mem_len, p, offset, std::mem::size_of::<$store_ty>() as u64)
+            else {
+                state.trap = Some(RuntimeError::MemoryOutOfBounds);
+                return trap(state);
+            };
             unsafe {
-                let raw_ptr = state.mem_ptr.add((p as usize) + (offset as usize)) as *mut $store_ty;
+                let raw_ptr = mem_ptr.add(pos) as *mut $store_ty;
                 std::ptr::write_unaligned(raw_ptr, $cast(v));
             }
             state.pc += 1;
@@ -1391,6 +1774,7 @@ select!(select_i32, get_i32, set_i32);
 select!(select_i64, get_i64, set_i64);
 select!(select_f32, get_f32, set_f32);
 select!(select_f64, get_f64, set_f64);
+select!(select_v128, get_v128, set_v128);
 
 // ============================================================================
 // Nop / Unreachable
@@ -1401,9 +1785,9 @@ pub fn nop(state: &mut VmState) -> Outcome {
     advance!(state)
 }
 
-pub fn unreachable(_state: &mut VmState) -> Outcome {
-    // Wasm `unreachable`: delegate to host. panic! on wasm32 produces a host trap.
-    panic!("wasm unreachable executed")
+pub fn unreachable(state: &mut VmState) -> Outcome {
+    state.trap = Some(RuntimeError::Unreachable);
+    trap(state)
 }
 
 pub fn br(state: &mut VmState) -> Outcome {
@@ -1516,6 +1900,27 @@ pub fn block(state: &mut VmState) -> Outcome {
         label: Label {
             is_loop,
             return_ip: next_ip,
+            try_ip: None,
+        },
+        processed_instrs: pi_rc,
+        ip: next_ip,
+    });
+    state.current_label_idx = state.label_stack().len() - 1;
+    state.pc = next_ip;
+    advance!(state)
+}
+
+pub fn try_table(state: &mut VmState) -> Outcome {
+    let try_ip = state.pc;
+    let next_ip = try_ip + 1;
+    let cur_idx = state.current_label_idx;
+    let label_stack = state.label_stack_mut();
+    let pi_rc = label_stack[cur_idx].processed_instrs.clone();
+    label_stack.push(LabelStack {
+        label: Label {
+            is_loop: false,
+            return_ip: next_ip,
+            try_ip: Some(try_ip),
         },
         processed_instrs: pi_rc,
         ip: next_ip,
@@ -1548,6 +1953,7 @@ pub fn r#if(state: &mut VmState) -> Outcome {
             label: Label {
                 is_loop: false,
                 return_ip: else_target_ip,
+                try_ip: None,
             },
             processed_instrs: pi_rc,
             ip: next_ip,
@@ -1562,6 +1968,7 @@ pub fn r#if(state: &mut VmState) -> Outcome {
             label: Label {
                 is_loop: false,
                 return_ip: else_target_ip,
+                try_ip: None,
             },
             processed_instrs: pi_rc,
             ip: else_target_ip,
@@ -1625,13 +2032,14 @@ pub fn jump(state: &mut VmState) -> Outcome {
 }
 
 // ============================================================================
-// Call / Return / CallIndirect / CallWasi (yield to runtime)
+// Call / Return / CallIndirect / CallWasi / ReturnCall (yield to runtime)
 // ============================================================================
 //
 // These handlers prepare a `ModuleLevelInstr` in `state.yielded` and return
 // `Outcome::Yield`. The dispatcher driver (in runtime.rs) handles frame
 // transitions. State.pc is advanced to the post-call position so resume
-// continues correctly.
+// continues correctly. Tail calls leave pc alone since their frame is
+// discarded.
 
 pub fn call(state: &mut VmState) -> Outcome {
     let instr = unsafe { &*state.instrs.add(state.pc) };
@@ -1687,8 +2095,13 @@ pub fn call_indirect(state: &mut VmState) -> Outcome {
             return trap(state);
         }
     };
-    // Out-of-bounds index / null reference: delegate to host via Rust panic.
-    let func_addr = table_addr.get_func_addr(i as usize).unwrap();
+    let func_addr = match table_addr.get_func_addr(i as u32 as usize) {
+        Ok(f) => f,
+        Err(e) => {
+            state.trap = Some(e);
+            return trap(state);
+        }
+    };
     let actual_type = func_addr.func_type();
     let expected_type = &state.module().types[type_idx.0 as usize];
     if *actual_type != *expected_type {
@@ -1707,6 +2120,192 @@ pub fn call_indirect(state: &mut VmState) -> Outcome {
     Outcome::Yield
 }
 
+pub fn return_call(state: &mut VmState) -> Outcome {
+    let instr = unsafe { &*state.instrs.add(state.pc) };
+    let ProcessedInstr::ReturnCallReg {
+        func_idx,
+        param_regs,
+    } = instr
+    else {
+        unsafe { std::hint::unreachable_unchecked() }
+    };
+    let func_addr = match state.module().func_addrs.get(func_idx.0 as usize) {
+        Some(fa) => fa.clone(),
+        None => {
+            state.trap = Some(RuntimeError::ExportFuncNotFound);
+            return trap(state);
+        }
+    };
+    let regs = state.reg_file();
+    let params: Vec<Val> = param_regs.iter().map(|r| regs.get_val(r)).collect();
+    state.yielded = Some(ModuleLevelInstr::TailInvokeReg { func_addr, params });
+    Outcome::Yield
+}
+
+pub fn return_call_indirect(state: &mut VmState) -> Outcome {
+    let instr = unsafe { &*state.instrs.add(state.pc) };
+    let ProcessedInstr::ReturnCallIndirectReg {
+        type_idx,
+        table_idx,
+        index_reg,
+        param_regs,
+    } = instr
+    else {
+        unsafe { std::hint::unreachable_unchecked() }
+    };
+    let module_inst = state.module();
+    let i = state.reg_file().get_i32(index_reg.index());
+    let table_addr = match module_inst.table_addrs.get(table_idx.0 as usize) {
+        Some(t) => t.clone(),
+        None => {
+            state.trap = Some(RuntimeError::TableNotFound);
+            return trap(state);
+        }
+    };
+    let func_addr = match table_addr.get_func_addr(i as u32 as usize) {
+        Ok(f) => f,
+        Err(e) => {
+            state.trap = Some(e);
+            return trap(state);
+        }
+    };
+    if *func_addr.func_type() != state.module().types[type_idx.0 as usize] {
+        state.trap = Some(RuntimeError::IndirectCallTypeMismatch);
+        return trap(state);
+    }
+    let regs = state.reg_file();
+    let params: Vec<Val> = param_regs.iter().map(|r| regs.get_val(r)).collect();
+    state.yielded = Some(ModuleLevelInstr::TailInvokeReg { func_addr, params });
+    Outcome::Yield
+}
+
+// ============================================================================
+// Throw / ThrowRef
+// ============================================================================
+//
+// Exceptions caught by a `try_table` of the current frame branch directly.
+// Otherwise the exception is yielded and the runtime unwinds caller frames,
+// retrying `catch_exception` in each.
+
+/// Finds the innermost `try_table` in `label_stack` with a clause matching
+/// `exn`, delivers the exception values to the clause's branch target and
+/// truncates the label stack accordingly. Returns the instruction pointer to
+/// resume at, or `None` if no clause in this frame catches the exception.
+pub fn catch_exception(
+    label_stack: &mut Vec<LabelStack>,
+    reg_file: &mut RegFile,
+    module: &ModuleInst,
+    exn: &ExnAddr,
+) -> Option<usize> {
+    for level in (1..label_stack.len()).rev() {
+        let Some(try_ip) = label_stack[level].label.try_ip else {
+            continue;
+        };
+        let processed_instrs = label_stack[level].processed_instrs.clone();
+        let Some(ProcessedInstr::TryTableReg { catches }) = processed_instrs.get(try_ip) else {
+            continue;
+        };
+        for clause in catches.iter() {
+            let (matches, with_ref) = match clause.kind {
+                CatchKind::Tag(tag_idx) => (
+                    module
+                        .tag_addrs
+                        .get(tag_idx.0 as usize)
+                        .is_some_and(|tag| exn.is_tag(tag)),
+                    false,
+                ),
+                CatchKind::TagRef(tag_idx) => (
+                    module
+                        .tag_addrs
+                        .get(tag_idx.0 as usize)
+                        .is_some_and(|tag| exn.is_tag(tag)),
+                    true,
+                ),
+                CatchKind::All => (true, false),
+                CatchKind::AllRef => (true, true),
+            };
+            if !matches {
+                continue;
+            }
+            let payload: &[Val] = match clause.kind {
+                CatchKind::Tag(_) | CatchKind::TagRef(_) => exn.fields(),
+                CatchKind::All | CatchKind::AllRef => &[],
+            };
+            let exn_val = Val::Ref(Ref::Exn(exn.clone()));
+            let values = payload
+                .iter()
+                .chain(with_ref.then_some(&exn_val))
+                .zip(clause.target_result_regs.iter());
+            for (val, reg) in values {
+                reg_file.set_val(reg, val);
+            }
+            // Branching to `label` from inside the try_table is a branch of
+            // depth `label + 1` from the try_table's own label.
+            let target_level = level.saturating_sub(clause.label as usize + 1);
+            label_stack.truncate(target_level.max(1));
+            return Some(clause.target_ip);
+        }
+    }
+    None
+}
+
+/// Catches `exn` within the current frame or yields it to the runtime.
+fn raise(state: &mut VmState, exn: ExnAddr) -> Outcome {
+    let module = unsafe { &*state.module };
+    let caught = catch_exception(
+        unsafe { &mut *state.label_stack },
+        unsafe { &mut *state.reg_file },
+        module,
+        &exn,
+    );
+    match caught {
+        Some(target_ip) => {
+            state.current_label_idx = state.label_stack().len() - 1;
+            state.pc = target_ip;
+            advance!(state)
+        }
+        None => {
+            state.yielded = Some(ModuleLevelInstr::Throw(exn));
+            Outcome::Yield
+        }
+    }
+}
+
+pub fn throw(state: &mut VmState) -> Outcome {
+    let instr = unsafe { &*state.instrs.add(state.pc) };
+    let ProcessedInstr::ThrowReg {
+        tag_idx,
+        param_regs,
+    } = instr
+    else {
+        unsafe { std::hint::unreachable_unchecked() }
+    };
+    let tag = match state.module().tag_addrs.get(tag_idx.0 as usize) {
+        Some(tag) => tag.clone(),
+        None => {
+            state.trap = Some(RuntimeError::InvalidWasm("Undefined Tag"));
+            return trap(state);
+        }
+    };
+    let regs = state.reg_file();
+    let fields: Vec<Val> = param_regs.iter().map(|r| regs.get_val(r)).collect();
+    raise(state, ExnAddr::new(tag, fields))
+}
+
+pub fn throw_ref(state: &mut VmState) -> Outcome {
+    let exn_reg = match state.current_instr() {
+        ProcessedInstr::ThrowRefReg { exn_reg } => *exn_reg,
+        _ => unsafe { std::hint::unreachable_unchecked() },
+    };
+    match state.reg_file().get_val(&exn_reg) {
+        Val::Ref(Ref::Exn(exn)) => raise(state, exn),
+        _ => {
+            state.trap = Some(RuntimeError::NullExceptionReference);
+            trap(state)
+        }
+    }
+}
+
 pub fn call_wasi(state: &mut VmState) -> Outcome {
     let instr = unsafe { &*state.instrs.add(state.pc) };
     let ProcessedInstr::CallWasiReg {
@@ -1809,6 +2408,54 @@ global_set!(global_set_i64, get_i64, I64);
 global_set!(global_set_f32, get_f32, F32);
 global_set!(global_set_f64, get_f64, F64);
 
+pub fn global_get_v128(state: &mut VmState) -> Outcome {
+    let (dst, global_index) = match state.current_instr() {
+        ProcessedInstr::GlobalGetReg {
+            dst, global_index, ..
+        } => (*dst, *global_index),
+        _ => unsafe { std::hint::unreachable_unchecked() },
+    };
+    let val = state
+        .module()
+        .global_addrs
+        .get_by_idx(crate::structure::types::GlobalIdx(global_index))
+        .get();
+    match dst {
+        RegOrLocal::Reg(idx) => {
+            if let Val::Vec_(Vec_::V128(v)) = val {
+                state.reg_file_mut().set_v128(idx, v);
+            }
+        }
+        RegOrLocal::Local(idx) => *state.local_mut(idx as usize) = val,
+    }
+    state.pc += 1;
+    advance!(state)
+}
+
+pub fn global_set_v128(state: &mut VmState) -> Outcome {
+    let (src, global_index) = match state.current_instr() {
+        ProcessedInstr::GlobalSetReg {
+            src, global_index, ..
+        } => (*src, *global_index),
+        _ => unsafe { std::hint::unreachable_unchecked() },
+    };
+    let val = match src {
+        RegOrLocal::Reg(idx) => Val::Vec_(Vec_::V128(state.reg_file().get_v128(idx))),
+        RegOrLocal::Local(idx) => state.local(idx as usize).clone(),
+    };
+    let global_addr = state
+        .module()
+        .global_addrs
+        .get_by_idx(crate::structure::types::GlobalIdx(global_index))
+        .clone();
+    if let Err(e) = global_addr.set(val) {
+        state.trap = Some(e);
+        return trap(state);
+    }
+    state.pc += 1;
+    advance!(state)
+}
+
 // ============================================================================
 // DataDrop
 // ============================================================================
@@ -1857,6 +2504,30 @@ pub fn ref_local_set(state: &mut VmState) -> Outcome {
     advance!(state)
 }
 
+// v128 locals share the RefLocalReg layout.
+pub fn v128_local_get(state: &mut VmState) -> Outcome {
+    let (dst, local_idx) = match state.current_instr() {
+        ProcessedInstr::RefLocalReg { dst, local_idx, .. } => (*dst, *local_idx as usize),
+        _ => unsafe { std::hint::unreachable_unchecked() },
+    };
+    if let Val::Vec_(Vec_::V128(v)) = *state.local(local_idx) {
+        state.reg_file_mut().set_v128(dst, v);
+    }
+    state.pc += 1;
+    advance!(state)
+}
+
+pub fn v128_local_set(state: &mut VmState) -> Outcome {
+    let (src, local_idx) = match state.current_instr() {
+        ProcessedInstr::RefLocalReg { src, local_idx, .. } => (*src, *local_idx as usize),
+        _ => unsafe { std::hint::unreachable_unchecked() },
+    };
+    let v = state.reg_file().get_v128(src);
+    *state.local_mut(local_idx) = Val::Vec_(Vec_::V128(v));
+    state.pc += 1;
+    advance!(state)
+}
+
 // ============================================================================
 // Table / ref ops (ref.null / ref.is_null / table.get / table.set / table.fill)
 // ============================================================================
@@ -1900,18 +2571,21 @@ pub fn table_get(state: &mut VmState) -> Outcome {
             return trap(state);
         }
     };
-    let index = state.reg_file().get_i32(regs[1]) as usize;
-    let val = table_addr.get(index);
-    match val {
-        Val::Ref(r) => {
+    let index = state.reg_file().get_i32(regs[1]) as u32;
+    match table_addr.get(index) {
+        Ok(Val::Ref(r)) => {
             state.reg_file_mut().set_ref(regs[0], r);
             state.pc += 1;
             advance!(state)
         }
-        _ => {
+        Ok(_) => {
             state.trap = Some(RuntimeError::TypeMismatch);
             trap(state)
         }
+        Err(e) => {
+            state.trap = Some(e);
+            trap(state)
+        }
     }
 }
 
@@ -1930,9 +2604,12 @@ pub fn table_set(state: &mut VmState) -> Outcome {
         }
     };
     let rf = state.reg_file();
-    let index = rf.get_i32(regs[0]) as usize;
+    let index = rf.get_i32(regs[0]) as u32;
     let ref_val = rf.get_ref(regs[1]);
-    table_addr.set(index, Val::Ref(ref_val));
+    if let Err(e) = table_addr.set(index, Val::Ref(ref_val)) {
+        state.trap = Some(e);
+        return trap(state);
+    }
     state.pc += 1;
     advance!(state)
 }
@@ -1952,98 +2629,251 @@ pub fn table_fill(state: &mut VmState) -> Outcome {
         }
     };
     let rf = state.reg_file();
-    let i = rf.get_i32(regs[0]) as usize;
+    let i = rf.get_i32(regs[0]) as u32;
     let ref_val = rf.get_ref(regs[1]);
-    let n = rf.get_i32(regs[2]) as usize;
-    table_addr.fill(i, Val::Ref(ref_val), n);
+    let n = rf.get_i32(regs[2]) as u32;
+    if let Err(e) = table_addr.fill(i, Val::Ref(ref_val), n) {
+        state.trap = Some(e);
+        return trap(state);
+    }
     state.pc += 1;
     advance!(state)
 }
 
 // ============================================================================
-// Memory ops (memory.size / grow / copy / init / fill)
+// Bulk table ops (table.size / grow / copy / init, elem.drop)
 // ============================================================================
 
-pub fn mem_size(state: &mut VmState) -> Outcome {
-    let dst = match state.current_instr() {
-        ProcessedInstr::MemoryOpsReg { dst, .. } => *dst,
+pub fn table_size(state: &mut VmState) -> Outcome {
+    let (table_idx, regs) = match state.current_instr() {
+        ProcessedInstr::TableOpsReg {
+            table_idx, regs, ..
+        } => (*table_idx, *regs),
         _ => unsafe { std::hint::unreachable_unchecked() },
     };
-    let mem_addr = match state.module().mem_addrs.first() {
-        Some(m) => m.clone(),
+    let size = match state.module().table_addrs.get(table_idx as usize) {
+        Some(t) => t.size(),
         None => {
-            state.trap = Some(RuntimeError::MemoryNotFound);
+            state.trap = Some(RuntimeError::TableNotFound);
             return trap(state);
         }
     };
-    let size = mem_addr.mem_size();
-    if let Some(d) = dst {
-        state.reg_file_mut().set_i32(d.index(), size);
-    }
+    state.reg_file_mut().set_i32(regs[0], size as i32);
     state.pc += 1;
     advance!(state)
 }
 
-pub fn mem_grow(state: &mut VmState) -> Outcome {
-    let instr = unsafe { &*state.instrs.add(state.pc) };
-    let ProcessedInstr::MemoryOpsReg { dst, args, .. } = instr else {
-        unsafe { std::hint::unreachable_unchecked() }
+pub fn table_grow(state: &mut VmState) -> Outcome {
+    let (table_idx, regs) = match state.current_instr() {
+        ProcessedInstr::TableOpsReg {
+            table_idx, regs, ..
+        } => (*table_idx, *regs),
+        _ => unsafe { std::hint::unreachable_unchecked() },
     };
-    let mem_addr = match state.module().mem_addrs.first() {
-        Some(m) => m.clone(),
+    let table_addr = match state.module().table_addrs.get(table_idx as usize) {
+        Some(t) => t.clone(),
         None => {
-            state.trap = Some(RuntimeError::MemoryNotFound);
-            return trap(state);
-        }
-    };
-    let delta = state.reg_file().get_i32(args[0].index());
-    let delta_u32: u32 = match delta.try_into() {
-        Ok(v) => v,
-        Err(_) => {
-            state.trap = Some(RuntimeError::InvalidParameterCount);
+            state.trap = Some(RuntimeError::TableNotFound);
             return trap(state);
         }
     };
-    let prev_size = mem_addr.mem_grow(delta_u32 as i32);
-    if let Some(d) = dst {
-        state.reg_file_mut().set_i32(d.index(), prev_size);
-    }
-    state.mem_ptr = mem_addr.data_ptr();
+    let rf = state.reg_file();
+    let ref_val = rf.get_ref(regs[1]);
+    let n = rf.get_i32(regs[2]) as u32;
+    let prev = table_addr
+        .grow(n, Val::Ref(ref_val))
+        .map_or(-1, |p| p as i32);
+    state.reg_file_mut().set_i32(regs[0], prev);
     state.pc += 1;
     advance!(state)
 }
 
-pub fn mem_copy(state: &mut VmState) -> Outcome {
-    let instr = unsafe { &*state.instrs.add(state.pc) };
-    let ProcessedInstr::MemoryOpsReg { args, .. } = instr else {
-        unsafe { std::hint::unreachable_unchecked() }
+pub fn table_copy(state: &mut VmState) -> Outcome {
+    let (table_idx, src_idx, regs) = match state.current_instr() {
+        ProcessedInstr::TableOpsReg {
+            table_idx,
+            src_idx,
+            regs,
+            ..
+        } => (*table_idx, *src_idx, *regs),
+        _ => unsafe { std::hint::unreachable_unchecked() },
     };
-    let mem_addr = match state.module().mem_addrs.first() {
-        Some(m) => m.clone(),
-        None => {
-            state.trap = Some(RuntimeError::MemoryNotFound);
-            return trap(state);
-        }
+    let table_addrs = &state.module().table_addrs;
+    let (Some(dst_table), Some(src_table)) = (
+        table_addrs.get(table_idx as usize).cloned(),
+        table_addrs.get(src_idx as usize).cloned(),
+    ) else {
+        state.trap = Some(RuntimeError::TableNotFound);
+        return trap(state);
     };
-    let regs = state.reg_file();
-    let dest = regs.get_i32(args[0].index());
-    let src = regs.get_i32(args[1].index());
-    let len = regs.get_i32(args[2].index());
-    mem_addr.memory_copy(dest, src, len);
+    let rf = state.reg_file();
+    let d = rf.get_i32(regs[0]) as u32;
+    let s = rf.get_i32(regs[1]) as u32;
+    let n = rf.get_i32(regs[2]) as u32;
+    if let Err(e) = dst_table.copy(d, &src_table, s, n) {
+        state.trap = Some(e);
+        return trap(state);
+    }
     state.pc += 1;
     advance!(state)
 }
 
-pub fn mem_init(state: &mut VmState) -> Outcome {
-    let instr = unsafe { &*state.instrs.add(state.pc) };
-    let ProcessedInstr::MemoryOpsReg {
-        args, data_index, ..
-    } = instr
-    else {
-        unsafe { std::hint::unreachable_unchecked() }
+pub fn table_init(state: &mut VmState) -> Outcome {
+    let (table_idx, elem_index, regs) = match state.current_instr() {
+        ProcessedInstr::TableOpsReg {
+            table_idx,
+            src_idx,
+            regs,
+            ..
+        } => (*table_idx, *src_idx, *regs),
+        _ => unsafe { std::hint::unreachable_unchecked() },
     };
     let module_inst = state.module();
-    let mem_addr = match module_inst.mem_addrs.first() {
+    let table_addr = match module_inst.table_addrs.get(table_idx as usize) {
+        Some(t) => t.clone(),
+        None => {
+            state.trap = Some(RuntimeError::TableNotFound);
+            return trap(state);
+        }
+    };
+    let elem_addr = match module_inst.elem_addrs.get(elem_index as usize) {
+        Some(e) => e.clone(),
+        None => {
+            state.trap = Some(RuntimeError::InvalidWasm("Undefined Element Segment"));
+            return trap(state);
+        }
+    };
+    let rf = state.reg_file();
+    let d = rf.get_i32(regs[0]) as u32;
+    let s = rf.get_i32(regs[1]) as u32;
+    let n = rf.get_i32(regs[2]) as u32;
+    if let Err(e) = table_addr.init(d, &elem_addr.elements(), s, n) {
+        state.trap = Some(e);
+        return trap(state);
+    }
+    state.pc += 1;
+    advance!(state)
+}
+
+pub fn elem_drop(state: &mut VmState) -> Outcome {
+    let elem_index = match state.current_instr() {
+        ProcessedInstr::ElemDropReg { elem_index } => *elem_index,
+        _ => unsafe { std::hint::unreachable_unchecked() },
+    };
+    if let Some(elem_addr) = state.module().elem_addrs.get(elem_index as usize) {
+        elem_addr.drop_elem();
+    }
+    state.pc += 1;
+    advance!(state)
+}
+
+// ============================================================================
+// Memory ops (memory.size / grow / copy / init / fill)
+// ============================================================================
+
+pub fn mem_size(state: &mut VmState) -> Outcome {
+    let (dst, memory) = match state.current_instr() {
+        ProcessedInstr::MemoryOpsReg { dst, memory, .. } => (*dst, *memory),
+        _ => unsafe { std::hint::unreachable_unchecked() },
+    };
+    let mem_addr = match state.module().mem_addrs.get(memory as usize) {
+        Some(m) => m.clone(),
+        None => {
+            state.trap = Some(RuntimeError::MemoryNotFound);
+            return trap(state);
+        }
+    };
+    let size = mem_addr.mem_size();
+    if let Some(d) = dst {
+        operand::write_reg_addr(state, &d, size);
+    }
+    state.pc += 1;
+    advance!(state)
+}
+
+pub fn mem_grow(state: &mut VmState) -> Outcome {
+    let instr = unsafe { &*state.instrs.add(state.pc) };
+    let ProcessedInstr::MemoryOpsReg {
+        dst, args, memory, ..
+    } = instr
+    else {
+        unsafe { std::hint::unreachable_unchecked() }
+    };
+    let mem_addr = match state.module().mem_addrs.get(*memory as usize) {
+        Some(m) => m.clone(),
+        None => {
+            state.trap = Some(RuntimeError::MemoryNotFound);
+            return trap(state);
+        }
+    };
+    let delta = operand::read_reg_addr(state, &args[0]);
+    // A failed grow returns -1 of the memory's address type
+    let prev_size = mem_addr.mem_grow(delta).unwrap_or(u64::MAX);
+    if let Some(d) = dst {
+        operand::write_reg_addr(state, d, prev_size);
+    }
+    // Another index may be bound to the same instance as memory 0
+    let is_primary = state
+        .module()
+        .mem_addrs
+        .first()
+        .is_some_and(|primary| primary.ptr_eq(&mem_addr));
+    if is_primary {
+        state.mem_ptr = mem_addr.data_ptr();
+        state.mem_len = mem_addr.data_len();
+    }
+    state.pc += 1;
+    advance!(state)
+}
+
+pub fn mem_copy(state: &mut VmState) -> Outcome {
+    let instr = unsafe { &*state.instrs.add(state.pc) };
+    let ProcessedInstr::MemoryOpsReg {
+        args,
+        memory,
+        src_memory,
+        ..
+    } = instr
+    else {
+        unsafe { std::hint::unreachable_unchecked() }
+    };
+    let mem_addrs = &state.module().mem_addrs;
+    let (Some(dest_mem), Some(src_mem)) = (
+        mem_addrs.get(*memory as usize).cloned(),
+        mem_addrs.get(*src_memory as usize).cloned(),
+    ) else {
+        state.trap = Some(RuntimeError::MemoryNotFound);
+        return trap(state);
+    };
+    let dest = operand::read_reg_addr(state, &args[0]);
+    let src = operand::read_reg_addr(state, &args[1]);
+    let len = operand::read_reg_addr(state, &args[2]);
+    let result = if dest_mem.ptr_eq(&src_mem) {
+        dest_mem.memory_copy(dest, src, len)
+    } else {
+        dest_mem.copy_from(dest, &src_mem, src, len)
+    };
+    if let Err(e) = result {
+        state.trap = Some(e);
+        return trap(state);
+    }
+    state.pc += 1;
+    advance!(state)
+}
+
+pub fn mem_init(state: &mut VmState) -> Outcome {
+    let instr = unsafe { &*state.instrs.add(state.pc) };
+    let ProcessedInstr::MemoryOpsReg {
+        args,
+        data_index,
+        memory,
+        ..
+    } = instr
+    else {
+        unsafe { std::hint::unreachable_unchecked() }
+    };
+    let module_inst = state.module();
+    let mem_addr = match module_inst.mem_addrs.get(*memory as usize) {
         Some(m) => m.clone(),
         None => {
             state.trap = Some(RuntimeError::MemoryNotFound);
@@ -2055,12 +2885,17 @@ pub fn mem_init(state: &mut VmState) -> Outcome {
         return trap(state);
     }
     let data_bytes = module_inst.data_addrs[*data_index as usize].get_data();
+    let dest = operand::read_reg_addr(state, &args[0]);
     let regs = state.reg_file();
-    let dest = regs.get_i32(args[0].index()) as usize;
-    let offset = regs.get_i32(args[1].index()) as usize;
-    let len = regs.get_i32(args[2].index()) as usize;
-    if len > 0 {
-        mem_addr.init(dest, &data_bytes[offset..offset + len]);
+    let offset = regs.get_i32(args[1].index()) as u32 as u64;
+    let len = regs.get_i32(args[2].index()) as u32 as u64;
+    let Some(src) = MemAddr::check_range(data_bytes.len(), offset, 0, len) else {
+        state.trap = Some(RuntimeError::MemoryOutOfBounds);
+        return trap(state);
+    };
+    if let Err(e) = mem_addr.init(dest, &data_bytes[src..src + len as usize]) {
+        state.trap = Some(e);
+        return trap(state);
     }
     state.pc += 1;
     advance!(state)
@@ -2068,25 +2903,775 @@ pub fn mem_init(state: &mut VmState) -> Outcome {
 
 pub fn mem_fill(state: &mut VmState) -> Outcome {
     let instr = unsafe { &*state.instrs.add(state.pc) };
-    let ProcessedInstr::MemoryOpsReg { args, .. } = instr else {
+    let ProcessedInstr::MemoryOpsReg { args, memory, .. } = instr else {
         unsafe { std::hint::unreachable_unchecked() }
     };
-    let mem_addr = match state.module().mem_addrs.first() {
+    let mem_addr = match state.module().mem_addrs.get(*memory as usize) {
         Some(m) => m.clone(),
         None => {
             state.trap = Some(RuntimeError::MemoryNotFound);
             return trap(state);
         }
     };
-    let regs = state.reg_file();
-    let dest = regs.get_i32(args[0].index());
-    let val = regs.get_i32(args[1].index()) as u8;
-    let size = regs.get_i32(args[2].index());
-    mem_addr.memory_fill(dest, val, size);
+    let dest = operand::read_reg_addr(state, &args[0]);
+    let val = state.reg_file().get_i32(args[1].index()) as u8;
+    let size = operand::read_reg_addr(state, &args[2]);
+    if let Err(e) = mem_addr.memory_fill(dest, val, size) {
+        state.trap = Some(e);
+        return trap(state);
+    }
     state.pc += 1;
     advance!(state)
 }
 
+// ============================================================================
+// SIMD (v128) handlers
+// ============================================================================
+
+// Lane views of a v128 register value. Lanes are little-endian, lane 0 in
+// the lowest bytes, matching the Wasm memory layout.
+macro_rules! v128_lanes {
+    ($to:ident, $from:ident, $ty:ty, $n:literal) => {
+        #[inline(always)]
+        fn $to(v: i128) -> [$ty; $n] {
+            const W: usize = 16 / $n;
+            let b = v.to_le_bytes();
+            std::array::from_fn(|i| <$ty>::from_le_bytes(b[i * W..(i + 1) * W].try_into().unwrap()))
+        }
+
+        #[inline(always)]
+        fn $from(lanes: [$ty; $n]) -> i128 {
+            const W: usize = 16 / $n;
+            let mut b = [0u8; 16];
+            for (i, lane) in lanes.iter().enumerate() {
+                b[i * W..(i + 1) * W].copy_from_slice(&lane.to_le_bytes());
+            }
+            i128::from_le_bytes(b)
+        }
+    };
+}
+
+v128_lanes!(to_i8x16, from_i8x16, i8, 16);
+v128_lanes!(to_u8x16, from_u8x16, u8, 16);
+v128_lanes!(to_i16x8, from_i16x8, i16, 8);
+v128_lanes!(to_u16x8, from_u16x8, u16, 8);
+v128_lanes!(to_i32x4, from_i32x4, i32, 4);
+v128_lanes!(to_u32x4, from_u32x4, u32, 4);
+v128_lanes!(to_i64x2, from_i64x2, i64, 2);
+v128_lanes!(to_u64x2, from_u64x2, u64, 2);
+v128_lanes!(to_f32x4, from_f32x4, f32, 4);
+v128_lanes!(to_f64x2, from_f64x2, f64, 2);
+
+#[inline(always)]
+fn simd_operands(state: &VmState) -> (u16, [u16; 3], [u8; 16]) {
+    match state.current_instr() {
+        ProcessedInstr::SimdReg { dst, srcs, imm, .. } => (*dst, *srcs, *imm),
+        _ => unsafe { std::hint::unreachable_unchecked() },
+    }
+}
+
+/// v128 -> v128
+macro_rules! v128_unop {
+    ($name:ident, $op:expr) => {
+        pub fn $name(state: &mut VmState) -> Outcome {
+            let (dst, srcs, _) = simd_operands(state);
+            let regs = state.reg_file_mut();
+            let a = regs.get_v128(srcs[0]);
+            regs.set_v128(dst, $op(a));
+            state.pc += 1;
+            advance!(state)
+        }
+    };
+}
+
+/// v128, v128 -> v128
+macro_rules! v128_binop {
+    ($name:ident, $op:expr) => {
+        pub fn $name(state: &mut VmState) -> Outcome {
+            let (dst, srcs, _) = simd_operands(state);
+            let regs = state.reg_file_mut();
+            let a = regs.get_v128(srcs[0]);
+            let b = regs.get_v128(srcs[1]);
+            regs.set_v128(dst, $op(a, b));
+            state.pc += 1;
+            advance!(state)
+        }
+    };
+}
+
+/// v128 -> i32 (any_true / all_true / bitmask)
+macro_rules! v128_test {
+    ($name:ident, $op:expr) => {
+        pub fn $name(state: &mut VmState) -> Outcome {
+            let (dst, srcs, _) = simd_operands(state);
+            let regs = state.reg_file_mut();
+            let a = regs.get_v128(srcs[0]);
+            regs.set_i32(dst, $op(a));
+            state.pc += 1;
+            advance!(state)
+        }
+    };
+}
+
+/// v128, i32 -> v128 (shift count is taken modulo the lane width by
+/// `wrapping_shl` / `wrapping_shr`)
+macro_rules! v128_shift {
+    ($name:ident, $to:ident, $from:ident, $op:ident) => {
+        pub fn $name(state: &mut VmState) -> Outcome {
+            let (dst, srcs, _) = simd_operands(state);
+            let regs = state.reg_file_mut();
+            let a = $to(regs.get_v128(srcs[0]));
+            let s = regs.get_i32(srcs[1]) as u32;
+            regs.set_v128(dst, $from(a.map(|x| x.$op(s))));
+            state.pc += 1;
+            advance!(state)
+        }
+    };
+}
+
+/// scalar -> v128
+macro_rules! v128_splat {
+    ($name:ident, $get:ident, $from:ident, $cast:ty) => {
+        pub fn $name(state: &mut VmState) -> Outcome {
+            let (dst, srcs, _) = simd_operands(state);
+            let regs = state.reg_file_mut();
+            let x = regs.$get(srcs[0]) as $cast;
+            regs.set_v128(dst, $from([x; 16 / std::mem::size_of::<$cast>()]));
+            state.pc += 1;
+            advance!(state)
+        }
+    };
+}
+
+/// v128 -> scalar, lane index in `imm[0]`
+macro_rules! v128_extract_lane {
+    ($name:ident, $to:ident, $set:ident, $cast:ty) => {
+        pub fn $name(state: &mut VmState) -> Outcome {
+            let (dst, srcs, imm) = simd_operands(state);
+            let regs = state.reg_file_mut();
+            let x = $to(regs.get_v128(srcs[0]))[imm[0] as usize];
+            regs.$set(dst, x as $cast);
+            state.pc += 1;
+            advance!(state)
+        }
+    };
+}
+
+/// v128, scalar -> v128, lane index in `imm[0]`
+macro_rules! v128_replace_lane {
+    ($name:ident, $to:ident, $from:ident, $get:ident, $cast:ty) => {
+        pub fn $name(state: &mut VmState) -> Outcome {
+            let (dst, srcs, imm) = simd_operands(state);
+            let regs = state.reg_file_mut();
+            let mut lanes = $to(regs.get_v128(srcs[0]));
+            lanes[imm[0] as usize] = regs.$get(srcs[1]) as $cast;
+            regs.set_v128(dst, $from(lanes));
+            state.pc += 1;
+            advance!(state)
+        }
+    };
+}
+
+// Lane-wise helpers built on the two-register macros.
+macro_rules! lanewise_unop {
+    ($name:ident, $to:ident, $from:ident, $op:expr) => {
+        v128_unop!($name, |a: i128| $from($to(a).map($op)));
+    };
+}
+
+macro_rules! lanewise_binop {
+    ($name:ident, $to:ident, $from:ident, $op:expr) => {
+        v128_binop!($name, |a: i128, b: i128| {
+            let (a, b) = ($to(a), $to(b));
+            $from(std::array::from_fn(|i| $op(a[i], b[i])))
+        });
+    };
+}
+
+/// Lane-wise comparison producing an all-ones / all-zeros mask per lane.
+macro_rules! lanewise_cmp {
+    ($name:ident, $to:ident, $mask:ident, $op:expr) => {
+        v128_binop!($name, |a: i128, b: i128| {
+            let (a, b) = ($to(a), $to(b));
+            $mask(std::array::from_fn(|i| -(($op(a[i], b[i])) as i8) as _))
+        });
+    };
+}
+
+macro_rules! all_true {
+    ($name:ident, $to:ident) => {
+        v128_test!($name, |a: i128| $to(a).iter().all(|&x| x != 0) as i32);
+    };
+}
+
+macro_rules! bitmask {
+    ($name:ident, $to:ident) => {
+        v128_test!($name, |a: i128| {
+            $to(a)
+                .iter()
+                .enumerate()
+                .fold(0i32, |m, (i, &x)| m | (((x < 0) as i32) << i))
+        });
+    };
+}
+
+/// Widens the low (`$base = 0`) or high (`$base = N/2`) half of the source
+/// lanes into the destination shape.
+macro_rules! extend_half {
+    ($name:ident, $to:ident, $from:ident, $wide:ty, $base:literal) => {
+        v128_unop!($name, |a: i128| {
+            let a = $to(a);
+            $from(std::array::from_fn(|i| a[$base + i] as $wide))
+        });
+    };
+}
+
+macro_rules! extmul_half {
+    ($name:ident, $to:ident, $from:ident, $wide:ty, $base:literal) => {
+        v128_binop!($name, |a: i128, b: i128| {
+            let (a, b) = ($to(a), $to(b));
+            $from(std::array::from_fn(|i| {
+                (a[$base + i] as $wide).wrapping_mul(b[$base + i] as $wide)
+            }))
+        });
+    };
+}
+
+macro_rules! extadd_pairwise {
+    ($name:ident, $to:ident, $from:ident, $wide:ty) => {
+        v128_unop!($name, |a: i128| {
+            let a = $to(a);
+            $from(std::array::from_fn(|i| {
+                (a[2 * i] as $wide).wrapping_add(a[2 * i + 1] as $wide)
+            }))
+        });
+    };
+}
+
+/// Saturating narrow of two source vectors (`a` fills the low half).
+macro_rules! narrow {
+    ($name:ident, $to:ident, $from:ident, $narrow:ty, $half:literal) => {
+        v128_binop!($name, |a: i128, b: i128| {
+            let (a, b) = ($to(a), $to(b));
+            $from(std::array::from_fn(|i| {
+                let x = if i < $half { a[i] } else { b[i - $half] };
+                x.clamp(<$narrow>::MIN as _, <$narrow>::MAX as _) as $narrow
+            }))
+        });
+    };
+}
+
+// Wasm-spec lane min/max: NaN propagates, -0.0 < +0.0.
+macro_rules! wasm_fmin_fmax {
+    ($min:ident, $max:ident, $ty:ty) => {
+        #[inline(always)]
+        fn $min(a: $ty, b: $ty) -> $ty {
+            if a.is_nan() || b.is_nan() {
+                <$ty>::NAN
+            } else if a == 0.0 && b == 0.0 {
+                if a.is_sign_negative() {
+                    a
+                } else {
+                    b
+                }
+            } else {
+                a.min(b)
+            }
+        }
+
+        #[inline(always)]
+        fn $max(a: $ty, b: $ty) -> $ty {
+            if a.is_nan() || b.is_nan() {
+                <$ty>::NAN
+            } else if a == 0.0 && b == 0.0 {
+                if a.is_sign_positive() {
+                    a
+                } else {
+                    b
+                }
+            } else {
+                a.max(b)
+            }
+        }
+    };
+}
+
+wasm_fmin_fmax!(wasm_f32_min, wasm_f32_max, f32);
+wasm_fmin_fmax!(wasm_f64_min, wasm_f64_max, f64);
+
+pub fn v128_const(state: &mut VmState) -> Outcome {
+    let (dst, _, imm) = simd_operands(state);
+    state.reg_file_mut().set_v128(dst, i128::from_le_bytes(imm));
+    state.pc += 1;
+    advance!(state)
+}
+
+pub fn i8x16_shuffle(state: &mut VmState) -> Outcome {
+    let (dst, srcs, imm) = simd_operands(state);
+    let regs = state.reg_file_mut();
+    let a = to_u8x16(regs.get_v128(srcs[0]));
+    let b = to_u8x16(regs.get_v128(srcs[1]));
+    // Lane indices are < 32 in a valid module.
+    let lanes = imm.map(|i| {
+        let i = i as usize;
+        if i < 16 {
+            a[i]
+        } else {
+            b[i - 16]
+        }
+    });
+    regs.set_v128(dst, from_u8x16(lanes));
+    state.pc += 1;
+    advance!(state)
+}
+
+pub fn v128_bitselect(state: &mut VmState) -> Outcome {
+    let (dst, srcs, _) = simd_operands(state);
+    let regs = state.reg_file_mut();
+    let a = regs.get_v128(srcs[0]);
+    let b = regs.get_v128(srcs[1]);
+    let c = regs.get_v128(srcs[2]);
+    regs.set_v128(dst, (a & c) | (b & !c));
+    state.pc += 1;
+    advance!(state)
+}
+
+// Bitwise
+v128_unop!(v128_not, |a: i128| !a);
+v128_binop!(v128_and, |a: i128, b: i128| a & b);
+v128_binop!(v128_andnot, |a: i128, b: i128| a & !b);
+v128_binop!(v128_or, |a: i128, b: i128| a | b);
+v128_binop!(v128_xor, |a: i128, b: i128| a ^ b);
+v128_test!(v128_any_true, |a: i128| (a != 0) as i32);
+
+// Splat / lane access
+v128_splat!(i8x16_splat, get_i32, from_i8x16, i8);
+v128_splat!(i16x8_splat, get_i32, from_i16x8, i16);
+v128_splat!(i32x4_splat, get_i32, from_i32x4, i32);
+v128_splat!(i64x2_splat, get_i64, from_i64x2, i64);
+v128_splat!(f32x4_splat, get_f32, from_f32x4, f32);
+v128_splat!(f64x2_splat, get_f64, from_f64x2, f64);
+
+v128_extract_lane!(i8x16_extract_lane_s, to_i8x16, set_i32, i32);
+v128_extract_lane!(i8x16_extract_lane_u, to_u8x16, set_i32, i32);
+v128_extract_lane!(i16x8_extract_lane_s, to_i16x8, set_i32, i32);
+v128_extract_lane!(i16x8_extract_lane_u, to_u16x8, set_i32, i32);
+v128_extract_lane!(i32x4_extract_lane, to_i32x4, set_i32, i32);
+v128_extract_lane!(i64x2_extract_lane, to_i64x2, set_i64, i64);
+v128_extract_lane!(f32x4_extract_lane, to_f32x4, set_f32, f32);
+v128_extract_lane!(f64x2_extract_lane, to_f64x2, set_f64, f64);
+
+v128_replace_lane!(i8x16_replace_lane, to_i8x16, from_i8x16, get_i32, i8);
+v128_replace_lane!(i16x8_replace_lane, to_i16x8, from_i16x8, get_i32, i16);
+v128_replace_lane!(i32x4_replace_lane, to_i32x4, from_i32x4, get_i32, i32);
+v128_replace_lane!(i64x2_replace_lane, to_i64x2, from_i64x2, get_i64, i64);
+v128_replace_lane!(f32x4_replace_lane, to_f32x4, from_f32x4, get_f32, f32);
+v128_replace_lane!(f64x2_replace_lane, to_f64x2, from_f64x2, get_f64, f64);
+
+// Out-of-range swizzle indices select zero.
+v128_binop!(i8x16_swizzle, |a: i128, s: i128| {
+    let a = to_u8x16(a);
+    from_u8x16(to_u8x16(s).map(|i| a.get(i as usize).copied().unwrap_or(0)))
+});
+
+// Comparisons
+lanewise_cmp!(i8x16_eq, to_i8x16, from_i8x16, |a, b| a == b);
+lanewise_cmp!(i8x16_ne, to_i8x16, from_i8x16, |a, b| a != b);
+lanewise_cmp!(i8x16_lt_s, to_i8x16, from_i8x16, |a, b| a < b);
+lanewise_cmp!(i8x16_lt_u, to_u8x16, from_i8x16, |a, b| a < b);
+lanewise_cmp!(i8x16_gt_s, to_i8x16, from_i8x16, |a, b| a > b);
+lanewise_cmp!(i8x16_gt_u, to_u8x16, from_i8x16, |a, b| a > b);
+lanewise_cmp!(i8x16_le_s, to_i8x16, from_i8x16, |a, b| a <= b);
+lanewise_cmp!(i8x16_le_u, to_u8x16, from_i8x16, |a, b| a <= b);
+lanewise_cmp!(i8x16_ge_s, to_i8x16, from_i8x16, |a, b| a >= b);
+lanewise_cmp!(i8x16_ge_u, to_u8x16, from_i8x16, |a, b| a >= b);
+lanewise_cmp!(i16x8_eq, to_i16x8, from_i16x8, |a, b| a == b);
+lanewise_cmp!(i16x8_ne, to_i16x8, from_i16x8, |a, b| a != b);
+lanewise_cmp!(i16x8_lt_s, to_i16x8, from_i16x8, |a, b| a < b);
+lanewise_cmp!(i16x8_lt_u, to_u16x8, from_i16x8, |a, b| a < b);
+lanewise_cmp!(i16x8_gt_s, to_i16x8, from_i16x8, |a, b| a > b);
+lanewise_cmp!(i16x8_gt_u, to_u16x8, from_i16x8, |a, b| a > b);
+lanewise_cmp!(i16x8_le_s, to_i16x8, from_i16x8, |a, b| a <= b);
+lanewise_cmp!(i16x8_le_u, to_u16x8, from_i16x8, |a, b| a <= b);
+lanewise_cmp!(i16x8_ge_s, to_i16x8, from_i16x8, |a, b| a >= b);
+lanewise_cmp!(i16x8_ge_u, to_u16x8, from_i16x8, |a, b| a >= b);
+lanewise_cmp!(i32x4_eq, to_i32x4, from_i32x4, |a, b| a == b);
+lanewise_cmp!(i32x4_ne, to_i32x4, from_i32x4, |a, b| a != b);
+lanewise_cmp!(i32x4_lt_s, to_i32x4, from_i32x4, |a, b| a < b);
+lanewise_cmp!(i32x4_lt_u, to_u32x4, from_i32x4, |a, b| a < b);
+lanewise_cmp!(i32x4_gt_s, to_i32x4, from_i32x4, |a, b| a > b);
+lanewise_cmp!(i32x4_gt_u, to_u32x4, from_i32x4, |a, b| a > b);
+lanewise_cmp!(i32x4_le_s, to_i32x4, from_i32x4, |a, b| a <= b);
+lanewise_cmp!(i32x4_le_u, to_u32x4, from_i32x4, |a, b| a <= b);
+lanewise_cmp!(i32x4_ge_s, to_i32x4, from_i32x4, |a, b| a >= b);
+lanewise_cmp!(i32x4_ge_u, to_u32x4, from_i32x4, |a, b| a >= b);
+lanewise_cmp!(i64x2_eq, to_i64x2, from_i64x2, |a, b| a == b);
+lanewise_cmp!(i64x2_ne, to_i64x2, from_i64x2, |a, b| a != b);
+lanewise_cmp!(i64x2_lt_s, to_i64x2, from_i64x2, |a, b| a < b);
+lanewise_cmp!(i64x2_gt_s, to_i64x2, from_i64x2, |a, b| a > b);
+lanewise_cmp!(i64x2_le_s, to_i64x2, from_i64x2, |a, b| a <= b);
+lanewise_cmp!(i64x2_ge_s, to_i64x2, from_i64x2, |a, b| a >= b);
+lanewise_cmp!(f32x4_eq, to_f32x4, from_i32x4, |a, b| a == b);
+lanewise_cmp!(f32x4_ne, to_f32x4, from_i32x4, |a, b| a != b);
+lanewise_cmp!(f32x4_lt, to_f32x4, from_i32x4, |a, b| a < b);
+lanewise_cmp!(f32x4_gt, to_f32x4, from_i32x4, |a, b| a > b);
+lanewise_cmp!(f32x4_le, to_f32x4, from_i32x4, |a, b| a <= b);
+lanewise_cmp!(f32x4_ge, to_f32x4, from_i32x4, |a, b| a >= b);
+lanewise_cmp!(f64x2_eq, to_f64x2, from_i64x2, |a, b| a == b);
+lanewise_cmp!(f64x2_ne, to_f64x2, from_i64x2, |a, b| a != b);
+lanewise_cmp!(f64x2_lt, to_f64x2, from_i64x2, |a, b| a < b);
+lanewise_cmp!(f64x2_gt, to_f64x2, from_i64x2, |a, b| a > b);
+lanewise_cmp!(f64x2_le, to_f64x2, from_i64x2, |a, b| a <= b);
+lanewise_cmp!(f64x2_ge, to_f64x2, from_i64x2, |a, b| a >= b);
+
+// i8x16
+lanewise_unop!(i8x16_abs, to_i8x16, from_i8x16, i8::wrapping_abs);
+lanewise_unop!(i8x16_neg, to_i8x16, from_i8x16, i8::wrapping_neg);
+lanewise_unop!(i8x16_popcnt, to_u8x16, from_u8x16, |x: u8| x.count_ones()
+    as u8);
+all_true!(i8x16_all_true, to_i8x16);
+bitmask!(i8x16_bitmask, to_i8x16);
+narrow!(i8x16_narrow_i16x8_s, to_i16x8, from_i8x16, i8, 8);
+narrow!(i8x16_narrow_i16x8_u, to_i16x8, from_u8x16, u8, 8);
+v128_shift!(i8x16_shl, to_i8x16, from_i8x16, wrapping_shl);
+v128_shift!(i8x16_shr_s, to_i8x16, from_i8x16, wrapping_shr);
+v128_shift!(i8x16_shr_u, to_u8x16, from_u8x16, wrapping_shr);
+lanewise_binop!(i8x16_add, to_i8x16, from_i8x16, i8::wrapping_add);
+lanewise_binop!(i8x16_add_sat_s, to_i8x16, from_i8x16, i8::saturating_add);
+lanewise_binop!(i8x16_add_sat_u, to_u8x16, from_u8x16, u8::saturating_add);
+lanewise_binop!(i8x16_sub, to_i8x16, from_i8x16, i8::wrapping_sub);
+lanewise_binop!(i8x16_sub_sat_s, to_i8x16, from_i8x16, i8::saturating_sub);
+lanewise_binop!(i8x16_sub_sat_u, to_u8x16, from_u8x16, u8::saturating_sub);
+lanewise_binop!(i8x16_min_s, to_i8x16, from_i8x16, i8::min);
+lanewise_binop!(i8x16_min_u, to_u8x16, from_u8x16, u8::min);
+lanewise_binop!(i8x16_max_s, to_i8x16, from_i8x16, i8::max);
+lanewise_binop!(i8x16_max_u, to_u8x16, from_u8x16, u8::max);
+lanewise_binop!(i8x16_avgr_u, to_u8x16, from_u8x16, |a: u8, b: u8| {
+    (a as u16 + b as u16).div_ceil(2) as u8
+});
+
+// i16x8
+extadd_pairwise!(i16x8_extadd_pairwise_i8x16_s, to_i8x16, from_i16x8, i16);
+extadd_pairwise!(i16x8_extadd_pairwise_i8x16_u, to_u8x16, from_u16x8, u16);
+lanewise_unop!(i16x8_abs, to_i16x8, from_i16x8, i16::wrapping_abs);
+lanewise_unop!(i16x8_neg, to_i16x8, from_i16x8, i16::wrapping_neg);
+lanewise_binop!(
+    i16x8_q15mulr_sat_s,
+    to_i16x8,
+    from_i16x8,
+    |a: i16, b: i16| {
+        ((a as i32 * b as i32 + 0x4000) >> 15).clamp(i16::MIN as i32, i16::MAX as i32) as i16
+    }
+);
+all_true!(i16x8_all_true, to_i16x8);
+bitmask!(i16x8_bitmask, to_i16x8);
+narrow!(i16x8_narrow_i32x4_s, to_i32x4, from_i16x8, i16, 4);
+narrow!(i16x8_narrow_i32x4_u, to_i32x4, from_u16x8, u16, 4);
+extend_half!(i16x8_extend_low_i8x16_s, to_i8x16, from_i16x8, i16, 0);
+extend_half!(i16x8_extend_high_i8x16_s, to_i8x16, from_i16x8, i16, 8);
+extend_half!(i16x8_extend_low_i8x16_u, to_u8x16, from_u16x8, u16, 0);
+extend_half!(i16x8_extend_high_i8x16_u, to_u8x16, from_u16x8, u16, 8);
+v128_shift!(i16x8_shl, to_i16x8, from_i16x8, wrapping_shl);
+v128_shift!(i16x8_shr_s, to_i16x8, from_i16x8, wrapping_shr);
+v128_shift!(i16x8_shr_u, to_u16x8, from_u16x8, wrapping_shr);
+lanewise_binop!(i16x8_add, to_i16x8, from_i16x8, i16::wrapping_add);
+lanewise_binop!(i16x8_add_sat_s, to_i16x8, from_i16x8, i16::saturating_add);
+lanewise_binop!(i16x8_add_sat_u, to_u16x8, from_u16x8, u16::saturating_add);
+lanewise_binop!(i16x8_sub, to_i16x8, from_i16x8, i16::wrapping_sub);
+lanewise_binop!(i16x8_sub_sat_s, to_i16x8, from_i16x8, i16::saturating_sub);
+lanewise_binop!(i16x8_sub_sat_u, to_u16x8, from_u16x8, u16::saturating_sub);
+lanewise_binop!(i16x8_mul, to_i16x8, from_i16x8, i16::wrapping_mul);
+lanewise_binop!(i16x8_min_s, to_i16x8, from_i16x8, i16::min);
+lanewise_binop!(i16x8_min_u, to_u16x8, from_u16x8, u16::min);
+lanewise_binop!(i16x8_max_s, to_i16x8, from_i16x8, i16::max);
+lanewise_binop!(i16x8_max_u, to_u16x8, from_u16x8, u16::max);
+lanewise_binop!(i16x8_avgr_u, to_u16x8, from_u16x8, |a: u16, b: u16| {
+    (a as u32 + b as u32).div_ceil(2) as u16
+});
+extmul_half!(i16x8_extmul_low_i8x16_s, to_i8x16, from_i16x8, i16, 0);
+extmul_half!(i16x8_extmul_high_i8x16_s, to_i8x16, from_i16x8, i16, 8);
+extmul_half!(i16x8_extmul_low_i8x16_u, to_u8x16, from_u16x8, u16, 0);
+extmul_half!(i16x8_extmul_high_i8x16_u, to_u8x16, from_u16x8, u16, 8);
+
+// i32x4
+extadd_pairwise!(i32x4_extadd_pairwise_i16x8_s, to_i16x8, from_i32x4, i32);
+extadd_pairwise!(i32x4_extadd_pairwise_i16x8_u, to_u16x8, from_u32x4, u32);
+lanewise_unop!(i32x4_abs, to_i32x4, from_i32x4, i32::wrapping_abs);
+lanewise_unop!(i32x4_neg, to_i32x4, from_i32x4, i32::wrapping_neg);
+all_true!(i32x4_all_true, to_i32x4);
+bitmask!(i32x4_bitmask, to_i32x4);
+extend_half!(i32x4_extend_low_i16x8_s, to_i16x8, from_i32x4, i32, 0);
+extend_half!(i32x4_extend_high_i16x8_s, to_i16x8, from_i32x4, i32, 4);
+extend_half!(i32x4_extend_low_i16x8_u, to_u16x8, from_u32x4, u32, 0);
+extend_half!(i32x4_extend_high_i16x8_u, to_u16x8, from_u32x4, u32, 4);
+v128_shift!(i32x4_shl, to_i32x4, from_i32x4, wrapping_shl);
+v128_shift!(i32x4_shr_s, to_i32x4, from_i32x4, wrapping_shr);
+v128_shift!(i32x4_shr_u, to_u32x4, from_u32x4, wrapping_shr);
+lanewise_binop!(i32x4_add, to_i32x4, from_i32x4, i32::wrapping_add);
+lanewise_binop!(i32x4_sub, to_i32x4, from_i32x4, i32::wrapping_sub);
+lanewise_binop!(i32x4_mul, to_i32x4, from_i32x4, i32::wrapping_mul);
+lanewise_binop!(i32x4_min_s, to_i32x4, from_i32x4, i32::min);
+lanewise_binop!(i32x4_min_u, to_u32x4, from_u32x4, u32::min);
+lanewise_binop!(i32x4_max_s, to_i32x4, from_i32x4, i32::max);
+lanewise_binop!(i32x4_max_u, to_u32x4, from_u32x4, u32::max);
+v128_binop!(i32x4_dot_i16x8_s, |a: i128, b: i128| {
+    let (a, b) = (to_i16x8(a), to_i16x8(b));
+    from_i32x4(std::array::from_fn(|i| {
+        (a[2 * i] as i32 * b[2 * i] as i32).wrapping_add(a[2 * i + 1] as i32 * b[2 * i + 1] as i32)
+    }))
+});
+extmul_half!(i32x4_extmul_low_i16x8_s, to_i16x8, from_i32x4, i32, 0);
+extmul_half!(i32x4_extmul_high_i16x8_s, to_i16x8, from_i32x4, i32, 4);
+extmul_half!(i32x4_extmul_low_i16x8_u, to_u16x8, from_u32x4, u32, 0);
+extmul_half!(i32x4_extmul_high_i16x8_u, to_u16x8, from_u32x4, u32, 4);
+
+// i64x2
+lanewise_unop!(i64x2_abs, to_i64x2, from_i64x2, i64::wrapping_abs);
+lanewise_unop!(i64x2_neg, to_i64x2, from_i64x2, i64::wrapping_neg);
+all_true!(i64x2_all_true, to_i64x2);
+bitmask!(i64x2_bitmask, to_i64x2);
+extend_half!(i64x2_extend_low_i32x4_s, to_i32x4, from_i64x2, i64, 0);
+extend_half!(i64x2_extend_high_i32x4_s, to_i32x4, from_i64x2, i64, 2);
+extend_half!(i64x2_extend_low_i32x4_u, to_u32x4, from_u64x2, u64, 0);
+extend_half!(i64x2_extend_high_i32x4_u, to_u32x4, from_u64x2, u64, 2);
+v128_shift!(i64x2_shl, to_i64x2, from_i64x2, wrapping_shl);
+v128_shift!(i64x2_shr_s, to_i64x2, from_i64x2, wrapping_shr);
+v128_shift!(i64x2_shr_u, to_u64x2, from_u64x2, wrapping_shr);
+lanewise_binop!(i64x2_add, to_i64x2, from_i64x2, i64::wrapping_add);
+lanewise_binop!(i64x2_sub, to_i64x2, from_i64x2, i64::wrapping_sub);
+lanewise_binop!(i64x2_mul, to_i64x2, from_i64x2, i64::wrapping_mul);
+extmul_half!(i64x2_extmul_low_i32x4_s, to_i32x4, from_i64x2, i64, 0);
+extmul_half!(i64x2_extmul_high_i32x4_s, to_i32x4, from_i64x2, i64, 2);
+extmul_half!(i64x2_extmul_low_i32x4_u, to_u32x4, from_u64x2, u64, 0);
+extmul_half!(i64x2_extmul_high_i32x4_u, to_u32x4, from_u64x2, u64, 2);
+
+// f32x4
+lanewise_unop!(f32x4_ceil, to_f32x4, from_f32x4, f32::ceil);
+lanewise_unop!(f32x4_floor, to_f32x4, from_f32x4, f32::floor);
+lanewise_unop!(f32x4_trunc, to_f32x4, from_f32x4, f32::trunc);
+lanewise_unop!(f32x4_nearest, to_f32x4, from_f32x4, f32::round_ties_even);
+lanewise_unop!(f32x4_abs, to_f32x4, from_f32x4, f32::abs);
+lanewise_unop!(f32x4_neg, to_f32x4, from_f32x4, |a: f32| -a);
+lanewise_unop!(f32x4_sqrt, to_f32x4, from_f32x4, f32::sqrt);
+lanewise_binop!(f32x4_add, to_f32x4, from_f32x4, |a: f32, b: f32| a + b);
+lanewise_binop!(f32x4_sub, to_f32x4, from_f32x4, |a: f32, b: f32| a - b);
+lanewise_binop!(f32x4_mul, to_f32x4, from_f32x4, |a: f32, b: f32| a * b);
+lanewise_binop!(f32x4_div, to_f32x4, from_f32x4, |a: f32, b: f32| a / b);
+lanewise_binop!(f32x4_min, to_f32x4, from_f32x4, wasm_f32_min);
+lanewise_binop!(f32x4_max, to_f32x4, from_f32x4, wasm_f32_max);
+lanewise_binop!(
+    f32x4_pmin,
+    to_f32x4,
+    from_f32x4,
+    |a: f32, b: f32| if b < a { b } else { a }
+);
+lanewise_binop!(
+    f32x4_pmax,
+    to_f32x4,
+    from_f32x4,
+    |a: f32, b: f32| if a < b { b } else { a }
+);
+
+// f64x2
+lanewise_unop!(f64x2_ceil, to_f64x2, from_f64x2, f64::ceil);
+lanewise_unop!(f64x2_floor, to_f64x2, from_f64x2, f64::floor);
+lanewise_unop!(f64x2_trunc, to_f64x2, from_f64x2, f64::trunc);
+lanewise_unop!(f64x2_nearest, to_f64x2, from_f64x2, f64::round_ties_even);
+lanewise_unop!(f64x2_abs, to_f64x2, from_f64x2, f64::abs);
+lanewise_unop!(f64x2_neg, to_f64x2, from_f64x2, |a: f64| -a);
+lanewise_unop!(f64x2_sqrt, to_f64x2, from_f64x2, f64::sqrt);
+lanewise_binop!(f64x2_add, to_f64x2, from_f64x2, |a: f64, b: f64| a + b);
+lanewise_binop!(f64x2_sub, to_f64x2, from_f64x2, |a: f64, b: f64| a - b);
+lanewise_binop!(f64x2_mul, to_f64x2, from_f64x2, |a: f64, b: f64| a * b);
+lanewise_binop!(f64x2_div, to_f64x2, from_f64x2, |a: f64, b: f64| a / b);
+lanewise_binop!(f64x2_min, to_f64x2, from_f64x2, wasm_f64_min);
+lanewise_binop!(f64x2_max, to_f64x2, from_f64x2, wasm_f64_max);
+lanewise_binop!(
+    f64x2_pmin,
+    to_f64x2,
+    from_f64x2,
+    |a: f64, b: f64| if b < a { b } else { a }
+);
+lanewise_binop!(
+    f64x2_pmax,
+    to_f64x2,
+    from_f64x2,
+    |a: f64, b: f64| if a < b { b } else { a }
+);
+
+// Conversions (`as` already saturates and maps NaN to 0, which is exactly
+// the trunc_sat semantics).
+lanewise_unop!(i32x4_trunc_sat_f32x4_s, to_f32x4, from_i32x4, |a: f32| a
+    as i32);
+lanewise_unop!(i32x4_trunc_sat_f32x4_u, to_f32x4, from_u32x4, |a: f32| a
+    as u32);
+lanewise_unop!(f32x4_convert_i32x4_s, to_i32x4, from_f32x4, |a: i32| a
+    as f32);
+lanewise_unop!(f32x4_convert_i32x4_u, to_u32x4, from_f32x4, |a: u32| a
+    as f32);
+v128_unop!(i32x4_trunc_sat_f64x2_s_zero, |a: i128| {
+    let a = to_f64x2(a);
+    from_i32x4([a[0] as i32, a[1] as i32, 0, 0])
+});
+v128_unop!(i32x4_trunc_sat_f64x2_u_zero, |a: i128| {
+    let a = to_f64x2(a);
+    from_u32x4([a[0] as u32, a[1] as u32, 0, 0])
+});
+v128_unop!(f64x2_convert_low_i32x4_s, |a: i128| {
+    let a = to_i32x4(a);
+    from_f64x2([a[0] as f64, a[1] as f64])
+});
+v128_unop!(f64x2_convert_low_i32x4_u, |a: i128| {
+    let a = to_u32x4(a);
+    from_f64x2([a[0] as f64, a[1] as f64])
+});
+v128_unop!(f32x4_demote_f64x2_zero, |a: i128| {
+    let a = to_f64x2(a);
+    from_f32x4([a[0] as f32, a[1] as f32, 0.0, 0.0])
+});
+v128_unop!(f64x2_promote_low_f32x4, |a: i128| {
+    let a = to_f32x4(a);
+    from_f64x2([a[0] as f64, a[1] as f64])
+});
+
+// ============================================================================
+// SIMD memory handlers
+// ============================================================================
+
+/// Macro for SIMD loads — reads `$bytes` bytes from `mem_ptr + addr + offset`
+/// and builds the result from them, the previous `value` register (for the
+/// `*_lane` variants) and the lane index.
+macro_rules! v128_load {
+    ($name:ident, $bytes:literal, $op:expr) => {
+        pub fn $name(state: &mut VmState) -> Outcome {
+            let (dst, addr, value, offset, lane, memory) = match state.current_instr() {
+                ProcessedInstr::SimdMemReg {
+                    dst,
+                    addr,
+                    value,
+                    offset,
+                    lane,
+                    memory,
+                    ..
+                } => (*dst, *addr, *value, *offset, *lane as usize, *memory),
+                _ => unsafe { std::hint::unreachable_unchecked() },
+            };
+            let p = operand::read_addr(state, &addr);
+            let (mem_ptr, mem_len) = mem_view(state, memory);
+            let Some(pos) = MemAddr::check_range(mem_len, p, offset, $bytes) else {
+                state.trap = Some(RuntimeError::MemoryOutOfBounds);
+                return trap(state);
+            };
+            let bytes: [u8; $bytes] =
+                unsafe { std::ptr::read_unaligned(mem_ptr.add(pos) as *const [u8; $bytes]) };
+            let regs = state.reg_file_mut();
+            let old = regs.get_v128(value);
+            regs.set_v128(dst, $op(bytes, old, lane));
+            state.pc += 1;
+            advance!(state)
+        }
+    };
+}
+
+/// Macro for SIMD stores — writes the `$bytes` bytes produced from the
+/// `value` register (and lane index) to `mem_ptr + addr + offset`.
+macro_rules! v128_store {
+    ($name:ident, $bytes:literal, $op:expr) => {
+        pub fn $name(state: &mut VmState) -> Outcome {
+            let (addr, value, offset, lane, memory) = match state.current_instr() {
+                ProcessedInstr::SimdMemReg {
+                    addr,
+                    value,
+                    offset,
+                    lane,
+                    memory,
+                    ..
+                } => (*addr, *value, *offset, *lane as usize, *memory),
+                _ => unsafe { std::hint::unreachable_unchecked() },
+            };
+            let p = operand::read_addr(state, &addr);
+            let v = state.reg_file().get_v128(value);
+            let (mem_ptr, mem_len) = mem_view(state, memory);
+            let Some(pos) = MemAddr::check_range(mem_len, p, offset, $bytes) else {
+                state.trap = Some(RuntimeError::MemoryOutOfBounds);
+                return trap(state);
+            };
+            let bytes: [u8; $bytes] = $op(v, lane);
+            unsafe {
+                std::ptr::write_unaligned(mem_ptr.add(pos) as *mut [u8; $bytes], bytes);
+            }
+            state.pc += 1;
+            advance!(state)
+        }
+    };
+}
+
+macro_rules! v128_load_extend {
+    ($name:ident, $narrow:ty, $wide:ty, $from:ident) => {
+        v128_load!($name, 8, |b: [u8; 8], _: i128, _: usize| {
+            const W: usize = std::mem::size_of::<$narrow>();
+            $from(std::array::from_fn(|i| {
+                <$narrow>::from_le_bytes(b[i * W..(i + 1) * W].try_into().unwrap()) as $wide
+            }))
+        });
+    };
+}
+
+macro_rules! v128_load_splat {
+    ($name:ident, $bytes:literal, $ty:ty, $from:ident) => {
+        v128_load!($name, $bytes, |b: [u8; $bytes], _: i128, _: usize| {
+            $from([<$ty>::from_le_bytes(b); 16 / $bytes])
+        });
+    };
+}
+
+macro_rules! v128_load_lane {
+    ($name:ident, $bytes:literal, $ty:ty, $to:ident, $from:ident) => {
+        v128_load!($name, $bytes, |b: [u8; $bytes], old: i128, lane: usize| {
+            let mut lanes = $to(old);
+            lanes[lane] = <$ty>::from_le_bytes(b);
+            $from(lanes)
+        });
+    };
+}
+
+macro_rules! v128_store_lane {
+    ($name:ident, $bytes:literal, $to:ident) => {
+        v128_store!($name, $bytes, |v: i128, lane: usize| $to(v)[lane]
+            .to_le_bytes());
+    };
+}
+
+v128_load!(v128_load, 16, |b: [u8; 16], _: i128, _: usize| {
+    i128::from_le_bytes(b)
+});
+v128_load_extend!(v128_load8x8_s, i8, i16, from_i16x8);
+v128_load_extend!(v128_load8x8_u, u8, u16, from_u16x8);
+v128_load_extend!(v128_load16x4_s, i16, i32, from_i32x4);
+v128_load_extend!(v128_load16x4_u, u16, u32, from_u32x4);
+v128_load_extend!(v128_load32x2_s, i32, i64, from_i64x2);
+v128_load_extend!(v128_load32x2_u, u32, u64, from_u64x2);
+v128_load_splat!(v128_load8_splat, 1, u8, from_u8x16);
+v128_load_splat!(v128_load16_splat, 2, u16, from_u16x8);
+v128_load_splat!(v128_load32_splat, 4, u32, from_u32x4);
+v128_load_splat!(v128_load64_splat, 8, u64, from_u64x2);
+v128_load!(v128_load32_zero, 4, |b: [u8; 4], _: i128, _: usize| {
+    u32::from_le_bytes(b) as i128
+});
+v128_load!(v128_load64_zero, 8, |b: [u8; 8], _: i128, _: usize| {
+    u64::from_le_bytes(b) as i128
+});
+v128_load_lane!(v128_load8_lane, 1, u8, to_u8x16, from_u8x16);
+v128_load_lane!(v128_load16_lane, 2, u16, to_u16x8, from_u16x8);
+v128_load_lane!(v128_load32_lane, 4, u32, to_u32x4, from_u32x4);
+v128_load_lane!(v128_load64_lane, 8, u64, to_u64x2, from_u64x2);
+
+v128_store!(v128_store, 16, |v: i128, _: usize| v.to_le_bytes());
+v128_store_lane!(v128_store8_lane, 1, to_u8x16);
+v128_store_lane!(v128_store16_lane, 2, to_u16x8);
+v128_store_lane!(v128_store32_lane, 4, to_u32x4);
+v128_store_lane!(v128_store64_lane, 8, to_u64x2);
+
 // ============================================================================
 // select_handler — map ProcessedInstr → Handler
 // ============================================================================
@@ -2298,6 +3883,7 @@ pub fn select_handler(instr: &ProcessedInstr) -> Handler {
             HANDLER_IDX_SELECT_I64 => select_i64,
             HANDLER_IDX_SELECT_F32 => select_f32,
             HANDLER_IDX_SELECT_F64 => select_f64,
+            HANDLER_IDX_SELECT_V128 => select_v128,
             _ => invalid,
         },
         ProcessedInstr::GlobalGetReg { handler_index, .. } => match *handler_index {
@@ -2305,6 +3891,7 @@ pub fn select_handler(instr: &ProcessedInstr) -> Handler {
             HANDLER_IDX_GLOBAL_GET_I64 => global_get_i64,
             HANDLER_IDX_GLOBAL_GET_F32 => global_get_f32,
             HANDLER_IDX_GLOBAL_GET_F64 => global_get_f64,
+            HANDLER_IDX_GLOBAL_GET_V128 => global_get_v128,
             _ => invalid,
         },
         ProcessedInstr::GlobalSetReg { handler_index, .. } => match *handler_index {
@@ -2312,11 +3899,14 @@ pub fn select_handler(instr: &ProcessedInstr) -> Handler {
             HANDLER_IDX_GLOBAL_SET_I64 => global_set_i64,
             HANDLER_IDX_GLOBAL_SET_F32 => global_set_f32,
             HANDLER_IDX_GLOBAL_SET_F64 => global_set_f64,
+            HANDLER_IDX_GLOBAL_SET_V128 => global_set_v128,
             _ => invalid,
         },
         ProcessedInstr::RefLocalReg { handler_index, .. } => match *handler_index {
             HANDLER_IDX_REF_LOCAL_GET => ref_local_get,
             HANDLER_IDX_REF_LOCAL_SET => ref_local_set,
+            HANDLER_IDX_V128_LOCAL_GET => v128_local_get,
+            HANDLER_IDX_V128_LOCAL_SET => v128_local_set,
             _ => invalid,
         },
         ProcessedInstr::TableRefReg { handler_index, .. } => match *handler_index {
@@ -2327,14 +3917,269 @@ pub fn select_handler(instr: &ProcessedInstr) -> Handler {
             HANDLER_IDX_TABLE_FILL => table_fill,
             _ => invalid,
         },
+        ProcessedInstr::TableOpsReg { handler_index, .. } => match *handler_index {
+            HANDLER_IDX_TABLE_SIZE => table_size,
+            HANDLER_IDX_TABLE_GROW => table_grow,
+            HANDLER_IDX_TABLE_COPY => table_copy,
+            HANDLER_IDX_TABLE_INIT => table_init,
+            _ => invalid,
+        },
+        ProcessedInstr::SimdReg { handler_index, .. } => match *handler_index {
+            HANDLER_IDX_V128_CONST => v128_const,
+            HANDLER_IDX_I8X16_SHUFFLE => i8x16_shuffle,
+            HANDLER_IDX_I8X16_SWIZZLE => i8x16_swizzle,
+            HANDLER_IDX_I8X16_SPLAT => i8x16_splat,
+            HANDLER_IDX_I16X8_SPLAT => i16x8_splat,
+            HANDLER_IDX_I32X4_SPLAT => i32x4_splat,
+            HANDLER_IDX_I64X2_SPLAT => i64x2_splat,
+            HANDLER_IDX_F32X4_SPLAT => f32x4_splat,
+            HANDLER_IDX_F64X2_SPLAT => f64x2_splat,
+            HANDLER_IDX_I8X16_EXTRACT_LANE_S => i8x16_extract_lane_s,
+            HANDLER_IDX_I8X16_EXTRACT_LANE_U => i8x16_extract_lane_u,
+            HANDLER_IDX_I8X16_REPLACE_LANE => i8x16_replace_lane,
+            HANDLER_IDX_I16X8_EXTRACT_LANE_S => i16x8_extract_lane_s,
+            HANDLER_IDX_I16X8_EXTRACT_LANE_U => i16x8_extract_lane_u,
+            HANDLER_IDX_I16X8_REPLACE_LANE => i16x8_replace_lane,
+            HANDLER_IDX_I32X4_EXTRACT_LANE => i32x4_extract_lane,
+            HANDLER_IDX_I32X4_REPLACE_LANE => i32x4_replace_lane,
+            HANDLER_IDX_I64X2_EXTRACT_LANE => i64x2_extract_lane,
+            HANDLER_IDX_I64X2_REPLACE_LANE => i64x2_replace_lane,
+            HANDLER_IDX_F32X4_EXTRACT_LANE => f32x4_extract_lane,
+            HANDLER_IDX_F32X4_REPLACE_LANE => f32x4_replace_lane,
+            HANDLER_IDX_F64X2_EXTRACT_LANE => f64x2_extract_lane,
+            HANDLER_IDX_F64X2_REPLACE_LANE => f64x2_replace_lane,
+            HANDLER_IDX_I8X16_EQ => i8x16_eq,
+            HANDLER_IDX_I8X16_NE => i8x16_ne,
+            HANDLER_IDX_I8X16_LT_S => i8x16_lt_s,
+            HANDLER_IDX_I8X16_LT_U => i8x16_lt_u,
+            HANDLER_IDX_I8X16_GT_S => i8x16_gt_s,
+            HANDLER_IDX_I8X16_GT_U => i8x16_gt_u,
+            HANDLER_IDX_I8X16_LE_S => i8x16_le_s,
+            HANDLER_IDX_I8X16_LE_U => i8x16_le_u,
+            HANDLER_IDX_I8X16_GE_S => i8x16_ge_s,
+            HANDLER_IDX_I8X16_GE_U => i8x16_ge_u,
+            HANDLER_IDX_I16X8_EQ => i16x8_eq,
+            HANDLER_IDX_I16X8_NE => i16x8_ne,
+            HANDLER_IDX_I16X8_LT_S => i16x8_lt_s,
+            HANDLER_IDX_I16X8_LT_U => i16x8_lt_u,
+            HANDLER_IDX_I16X8_GT_S => i16x8_gt_s,
+            HANDLER_IDX_I16X8_GT_U => i16x8_gt_u,
+            HANDLER_IDX_I16X8_LE_S => i16x8_le_s,
+            HANDLER_IDX_I16X8_LE_U => i16x8_le_u,
+            HANDLER_IDX_I16X8_GE_S => i16x8_ge_s,
+            HANDLER_IDX_I16X8_GE_U => i16x8_ge_u,
+            HANDLER_IDX_I32X4_EQ => i32x4_eq,
+            HANDLER_IDX_I32X4_NE => i32x4_ne,
+            HANDLER_IDX_I32X4_LT_S => i32x4_lt_s,
+            HANDLER_IDX_I32X4_LT_U => i32x4_lt_u,
+            HANDLER_IDX_I32X4_GT_S => i32x4_gt_s,
+            HANDLER_IDX_I32X4_GT_U => i32x4_gt_u,
+            HANDLER_IDX_I32X4_LE_S => i32x4_le_s,
+            HANDLER_IDX_I32X4_LE_U => i32x4_le_u,
+            HANDLER_IDX_I32X4_GE_S => i32x4_ge_s,
+            HANDLER_IDX_I32X4_GE_U => i32x4_ge_u,
+            HANDLER_IDX_F32X4_EQ => f32x4_eq,
+            HANDLER_IDX_F32X4_NE => f32x4_ne,
+            HANDLER_IDX_F32X4_LT => f32x4_lt,
+            HANDLER_IDX_F32X4_GT => f32x4_gt,
+            HANDLER_IDX_F32X4_LE => f32x4_le,
+            HANDLER_IDX_F32X4_GE => f32x4_ge,
+            HANDLER_IDX_F64X2_EQ => f64x2_eq,
+            HANDLER_IDX_F64X2_NE => f64x2_ne,
+            HANDLER_IDX_F64X2_LT => f64x2_lt,
+            HANDLER_IDX_F64X2_GT => f64x2_gt,
+            HANDLER_IDX_F64X2_LE => f64x2_le,
+            HANDLER_IDX_F64X2_GE => f64x2_ge,
+            HANDLER_IDX_V128_NOT => v128_not,
+            HANDLER_IDX_V128_AND => v128_and,
+            HANDLER_IDX_V128_ANDNOT => v128_andnot,
+            HANDLER_IDX_V128_OR => v128_or,
+            HANDLER_IDX_V128_XOR => v128_xor,
+            HANDLER_IDX_V128_BITSELECT => v128_bitselect,
+            HANDLER_IDX_V128_ANY_TRUE => v128_any_true,
+            HANDLER_IDX_F32X4_DEMOTE_F64X2_ZERO => f32x4_demote_f64x2_zero,
+            HANDLER_IDX_F64X2_PROMOTE_LOW_F32X4 => f64x2_promote_low_f32x4,
+            HANDLER_IDX_I8X16_ABS => i8x16_abs,
+            HANDLER_IDX_I8X16_NEG => i8x16_neg,
+            HANDLER_IDX_I8X16_POPCNT => i8x16_popcnt,
+            HANDLER_IDX_I8X16_ALL_TRUE => i8x16_all_true,
+            HANDLER_IDX_I8X16_BITMASK => i8x16_bitmask,
+            HANDLER_IDX_I8X16_NARROW_I16X8_S => i8x16_narrow_i16x8_s,
+            HANDLER_IDX_I8X16_NARROW_I16X8_U => i8x16_narrow_i16x8_u,
+            HANDLER_IDX_F32X4_CEIL => f32x4_ceil,
+            HANDLER_IDX_F32X4_FLOOR => f32x4_floor,
+            HANDLER_IDX_F32X4_TRUNC => f32x4_trunc,
+            HANDLER_IDX_F32X4_NEAREST => f32x4_nearest,
+            HANDLER_IDX_I8X16_SHL => i8x16_shl,
+            HANDLER_IDX_I8X16_SHR_S => i8x16_shr_s,
+            HANDLER_IDX_I8X16_SHR_U => i8x16_shr_u,
+            HANDLER_IDX_I8X16_ADD => i8x16_add,
+            HANDLER_IDX_I8X16_ADD_SAT_S => i8x16_add_sat_s,
+            HANDLER_IDX_I8X16_ADD_SAT_U => i8x16_add_sat_u,
+            HANDLER_IDX_I8X16_SUB => i8x16_sub,
+            HANDLER_IDX_I8X16_SUB_SAT_S => i8x16_sub_sat_s,
+            HANDLER_IDX_I8X16_SUB_SAT_U => i8x16_sub_sat_u,
+            HANDLER_IDX_F64X2_CEIL => f64x2_ceil,
+            HANDLER_IDX_F64X2_FLOOR => f64x2_floor,
+            HANDLER_IDX_I8X16_MIN_S => i8x16_min_s,
+            HANDLER_IDX_I8X16_MIN_U => i8x16_min_u,
+            HANDLER_IDX_I8X16_MAX_S => i8x16_max_s,
+            HANDLER_IDX_I8X16_MAX_U => i8x16_max_u,
+            HANDLER_IDX_F64X2_TRUNC => f64x2_trunc,
+            HANDLER_IDX_I8X16_AVGR_U => i8x16_avgr_u,
+            HANDLER_IDX_I16X8_EXTADD_PAIRWISE_I8X16_S => i16x8_extadd_pairwise_i8x16_s,
+            HANDLER_IDX_I16X8_EXTADD_PAIRWISE_I8X16_U => i16x8_extadd_pairwise_i8x16_u,
+            HANDLER_IDX_I32X4_EXTADD_PAIRWISE_I16X8_S => i32x4_extadd_pairwise_i16x8_s,
+            HANDLER_IDX_I32X4_EXTADD_PAIRWISE_I16X8_U => i32x4_extadd_pairwise_i16x8_u,
+            HANDLER_IDX_I16X8_ABS => i16x8_abs,
+            HANDLER_IDX_I16X8_NEG => i16x8_neg,
+            HANDLER_IDX_I16X8_Q15MULR_SAT_S => i16x8_q15mulr_sat_s,
+            HANDLER_IDX_I16X8_ALL_TRUE => i16x8_all_true,
+            HANDLER_IDX_I16X8_BITMASK => i16x8_bitmask,
+            HANDLER_IDX_I16X8_NARROW_I32X4_S => i16x8_narrow_i32x4_s,
+            HANDLER_IDX_I16X8_NARROW_I32X4_U => i16x8_narrow_i32x4_u,
+            HANDLER_IDX_I16X8_EXTEND_LOW_I8X16_S => i16x8_extend_low_i8x16_s,
+            HANDLER_IDX_I16X8_EXTEND_HIGH_I8X16_S => i16x8_extend_high_i8x16_s,
+            HANDLER_IDX_I16X8_EXTEND_LOW_I8X16_U => i16x8_extend_low_i8x16_u,
+            HANDLER_IDX_I16X8_EXTEND_HIGH_I8X16_U => i16x8_extend_high_i8x16_u,
+            HANDLER_IDX_I16X8_SHL => i16x8_shl,
+            HANDLER_IDX_I16X8_SHR_S => i16x8_shr_s,
+            HANDLER_IDX_I16X8_SHR_U => i16x8_shr_u,
+            HANDLER_IDX_I16X8_ADD => i16x8_add,
+            HANDLER_IDX_I16X8_ADD_SAT_S => i16x8_add_sat_s,
+            HANDLER_IDX_I16X8_ADD_SAT_U => i16x8_add_sat_u,
+            HANDLER_IDX_I16X8_SUB => i16x8_sub,
+            HANDLER_IDX_I16X8_SUB_SAT_S => i16x8_sub_sat_s,
+            HANDLER_IDX_I16X8_SUB_SAT_U => i16x8_sub_sat_u,
+            HANDLER_IDX_F64X2_NEAREST => f64x2_nearest,
+            HANDLER_IDX_I16X8_MUL => i16x8_mul,
+            HANDLER_IDX_I16X8_MIN_S => i16x8_min_s,
+            HANDLER_IDX_I16X8_MIN_U => i16x8_min_u,
+            HANDLER_IDX_I16X8_MAX_S => i16x8_max_s,
+            HANDLER_IDX_I16X8_MAX_U => i16x8_max_u,
+            HANDLER_IDX_I16X8_AVGR_U => i16x8_avgr_u,
+            HANDLER_IDX_I16X8_EXTMUL_LOW_I8X16_S => i16x8_extmul_low_i8x16_s,
+            HANDLER_IDX_I16X8_EXTMUL_HIGH_I8X16_S => i16x8_extmul_high_i8x16_s,
+            HANDLER_IDX_I16X8_EXTMUL_LOW_I8X16_U => i16x8_extmul_low_i8x16_u,
+            HANDLER_IDX_I16X8_EXTMUL_HIGH_I8X16_U => i16x8_extmul_high_i8x16_u,
+            HANDLER_IDX_I32X4_ABS => i32x4_abs,
+            HANDLER_IDX_I32X4_NEG => i32x4_neg,
+            HANDLER_IDX_I32X4_ALL_TRUE => i32x4_all_true,
+            HANDLER_IDX_I32X4_BITMASK => i32x4_bitmask,
+            HANDLER_IDX_I32X4_EXTEND_LOW_I16X8_S => i32x4_extend_low_i16x8_s,
+            HANDLER_IDX_I32X4_EXTEND_HIGH_I16X8_S => i32x4_extend_high_i16x8_s,
+            HANDLER_IDX_I32X4_EXTEND_LOW_I16X8_U => i32x4_extend_low_i16x8_u,
+            HANDLER_IDX_I32X4_EXTEND_HIGH_I16X8_U => i32x4_extend_high_i16x8_u,
+            HANDLER_IDX_I32X4_SHL => i32x4_shl,
+            HANDLER_IDX_I32X4_SHR_S => i32x4_shr_s,
+            HANDLER_IDX_I32X4_SHR_U => i32x4_shr_u,
+            HANDLER_IDX_I32X4_ADD => i32x4_add,
+            HANDLER_IDX_I32X4_SUB => i32x4_sub,
+            HANDLER_IDX_I32X4_MUL => i32x4_mul,
+            HANDLER_IDX_I32X4_MIN_S => i32x4_min_s,
+            HANDLER_IDX_I32X4_MIN_U => i32x4_min_u,
+            HANDLER_IDX_I32X4_MAX_S => i32x4_max_s,
+            HANDLER_IDX_I32X4_MAX_U => i32x4_max_u,
+            HANDLER_IDX_I32X4_DOT_I16X8_S => i32x4_dot_i16x8_s,
+            HANDLER_IDX_I32X4_EXTMUL_LOW_I16X8_S => i32x4_extmul_low_i16x8_s,
+            HANDLER_IDX_I32X4_EXTMUL_HIGH_I16X8_S => i32x4_extmul_high_i16x8_s,
+            HANDLER_IDX_I32X4_EXTMUL_LOW_I16X8_U => i32x4_extmul_low_i16x8_u,
+            HANDLER_IDX_I32X4_EXTMUL_HIGH_I16X8_U => i32x4_extmul_high_i16x8_u,
+            HANDLER_IDX_I64X2_ABS => i64x2_abs,
+            HANDLER_IDX_I64X2_NEG => i64x2_neg,
+            HANDLER_IDX_I64X2_ALL_TRUE => i64x2_all_true,
+            HANDLER_IDX_I64X2_BITMASK => i64x2_bitmask,
+            HANDLER_IDX_I64X2_EXTEND_LOW_I32X4_S => i64x2_extend_low_i32x4_s,
+            HANDLER_IDX_I64X2_EXTEND_HIGH_I32X4_S => i64x2_extend_high_i32x4_s,
+            HANDLER_IDX_I64X2_EXTEND_LOW_I32X4_U => i64x2_extend_low_i32x4_u,
+            HANDLER_IDX_I64X2_EXTEND_HIGH_I32X4_U => i64x2_extend_high_i32x4_u,
+            HANDLER_IDX_I64X2_SHL => i64x2_shl,
+            HANDLER_IDX_I64X2_SHR_S => i64x2_shr_s,
+            HANDLER_IDX_I64X2_SHR_U => i64x2_shr_u,
+            HANDLER_IDX_I64X2_ADD => i64x2_add,
+            HANDLER_IDX_I64X2_SUB => i64x2_sub,
+            HANDLER_IDX_I64X2_MUL => i64x2_mul,
+            HANDLER_IDX_I64X2_EQ => i64x2_eq,
+            HANDLER_IDX_I64X2_NE => i64x2_ne,
+            HANDLER_IDX_I64X2_LT_S => i64x2_lt_s,
+            HANDLER_IDX_I64X2_GT_S => i64x2_gt_s,
+            HANDLER_IDX_I64X2_LE_S => i64x2_le_s,
+            HANDLER_IDX_I64X2_GE_S => i64x2_ge_s,
+            HANDLER_IDX_I64X2_EXTMUL_LOW_I32X4_S => i64x2_extmul_low_i32x4_s,
+            HANDLER_IDX_I64X2_EXTMUL_HIGH_I32X4_S => i64x2_extmul_high_i32x4_s,
+            HANDLER_IDX_I64X2_EXTMUL_LOW_I32X4_U => i64x2_extmul_low_i32x4_u,
+            HANDLER_IDX_I64X2_EXTMUL_HIGH_I32X4_U => i64x2_extmul_high_i32x4_u,
+            HANDLER_IDX_F32X4_ABS => f32x4_abs,
+            HANDLER_IDX_F32X4_NEG => f32x4_neg,
+            HANDLER_IDX_F32X4_SQRT => f32x4_sqrt,
+            HANDLER_IDX_F32X4_ADD => f32x4_add,
+            HANDLER_IDX_F32X4_SUB => f32x4_sub,
+            HANDLER_IDX_F32X4_MUL => f32x4_mul,
+            HANDLER_IDX_F32X4_DIV => f32x4_div,
+            HANDLER_IDX_F32X4_MIN => f32x4_min,
+            HANDLER_IDX_F32X4_MAX => f32x4_max,
+            HANDLER_IDX_F32X4_PMIN => f32x4_pmin,
+            HANDLER_IDX_F32X4_PMAX => f32x4_pmax,
+            HANDLER_IDX_F64X2_ABS => f64x2_abs,
+            HANDLER_IDX_F64X2_NEG => f64x2_neg,
+            HANDLER_IDX_F64X2_SQRT => f64x2_sqrt,
+            HANDLER_IDX_F64X2_ADD => f64x2_add,
+            HANDLER_IDX_F64X2_SUB => f64x2_sub,
+            HANDLER_IDX_F64X2_MUL => f64x2_mul,
+            HANDLER_IDX_F64X2_DIV => f64x2_div,
+            HANDLER_IDX_F64X2_MIN => f64x2_min,
+            HANDLER_IDX_F64X2_MAX => f64x2_max,
+            HANDLER_IDX_F64X2_PMIN => f64x2_pmin,
+            HANDLER_IDX_F64X2_PMAX => f64x2_pmax,
+            HANDLER_IDX_I32X4_TRUNC_SAT_F32X4_S => i32x4_trunc_sat_f32x4_s,
+            HANDLER_IDX_I32X4_TRUNC_SAT_F32X4_U => i32x4_trunc_sat_f32x4_u,
+            HANDLER_IDX_F32X4_CONVERT_I32X4_S => f32x4_convert_i32x4_s,
+            HANDLER_IDX_F32X4_CONVERT_I32X4_U => f32x4_convert_i32x4_u,
+            HANDLER_IDX_I32X4_TRUNC_SAT_F64X2_S_ZERO => i32x4_trunc_sat_f64x2_s_zero,
+            HANDLER_IDX_I32X4_TRUNC_SAT_F64X2_U_ZERO => i32x4_trunc_sat_f64x2_u_zero,
+            HANDLER_IDX_F64X2_CONVERT_LOW_I32X4_S => f64x2_convert_low_i32x4_s,
+            HANDLER_IDX_F64X2_CONVERT_LOW_I32X4_U => f64x2_convert_low_i32x4_u,
+            _ => invalid,
+        },
+        ProcessedInstr::SimdMemReg { handler_index, .. } => match *handler_index {
+            HANDLER_IDX_V128_LOAD => v128_load,
+            HANDLER_IDX_V128_LOAD8X8_S => v128_load8x8_s,
+            HANDLER_IDX_V128_LOAD8X8_U => v128_load8x8_u,
+            HANDLER_IDX_V128_LOAD16X4_S => v128_load16x4_s,
+            HANDLER_IDX_V128_LOAD16X4_U => v128_load16x4_u,
+            HANDLER_IDX_V128_LOAD32X2_S => v128_load32x2_s,
+            HANDLER_IDX_V128_LOAD32X2_U => v128_load32x2_u,
+            HANDLER_IDX_V128_LOAD8_SPLAT => v128_load8_splat,
+            HANDLER_IDX_V128_LOAD16_SPLAT => v128_load16_splat,
+            HANDLER_IDX_V128_LOAD32_SPLAT => v128_load32_splat,
+            HANDLER_IDX_V128_LOAD64_SPLAT => v128_load64_splat,
+            HANDLER_IDX_V128_STORE => v128_store,
+            HANDLER_IDX_V128_LOAD8_LANE => v128_load8_lane,
+            HANDLER_IDX_V128_LOAD16_LANE => v128_load16_lane,
+            HANDLER_IDX_V128_LOAD32_LANE => v128_load32_lane,
+            HANDLER_IDX_V128_LOAD64_LANE => v128_load64_lane,
+            HANDLER_IDX_V128_STORE8_LANE => v128_store8_lane,
+            HANDLER_IDX_V128_STORE16_LANE => v128_store16_lane,
+            HANDLER_IDX_V128_STORE32_LANE => v128_store32_lane,
+            HANDLER_IDX_V128_STORE64_LANE => v128_store64_lane,
+            HANDLER_IDX_V128_LOAD32_ZERO => v128_load32_zero,
+            HANDLER_IDX_V128_LOAD64_ZERO => v128_load64_zero,
+            _ => invalid,
+        },
         ProcessedInstr::DataDropReg { .. } => data_drop,
+        ProcessedInstr::ElemDropReg { .. } => elem_drop,
         ProcessedInstr::CallReg { .. } => call,
         ProcessedInstr::CallIndirectReg { .. } => call_indirect,
+        ProcessedInstr::ReturnCallReg { .. } => return_call,
+        ProcessedInstr::ReturnCallIndirectReg { .. } => return_call_indirect,
         ProcessedInstr::CallWasiReg { .. } => call_wasi,
         ProcessedInstr::ReturnReg { .. } => r#return,
         ProcessedInstr::JumpReg { .. } => jump,
         ProcessedInstr::BlockReg { .. } => block,
         ProcessedInstr::IfReg { .. } => r#if,
+        ProcessedInstr::TryTableReg { .. } => try_table,
+        ProcessedInstr::ThrowReg { .. } => throw,
+        ProcessedInstr::ThrowRefReg { .. } => throw_ref,
         ProcessedInstr::EndReg { .. } => end,
         ProcessedInstr::BrReg { .. } => br,
         ProcessedInstr::BrIfReg { .. } => br_if,
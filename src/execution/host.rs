@@ -0,0 +1,217 @@
+//! Host functions: typed Rust closures callable from WebAssembly.
+//!
+//! Embedders register host functions on a [`Linker`](super::linker::Linker)
+//! with `func_wrap` (typed closures such as `Fn(i32, i64) -> f64`) or
+//! `func_new` (untyped `Vec<Val>` closures with an explicit `FuncType`).
+//! The function type is derived from the closure's signature and checked
+//! against the importing module's `FuncType` at instantiation.
+
+use super::func::HostCode;
+use super::global::GlobalAddr;
+use super::mem::MemAddr;
+use super::migration::CheckpointRequest;
+use super::module::ModuleInst;
+use super::value::{Externval, Num, Val};
+use crate::error::RuntimeError;
+use crate::structure::types::{FuncType, NumType, ValueType};
+use std::rc::Rc;
+
+/// Context passed to a host function, giving access to the instance whose
+/// code made the call.
+pub struct Caller {
+    instance: Rc<ModuleInst>,
+    checkpoint_request: CheckpointRequest,
+}
+
+impl Caller {
+    /// Creates a caller context for `instance`, run by the runtime owning
+    /// `checkpoint_request`.
+    pub fn new(instance: Rc<ModuleInst>, checkpoint_request: CheckpointRequest) -> Caller {
+        Caller {
+            instance,
+            checkpoint_request,
+        }
+    }
+
+    /// Returns the calling instance.
+    pub fn instance(&self) -> &Rc<ModuleInst> {
+        &self.instance
+    }
+
+    /// Returns the caller's memory at `idx` (including imported memories).
+    pub fn memory(&self, idx: u32) -> Option<&MemAddr> {
+        self.instance.mem_addrs.get(idx as usize)
+    }
+
+    /// Returns the caller's global at `idx` (including imported globals).
+    pub fn global(&self, idx: u32) -> Option<&GlobalAddr> {
+        self.instance.global_addrs.get(idx as usize)
+    }
+
+    /// Requests an in-process checkpoint of the calling runtime. If it has
+    /// checkpointing enabled, it pauses with `RunStatus::CheckpointRequested`
+    /// once this call returns.
+    pub fn request_checkpoint(&self) {
+        self.checkpoint_request.request();
+    }
+
+    /// Looks up an export of the caller by name.
+    pub fn get_export(&self, name: &str) -> Option<Externval> {
+        self.instance
+            .exports
+            .iter()
+            .find(|export| export.name == name)
+            .map(|export| export.value.clone())
+    }
+}
+
+/// Rust types that map to a WebAssembly value type.
+pub trait WasmTy: Sized {
+    /// The WebAssembly type of this Rust type.
+    fn value_type() -> ValueType;
+    /// Converts a runtime value, failing with `TypeMismatch`.
+    fn from_val(val: &Val) -> Result<Self, RuntimeError>;
+    /// Converts into a runtime value.
+    fn into_val(self) -> Val;
+}
+
+macro_rules! impl_wasm_ty {
+    ($($ty:ty => $num:ident, $to:ident;)*) => {
+        $(
+            impl WasmTy for $ty {
+                fn value_type() -> ValueType {
+                    ValueType::NumType(NumType::$num)
+                }
+                fn from_val(val: &Val) -> Result<Self, RuntimeError> {
+                    val.$to()
+                }
+                fn into_val(self) -> Val {
+                    Val::Num(Num::$num(self))
+                }
+            }
+        )*
+    };
+}
+
+impl_wasm_ty! {
+    i32 => I32, to_i32;
+    i64 => I64, to_i64;
+    f32 => F32, to_f32;
+    f64 => F64, to_f64;
+}
+
+/// Return types of typed host functions: `()`, a single `WasmTy`, a tuple
+/// of them for multiple results, or a `Result` of any of these to trap.
+pub trait WasmResults {
+    /// The WebAssembly result types.
+    fn value_types() -> Vec<ValueType>;
+    /// Converts into runtime values.
+    fn into_vals(self) -> Result<Vec<Val>, RuntimeError>;
+}
+
+impl<T: WasmTy> WasmResults for T {
+    fn value_types() -> Vec<ValueType> {
+        vec![T::value_type()]
+    }
+    fn into_vals(self) -> Result<Vec<Val>, RuntimeError> {
+        Ok(vec![self.into_val()])
+    }
+}
+
+impl<T: WasmResults> WasmResults for Result<T, RuntimeError> {
+    fn value_types() -> Vec<ValueType> {
+        T::value_types()
+    }
+    fn into_vals(self) -> Result<Vec<Val>, RuntimeError> {
+        self?.into_vals()
+    }
+}
+
+macro_rules! impl_wasm_results {
+    ($($t:ident $v:ident),*) => {
+        impl<$($t: WasmTy),*> WasmResults for ($($t,)*) {
+            fn value_types() -> Vec<ValueType> {
+                vec![$($t::value_type()),*]
+            }
+            fn into_vals(self) -> Result<Vec<Val>, RuntimeError> {
+                let ($($v,)*) = self;
+                Ok(vec![$($v.into_val()),*])
+            }
+        }
+    };
+}
+
+impl_wasm_results!();
+impl_wasm_results!(A a, B b);
+impl_wasm_results!(A a, B b, C c);
+impl_wasm_results!(A a, B b, C c, D d);
+
+/// Closures that can be registered as host functions.
+///
+/// Implemented for `Fn(A1, .., An) -> R` and `Fn(&Caller, A1, .., An) -> R`
+/// where every `Ai` is a `WasmTy` and `R` is `WasmResults`. `Params` only
+/// tells the two forms apart and is inferred.
+pub trait IntoHostFunc<Params, Results> {
+    /// Returns the function type and the type-erased host code.
+    fn into_host_func(self) -> (FuncType, HostCode);
+}
+
+fn next_param<T: WasmTy>(params: &mut std::slice::Iter<'_, Val>) -> Result<T, RuntimeError> {
+    T::from_val(params.next().ok_or(RuntimeError::TypeMismatch)?)
+}
+
+macro_rules! impl_into_host_func {
+    ($($t:ident $v:ident),*) => {
+        impl<F, $($t,)* R> IntoHostFunc<($($t,)*), R> for F
+        where
+            F: Fn($($t),*) -> R + 'static,
+            $($t: WasmTy,)*
+            R: WasmResults,
+        {
+            fn into_host_func(self) -> (FuncType, HostCode) {
+                let type_ = FuncType {
+                    params: vec![$($t::value_type()),*],
+                    results: R::value_types(),
+                };
+                let code: HostCode = Rc::new(move |_caller: &Caller, params: Vec<Val>| {
+                    #[allow(unused_mut, unused_variables)]
+                    let mut params = params.iter();
+                    $(let $v: $t = next_param(&mut params)?;)*
+                    self($($v),*).into_vals()
+                });
+                (type_, code)
+            }
+        }
+
+        impl<F, $($t,)* R> IntoHostFunc<(Caller, $($t,)*), R> for F
+        where
+            F: Fn(&Caller, $($t),*) -> R + 'static,
+            $($t: WasmTy,)*
+            R: WasmResults,
+        {
+            fn into_host_func(self) -> (FuncType, HostCode) {
+                let type_ = FuncType {
+                    params: vec![$($t::value_type()),*],
+                    results: R::value_types(),
+                };
+                let code: HostCode = Rc::new(move |caller: &Caller, params: Vec<Val>| {
+                    #[allow(unused_mut, unused_variables)]
+                    let mut params = params.iter();
+                    $(let $v: $t = next_param(&mut params)?;)*
+                    self(caller, $($v),*).into_vals()
+                });
+                (type_, code)
+            }
+        }
+    };
+}
+
+impl_into_host_func!();
+impl_into_host_func!(A1 a1);
+impl_into_host_func!(A1 a1, A2 a2);
+impl_into_host_func!(A1 a1, A2 a2, A3 a3);
+impl_into_host_func!(A1 a1, A2 a2, A3 a3, A4 a4);
+impl_into_host_func!(A1 a1, A2 a2, A3 a3, A4 a4, A5 a5);
+impl_into_host_func!(A1 a1, A2 a2, A3 a3, A4 a4, A5 a5, A6 a6);
+impl_into_host_func!(A1 a1, A2 a2, A3 a3, A4 a4, A5 a5, A6 a6, A7 a7);
+impl_into_host_func!(A1 a1, A2 a2, A3 a3, A4 a4, A5 a5, A6 a6, A7 a7, A8 a8);
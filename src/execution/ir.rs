@@ -6,16 +6,24 @@
 
 use crate::execution::handlers::{
     HANDLER_IDX_BLOCK, HANDLER_IDX_BR, HANDLER_IDX_BR_IF, HANDLER_IDX_BR_TABLE, HANDLER_IDX_CALL,
-    HANDLER_IDX_CALL_INDIRECT, HANDLER_IDX_CALL_WASI, HANDLER_IDX_DATA_DROP, HANDLER_IDX_ELSE,
-    HANDLER_IDX_END, HANDLER_IDX_IF, HANDLER_IDX_LOOP, HANDLER_IDX_NOP, HANDLER_IDX_RETURN,
-    HANDLER_IDX_UNREACHABLE,
+    HANDLER_IDX_CALL_INDIRECT, HANDLER_IDX_CALL_WASI, HANDLER_IDX_DATA_DROP, HANDLER_IDX_ELEM_DROP,
+    HANDLER_IDX_ELSE, HANDLER_IDX_END, HANDLER_IDX_IF, HANDLER_IDX_LOOP, HANDLER_IDX_NOP,
+    HANDLER_IDX_RETURN, HANDLER_IDX_RETURN_CALL, HANDLER_IDX_RETURN_CALL_INDIRECT,
+    HANDLER_IDX_THROW, HANDLER_IDX_THROW_REF, HANDLER_IDX_TRY_TABLE, HANDLER_IDX_UNREACHABLE,
 };
 use crate::execution::regs::Reg;
 use crate::execution::state::VmState;
 use crate::structure::module::WasiFuncType;
-use crate::structure::types::{FuncIdx, RefType, TableIdx, TypeIdx};
+use crate::structure::types::{FuncIdx, RefType, TableIdx, TagIdx, TypeIdx};
 use serde::{Deserialize, Serialize};
 
+/// Version of the `ProcessedInstr` stream layout produced by the parser.
+///
+/// Checkpoints store instruction pointers into this stream, so bump this
+/// whenever a parser change can shift instruction indices or change the
+/// encoding; restoring a checkpoint taken with another version is refused.
+pub const IR_LAYOUT_VERSION: u32 = 1;
+
 /// Type alias for boxed register slice (ProcessedInstr use).
 /// 16 bytes vs Vec's 24 bytes, no capacity overhead.
 pub type RegSlice = Box<[Reg]>;
@@ -102,6 +110,38 @@ pub enum F64RegOperand {
     Param(u16),
 }
 
+/// Address operand of a memory access: `I32` for 32-bit memories, `I64`
+/// for 64-bit memories.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum AddrOperand {
+    I32(I32RegOperand),
+    I64(I64RegOperand),
+}
+
+/// Kind of a `try_table` catch clause.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum CatchKind {
+    /// `catch`: matches the tag and delivers the payload.
+    Tag(TagIdx),
+    /// `catch_ref`: matches the tag and delivers the payload and exnref.
+    TagRef(TagIdx),
+    /// `catch_all`: matches any exception and delivers nothing.
+    All,
+    /// `catch_all_ref`: matches any exception and delivers the exnref.
+    AllRef,
+}
+
+/// `try_table` catch clause with its branch target resolved at parse time.
+///
+/// `label` is relative to the block enclosing the `try_table`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CatchClause {
+    pub kind: CatchKind,
+    pub label: u32,
+    pub target_ip: usize,
+    pub target_result_regs: RegSlice,
+}
+
 /// Processed instruction for DTC execution with pre-resolved operands.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum ProcessedInstr {
@@ -137,20 +177,26 @@ pub enum ProcessedInstr {
     MemoryLoadReg {
         handler_index: usize,
         dst: RegOrLocal,
-        addr: I32RegOperand,
+        addr: AddrOperand,
         offset: u64,
+        memory: u32,
     },
     MemoryStoreReg {
         handler_index: usize,
-        addr: I32RegOperand,
+        addr: AddrOperand,
         value: Reg,
         offset: u64,
+        memory: u32,
     },
+    /// `memory` is the memory operated on (the destination of `memory.copy`);
+    /// `src_memory` is the source of `memory.copy` and unused otherwise.
     MemoryOpsReg {
         handler_index: usize,
         dst: Option<Reg>,
         args: RegSlice,
         data_index: u32,
+        memory: u32,
+        src_memory: u32,
     },
     SelectReg {
         handler_index: usize,
@@ -172,6 +218,9 @@ pub enum ProcessedInstr {
     DataDropReg {
         data_index: u32,
     },
+    ElemDropReg {
+        elem_index: u32,
+    },
     RefLocalReg {
         handler_index: usize,
         dst: u16,
@@ -184,6 +233,16 @@ pub enum ProcessedInstr {
         regs: [u16; 3],
         ref_type: RefType,
     },
+    /// `table.size` / `table.grow` / `table.copy` / `table.init`.
+    /// `table_idx` is the table operated on (the destination of `table.copy`);
+    /// `src_idx` is the source table of `table.copy` or the element segment of
+    /// `table.init`, and unused otherwise.
+    TableOpsReg {
+        handler_index: usize,
+        table_idx: u32,
+        src_idx: u32,
+        regs: [u16; 3],
+    },
     CallWasiReg {
         wasi_func_type: WasiFuncType,
         param_regs: RegSlice,
@@ -204,6 +263,16 @@ pub enum ProcessedInstr {
     ReturnReg {
         result_regs: RegSlice,
     },
+    ReturnCallReg {
+        func_idx: FuncIdx,
+        param_regs: RegSlice,
+    },
+    ReturnCallIndirectReg {
+        type_idx: TypeIdx,
+        table_idx: TableIdx,
+        index_reg: Reg,
+        param_regs: RegSlice,
+    },
     JumpReg {
         target_ip: usize,
     },
@@ -212,6 +281,16 @@ pub enum ProcessedInstr {
         param_count: usize,
         is_loop: bool,
     },
+    TryTableReg {
+        catches: Box<[CatchClause]>,
+    },
+    ThrowReg {
+        tag_idx: TagIdx,
+        param_regs: RegSlice,
+    },
+    ThrowRefReg {
+        exn_reg: Reg,
+    },
     IfReg {
         arity: usize,
         cond_reg: Reg,
@@ -241,6 +320,27 @@ pub enum ProcessedInstr {
         index_reg: Reg,
         source_regs: RegSlice,
     },
+    /// Fixed-width SIMD instruction. `srcs` holds register indices in Wasm
+    /// operand order; each handler knows which register class (v128 or a
+    /// scalar) every slot refers to. `imm` carries the `v128.const` value,
+    /// the `i8x16.shuffle` lane indices, or the lane index in `imm[0]`.
+    SimdReg {
+        handler_index: usize,
+        dst: u16,
+        srcs: [u16; 3],
+        imm: [u8; 16],
+    },
+    /// SIMD load/store. `value` is the v128 operand of stores and of the
+    /// `*_lane` loads, `lane` the lane index of the `*_lane` variants.
+    SimdMemReg {
+        handler_index: usize,
+        dst: u16,
+        addr: AddrOperand,
+        value: u16,
+        offset: u64,
+        lane: u8,
+        memory: u32,
+    },
     NopReg,
     UnreachableReg,
 }
@@ -261,20 +361,29 @@ impl ProcessedInstr {
             ProcessedInstr::GlobalGetReg { handler_index, .. } => *handler_index,
             ProcessedInstr::GlobalSetReg { handler_index, .. } => *handler_index,
             ProcessedInstr::DataDropReg { .. } => HANDLER_IDX_DATA_DROP,
+            ProcessedInstr::ElemDropReg { .. } => HANDLER_IDX_ELEM_DROP,
             ProcessedInstr::RefLocalReg { handler_index, .. } => *handler_index,
             ProcessedInstr::TableRefReg { handler_index, .. } => *handler_index,
+            ProcessedInstr::TableOpsReg { handler_index, .. } => *handler_index,
             ProcessedInstr::CallWasiReg { .. } => HANDLER_IDX_CALL_WASI,
             ProcessedInstr::CallIndirectReg { .. } => HANDLER_IDX_CALL_INDIRECT,
             ProcessedInstr::CallReg { .. } => HANDLER_IDX_CALL,
             ProcessedInstr::ReturnReg { .. } => HANDLER_IDX_RETURN,
+            ProcessedInstr::ReturnCallReg { .. } => HANDLER_IDX_RETURN_CALL,
+            ProcessedInstr::ReturnCallIndirectReg { .. } => HANDLER_IDX_RETURN_CALL_INDIRECT,
             ProcessedInstr::JumpReg { .. } => HANDLER_IDX_ELSE,
             ProcessedInstr::BlockReg { is_loop: false, .. } => HANDLER_IDX_BLOCK,
             ProcessedInstr::BlockReg { is_loop: true, .. } => HANDLER_IDX_LOOP,
+            ProcessedInstr::TryTableReg { .. } => HANDLER_IDX_TRY_TABLE,
+            ProcessedInstr::ThrowReg { .. } => HANDLER_IDX_THROW,
+            ProcessedInstr::ThrowRefReg { .. } => HANDLER_IDX_THROW_REF,
             ProcessedInstr::IfReg { .. } => HANDLER_IDX_IF,
             ProcessedInstr::EndReg { .. } => HANDLER_IDX_END,
             ProcessedInstr::BrReg { .. } => HANDLER_IDX_BR,
             ProcessedInstr::BrIfReg { .. } => HANDLER_IDX_BR_IF,
             ProcessedInstr::BrTableReg { .. } => HANDLER_IDX_BR_TABLE,
+            ProcessedInstr::SimdReg { handler_index, .. } => *handler_index,
+            ProcessedInstr::SimdMemReg { handler_index, .. } => *handler_index,
             ProcessedInstr::NopReg => HANDLER_IDX_NOP,
             ProcessedInstr::UnreachableReg => HANDLER_IDX_UNREACHABLE,
         }
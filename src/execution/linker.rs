@@ -0,0 +1,138 @@
+//! Linker for instantiating several modules that import each other's exports.
+
+use super::func::FuncAddr;
+use super::host::{Caller, IntoHostFunc};
+use super::module::{ImportObjects, ModuleInst};
+use super::value::{Externval, Val};
+use crate::error::RuntimeError;
+use crate::structure::module::Module;
+use crate::structure::types::FuncType;
+use std::rc::Rc;
+
+/// Registry of named instances whose exports satisfy later imports.
+///
+/// Modules are instantiated in dependency order: each instance's exports are
+/// registered under its name, so a module instantiated later can import them
+/// as `(import "<name>" "<export>" ...)`.
+///
+/// The linker keeps every instance alive. Functions store only a `Weak`
+/// reference to their instance, so it must outlive any runtime that calls
+/// into the linked instances.
+#[derive(Default)]
+pub struct Linker {
+    imports: ImportObjects,
+    instances: Vec<(String, Rc<ModuleInst>)>,
+}
+
+impl Linker {
+    /// Creates an empty linker.
+    pub fn new() -> Linker {
+        Linker::default()
+    }
+
+    /// Defines a single external value (e.g. a host function) as
+    /// `module`.`name`.
+    pub fn define(&mut self, module: &str, name: &str, value: Externval) {
+        self.imports
+            .entry(module.to_string())
+            .or_default()
+            .insert(name.to_string(), value);
+    }
+
+    /// Defines a host function from a typed closure as `module`.`name`.
+    ///
+    /// Parameters and results are Rust numeric types (`i32`, `i64`, `f32`,
+    /// `f64`); a tuple return gives multiple results and returning
+    /// `Result<_, RuntimeError>` traps on `Err`. Taking `&Caller` as the
+    /// first argument gives access to the calling instance's memories and
+    /// globals. Instantiation fails with `LinkError` if the derived type does
+    /// not match the import's `FuncType`.
+    ///
+    /// ```ignore
+    /// linker
+    ///     .func_wrap("env", "mul", |a: i32, b: i64| a as f64 * b as f64)
+    ///     .func_wrap("env", "peek", |caller: &Caller, ptr: i32| {
+    ///         caller.memory(0).unwrap().load::<i32>(0, ptr as u64)
+    ///     });
+    /// ```
+    pub fn func_wrap<Params, Results>(
+        &mut self,
+        module: &str,
+        name: &str,
+        func: impl IntoHostFunc<Params, Results>,
+    ) -> &mut Self {
+        self.define(module, name, Externval::Func(FuncAddr::wrap(func)));
+        self
+    }
+
+    /// Defines a host function of type `type_` from an untyped closure as
+    /// `module`.`name`.
+    pub fn func_new<F>(&mut self, module: &str, name: &str, type_: FuncType, func: F) -> &mut Self
+    where
+        F: Fn(&Caller, Vec<Val>) -> Result<Vec<Val>, RuntimeError> + 'static,
+    {
+        self.define(
+            module,
+            name,
+            Externval::Func(FuncAddr::alloc_host(type_, func)),
+        );
+        self
+    }
+
+    /// Registers the exports of an existing instance under `name`.
+    pub fn register(&mut self, name: &str, inst: Rc<ModuleInst>) {
+        let exports = self.imports.entry(name.to_string()).or_default();
+        for export in &inst.exports {
+            exports.insert(export.name.clone(), export.value.clone());
+        }
+        self.instances.push((name.to_string(), inst));
+    }
+
+    /// Instantiates `module` against the values defined so far, runs its
+    /// start function and registers its exports under `name`.
+    pub fn instantiate(
+        &mut self,
+        name: &str,
+        module: &Module,
+        argv: Vec<String>,
+    ) -> Result<Rc<ModuleInst>, RuntimeError> {
+        let inst = ModuleInst::new_without_start(module, self.imports(), argv)?;
+        inst.run_start(None, &self.instances())?;
+        self.register(name, Rc::clone(&inst));
+        Ok(inst)
+    }
+
+    /// Like `instantiate`, but does not run the start function (used when
+    /// restoring from a checkpoint).
+    pub fn instantiate_without_start(
+        &mut self,
+        name: &str,
+        module: &Module,
+        argv: Vec<String>,
+    ) -> Result<Rc<ModuleInst>, RuntimeError> {
+        let inst = ModuleInst::new_without_start(module, self.imports(), argv)?;
+        self.register(name, Rc::clone(&inst));
+        Ok(inst)
+    }
+
+    /// Returns the import objects for the next module to instantiate.
+    pub fn imports(&self) -> ImportObjects {
+        self.imports.clone()
+    }
+
+    /// Returns the registered instances in instantiation order.
+    pub fn instances(&self) -> Vec<Rc<ModuleInst>> {
+        self.instances
+            .iter()
+            .map(|(_, inst)| Rc::clone(inst))
+            .collect()
+    }
+
+    /// Looks up a registered instance by name.
+    pub fn get(&self, name: &str) -> Option<&Rc<ModuleInst>> {
+        self.instances
+            .iter()
+            .find(|(inst_name, _)| inst_name == name)
+            .map(|(_, inst)| inst)
+    }
+}
@@ -1,5 +1,6 @@
 //! Linear memory instances and load/store operations.
 
+use crate::error::RuntimeError;
 use crate::structure::types::*;
 use serde::{Deserialize, Serialize};
 use std::cell::UnsafeCell;
@@ -20,54 +21,112 @@ pub struct MemInst {
     pub data: Vec<u8>,
 }
 
+/// Size of a memory page in bytes.
+const PAGE_SIZE: u64 = 65536;
+
 impl MemAddr {
     /// Creates a new memory instance with initial size from type.
-    pub fn new(type_: &MemType) -> MemAddr {
-        let min = (type_.0.min * 65536) as usize;
+    ///
+    /// Fails with `MemoryAllocationFailed` if the initial size does not fit
+    /// in the host's address space or cannot be allocated.
+    pub fn new(type_: &MemType) -> Result<MemAddr, RuntimeError> {
+        let min = type_
+            .0
+            .min
+            .checked_mul(PAGE_SIZE)
+            .and_then(|min| usize::try_from(min).ok())
+            .ok_or(RuntimeError::MemoryAllocationFailed)?;
         let max = type_.0.max.map(|max| max);
-        MemAddr {
+        let mut data = Vec::new();
+        data.try_reserve_exact(min)
+            .map_err(|_| RuntimeError::MemoryAllocationFailed)?;
+        data.resize(min, 0);
+        Ok(MemAddr {
             mem_inst: Rc::new(UnsafeCell::new(MemInst {
-                _type_: MemType(Limits {
-                    min: min as u32,
-                    max,
-                }),
-                data: {
-                    let mut vec = Vec::with_capacity(min);
-                    vec.resize(min, 0);
-                    vec
-                },
+                _type_: MemType(
+                    Limits {
+                        min: min as u64,
+                        max,
+                    },
+                    type_.1,
+                ),
+                data,
             })),
+        })
+    }
+
+    /// Returns the start of `[base + offset, base + offset + len)` if the
+    /// range lies within memory of `mem_len` bytes.
+    #[inline(always)]
+    pub fn check_range(mem_len: usize, base: u64, offset: u64, len: u64) -> Option<usize> {
+        let start = base.checked_add(offset)?;
+        let end = start.checked_add(len)?;
+        if end <= mem_len as u64 {
+            Some(start as usize)
+        } else {
+            None
         }
     }
 
+    /// Returns true if both handles refer to the same memory instance.
+    pub fn ptr_eq(&self, other: &MemAddr) -> bool {
+        Rc::ptr_eq(&self.mem_inst, &other.mem_inst)
+    }
+
+    /// Returns the address type of this memory.
+    #[inline]
+    pub fn addr_type(&self) -> AddrType {
+        // Safety: Single-threaded access
+        let mem = unsafe { &*self.mem_inst.get() };
+        mem._type_.1
+    }
+
+    /// Returns the type of this memory, with the current size in pages as
+    /// the minimum (used to match memory imports).
+    pub fn mem_type(&self) -> MemType {
+        // Safety: Single-threaded access
+        let mem = unsafe { &*self.mem_inst.get() };
+        MemType(
+            Limits {
+                min: self.mem_size(),
+                max: mem._type_.0.max,
+            },
+            mem._type_.1,
+        )
+    }
+
     /// Initializes memory region from data segment.
     #[inline]
-    pub fn init(&self, offset: usize, init: &[u8]) {
+    pub fn init(&self, offset: u64, init: &[u8]) -> Result<(), RuntimeError> {
         // Safety: Single-threaded access, no overlapping borrows
         let mem = unsafe { &mut *self.mem_inst.get() };
-        mem.data[offset..offset + init.len()].copy_from_slice(init);
+        let pos = Self::check_range(mem.data.len(), offset, 0, init.len() as u64)
+            .ok_or(RuntimeError::MemoryOutOfBounds)?;
+        mem.data[pos..pos + init.len()].copy_from_slice(init);
+        Ok(())
     }
 
     /// Loads a typed value from memory at ptr + offset.
-    /// No bounds checking - relies on host runtime for memory safety.
     /// No heap allocation - reads directly from memory pointer.
     #[inline(always)]
-    pub fn load<T: ByteMem>(&self, offset: u64, ptr: i32) -> T {
-        let pos = (ptr as usize) + (offset as usize);
+    pub fn load<T: ByteMem>(&self, offset: u64, ptr: u64) -> Result<T, RuntimeError> {
         // Safety: Single-threaded access, no overlapping borrows
         let mem = unsafe { &*self.mem_inst.get() };
-        unsafe { T::read_from_ptr(mem.data.as_ptr().add(pos)) }
+        let pos = Self::check_range(mem.data.len(), ptr, offset, std::mem::size_of::<T>() as u64)
+            .ok_or(RuntimeError::MemoryOutOfBounds)?;
+        Ok(unsafe { T::read_from_ptr(mem.data.as_ptr().add(pos)) })
     }
 
     /// Stores a typed value to memory at ptr + offset.
-    /// No bounds checking - relies on host runtime for memory safety.
     /// No heap allocation - writes directly to memory pointer.
     #[inline(always)]
-    pub fn store<T: ByteMem>(&self, offset: u64, ptr: i32, data: T) {
-        let pos = (ptr as usize) + (offset as usize);
+    pub fn store<T: ByteMem>(&self, offset: u64, ptr: u64, data: T) -> Result<(), RuntimeError> {
         // Safety: Single-threaded access, no overlapping borrows
         let mem = unsafe { &mut *self.mem_inst.get() };
-        unsafe { data.write_to_ptr(mem.data.as_mut_ptr().add(pos)) }
+        let pos = Self::check_range(mem.data.len(), ptr, offset, std::mem::size_of::<T>() as u64)
+            .ok_or(RuntimeError::MemoryOutOfBounds)?;
+        unsafe { data.write_to_ptr(mem.data.as_mut_ptr().add(pos)) };
+        Ok(())
     }
 
     /// Returns raw mutable pointer to memory data for caching.
@@ -81,35 +140,32 @@ impl MemAddr {
 
     /// Returns current memory size in pages (64KB each).
     #[inline]
-    pub fn mem_size(&self) -> i32 {
+    pub fn mem_size(&self) -> u64 {
         // Safety: Single-threaded access
         let mem = unsafe { &*self.mem_inst.get() };
-        (mem.data.len() / 65536) as i32
+        mem.data.len() as u64 / PAGE_SIZE
     }
 
-    /// Grows memory by the given number of pages. Returns previous size or -1 on failure.
-    pub fn mem_grow(&self, size: i32) -> i32 {
+    /// Grows memory by the given number of pages. Returns the previous size,
+    /// or None if the new size exceeds the limits or cannot be allocated.
+    pub fn mem_grow(&self, delta: u64) -> Option<u64> {
         let prev_size = self.mem_size();
-        let new = prev_size + size;
+        let new = prev_size.checked_add(delta)?;
 
-        // Safety: Single-threaded access
-        let mem = unsafe { &*self.mem_inst.get() };
-        let max_pages = mem._type_.0.max;
-
-        if let Some(max) = max_pages {
-            if new > max as i32 {
-                return -1;
-            }
+        // Safety: Single-threaded access, no overlapping borrows
+        let mem = unsafe { &mut *self.mem_inst.get() };
+        let max_pages = match mem._type_.1 {
+            AddrType::I32 => 1 << 16,
+            AddrType::I64 => 1 << 48,
+        };
+        if new > mem._type_.0.max.unwrap_or(max_pages).min(max_pages) {
+            return None;
         }
 
-        if new > 65536 {
-            -1
-        } else {
-            // Safety: Single-threaded access, no overlapping borrows
-            let mem = unsafe { &mut *self.mem_inst.get() };
-            mem.data.resize(new as usize * 65536, 0);
-            prev_size
-        }
+        let new_len = usize::try_from(new.checked_mul(PAGE_SIZE)?).ok()?;
+        mem.data.try_reserve_exact(new_len - mem.data.len()).ok()?;
+        mem.data.resize(new_len, 0);
+        Some(prev_size)
     }
 
     /// Returns a copy of all memory contents.
@@ -121,12 +177,12 @@ impl MemAddr {
     }
 
     /// Store multiple bytes at once (bulk operation)
-    /// No bounds checking - relies on host runtime for memory safety.
     #[inline]
-    pub fn store_bytes(&self, ptr: i32, data: &[u8]) {
-        let pos = ptr as usize;
+    pub fn store_bytes(&self, ptr: u64, data: &[u8]) -> Result<(), RuntimeError> {
         // Safety: Single-threaded access, no overlapping borrows
         let mem = unsafe { &mut *self.mem_inst.get() };
+        let pos = Self::check_range(mem.data.len(), ptr, 0, data.len() as u64)
+            .ok_or(RuntimeError::MemoryOutOfBounds)?;
 
         unsafe {
             std::ptr::copy_nonoverlapping(
@@ -135,6 +191,7 @@ impl MemAddr {
                 data.len(),
             );
         }
+        Ok(())
     }
 
     /// Replaces all memory contents (used during restore).
@@ -146,34 +203,63 @@ impl MemAddr {
     }
 
     /// Copies len bytes from src to dest within memory.
-    /// No bounds checking - relies on host runtime for memory safety.
+    /// Traps if either range exceeds the memory, even when len is zero.
     #[inline]
-    pub fn memory_copy(&self, dest: i32, src: i32, len: i32) {
-        let dest_pos = dest as usize;
-        let src_pos = src as usize;
-        let len_usize = len as usize;
+    pub fn memory_copy(&self, dest: u64, src: u64, len: u64) -> Result<(), RuntimeError> {
         // Safety: Single-threaded access, no overlapping borrows
         let mem = unsafe { &mut *self.mem_inst.get() };
+        let mem_len = mem.data.len();
+        let dest_pos =
+            Self::check_range(mem_len, dest, 0, len).ok_or(RuntimeError::MemoryOutOfBounds)?;
+        let src_pos =
+            Self::check_range(mem_len, src, 0, len).ok_or(RuntimeError::MemoryOutOfBounds)?;
 
         unsafe {
             let src_ptr = mem.data.as_ptr().add(src_pos);
             let dest_ptr = mem.data.as_mut_ptr().add(dest_pos);
-            std::ptr::copy(src_ptr, dest_ptr, len_usize);
+            std::ptr::copy(src_ptr, dest_ptr, len as usize);
         }
+        Ok(())
+    }
+
+    /// Copies len bytes from src in `src_mem` to dest within this memory.
+    /// `src_mem` must be a different memory instance; use `memory_copy` for
+    /// copies within one memory.
+    /// Traps if either range exceeds its memory, even when len is zero.
+    #[inline]
+    pub fn copy_from(
+        &self,
+        dest: u64,
+        src_mem: &MemAddr,
+        src: u64,
+        len: u64,
+    ) -> Result<(), RuntimeError> {
+        // Safety: Single-threaded access; the two instances are distinct
+        let dest_data = unsafe { &mut (*self.mem_inst.get()).data };
+        let src_data = unsafe { &(*src_mem.mem_inst.get()).data };
+        let dest_pos = Self::check_range(dest_data.len(), dest, 0, len)
+            .ok_or(RuntimeError::MemoryOutOfBounds)?;
+        let src_pos = Self::check_range(src_data.len(), src, 0, len)
+            .ok_or(RuntimeError::MemoryOutOfBounds)?;
+
+        dest_data[dest_pos..dest_pos + len as usize]
+            .copy_from_slice(&src_data[src_pos..src_pos + len as usize]);
+        Ok(())
     }
 
     /// Fills len bytes starting at dest with val.
-    /// No bounds checking - relies on host runtime for memory safety.
+    /// Traps if the range exceeds the memory, even when len is zero.
     #[inline]
-    pub fn memory_fill(&self, dest: i32, val: u8, len: i32) {
-        let dest_pos = dest as usize;
-        let len_usize = len as usize;
+    pub fn memory_fill(&self, dest: u64, val: u8, len: u64) -> Result<(), RuntimeError> {
         // Safety: Single-threaded access, no overlapping borrows
         let mem = unsafe { &mut *self.mem_inst.get() };
+        let dest_pos = Self::check_range(mem.data.len(), dest, 0, len)
+            .ok_or(RuntimeError::MemoryOutOfBounds)?;
 
         unsafe {
-            std::ptr::write_bytes(mem.data.as_mut_ptr().add(dest_pos), val, len_usize);
+            std::ptr::write_bytes(mem.data.as_mut_ptr().add(dest_pos), val, len as usize);
         }
+        Ok(())
     }
 
     /// Returns a raw pointer to the memory data for direct access.
@@ -5,17 +5,38 @@
 //!
 //! ## Serializable State
 //!
+//! Every checkpoint starts with `CHECKPOINT_MAGIC`, a format version and a
+//! `CheckpointHeader` naming the chiwawa version, IR layout version,
+//! layout-relevant features and a fingerprint of each module. `restore`
+//! refuses images whose header does not match the running build and the
+//! instances it restores into. The state follows as a list of sections
+//! (frames, register file, each memory, each instance's globals, each
+//! modified table, each instance's WASI descriptors, extern handles), each
+//! with an XXH64 checksum that is verified before any state is applied.
+//!
 //! The checkpoint captures:
 //! - Activation frame stack with register file and per-frame locals
-//! - Linear memory contents (LZ4 compressed)
-//! - Global variable values
-//! - Per-frame function indices (used to rebuild skipped `Rc` fields on restore)
+//! - For every linked module instance (the main instance first, then the
+//!   linked ones in instantiation order): contents of every memory instance
+//!   (LZ4 compressed), global variable values, and the tables that were
+//!   modified since instantiation, as a `TableDiff` against their contents
+//!   after the element segments were applied
+//! - For every instance with a WASI implementation: the host's preopens and
+//!   the file descriptors the guest opened (`WasiState`), which are reopened
+//!   under their original numbers and re-seeked on restore
+//! - Per-frame instance and function indices (used to rebuild skipped `Rc`
+//!   fields on restore)
+//! - Function references, as instance and function index, exception
+//!   references, as the instance and index of their tag plus payload, and
+//!   extern references, as ids into a table of host handles (`SavedExtern`);
+//!   all are rebound to the live instances while the sections are decoded
+//! - Whether execution was inside the start function (`Stacks::in_start`), so
+//!   that the entry function is invoked after the resumed start completes
 //!
 //! `FrameStack` fields that are derived from the module instance
 //! (`handlers`, `processed_instrs`, `primary_mem`, `cached_mem_ptr`) are
-//! `#[serde(skip)]` and reconstructed during `restore`. Tables are excluded:
-//! they are deterministically initialized from element segments during
-//! module instantiation.
+//! `#[serde(skip)]` and reconstructed during `restore`. Unmodified tables
+//! are not stored: instantiation reproduces them from the element segments.
 //!
 //! ## Trigger Mechanisms
 //!
@@ -25,44 +46,174 @@
 //! compile time:
 //!
 //! - **wasm32-wasip1-threads** (`target_feature = "atomics"`): background
-//!   thread set up by `setup_checkpoint_monitor` watches the
-//!   `checkpoint.trigger` file and toggles an atomic flag. `poll_checkpoint`
-//!   only does a cheap relaxed atomic load on the hot path.
+//!   threads set up by `setup_checkpoint_monitor` watch the configured
+//!   triggers and toggle an atomic flag. `poll_checkpoint` only does a cheap
+//!   relaxed atomic load on the hot path.
 //! - **wasm32-wasip1** (no atomics): `poll_checkpoint` throttles itself with
-//!   `VmState.checkpoint_poll_counter` and only issues the WASI file-existence
-//!   syscall every `CHECKPOINT_POLL_MASK + 1` (= 1024) instructions to keep
-//!   the dispatcher overhead bounded.
+//!   `VmState.checkpoint_poll_counter` and only checks the triggers (WASI
+//!   file-existence and fd-readiness syscalls, clock reads) every
+//!   `CHECKPOINT_POLL_MASK + 1` (= 1024) instructions to keep the dispatcher
+//!   overhead bounded.
+//!
+//! The triggers and the checkpoint file come from the runtime's
+//! `CheckpointConfig`: a trigger file (`./checkpoint.trigger` by default), a
+//! byte arriving on a file descriptor, or a deadline.
+//!
+//! Embedders can also request a checkpoint in-process through the runtime's
+//! `CheckpointRequest` (e.g. `Caller::request_checkpoint` from a host
+//! function), a per-runtime flag that is checked on every poll on both
+//! targets.
 //!
 //! Either path triggers `Outcome::Trap(CheckpointRequested)`, which
-//! `runtime.rs` translates into a `checkpoint` call.
+//! `runtime.rs` translates into a `checkpoint` call. An in-process request
+//! instead pauses the runtime without touching the filesystem, so the
+//! embedder can take a `Runtime::snapshot` and ship the bytes over its own
+//! transport.
 
 use crate::error::RuntimeError;
-use crate::execution::func::FuncInst;
+use crate::execution::func::{FuncAddr, FuncInst};
 use crate::execution::global::GlobalAddr;
+use crate::execution::ir::IR_LAYOUT_VERSION;
 use crate::execution::mem::MemAddr;
 use crate::execution::module::ModuleInst;
 use crate::execution::state::{Stacks, VmState};
-use crate::execution::value::Val;
-use serde::{Deserialize, Serialize};
+use crate::execution::table::TableAddr;
+pub use crate::execution::table::TableDiff;
+use crate::execution::tag::TagAddr;
+use crate::execution::value::{ExternAddr, Externval, Val, WasiFuncAddr};
+use crate::structure::module::WasiFuncType;
+use crate::wasi::fd_table::WasiState;
+use crate::wasi::passthrough::PassthroughWasiImpl;
+use serde::de::{DeserializeOwned, Error as _};
+use serde::ser::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cell::{Cell, RefCell};
 use std::fs::File;
 use std::io::{Read, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use std::str::FromStr;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use twox_hash::XxHash64;
 
 static CHECKPOINT_TRIGGERED: AtomicBool = AtomicBool::new(false);
-const CHECKPOINT_TRIGGER_FILE: &str = "./checkpoint.trigger";
 
-/// Starts background thread to monitor checkpoint trigger file.
+/// Checkpoint file written when no output path is configured.
+pub const DEFAULT_CHECKPOINT_FILE: &str = "./checkpoint.bin";
+/// Trigger file polled when no triggers are configured.
+pub const DEFAULT_TRIGGER_FILE: &str = "./checkpoint.trigger";
+
+/// Condition that requests a checkpoint while the guest runs.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CheckpointTrigger {
+    /// A file appears at this path. The file is removed once seen.
+    File(PathBuf),
+    /// A byte becomes readable on this file descriptor. The byte is
+    /// consumed.
+    Fd(u32),
+    /// The host clock passes this instant.
+    Deadline(Instant),
+}
+
+impl CheckpointTrigger {
+    /// Returns `true` for a deadline that has passed.
+    fn is_expired(&self) -> bool {
+        matches!(self, CheckpointTrigger::Deadline(deadline) if Instant::now() >= *deadline)
+    }
+}
+
+impl FromStr for CheckpointTrigger {
+    type Err = String;
+
+    /// Parses `file:PATH`, `fd:N` or `after:SECONDS` (relative to now).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, arg) = s
+            .split_once(':')
+            .ok_or_else(|| format!("Invalid trigger '{}', expected KIND:ARG", s))?;
+        match kind {
+            "file" => Ok(CheckpointTrigger::File(PathBuf::from(arg))),
+            "fd" => arg
+                .parse()
+                .map(CheckpointTrigger::Fd)
+                .map_err(|e| format!("Invalid fd '{}': {}", arg, e)),
+            "after" => Duration::try_from_secs_f64(
+                arg.parse()
+                    .map_err(|e| format!("Invalid number of seconds '{}': {}", arg, e))?,
+            )
+            .map(|after| CheckpointTrigger::Deadline(Instant::now() + after))
+            .map_err(|e| format!("Invalid number of seconds '{}': {}", arg, e)),
+            _ => Err(format!(
+                "Unknown trigger kind '{}', expected file, fd or after",
+                kind
+            )),
+        }
+    }
+}
+
+/// Where a runtime writes its checkpoint and what requests one.
+#[derive(Clone, Debug)]
+pub struct CheckpointConfig {
+    /// File written when a trigger fires.
+    pub output_path: PathBuf,
+    /// Triggers polled by the dispatcher; any one of them requests a
+    /// checkpoint.
+    pub triggers: Vec<CheckpointTrigger>,
+}
+
+impl Default for CheckpointConfig {
+    fn default() -> Self {
+        CheckpointConfig {
+            output_path: PathBuf::from(DEFAULT_CHECKPOINT_FILE),
+            triggers: vec![CheckpointTrigger::File(PathBuf::from(DEFAULT_TRIGGER_FILE))],
+        }
+    }
+}
+
+impl CheckpointConfig {
+    /// Drops deadlines that have passed, so that a runtime resumed after a
+    /// deadline checkpoint does not fire again immediately.
+    pub fn disarm_expired(&mut self) {
+        self.triggers.retain(|trigger| !trigger.is_expired());
+    }
+}
+
+/// Starts background threads to monitor the checkpoint triggers.
 ///
-/// Used on `wasm32-wasip1-threads` target for non-blocking checkpoint detection.
-pub fn setup_checkpoint_monitor() {
-    thread::spawn(|| loop {
-        if std::path::Path::new(CHECKPOINT_TRIGGER_FILE).exists() {
-            CHECKPOINT_TRIGGERED.store(true, Ordering::Relaxed);
-            let _ = std::fs::remove_file(CHECKPOINT_TRIGGER_FILE);
+/// Used on `wasm32-wasip1-threads` target for non-blocking checkpoint
+/// detection. File and deadline triggers share a polling thread; each fd
+/// trigger gets a thread blocked on a one-byte read.
+pub fn setup_checkpoint_monitor(triggers: Vec<CheckpointTrigger>) {
+    for trigger in &triggers {
+        if let CheckpointTrigger::Fd(fd) = *trigger {
+            thread::spawn(move || {
+                use std::os::fd::FromRawFd;
+                // The fd belongs to the embedder; never close it here.
+                let mut source =
+                    std::mem::ManuallyDrop::new(unsafe { File::from_raw_fd(fd as i32) });
+                let mut byte = [0u8; 1];
+                while let Ok(n) = source.read(&mut byte) {
+                    if n == 0 {
+                        break;
+                    }
+                    CHECKPOINT_TRIGGERED.store(true, Ordering::Relaxed);
+                }
+            });
+        }
+    }
+    thread::spawn(move || loop {
+        for trigger in &triggers {
+            match trigger {
+                CheckpointTrigger::File(path) if path.exists() => {
+                    CHECKPOINT_TRIGGERED.store(true, Ordering::Relaxed);
+                    let _ = std::fs::remove_file(path);
+                }
+                CheckpointTrigger::Deadline(_) if trigger.is_expired() => {
+                    CHECKPOINT_TRIGGERED.store(true, Ordering::Relaxed);
+                }
+                _ => {}
+            }
         }
         thread::sleep(Duration::from_millis(10));
     });
@@ -74,6 +225,32 @@ pub fn check_checkpoint_flag() -> bool {
     CHECKPOINT_TRIGGERED.load(Ordering::Relaxed)
 }
 
+/// Handle for requesting an in-process checkpoint of one runtime.
+///
+/// Each `Runtime` owns one; clones share its flag. Host functions reach it
+/// through `Caller::request_checkpoint`. When checkpointing is enabled, the
+/// runtime pauses with `RunStatus::CheckpointRequested` at the next
+/// instruction instead of writing a checkpoint file.
+#[derive(Clone, Debug, Default)]
+pub struct CheckpointRequest(Rc<Cell<bool>>);
+
+impl CheckpointRequest {
+    /// Requests a checkpoint at the next instruction.
+    pub fn request(&self) {
+        self.0.set(true);
+    }
+
+    /// Returns whether a request is pending.
+    pub fn is_pending(&self) -> bool {
+        self.0.get()
+    }
+
+    /// Consumes a pending request, returning whether one was set.
+    pub fn take(&self) -> bool {
+        self.0.replace(false)
+    }
+}
+
 /// Polls for a checkpoint request from the dispatcher hot path.
 ///
 /// Returns `true` if a checkpoint should be taken. Designed to be called per
@@ -103,6 +280,10 @@ pub fn poll_checkpoint(state: &mut VmState) -> bool {
 
 #[inline(never)]
 fn do_poll_checkpoint(state: &mut VmState) -> bool {
+    if state.checkpoint_request().is_pending() {
+        return true;
+    }
+
     #[cfg(all(
         target_arch = "wasm32",
         target_os = "wasi",
@@ -110,8 +291,9 @@ fn do_poll_checkpoint(state: &mut VmState) -> bool {
         target_feature = "atomics"
     ))]
     {
-        let _ = state;
-        check_checkpoint_flag()
+        // The monitor is process-wide, so a runtime without triggers of its
+        // own must not act on it
+        !state.checkpoint_triggers().is_empty() && check_checkpoint_flag()
     }
 
     #[cfg(not(all(
@@ -125,158 +307,886 @@ fn do_poll_checkpoint(state: &mut VmState) -> bool {
         if state.checkpoint_poll_counter & CHECKPOINT_POLL_MASK != 0 {
             return false;
         }
-        if let Some(ref wasi) = state.module().wasi_impl {
-            if wasi.check_file_exists(CHECKPOINT_TRIGGER_FILE) {
-                let _ = std::fs::remove_file(CHECKPOINT_TRIGGER_FILE);
-                return true;
-            }
-        }
-        false
+        let wasi = state.module().wasi_impl.as_ref();
+        state
+            .checkpoint_triggers()
+            .iter()
+            .any(|trigger| match trigger {
+                CheckpointTrigger::File(path) => {
+                    let exists = match wasi {
+                        Some(wasi) => wasi.check_file_exists(&path.to_string_lossy()),
+                        None => path.exists(),
+                    };
+                    if exists {
+                        let _ = std::fs::remove_file(path);
+                    }
+                    exists
+                }
+                CheckpointTrigger::Fd(fd) => PassthroughWasiImpl::take_fd_byte(*fd),
+                CheckpointTrigger::Deadline(_) => trigger.is_expired(),
+            })
     }
 }
 
 /// Throttle interval for non-atomics file polling (= every 1024 instructions).
 const CHECKPOINT_POLL_MASK: u32 = 0x3FF;
 
+/// Magic bytes at the start of every checkpoint image.
+pub const CHECKPOINT_MAGIC: [u8; 8] = *b"CHIWAWA\0";
+
+/// Version of the checkpoint image layout written by this build.
+pub const CHECKPOINT_FORMAT_VERSION: u32 = 7;
+
+/// Identifies the build and the modules a checkpoint was taken from.
+///
+/// A checkpoint image is `CHECKPOINT_MAGIC`, the format version (`u32`,
+/// little endian), then this header and the `Section` list of the
+/// `SerializableState`, both bincode-encoded.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct CheckpointHeader {
+    /// Version of the chiwawa crate that wrote the checkpoint.
+    pub chiwawa_version: String,
+    /// `ir::IR_LAYOUT_VERSION` of that build.
+    pub ir_layout_version: u32,
+    /// Compile-time features that affect execution state (e.g. `tco`).
+    pub features: Vec<String>,
+    /// `ModuleInst::fingerprint` of each checkpointed instance, main
+    /// instance first.
+    pub module_fingerprints: Vec<u64>,
+}
+
+impl CheckpointHeader {
+    /// Describes the running build and `instances`.
+    pub fn new(instances: &[Rc<ModuleInst>]) -> Self {
+        CheckpointHeader {
+            chiwawa_version: env!("CARGO_PKG_VERSION").to_string(),
+            ir_layout_version: IR_LAYOUT_VERSION,
+            features: layout_features(),
+            module_fingerprints: instances.iter().map(|inst| inst.fingerprint).collect(),
+        }
+    }
+
+    /// Checks that this build can restore the checkpoint into `instances`.
+    pub fn check(&self, instances: &[Rc<ModuleInst>]) -> Result<(), RuntimeError> {
+        let expected = CheckpointHeader::new(instances);
+        if self.chiwawa_version != expected.chiwawa_version {
+            return Err(RuntimeError::CheckpointLoadError(format!(
+                "Checkpoint was written by chiwawa {}, this is chiwawa {}",
+                self.chiwawa_version, expected.chiwawa_version
+            )));
+        }
+        if self.ir_layout_version != expected.ir_layout_version {
+            return Err(RuntimeError::CheckpointLoadError(format!(
+                "Checkpoint uses IR layout version {}, this build uses {}",
+                self.ir_layout_version, expected.ir_layout_version
+            )));
+        }
+        if self.features != expected.features {
+            return Err(RuntimeError::CheckpointLoadError(format!(
+                "Checkpoint was written with features {:?}, this build has {:?}",
+                self.features, expected.features
+            )));
+        }
+        if self.module_fingerprints.len() != instances.len() {
+            return Err(RuntimeError::CheckpointLoadError(format!(
+                "Checkpoint covers {} instances, but {} are linked",
+                self.module_fingerprints.len(),
+                instances.len()
+            )));
+        }
+        if let Some(idx) = self
+            .module_fingerprints
+            .iter()
+            .zip(&expected.module_fingerprints)
+            .position(|(saved, current)| saved != current)
+        {
+            return Err(RuntimeError::CheckpointLoadError(format!(
+                "Instance {} was created from a different module than the checkpointed one \
+                 (checkpoint {:016x}, instance {:016x})",
+                idx, self.module_fingerprints[idx], expected.module_fingerprints[idx]
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Compile-time features recorded in the checkpoint header.
+fn layout_features() -> Vec<String> {
+    let mut features = Vec::new();
+    if cfg!(feature = "tco") {
+        features.push("tco".to_string());
+    }
+    features
+}
+
+/// Reads the header of a checkpoint image, returning it together with the
+/// encoded `SerializableState` that follows.
+pub fn read_checkpoint_header(encoded: &[u8]) -> Result<(CheckpointHeader, &[u8]), RuntimeError> {
+    let prefix_len = CHECKPOINT_MAGIC.len() + 4;
+    if encoded.len() < prefix_len || encoded[..CHECKPOINT_MAGIC.len()] != CHECKPOINT_MAGIC {
+        return Err(RuntimeError::CheckpointLoadError(
+            "Not a chiwawa checkpoint (bad magic bytes)".to_string(),
+        ));
+    }
+    let version = u32::from_le_bytes(
+        encoded[CHECKPOINT_MAGIC.len()..prefix_len]
+            .try_into()
+            .unwrap(),
+    );
+    if version != CHECKPOINT_FORMAT_VERSION {
+        return Err(RuntimeError::CheckpointLoadError(format!(
+            "Unsupported checkpoint format version {} (expected {})",
+            version, CHECKPOINT_FORMAT_VERSION
+        )));
+    }
+    let mut rest = &encoded[prefix_len..];
+    let header: CheckpointHeader = bincode::deserialize_from(&mut rest)
+        .map_err(|e| RuntimeError::CheckpointLoadError(format!("Corrupt header: {}", e)))?;
+    Ok((header, rest))
+}
+
+/// Position of a function among the checkpointed instances. Function
+/// references are stored in this form.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct FuncRefIndex {
+    pub instance: u32,
+    pub func: u32,
+}
+
+/// Position of an exception's tag among the checkpointed instances.
+/// Exception references store their tag in this form, so an exception
+/// thrown by a linked instance keeps its tag after restore.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct TagIndex {
+    pub instance: u32,
+    pub tag: u32,
+}
+
+/// Checkpointed form of the value behind an extern reference, given as
+/// indices into the checkpointed instances. Extern references themselves are
+/// stored as their position (host-handle id) in the `Externs` section, so
+/// references to the same handle stay shared after restore.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum SavedExtern {
+    Func(FuncRefIndex),
+    Table { instance: u32, index: u32 },
+    Mem { instance: u32, index: u32 },
+    Global { instance: u32, index: u32 },
+    Tag { instance: u32, index: u32 },
+    WasiFunc(WasiFuncType),
+}
+
+/// Translates function, exception and extern references to checkpoint
+/// indices and back while sections are encoded or decoded.
+///
+/// `FuncAddr`, `TagAddr` and `ExternAddr` are bare `Rc` handles that do not know their
+/// index, so the serde functions used for `Ref` look it up in the context
+/// installed by `RefContext::scope`.
+struct RefContext {
+    instances: Vec<Rc<ModuleInst>>,
+    /// Extern handles by id: collected while encoding, rebuilt from the
+    /// `Externs` section before decoding.
+    externs: Vec<ExternAddr>,
+}
+
+thread_local! {
+    static REF_CONTEXT: RefCell<Option<RefContext>> = const { RefCell::new(None) };
+}
+
+impl RefContext {
+    fn new(instances: &[Rc<ModuleInst>]) -> Self {
+        RefContext {
+            instances: instances.to_vec(),
+            externs: Vec::new(),
+        }
+    }
+
+    /// Installs the context while `f` runs and hands it back afterwards.
+    fn scope<T>(self, f: impl FnOnce() -> T) -> (T, RefContext) {
+        REF_CONTEXT.with(|ctx| *ctx.borrow_mut() = Some(self));
+        let ret = f();
+        let ctx = REF_CONTEXT
+            .with(|ctx| ctx.borrow_mut().take())
+            .expect("reference context removed during checkpoint encoding");
+        (ret, ctx)
+    }
+
+    /// Runs `f` against the installed context.
+    fn with<T>(f: impl FnOnce(&mut RefContext) -> Result<T, String>) -> Result<T, String> {
+        REF_CONTEXT.with(|ctx| match ctx.borrow_mut().as_mut() {
+            Some(ctx) => f(ctx),
+            None => Err("Reference encoded outside a checkpoint".to_string()),
+        })
+    }
+
+    /// Finds the first instance and index whose `addrs` entry matches `eq`.
+    fn position<T>(
+        &self,
+        addrs: impl Fn(&ModuleInst) -> &[T],
+        eq: impl Fn(&T) -> bool,
+    ) -> Option<(u32, u32)> {
+        self.instances
+            .iter()
+            .enumerate()
+            .find_map(|(instance, inst)| {
+                addrs(inst)
+                    .iter()
+                    .position(&eq)
+                    .map(|index| (instance as u32, index as u32))
+            })
+    }
+
+    fn func_index(&self, func_addr: &FuncAddr) -> Option<FuncRefIndex> {
+        self.position(|inst| &inst.func_addrs, |f| f.ptr_eq(func_addr))
+            .map(|(instance, func)| FuncRefIndex { instance, func })
+    }
+
+    fn func_addr(&self, index: FuncRefIndex) -> Option<FuncAddr> {
+        self.instances
+            .get(index.instance as usize)?
+            .func_addrs
+            .get(index.func as usize)
+            .cloned()
+    }
+
+    fn tag_index(&self, tag_addr: &TagAddr) -> Option<TagIndex> {
+        self.position(|inst| &inst.tag_addrs, |t| t.ptr_eq(tag_addr))
+            .map(|(instance, tag)| TagIndex { instance, tag })
+    }
+
+    fn tag_addr(&self, index: TagIndex) -> Option<TagAddr> {
+        self.instances
+            .get(index.instance as usize)?
+            .tag_addrs
+            .get(index.tag as usize)
+            .cloned()
+    }
+
+    /// Host-handle id of `extern_addr`, allocating one on first use.
+    fn extern_id(&mut self, extern_addr: &ExternAddr) -> u32 {
+        match self.externs.iter().position(|e| e.ptr_eq(extern_addr)) {
+            Some(id) => id as u32,
+            None => {
+                self.externs.push(extern_addr.clone());
+                (self.externs.len() - 1) as u32
+            }
+        }
+    }
+
+    fn save_extern(&self, externval: &Externval) -> Option<SavedExtern> {
+        match externval {
+            Externval::Func(f) => self.func_index(f).map(SavedExtern::Func),
+            Externval::Table(t) => self
+                .position(|inst| &inst.table_addrs, |a| a.ptr_eq(t))
+                .map(|(instance, index)| SavedExtern::Table { instance, index }),
+            Externval::Mem(m) => self
+                .position(|inst| &inst.mem_addrs, |a| a.ptr_eq(m))
+                .map(|(instance, index)| SavedExtern::Mem { instance, index }),
+            Externval::Global(g) => self
+                .position(|inst| &inst.global_addrs, |a| a.ptr_eq(g))
+                .map(|(instance, index)| SavedExtern::Global { instance, index }),
+            Externval::Tag(t) => self
+                .position(|inst| &inst.tag_addrs, |a| a.ptr_eq(t))
+                .map(|(instance, index)| SavedExtern::Tag { instance, index }),
+            Externval::WasiFunc(w) => Some(SavedExtern::WasiFunc(w.func_type)),
+        }
+    }
+
+    fn load_extern(&self, saved: &SavedExtern) -> Option<Externval> {
+        let inst = |instance: u32| self.instances.get(instance as usize);
+        Some(match *saved {
+            SavedExtern::Func(index) => Externval::Func(self.func_addr(index)?),
+            SavedExtern::Table { instance, index } => {
+                Externval::Table(inst(instance)?.table_addrs.get(index as usize)?.clone())
+            }
+            SavedExtern::Mem { instance, index } => {
+                Externval::Mem(inst(instance)?.mem_addrs.get(index as usize)?.clone())
+            }
+            SavedExtern::Global { instance, index } => {
+                Externval::Global(inst(instance)?.global_addrs.get(index as usize)?.clone())
+            }
+            SavedExtern::Tag { instance, index } => {
+                Externval::Tag(inst(instance)?.tag_addrs.get(index as usize)?.clone())
+            }
+            SavedExtern::WasiFunc(func_type) => Externval::WasiFunc(WasiFuncAddr::new(func_type)),
+        })
+    }
+}
+
+/// Serializes `Ref::FuncAddr` as its `FuncRefIndex`.
+pub(crate) fn serialize_func_ref<S: Serializer>(
+    func_addr: &FuncAddr,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    RefContext::with(|ctx| {
+        ctx.func_index(func_addr).ok_or_else(|| {
+            "Function reference to a function outside the checkpointed instances".to_string()
+        })
+    })
+    .map_err(S::Error::custom)?
+    .serialize(serializer)
+}
+
+/// Rebinds a `FuncRefIndex` to the live `FuncAddr`.
+pub(crate) fn deserialize_func_ref<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<FuncAddr, D::Error> {
+    let index = FuncRefIndex::deserialize(deserializer)?;
+    RefContext::with(|ctx| {
+        ctx.func_addr(index)
+            .ok_or_else(|| format!("Function reference {:?} out of range", index))
+    })
+    .map_err(D::Error::custom)
+}
+
+/// Serializes the tag of an exception as its `TagIndex`.
+pub(crate) fn serialize_tag<S: Serializer>(
+    tag_addr: &TagAddr,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    RefContext::with(|ctx| {
+        ctx.tag_index(tag_addr).ok_or_else(|| {
+            "Exception reference to a tag outside the checkpointed instances".to_string()
+        })
+    })
+    .map_err(S::Error::custom)?
+    .serialize(serializer)
+}
+
+/// Rebinds a `TagIndex` to the live `TagAddr`.
+pub(crate) fn deserialize_tag<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<TagAddr, D::Error> {
+    let index = TagIndex::deserialize(deserializer)?;
+    RefContext::with(|ctx| {
+        ctx.tag_addr(index)
+            .ok_or_else(|| format!("Exception tag {:?} out of range", index))
+    })
+    .map_err(D::Error::custom)
+}
+
+/// Serializes `Ref::RefExtern` as its host-handle id.
+pub(crate) fn serialize_extern_ref<S: Serializer>(
+    extern_addr: &ExternAddr,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    RefContext::with(|ctx| Ok(ctx.extern_id(extern_addr)))
+        .map_err(S::Error::custom)?
+        .serialize(serializer)
+}
+
+/// Rebinds a host-handle id to the `ExternAddr` rebuilt for it.
+pub(crate) fn deserialize_extern_ref<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<ExternAddr, D::Error> {
+    let id = u32::deserialize(deserializer)?;
+    RefContext::with(|ctx| {
+        ctx.externs
+            .get(id as usize)
+            .cloned()
+            .ok_or_else(|| format!("Extern handle {} out of range", id))
+    })
+    .map_err(D::Error::custom)
+}
+
+/// State of one module instance.
+#[derive(Debug)]
+pub struct InstanceState {
+    /// Index of each memory of this instance into
+    /// `SerializableState::memory_data_compressed`.
+    pub memories: Vec<u32>,
+    /// Index of each global of this instance into
+    /// `SerializableState::global_values`.
+    pub globals: Vec<u32>,
+    /// Table index and changes of every modified table owned by this
+    /// instance. A table shared through an import is stored once, with the
+    /// first instance that holds it.
+    pub table_diffs: Vec<(u32, TableDiff)>,
+    /// Open file descriptors, if the instance has a WASI implementation.
+    pub wasi: Option<WasiState>,
+}
+
 /// Complete runtime state for checkpoint serialization.
 ///
 /// Contains all information needed to restore execution:
 /// - Call stack and register state
-/// - Linear memory contents (LZ4 compressed)
-/// - Global variable values
+/// - The contents of every distinct memory (LZ4 compressed) and the value of
+///   every distinct global; a memory or global shared through imports is
+///   stored once
+/// - Per-instance indices of its memories and globals into those, modified
+///   tables and WASI file descriptors, for the main instance followed by
+///   linked instances
+/// - The instance and function each activation frame belongs to
 ///
-/// Tables are excluded: they are deterministically initialized from element
-/// segments during module instantiation.
-#[derive(Serialize, Deserialize, Debug)]
+/// Function and extern references inside the stacks, globals and tables are
+/// encoded through `RefContext`; the extern handles they use get their own
+/// section.
+///
+/// The state is stored as a list of checksummed `Section`s so that a
+/// corrupted image is rejected before any of it is applied.
+#[derive(Debug)]
 pub struct SerializableState {
     pub stacks: Stacks,
-    pub memory_data_compressed: Vec<u8>,
+    pub memory_data_compressed: Vec<Vec<u8>>,
     pub global_values: Vec<Val>,
+    pub instances: Vec<InstanceState>,
+    pub frame_instance_indices: Vec<u32>,
     pub frame_func_indices: Vec<u32>,
 }
 
+/// Kind of data held by a checkpoint section.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum SectionKind {
+    /// Activation frames, the instance and function of each frame, and
+    /// `Stacks::in_start`.
+    Frames,
+    /// The register file shared by all frames.
+    RegFile,
+    /// One distinct memory, LZ4 compressed.
+    Memory { memory: u32 },
+    /// Values of all distinct globals.
+    Globals,
+    /// Indices of the memories and globals of one instance into the
+    /// `Memory` and `Globals` sections.
+    Layout { instance: u32 },
+    /// `TableDiff` of one modified table of one instance.
+    Table { instance: u32, table: u32 },
+    /// `WasiState` of one instance with a WASI implementation.
+    Wasi { instance: u32 },
+    /// The `SavedExtern` of every extern reference in the other sections,
+    /// indexed by host-handle id.
+    Externs,
+}
+
+/// Checksummed part of a checkpoint image.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Section {
+    pub kind: SectionKind,
+    /// XXH64 of `data`.
+    pub checksum: u64,
+    pub data: Vec<u8>,
+}
+
+impl Section {
+    fn new(kind: SectionKind, data: Vec<u8>) -> Self {
+        Section {
+            kind,
+            checksum: XxHash64::oneshot(0, &data),
+            data,
+        }
+    }
+
+    fn encode<T: Serialize>(kind: SectionKind, value: &T) -> Result<Self, RuntimeError> {
+        let data = bincode::serialize(value)
+            .map_err(|e| RuntimeError::SerializationError(e.to_string()))?;
+        Ok(Section::new(kind, data))
+    }
+
+    /// Checks `data` against the stored checksum.
+    pub fn verify(&self) -> Result<(), RuntimeError> {
+        if XxHash64::oneshot(0, &self.data) != self.checksum {
+            return Err(RuntimeError::CheckpointLoadError(format!(
+                "Checksum mismatch in section {:?}",
+                self.kind
+            )));
+        }
+        Ok(())
+    }
+
+    fn find(sections: &[Section], kind: SectionKind) -> Result<&Section, RuntimeError> {
+        sections
+            .iter()
+            .find(|section| section.kind == kind)
+            .ok_or_else(|| RuntimeError::CheckpointLoadError(format!("Missing section {:?}", kind)))
+    }
+
+    fn decode<T: DeserializeOwned>(&self) -> Result<T, RuntimeError> {
+        bincode::deserialize(&self.data).map_err(|e| {
+            RuntimeError::CheckpointLoadError(format!("Section {:?}: {}", self.kind, e))
+        })
+    }
+}
+
+/// Deduplicates the addresses `addrs` returns for each of `instances` with
+/// `ptr_eq`. Returns the distinct addresses in first-seen order and, for
+/// each instance, the index of every one of its addresses among them.
+fn unique_addrs<T: Clone>(
+    instances: &[Rc<ModuleInst>],
+    addrs: impl Fn(&ModuleInst) -> &[T],
+    ptr_eq: impl Fn(&T, &T) -> bool,
+) -> (Vec<T>, Vec<Vec<u32>>) {
+    let mut unique: Vec<T> = Vec::new();
+    let indices = instances
+        .iter()
+        .map(|inst| {
+            addrs(inst)
+                .iter()
+                .map(
+                    |addr| match unique.iter().position(|seen| ptr_eq(seen, addr)) {
+                        Some(index) => index as u32,
+                        None => {
+                            unique.push(addr.clone());
+                            (unique.len() - 1) as u32
+                        }
+                    },
+                )
+                .collect()
+        })
+        .collect();
+    (unique, indices)
+}
+
+impl SerializableState {
+    /// Splits the state into checksummed sections. References are encoded
+    /// as indices into `instances`.
+    fn to_sections(&self, instances: &[Rc<ModuleInst>]) -> Result<Vec<Section>, RuntimeError> {
+        let (sections, ctx) = RefContext::new(instances).scope(|| self.encode_sections());
+        let mut sections = sections?;
+        let externs = ctx
+            .externs
+            .iter()
+            .map(|extern_addr| {
+                ctx.save_extern(&extern_addr.externval()).ok_or_else(|| {
+                    RuntimeError::SerializationError(
+                        "Extern reference to a value outside the checkpointed instances"
+                            .to_string(),
+                    )
+                })
+            })
+            .collect::<Result<Vec<SavedExtern>, RuntimeError>>()?;
+        sections.push(Section::encode(SectionKind::Externs, &externs)?);
+        Ok(sections)
+    }
+
+    fn encode_sections(&self) -> Result<Vec<Section>, RuntimeError> {
+        let mut sections = vec![
+            Section::encode(
+                SectionKind::Frames,
+                &(
+                    &self.stacks.activation_frame_stack,
+                    &self.frame_instance_indices,
+                    &self.frame_func_indices,
+                    self.stacks.in_start,
+                ),
+            )?,
+            Section::encode(SectionKind::RegFile, &self.stacks.reg_file)?,
+        ];
+        for (memory, compressed) in self.memory_data_compressed.iter().enumerate() {
+            sections.push(Section::new(
+                SectionKind::Memory {
+                    memory: memory as u32,
+                },
+                compressed.clone(),
+            ));
+        }
+        sections.push(Section::encode(SectionKind::Globals, &self.global_values)?);
+        for (instance, instance_state) in self.instances.iter().enumerate() {
+            let instance = instance as u32;
+            sections.push(Section::encode(
+                SectionKind::Layout { instance },
+                &(&instance_state.memories, &instance_state.globals),
+            )?);
+            for (table, diff) in &instance_state.table_diffs {
+                sections.push(Section::encode(
+                    SectionKind::Table {
+                        instance,
+                        table: *table,
+                    },
+                    diff,
+                )?);
+            }
+            if let Some(wasi) = &instance_state.wasi {
+                sections.push(Section::encode(SectionKind::Wasi { instance }, wasi)?);
+            }
+        }
+        Ok(sections)
+    }
+
+    /// Reassembles the state of `instances` from verified sections, rebinding
+    /// references to them.
+    fn from_sections(
+        sections: Vec<Section>,
+        instances: &[Rc<ModuleInst>],
+    ) -> Result<Self, RuntimeError> {
+        let mut ctx = RefContext::new(instances);
+        let saved_externs: Vec<SavedExtern> =
+            Section::find(&sections, SectionKind::Externs)?.decode()?;
+        for saved in &saved_externs {
+            let externval = ctx.load_extern(saved).ok_or_else(|| {
+                RuntimeError::CheckpointLoadError(format!("Extern {:?} out of range", saved))
+            })?;
+            ctx.externs.push(ExternAddr::new(externval));
+        }
+        ctx.scope(|| Self::decode_sections(&sections, instances.len()))
+            .0
+    }
+
+    fn decode_sections(sections: &[Section], instance_count: usize) -> Result<Self, RuntimeError> {
+        let find = |kind: SectionKind| Section::find(sections, kind);
+
+        let (activation_frame_stack, frame_instance_indices, frame_func_indices, in_start) =
+            find(SectionKind::Frames)?.decode()?;
+        let reg_file = find(SectionKind::RegFile)?.decode()?;
+        let memory_count = sections
+            .iter()
+            .filter(|section| matches!(section.kind, SectionKind::Memory { .. }))
+            .count() as u32;
+        let memory_data_compressed = (0..memory_count)
+            .map(|memory| find(SectionKind::Memory { memory }).map(|section| section.data.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let global_values = find(SectionKind::Globals)?.decode()?;
+
+        let mut instances = Vec::with_capacity(instance_count);
+        for instance in 0..instance_count as u32 {
+            let (memories, globals) = find(SectionKind::Layout { instance })?.decode()?;
+            let table_diffs = sections
+                .iter()
+                .filter_map(|section| match section.kind {
+                    SectionKind::Table { instance: i, table } if i == instance => {
+                        Some(section.decode().map(|diff| (table, diff)))
+                    }
+                    _ => None,
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let wasi = sections
+                .iter()
+                .find(|section| section.kind == SectionKind::Wasi { instance })
+                .map(Section::decode)
+                .transpose()?;
+            instances.push(InstanceState {
+                memories,
+                globals,
+                table_diffs,
+                wasi,
+            });
+        }
+
+        Ok(SerializableState {
+            stacks: Stacks {
+                reg_file,
+                activation_frame_stack,
+                in_start,
+            },
+            memory_data_compressed,
+            global_values,
+            instances,
+            frame_instance_indices,
+            frame_func_indices,
+        })
+    }
+}
+
 /// Serializes runtime state to a checkpoint file.
 ///
 /// Captures memory, globals, and stack state for later restoration.
+/// `instances` holds the main instance first, followed by the instances it
+/// is linked with in instantiation order.
+///
+/// The image is written to a temporary file next to `output_path`, synced
+/// and then renamed over it, so a crash mid-checkpoint never leaves a
+/// partially written checkpoint in place.
 pub fn checkpoint<P: AsRef<Path>>(
-    module_inst: &ModuleInst,
+    instances: &[Rc<ModuleInst>],
     stacks: &Stacks,
-    mem_addrs: &[MemAddr],
-    global_addrs: &[GlobalAddr],
     output_path: P,
 ) -> Result<(), RuntimeError> {
-    println!("Checkpointing state to {:?}...", output_path.as_ref());
-
-    // 1. Gather Memory state (LZ4 compressed)
-    let (memory_data_compressed, mem_raw_size) = if let Some(mem_addr) = mem_addrs.get(0) {
-        let raw = mem_addr.get_data();
-        let raw_len = raw.len();
-        let compressed = lz4_flex::compress_prepend_size(&raw);
-        (compressed, raw_len)
-    } else {
-        (Vec::new(), 0)
-    };
+    let output_path = output_path.as_ref();
+    println!("Checkpointing state to {:?}...", output_path);
+
+    let encoded = checkpoint_to_bytes(instances, stacks)?;
+
+    let mut temp_path = output_path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+    let written = File::create(&temp_path).and_then(|mut file| {
+        file.write_all(&encoded)?;
+        file.sync_all()
+    });
+    if let Err(e) = written.and_then(|_| std::fs::rename(&temp_path, output_path)) {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(RuntimeError::CheckpointSaveError(e.to_string()));
+    }
 
-    // 2. Gather Global state
+    println!("Checkpoint successful.");
+    Ok(())
+}
+
+/// Serializes runtime state to an in-memory checkpoint image.
+///
+/// Produces the same bytes that `checkpoint` writes to a file.
+pub fn checkpoint_to_bytes(
+    instances: &[Rc<ModuleInst>],
+    stacks: &Stacks,
+) -> Result<Vec<u8>, RuntimeError> {
+    // 1. Gather Memory state (LZ4 compressed, one blob per memory instance)
+    //    and Global state once per distinct memory and global, and the
+    //    memory and global indices, modified tables and WASI descriptors of
+    //    every module instance
+    let (mem_addrs, mem_indices) = unique_addrs(instances, |inst| &inst.mem_addrs, MemAddr::ptr_eq);
+    let (global_addrs, global_indices) =
+        unique_addrs(instances, |inst| &inst.global_addrs, GlobalAddr::ptr_eq);
+    let mut mem_raw_size = 0;
+    let memory_data_compressed = mem_addrs
+        .iter()
+        .map(|mem_addr| {
+            let raw = mem_addr.get_data();
+            mem_raw_size += raw.len();
+            lz4_flex::compress_prepend_size(&raw)
+        })
+        .collect::<Vec<Vec<u8>>>();
     let global_values = global_addrs
         .iter()
-        .map(|global_addr| Ok(global_addr.get()))
-        .collect::<Result<Vec<Val>, RuntimeError>>()?;
-
-    // 3. Compute function indices for each activation frame (using Rc::ptr_eq)
-    let frame_func_indices = stacks
-        .activation_frame_stack
+        .map(|global_addr| global_addr.get())
+        .collect::<Vec<Val>>();
+    let mut seen_tables: Vec<TableAddr> = Vec::new();
+    let instance_states = instances
         .iter()
-        .map(|frame_stack| {
-            let frame_instrs = &frame_stack.label_stack[0].processed_instrs;
-            module_inst
-                .func_addrs
-                .iter()
-                .position(|func_addr| {
-                    let inst = func_addr.read_lock();
-                    if let FuncInst::RuntimeFunc { code, .. } = inst {
-                        Rc::ptr_eq(frame_instrs, &code.body)
-                    } else {
-                        false
-                    }
-                })
-                .expect("Function not found in module func_addrs during checkpoint")
-                as u32
+        .zip(mem_indices.into_iter().zip(global_indices))
+        .map(|(module_inst, (memories, globals))| {
+            let mut table_diffs = Vec::new();
+            for (table, table_addr) in module_inst.table_addrs.iter().enumerate() {
+                if seen_tables.iter().any(|seen| seen.ptr_eq(table_addr)) {
+                    continue;
+                }
+                seen_tables.push(table_addr.clone());
+                if let Some(diff) = table_addr.diff() {
+                    table_diffs.push((table as u32, diff));
+                }
+            }
+            InstanceState {
+                memories,
+                globals,
+                table_diffs,
+                wasi: module_inst.wasi_impl.as_ref().map(|wasi| wasi.save_state()),
+            }
         })
-        .collect::<Vec<u32>>();
+        .collect::<Vec<InstanceState>>();
 
-    // 4. Assemble state
+    // 2. Compute instance and function indices for each activation frame
+    //    (using Rc::ptr_eq)
+    let mut frame_instance_indices = Vec::new();
+    let mut frame_func_indices = Vec::new();
+    for frame_stack in stacks.activation_frame_stack.iter() {
+        let instance_idx = instances
+            .iter()
+            .position(|module_inst| {
+                std::ptr::eq(frame_stack.frame.module.as_ptr(), Rc::as_ptr(module_inst))
+            })
+            .ok_or_else(|| {
+                RuntimeError::SerializationError(
+                    "Frame belongs to an instance not covered by the checkpoint".to_string(),
+                )
+            })?;
+        let frame_instrs = &frame_stack.label_stack[0].processed_instrs;
+        let func_idx = instances[instance_idx]
+            .func_addrs
+            .iter()
+            .position(|func_addr| {
+                let inst = func_addr.read_lock();
+                if let FuncInst::RuntimeFunc { code, .. } = inst {
+                    Rc::ptr_eq(frame_instrs, &code.body)
+                } else {
+                    false
+                }
+            })
+            .expect("Function not found in module func_addrs during checkpoint");
+        frame_instance_indices.push(instance_idx as u32);
+        frame_func_indices.push(func_idx as u32);
+    }
+
+    // 3. Assemble state and split it into checksummed sections
     // Note: Register file is already compact because restore_offsets() truncates
     // register vectors on function return, so no checkpoint-time compaction needed.
     let state = SerializableState {
         stacks: stacks.clone(),
         memory_data_compressed,
         global_values,
+        instances: instance_states,
+        frame_instance_indices,
         frame_func_indices,
     };
+    let sections = state.to_sections(instances)?;
 
-    // 6. Serialize and write (with per-component size diagnostics)
-    let reg_file_size = bincode::serialize(&state.stacks.reg_file)
-        .map(|v| v.len())
-        .unwrap_or(0);
-    let frames_count = state.stacks.activation_frame_stack.len();
-    let total_labels: usize = state
-        .stacks
+    // 4. Per-component size diagnostics
+    let section_size = |pred: fn(&SectionKind) -> bool| -> usize {
+        sections
+            .iter()
+            .filter(|section| pred(&section.kind))
+            .map(|section| section.data.len())
+            .sum()
+    };
+    let total_labels: usize = stacks
         .activation_frame_stack
         .iter()
         .map(|f| f.label_stack.len())
         .sum();
-    let frames_size = bincode::serialize(&state.stacks.activation_frame_stack)
-        .map(|v| v.len())
-        .unwrap_or(0);
-    let total_locals: usize = state
-        .stacks
+    let total_locals: usize = stacks
         .activation_frame_stack
         .iter()
         .map(|f| f.frame.locals.len())
         .sum();
-    let _stacks_size = reg_file_size + frames_size;
-    let memory_size = bincode::serialize(&state.memory_data_compressed)
-        .map(|v| v.len())
-        .unwrap_or(0);
-    let globals_size = bincode::serialize(&state.global_values)
-        .map(|v| v.len())
-        .unwrap_or(0);
-    let indices_size = bincode::serialize(&state.frame_func_indices)
-        .map(|v| v.len())
-        .unwrap_or(0);
     println!("Checkpoint component sizes:");
-    println!("  reg_file:           {} bytes", reg_file_size);
+    println!(
+        "  reg_file:           {} bytes",
+        section_size(|kind| matches!(kind, SectionKind::RegFile))
+    );
     println!(
         "  frames:             {} bytes ({} frames, {} labels, {} locals total)",
-        frames_size, frames_count, total_labels, total_locals
+        section_size(|kind| matches!(kind, SectionKind::Frames)),
+        stacks.activation_frame_stack.len(),
+        total_labels,
+        total_locals
     );
     println!(
-        "  memory_data:        {} bytes (raw {} bytes, LZ4 compressed)",
-        memory_size, mem_raw_size
+        "  memory_data:        {} bytes ({} memories in {} instances, raw {} bytes, LZ4 compressed)",
+        section_size(|kind| matches!(kind, SectionKind::Memory { .. })),
+        state.memory_data_compressed.len(),
+        state.instances.len(),
+        mem_raw_size
+    );
+    println!(
+        "  global_values:      {} bytes",
+        section_size(|kind| matches!(kind, SectionKind::Globals))
+    );
+    println!(
+        "  tables:             {} bytes ({} modified)",
+        section_size(|kind| matches!(kind, SectionKind::Table { .. })),
+        sections
+            .iter()
+            .filter(|section| matches!(section.kind, SectionKind::Table { .. }))
+            .count()
+    );
+    println!(
+        "  wasi:               {} bytes ({} open fds)",
+        section_size(|kind| matches!(kind, SectionKind::Wasi { .. })),
+        state
+            .instances
+            .iter()
+            .filter_map(|instance_state| instance_state.wasi.as_ref())
+            .map(|wasi| wasi.fds.len())
+            .sum::<usize>()
     );
-    println!("  global_values:      {} bytes", globals_size);
-    println!("  frame_func_indices: {} bytes", indices_size);
 
-    let encoded: Vec<u8> =
-        bincode::serialize(&state).map_err(|e| RuntimeError::SerializationError(e.to_string()))?;
+    // 5. Serialize: magic, format version, header, sections
+    let mut encoded = CHECKPOINT_MAGIC.to_vec();
+    encoded.extend_from_slice(&CHECKPOINT_FORMAT_VERSION.to_le_bytes());
+    bincode::serialize_into(&mut encoded, &CheckpointHeader::new(instances))
+        .map_err(|e| RuntimeError::SerializationError(e.to_string()))?;
+    bincode::serialize_into(&mut encoded, &sections)
+        .map_err(|e| RuntimeError::SerializationError(e.to_string()))?;
 
     println!("  total encoded:      {} bytes", encoded.len());
 
-    let mut file =
-        File::create(output_path).map_err(|e| RuntimeError::CheckpointSaveError(e.to_string()))?;
-    file.write_all(&encoded)
-        .map_err(|e| RuntimeError::CheckpointSaveError(e.to_string()))?;
-
-    println!("Checkpoint successful.");
-    Ok(())
+    Ok(encoded)
 }
 
 /// Restores runtime state from a checkpoint file.
 ///
 /// Reads serialized state and restores memory, globals, and stacks.
+/// `instances` must be instantiated from the same modules, in the same
+/// order, as the ones passed to `checkpoint`.
 pub fn restore<P: AsRef<Path>>(
-    module_inst: Rc<ModuleInst>,
+    instances: &[Rc<ModuleInst>],
     input_path: P,
 ) -> Result<Stacks, RuntimeError> {
     println!("Restoring state from {:?}...", input_path.as_ref());
@@ -288,45 +1198,171 @@ pub fn restore<P: AsRef<Path>>(
     file.read_to_end(&mut encoded)
         .map_err(|e| RuntimeError::CheckpointLoadError(e.to_string()))?;
 
-    // 2. Deserialize the state using bincode
-    let mut state: SerializableState = bincode::deserialize(&encoded[..])
-        .map_err(|e| RuntimeError::DeserializationError(e.to_string()))?;
+    restore_from_bytes(instances, &encoded)
+}
 
-    // 3. Restore memory state (LZ4 decompress)
-    if let Some(mem_addr) = module_inst.mem_addrs.get(0) {
-        let memory_data = lz4_flex::decompress_size_prepended(&state.memory_data_compressed)
-            .map_err(|e| {
-                RuntimeError::DeserializationError(format!("LZ4 decompression failed: {}", e))
-            })?;
-        mem_addr.set_data(memory_data);
-        println!("Memory state restored into module instance.");
-    } else if !state.memory_data_compressed.is_empty() {
-        eprintln!("Warning: Checkpoint contains memory data, but module has no memory instance.");
+/// Restores runtime state from an in-memory checkpoint image produced by
+/// `checkpoint_to_bytes`.
+///
+/// The header, every section checksum, the frame indices and the memory,
+/// global and table layouts are verified, all memories are decompressed and
+/// the WASI file descriptors are reopened before anything is written to
+/// `instances`, so a corrupted image or a host missing one of the files
+/// leaves them untouched.
+pub fn restore_from_bytes(
+    instances: &[Rc<ModuleInst>],
+    encoded: &[u8],
+) -> Result<Stacks, RuntimeError> {
+    // 2. Check the header against this build and the instances
+    let (header, mut encoded) = read_checkpoint_header(encoded)?;
+    header.check(instances)?;
+
+    // 3. Read the section table and verify every checksum
+    let sections: Vec<Section> = bincode::deserialize_from(&mut encoded).map_err(|e| {
+        RuntimeError::CheckpointLoadError(format!("Truncated or corrupt checkpoint: {}", e))
+    })?;
+    if !encoded.is_empty() {
+        return Err(RuntimeError::CheckpointLoadError(format!(
+            "{} unexpected trailing bytes after the checkpoint",
+            encoded.len()
+        )));
+    }
+    for section in &sections {
+        section.verify()?;
     }
+    let mut state = SerializableState::from_sections(sections, instances)?;
 
-    // 4. Restore global state into module_inst
-    if module_inst.global_addrs.len() == state.global_values.len() {
-        for (global_addr, value) in module_inst.global_addrs.iter().zip(state.global_values) {
-            global_addr.set(value)?;
+    // 4. Validate frames, memories, globals and tables and decompress
+    //    memories before applying anything
+    for (&instance_idx, &func_idx) in state
+        .frame_instance_indices
+        .iter()
+        .zip(state.frame_func_indices.iter())
+    {
+        let in_range = instances
+            .get(instance_idx as usize)
+            .is_some_and(|inst| (func_idx as usize) < inst.func_addrs.len());
+        if !in_range {
+            return Err(RuntimeError::CheckpointLoadError(
+                "Frame instance or function index out of range".to_string(),
+            ));
         }
-        println!("Global state restored into module instance.");
-    } else {
-        eprintln!(
-            "Warning: Mismatch in global variable count between module ({}) and checkpoint ({}). Globals not restored.",
-            module_inst.global_addrs.len(),
+    }
+    let (mem_addrs, mem_indices) = unique_addrs(instances, |inst| &inst.mem_addrs, MemAddr::ptr_eq);
+    let (global_addrs, global_indices) =
+        unique_addrs(instances, |inst| &inst.global_addrs, GlobalAddr::ptr_eq);
+    if mem_addrs.len() != state.memory_data_compressed.len() {
+        return Err(RuntimeError::CheckpointLoadError(format!(
+            "Mismatch in memory count between module ({}) and checkpoint ({})",
+            mem_addrs.len(),
+            state.memory_data_compressed.len()
+        )));
+    }
+    if global_addrs.len() != state.global_values.len() {
+        return Err(RuntimeError::CheckpointLoadError(format!(
+            "Mismatch in global variable count between module ({}) and checkpoint ({})",
+            global_addrs.len(),
             state.global_values.len()
-        );
+        )));
+    }
+    for (global, (global_addr, value)) in global_addrs.iter().zip(&state.global_values).enumerate()
+    {
+        let expected = global_addr.get().val_type();
+        if expected != value.val_type() {
+            return Err(RuntimeError::CheckpointLoadError(format!(
+                "Global {} has type {:?} in the module but {:?} in the checkpoint",
+                global,
+                expected,
+                value.val_type()
+            )));
+        }
+    }
+    for (instance, ((module_inst, instance_state), (memories, globals))) in instances
+        .iter()
+        .zip(&state.instances)
+        .zip(mem_indices.iter().zip(&global_indices))
+        .enumerate()
+    {
+        if instance_state.memories != *memories || instance_state.globals != *globals {
+            return Err(RuntimeError::CheckpointLoadError(format!(
+                "Instance {} shares memories or globals differently than in the checkpoint",
+                instance
+            )));
+        }
+        for (table, diff) in &instance_state.table_diffs {
+            if (*table as usize) >= module_inst.table_addrs.len() || !diff.in_bounds() {
+                return Err(RuntimeError::CheckpointLoadError(format!(
+                    "Table {} out of range or inconsistent",
+                    table
+                )));
+            }
+        }
+    }
+    let memories = state
+        .memory_data_compressed
+        .iter()
+        .map(|compressed| {
+            lz4_flex::decompress_size_prepended(compressed).map_err(|e| {
+                RuntimeError::DeserializationError(format!("LZ4 decompression failed: {}", e))
+            })
+        })
+        .collect::<Result<Vec<Vec<u8>>, RuntimeError>>()?;
+
+    // 5. Reopen WASI file descriptors under their original numbers. Every
+    //    check that can fail has run by now, so only a failed reopen has to
+    //    roll back the descriptors reopened before it
+    let mut reopened: Vec<(&PassthroughWasiImpl, &WasiState)> = Vec::new();
+    for (module_inst, instance_state) in instances.iter().zip(&state.instances) {
+        let (Some(wasi_impl), Some(wasi)) = (&module_inst.wasi_impl, &instance_state.wasi) else {
+            continue;
+        };
+        if let Err(e) = wasi_impl.restore_state(wasi) {
+            for (wasi_impl, wasi) in reopened {
+                wasi_impl.discard_state(wasi);
+            }
+            return Err(RuntimeError::CheckpointLoadError(e));
+        }
+        reopened.push((wasi_impl, wasi));
+    }
+
+    // 6. Restore memory state, once per distinct memory
+    for (mem_addr, data) in mem_addrs.iter().zip(memories) {
+        mem_addr.set_data(data);
+    }
+    if !mem_addrs.is_empty() {
+        println!("Memory state restored into module instances.");
+    }
+
+    // 7. Restore global state, once per distinct global (types checked in
+    //    step 4)
+    for (global_addr, value) in global_addrs.iter().zip(state.global_values) {
+        global_addr
+            .set(value)
+            .expect("global type checked before restoring");
+    }
+    println!("Global state restored into module instances.");
+
+    for (module_inst, instance_state) in instances.iter().zip(state.instances) {
+        // 8. Reapply changes to tables modified since instantiation (bounds
+        //    checked in step 4)
+        for (table, diff) in instance_state.table_diffs {
+            module_inst.table_addrs[table as usize]
+                .apply_diff(&diff)
+                .expect("table diff checked before restoring");
+        }
     }
 
-    // 5. Reconstruct skipped fields in Stacks (Frame::module, primary_mem, processed_instrs)
-    let primary_mem = module_inst.mem_addrs.first().cloned();
-    for (frame_stack, &func_idx) in state
+    // 9. Reconstruct skipped fields in Stacks (Frame::module, primary_mem, processed_instrs)
+    for ((frame_stack, &instance_idx), &func_idx) in state
         .stacks
         .activation_frame_stack
         .iter_mut()
+        .zip(state.frame_instance_indices.iter())
         .zip(state.frame_func_indices.iter())
     {
-        frame_stack.frame.module = Rc::downgrade(&module_inst);
+        let module_inst = &instances[instance_idx as usize];
+        let primary_mem = module_inst.mem_addrs.first().cloned();
+        frame_stack.frame.module = Rc::downgrade(module_inst);
         frame_stack.primary_mem = primary_mem.clone();
 
         // Reconstruct skipped fields from module function body
@@ -2,8 +2,17 @@
 
 use super::value::*;
 use super::{
-    data::DataAddr, elem::ElemAddr, export::ExportInst, func::FuncAddr, global::GlobalAddr,
-    mem::MemAddr, table::TableAddr,
+    data::DataAddr,
+    elem::ElemAddr,
+    export::ExportInst,
+    func::{FuncAddr, FuncInst},
+    global::GlobalAddr,
+    host::Caller,
+    mem::MemAddr,
+    migration::{CheckpointConfig, CheckpointRequest},
+    runtime::Runtime,
+    table::TableAddr,
+    tag::TagAddr,
 };
 use crate::error::RuntimeError;
 use crate::structure::{instructions::*, module::*, types::*};
@@ -19,11 +28,15 @@ pub struct ModuleInst {
     pub table_addrs: Vec<TableAddr>,
     pub mem_addrs: Vec<MemAddr>,
     pub global_addrs: Vec<GlobalAddr>,
+    pub tag_addrs: Vec<TagAddr>,
     pub elem_addrs: Vec<ElemAddr>,
     pub data_addrs: Vec<DataAddr>,
     pub exports: Vec<ExportInst>,
+    pub start: Option<FuncAddr>,
     pub wasi_func_addrs: Vec<WasiFuncAddr>,
     pub wasi_impl: Option<Arc<PassthroughWasiImpl>>,
+    /// Fingerprint of the module binary this instance was created from.
+    pub fingerprint: u64,
 }
 
 /// Trait for indexed access to instance vectors.
@@ -43,16 +56,52 @@ impl GetInstanceByIdx<FuncIdx> for Vec<FuncAddr> {}
 impl GetInstanceByIdx<TableIdx> for Vec<TableAddr> {}
 impl GetInstanceByIdx<MemIdx> for Vec<MemAddr> {}
 impl GetInstanceByIdx<GlobalIdx> for Vec<GlobalAddr> {}
+impl GetInstanceByIdx<TagIdx> for Vec<TagAddr> {}
 
 /// Map of module name -> (export name -> external value) for imports.
 pub type ImportObjects = FxHashMap<String, FxHashMap<String, Externval>>;
 
+/// Looks up `import` in `imports` and returns the extern if it is of the
+/// kind selected by `as_kind` and its type satisfies `type_matches`.
+/// Fails with `LinkError` if the import is missing or does not match.
+fn resolve_import<T>(
+    imports: &ImportObjects,
+    import: &Import,
+    as_kind: fn(Externval) -> Option<T>,
+    type_matches: impl FnOnce(&T) -> bool,
+) -> Result<T, RuntimeError> {
+    imports
+        .get(&import.module.0)
+        .and_then(|module| module.get(&import.name.0))
+        .cloned()
+        .and_then(as_kind)
+        .filter(type_matches)
+        .ok_or(RuntimeError::LinkError)
+}
+
 impl ModuleInst {
     /// Instantiates a module with the given imports and command-line arguments.
+    ///
+    /// Runs the start function, if any, with checkpointing disabled. A trap
+    /// in the start function fails the instantiation.
     pub fn new(
         module: &Module,
         imports: ImportObjects,
         argv: Vec<String>,
+    ) -> Result<Rc<ModuleInst>, RuntimeError> {
+        let inst = Self::new_without_start(module, imports, argv)?;
+        inst.run_start(None, &[])?;
+        Ok(inst)
+    }
+
+    /// Instantiates a module without running its start function.
+    ///
+    /// Used when the start function is run separately with `run_start`, or
+    /// when restoring from a checkpoint where it has already run.
+    pub fn new_without_start(
+        module: &Module,
+        imports: ImportObjects,
+        argv: Vec<String>,
     ) -> Result<Rc<ModuleInst>, RuntimeError> {
         let mut module_inst = ModuleInst {
             types: module.types.clone(),
@@ -60,11 +109,14 @@ impl ModuleInst {
             table_addrs: Vec::new(),
             mem_addrs: Vec::new(),
             global_addrs: Vec::new(),
+            tag_addrs: Vec::new(),
             elem_addrs: Vec::new(),
             data_addrs: Vec::new(),
             exports: Vec::new(),
+            start: None,
             wasi_func_addrs: Vec::new(),
             wasi_impl: None,
+            fingerprint: module.fingerprint,
         };
 
         // Check if we need WASI support
@@ -82,19 +134,11 @@ impl ModuleInst {
             for import in &module.imports {
                 match &import.desc {
                     ImportDesc::Func(idx) => {
-                        let val = imports
-                            .get(&import.module.0)
-                            .and_then(|module| module.get(&import.name.0))
-                            .cloned()
-                            .ok_or_else(|| RuntimeError::LinkError)?;
-                        module_inst.func_addrs.push(
-                            val.as_func()
-                                .filter(|func| {
-                                    func.func_type()
-                                        .type_match(&module_inst.types[idx.0 as usize])
-                                })
-                                .ok_or_else(|| RuntimeError::LinkError)?,
-                        );
+                        let func = resolve_import(&imports, import, Externval::as_func, |func| {
+                            func.func_type()
+                                .type_match(&module_inst.types[idx.0 as usize])
+                        })?;
+                        module_inst.func_addrs.push(func);
                     }
                     ImportDesc::WasiFunc(wasi_func_type) => {
                         // Create WASI function address
@@ -104,7 +148,34 @@ impl ModuleInst {
                             .push(FuncAddr::alloc_wasi(wasi_func_addr.clone()));
                         module_inst.wasi_func_addrs.push(wasi_func_addr);
                     }
-                    _ => todo!(),
+                    ImportDesc::Tag(idx) => {
+                        let tag = resolve_import(&imports, import, Externval::as_tag, |tag| {
+                            *tag.tag_type() == module_inst.types[idx.0 as usize]
+                        })?;
+                        module_inst.tag_addrs.push(tag);
+                    }
+                    ImportDesc::Table(type_) => {
+                        let table =
+                            resolve_import(&imports, import, Externval::as_table, |table| {
+                                let actual = table.table_type();
+                                actual.1 == type_.1 && actual.0.type_match(&type_.0)
+                            })?;
+                        module_inst.table_addrs.push(table);
+                    }
+                    ImportDesc::Mem(type_) => {
+                        let mem = resolve_import(&imports, import, Externval::as_mem, |mem| {
+                            let actual = mem.mem_type();
+                            actual.1 == type_.1 && actual.0.type_match(&type_.0)
+                        })?;
+                        module_inst.mem_addrs.push(mem);
+                    }
+                    ImportDesc::Global(type_) => {
+                        let global =
+                            resolve_import(&imports, import, Externval::as_global, |global| {
+                                global.global_type() == *type_
+                            })?;
+                        module_inst.global_addrs.push(global);
+                    }
                 }
             }
         }
@@ -118,7 +189,13 @@ impl ModuleInst {
         }
 
         for mem in &module.mems {
-            module_inst.mem_addrs.push(MemAddr::new(&mem.type_))
+            module_inst.mem_addrs.push(MemAddr::new(&mem.type_)?)
+        }
+
+        for tag in &module.tags {
+            module_inst
+                .tag_addrs
+                .push(TagAddr::new(&module_inst.types[tag.type_.0 as usize]))
         }
 
         for global in &module.globals {
@@ -131,18 +208,20 @@ impl ModuleInst {
         }
 
         for elem in &module.elems {
-            if elem.type_ == RefType::ExternalRef {
-                panic!();
-            }
-            let mut init: Vec<i32> = Vec::new();
-            if let Some(idxes) = &elem.idxes {
-                for idx in idxes {
-                    init.push(idx.0 as i32);
-                }
-            }
-            module_inst
-                .elem_addrs
-                .push(ElemAddr::new(&elem.type_, &module_inst.func_addrs, &init));
+            let refs = match (&elem.idxes, &elem.init) {
+                (Some(idxes), _) => idxes
+                    .iter()
+                    .map(|idx| Ref::FuncAddr(module_inst.func_addrs.get_by_idx(*idx).clone()))
+                    .collect(),
+                (None, Some(exprs)) => exprs
+                    .iter()
+                    .map(|expr| match module_inst.expr_to_const(expr) {
+                        Some(Val::Ref(r)) => Ok(r),
+                        _ => Err(RuntimeError::InvalidConstantExpression),
+                    })
+                    .collect::<Result<Vec<Ref>, RuntimeError>>()?,
+                (None, None) => Vec::new(),
+            };
 
             if elem.mode == ElemMode::Active {
                 let offset_res = match &elem.offset {
@@ -158,12 +237,29 @@ impl ModuleInst {
                     Some(i) => i.0,
                     None => 0,
                 };
-                module_inst.table_addrs[table_idx as usize].init(
-                    offset as usize,
-                    &module_inst.func_addrs,
-                    &init,
-                );
+                module_inst
+                    .table_addrs
+                    .get(table_idx as usize)
+                    .ok_or(RuntimeError::TableNotFound)?
+                    .init(offset as u32, &refs, 0, refs.len() as u32)?;
             }
+
+            // Active and declarative segments are dropped once instantiated
+            let refs = match elem.mode {
+                ElemMode::Passive => refs,
+                ElemMode::Active | ElemMode::Declarative => Vec::new(),
+            };
+            module_inst
+                .elem_addrs
+                .push(ElemAddr::new(&elem.type_, refs));
+        }
+
+        // Tables as left by the element segments are the baseline that
+        // checkpoints diff against. Imported tables keep the baseline of the
+        // instance that defines them.
+        let imported_tables = module_inst.table_addrs.len() - module.tables.len();
+        for table_addr in &module_inst.table_addrs[imported_tables..] {
+            table_addr.mark_initial();
         }
 
         for data in &module.datas {
@@ -171,21 +267,28 @@ impl ModuleInst {
             module_inst.data_addrs.push(DataAddr::new(&init));
 
             if data.mode == DataMode::Active {
-                let offset_res = match &data.offset {
-                    Some(x) => module_inst
+                // i64 offsets belong to 64-bit memories
+                let offset = match &data.offset {
+                    Some(x) => match module_inst
                         .expr_to_const(x)
                         .ok_or(RuntimeError::InvalidConstantExpression)?
-                        .to_i32(),
-                    None => Ok(0),
+                    {
+                        Val::Num(Num::I64(v)) => v as u64,
+                        v => v.to_i32()? as u32 as u64,
+                    },
+                    None => 0,
                 };
-                let offset = offset_res?;
 
                 let idx = match &data.memory {
                     Some(i) => i.0,
                     None => 0,
                 };
 
-                module_inst.mem_addrs[idx as usize].init(offset as usize, &init);
+                module_inst
+                    .mem_addrs
+                    .get(idx as usize)
+                    .ok_or(RuntimeError::MemoryNotFound)?
+                    .init(offset, &init)?;
             }
         }
 
@@ -205,9 +308,21 @@ impl ModuleInst {
                     ExportDesc::Global(idx) => {
                         Externval::Global(module_inst.global_addrs[idx.0 as usize].clone())
                     }
+                    ExportDesc::Tag(idx) => {
+                        Externval::Tag(module_inst.tag_addrs[idx.0 as usize].clone())
+                    }
                 },
             })
         }
+        if let Some(start) = &module.start {
+            module_inst.start = Some(
+                module_inst
+                    .func_addrs
+                    .get(start.func.0 as usize)
+                    .ok_or(RuntimeError::InstantiateFailed)?
+                    .clone(),
+            );
+        }
         let arc_module_inst = Rc::new(module_inst);
 
         for (base, func) in module.funcs.iter().enumerate() {
@@ -224,12 +339,36 @@ impl ModuleInst {
             arc_module_inst.func_addrs[index]
                 .replace(func.clone(), Rc::downgrade(&arc_module_inst));
         }
-        if let Some(start) = &module.start {
-            arc_module_inst.func_addrs.get_by_idx(start.func);
-        }
         Ok(arc_module_inst)
     }
 
+    /// Runs the start function of this instance, if it has one.
+    ///
+    /// With a `checkpoint` configuration, a checkpoint may be taken during
+    /// the start function; this returns `CheckpointRequested` after the
+    /// state has been saved. `linked_instances` are the other instances this one
+    /// is linked with, which the checkpoint covers as well.
+    pub fn run_start(
+        self: &Rc<Self>,
+        checkpoint: Option<&CheckpointConfig>,
+        linked_instances: &[Rc<ModuleInst>],
+    ) -> Result<(), RuntimeError> {
+        let Some(start) = &self.start else {
+            return Ok(());
+        };
+        if let FuncInst::HostFunc { host_code, .. } = start.read_lock() {
+            // No runtime runs a host start function, so nothing can be paused
+            let caller = Caller::new(Rc::clone(self), CheckpointRequest::default());
+            return host_code(&caller, Vec::new()).map(|_| ());
+        }
+        let mut runtime = Runtime::new_start(Rc::clone(self), start, checkpoint.is_some())?;
+        if let Some(config) = checkpoint {
+            runtime.set_checkpoint_config(config.clone());
+        }
+        runtime.set_linked_instances(linked_instances.to_vec());
+        runtime.run().map(|_| ())
+    }
+
     /// Looks up an exported function by name.
     pub fn get_export_func(&self, name: &str) -> Result<FuncAddr, RuntimeError> {
         let externval = self
@@ -254,6 +393,9 @@ impl ModuleInst {
             &[Instr::V128Const(i)] => Some(Val::Vec_(Vec_::V128(i))),
             &[Instr::RefNull(_)] => Some(Val::Ref(Ref::RefNull)),
             [Instr::GlobalGet(i)] => Some(self.global_addrs.get_by_idx(*i).get()),
+            [Instr::RefFunc(i)] => Some(Val::Ref(Ref::FuncAddr(
+                self.func_addrs.get_by_idx(*i).clone(),
+            ))),
             _ => None,
         }
     }
@@ -1,7 +1,8 @@
 //! Operand read/write helpers for v2 dispatcher handlers.
 //!
 //! These functions bridge the typed operand enums (`I32RegOperand`,
-//! `I64RegOperand`, `F32RegOperand`, `F64RegOperand`, `RegOrLocal`, `Reg`)
+//! `I64RegOperand`, `F32RegOperand`, `F64RegOperand`, `AddrOperand`,
+//! `RegOrLocal`, `Reg`)
 //! defined in `ir.rs` to the values they refer to. All access goes through
 //! the safe accessor methods on `VmState`, so the helpers themselves can be
 //! written in safe Rust; the only remaining `unsafe` is the small
@@ -12,7 +13,7 @@
 //! without function-call overhead per operand access.
 
 use crate::execution::ir::{
-    F32RegOperand, F64RegOperand, I32RegOperand, I64RegOperand, RegOrLocal,
+    AddrOperand, F32RegOperand, F64RegOperand, I32RegOperand, I64RegOperand, RegOrLocal,
 };
 use crate::execution::regs::Reg;
 use crate::execution::state::VmState;
@@ -162,6 +163,19 @@ pub fn write_f64dst_i32(state: &mut VmState, dst: &F64RegOperand, val: i32) {
     }
 }
 
+// ============================================================================
+// Memory address (AddrOperand: I32 | I64) — used by memory load/store
+// ============================================================================
+
+/// Reads an address as u64; i32 addresses are zero-extended.
+#[inline(always)]
+pub fn read_addr(state: &VmState, op: &AddrOperand) -> u64 {
+    match op {
+        AddrOperand::I32(op) => read_i32(state, op) as u32 as u64,
+        AddrOperand::I64(op) => read_i64(state, op) as u64,
+    }
+}
+
 // ============================================================================
 // Reg (typed register: I32/I64/F32/F64/Ref/V128) — used by ConversionReg src
 // ============================================================================
@@ -174,6 +188,23 @@ pub fn read_reg_i32(state: &VmState, reg: &Reg) -> i32 {
 pub fn read_reg_i64(state: &VmState, reg: &Reg) -> i64 {
     state.reg_file().get_i64(reg.index())
 }
+/// Reads an address or length register of a memory instruction: I64
+/// registers for 64-bit memories, zero-extended I32 registers otherwise.
+#[inline(always)]
+pub fn read_reg_addr(state: &VmState, reg: &Reg) -> u64 {
+    match reg {
+        Reg::I64(idx) => state.reg_file().get_i64(*idx) as u64,
+        _ => state.reg_file().get_i32(reg.index()) as u32 as u64,
+    }
+}
+/// Writes a page count (or -1 on failed grow) to an I32 or I64 register.
+#[inline(always)]
+pub fn write_reg_addr(state: &mut VmState, reg: &Reg, val: u64) {
+    match reg {
+        Reg::I64(idx) => state.reg_file_mut().set_i64(*idx, val as i64),
+        _ => state.reg_file_mut().set_i32(reg.index(), val as i32),
+    }
+}
 #[inline(always)]
 pub fn read_reg_f32(state: &VmState, reg: &Reg) -> f32 {
     state.reg_file().get_f32(reg.index())
@@ -385,7 +385,11 @@ impl RegFile {
                     self.set_ref(*idx, r.clone());
                 }
             }
-            Reg::V128(_) => {}
+            Reg::V128(idx) => {
+                if let Val::Vec_(Vec_::V128(v)) = val {
+                    self.set_v128(*idx, *v);
+                }
+            }
         }
     }
 
@@ -3,28 +3,53 @@
 use crate::error::RuntimeError;
 use crate::execution::dispatch;
 use crate::execution::func::{FuncAddr, FuncInst};
+use crate::execution::handlers;
+use crate::execution::host::Caller;
 use crate::execution::ir::Outcome;
-use crate::execution::migration;
+use crate::execution::migration::{self, CheckpointConfig, CheckpointRequest, CheckpointTrigger};
 use crate::execution::module::ModuleInst;
 use crate::execution::regs::{Reg, RegFile};
 use crate::execution::state::VmState;
 use crate::execution::state::{Frame, FrameStack, Label, LabelStack, ModuleLevelInstr, Stacks};
 use crate::execution::stats::ExecutionStats;
+use crate::execution::tag::ExnAddr;
 #[cfg(feature = "trace")]
 use crate::execution::trace::{TraceConfig, Tracer};
-use crate::execution::value::{Num, Val, Vec_};
-use crate::structure::module::WasiFuncType;
-use crate::structure::types::{NumType, ValueType, VecType};
+use crate::execution::value::{Num, Ref, Val, Vec_};
+use crate::structure::module::{Func, WasiFuncType};
+use crate::structure::types::{FuncType, NumType, ValueType, VecType};
 use crate::wasi::{WasiError, WasiResult};
 use arrayvec::ArrayVec;
 use std::path::Path;
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
 #[cfg(all(target_os = "wasi", target_env = "p1", target_feature = "atomics"))]
 use std::sync::Once;
+use std::time::{Duration, Instant};
+
+/// Instruction budget meaning "run until the guest finishes".
+const UNBOUNDED_FUEL: u64 = u64::MAX;
+
+/// Instructions executed between deadline checks in `run_for_duration`.
+const DEADLINE_POLL_INTERVAL: u64 = 1024;
+
+/// Outcome of a step-bounded run.
+#[derive(Debug, PartialEq)]
+pub enum RunStatus {
+    /// The entry function returned these values.
+    Finished(Vec<Val>),
+    /// The budget ran out before the guest finished. The runtime keeps its
+    /// state and can be resumed, checkpointed, or dropped.
+    Paused,
+    /// The runtime's `CheckpointRequest` was set while the guest ran. The
+    /// runtime is paused as with `Paused`; no checkpoint file is written.
+    CheckpointRequested,
+}
 
 /// Execution entry point that manages the interpreter loop.
 pub struct Runtime {
     module_inst: Rc<ModuleInst>,
+    /// Other instances linked with `module_inst`, in instantiation order.
+    linked_instances: Vec<Rc<ModuleInst>>,
     stacks: Stacks,
     execution_stats: Option<ExecutionStats>,
     #[cfg(feature = "trace")]
@@ -32,13 +57,22 @@ pub struct Runtime {
     #[cfg_attr(not(feature = "stats"), allow(dead_code))]
     enable_stats: bool,
     enable_checkpoint: bool,
+    /// Checkpoint file and triggers used when checkpointing is enabled.
+    checkpoint_config: CheckpointConfig,
+    /// In-process checkpoint requests, shared with host calls.
+    checkpoint_request: CheckpointRequest,
+    /// Poll throttle counter, carried across frames so that loops making
+    /// calls still reach the polling interval.
+    checkpoint_poll_counter: u32,
+    /// Instructions left before the current bounded run pauses.
+    fuel: u64,
 }
 
 impl Drop for Runtime {
     fn drop(&mut self) {
         #[cfg(feature = "stats")]
         if self.enable_stats {
-            if let Some(ref stats) = self.execution_stats {
+            if let Some(ref mut stats) = self.execution_stats {
                 stats.report();
             }
         }
@@ -57,6 +91,19 @@ impl Runtime {
     ) -> Result<Self, RuntimeError> {
         let stacks = Stacks::new(func_addr, params)?;
 
+        #[cfg_attr(not(feature = "stats"), allow(unused_mut))]
+        let mut execution_stats = if enable_stats {
+            Some(ExecutionStats::new(Rc::as_ptr(&module_inst)))
+        } else {
+            None
+        };
+        #[cfg(feature = "stats")]
+        if let Some(ref mut stats) = execution_stats {
+            if let Some((instance, func)) = Self::func_location(func_addr) {
+                stats.record_call(instance, func);
+            }
+        }
+
         #[cfg(feature = "trace")]
         let tracer = if let Some(config) = trace_config {
             match Tracer::new(config) {
@@ -72,19 +119,42 @@ impl Runtime {
 
         Ok(Runtime {
             module_inst,
+            linked_instances: Vec::new(),
             stacks,
-            execution_stats: if enable_stats {
-                Some(ExecutionStats::new())
-            } else {
-                None
-            },
+            execution_stats,
             #[cfg(feature = "trace")]
             tracer,
             enable_stats,
             enable_checkpoint,
+            checkpoint_config: CheckpointConfig::default(),
+            checkpoint_request: CheckpointRequest::default(),
+            checkpoint_poll_counter: 0,
+            fuel: UNBOUNDED_FUEL,
         })
     }
 
+    /// Creates a runtime for a module's start function.
+    ///
+    /// The state is marked as `in_start` so that a checkpoint taken during
+    /// start is resumed before the entry function on restore.
+    pub fn new_start(
+        module_inst: Rc<ModuleInst>,
+        func_addr: &FuncAddr,
+        enable_checkpoint: bool,
+    ) -> Result<Self, RuntimeError> {
+        let mut runtime = Self::new(
+            module_inst,
+            func_addr,
+            Vec::new(),
+            false,
+            enable_checkpoint,
+            #[cfg(feature = "trace")]
+            None,
+        )?;
+        runtime.stacks.in_start = true;
+        Ok(runtime)
+    }
+
     /// Creates a runtime restored from a checkpoint.
     ///
     /// Used to resume execution after restoring state from a checkpoint file.
@@ -108,21 +178,92 @@ impl Runtime {
             None
         };
 
+        let execution_stats = if enable_stats {
+            Some(ExecutionStats::new(Rc::as_ptr(&module_inst)))
+        } else {
+            None
+        };
+
         Runtime {
             module_inst,
+            linked_instances: Vec::new(),
             stacks,
-            execution_stats: if enable_stats {
-                Some(ExecutionStats::new())
-            } else {
-                None
-            },
+            execution_stats,
             #[cfg(feature = "trace")]
             tracer,
             enable_stats,
             enable_checkpoint,
+            checkpoint_config: CheckpointConfig::default(),
+            checkpoint_request: CheckpointRequest::default(),
+            checkpoint_poll_counter: 0,
+            fuel: UNBOUNDED_FUEL,
         }
     }
 
+    /// Sets the other instances linked with this runtime's module, in
+    /// instantiation order. Checkpoints cover these instances as well.
+    pub fn set_linked_instances(&mut self, instances: Vec<Rc<ModuleInst>>) {
+        self.linked_instances = instances;
+    }
+
+    /// Sets where checkpoints are written and which triggers request them.
+    /// Defaults to `./checkpoint.bin` and the `./checkpoint.trigger` file.
+    pub fn set_checkpoint_config(&mut self, config: CheckpointConfig) {
+        self.checkpoint_config = config;
+    }
+
+    /// Returns a handle for requesting an in-process checkpoint of this
+    /// runtime, e.g. from another part of the embedder.
+    pub fn checkpoint_request(&self) -> CheckpointRequest {
+        self.checkpoint_request.clone()
+    }
+
+    /// Returns the instance defining the guest function `func_addr` and its
+    /// index within that instance's function space.
+    #[cfg(feature = "stats")]
+    fn func_location(func_addr: &FuncAddr) -> Option<(*const ModuleInst, u32)> {
+        let FuncInst::RuntimeFunc { module, .. } = func_addr.read_lock() else {
+            return None;
+        };
+        let module = module.upgrade()?;
+        let idx = module
+            .func_addrs
+            .iter()
+            .position(|f| Rc::ptr_eq(f.get_rc(), func_addr.get_rc()))?;
+        Some((Rc::as_ptr(&module), idx as u32))
+    }
+
+    /// Attributes subsequent instruction counts to the function owning the
+    /// given frame stack.
+    #[cfg(feature = "stats")]
+    fn enter_frame_stats(&mut self, frame_stack_idx: usize) {
+        let Some(stats) = self.execution_stats.as_mut() else {
+            return;
+        };
+        let frame_stack = &self.stacks.activation_frame_stack[frame_stack_idx];
+        let body = &frame_stack.label_stack[0].processed_instrs;
+        let module = &frame_stack.frame.module;
+        let key = stats.function_key(Rc::as_ptr(body) as usize, || {
+            let module = module.upgrade()?;
+            let idx =
+                module
+                    .func_addrs
+                    .iter()
+                    .position(|func_addr| match func_addr.read_lock() {
+                        FuncInst::RuntimeFunc { code, .. } => Rc::ptr_eq(body, &code.body),
+                        _ => false,
+                    })?;
+            Some((Rc::as_ptr(&module), idx as u32))
+        });
+        stats.enter_function(key);
+    }
+
+    /// Returns the execution statistics collected so far, if enabled.
+    #[cfg(feature = "stats")]
+    pub fn execution_stats(&mut self) -> Option<&mut ExecutionStats> {
+        self.execution_stats.as_mut()
+    }
+
     /// Executes interpreter loop for a specific frame stack via the v2
     /// dispatcher (`dispatch::execute_instructions`). Constructs a `VmState`,
     /// runs dispatch, writes back state, and translates `Outcome` into the
@@ -132,9 +273,24 @@ impl Runtime {
         frame_stack_idx: usize,
         _called_func_addr: &mut Option<FuncAddr>,
     ) -> Result<Result<Option<ModuleLevelInstr>, RuntimeError>, RuntimeError> {
-        let module_ptr: *const ModuleInst = Rc::as_ptr(&self.module_inst);
+        #[cfg(feature = "stats")]
+        self.enter_frame_stats(frame_stack_idx);
+        #[cfg(feature = "stats")]
+        let stats_ptr: *mut ExecutionStats = self
+            .execution_stats
+            .as_mut()
+            .map_or(std::ptr::null_mut(), |s| s as *mut ExecutionStats);
+
         let reg_file_ptr: *mut RegFile = &mut self.stacks.reg_file as *mut RegFile;
         let frame_stack = &mut self.stacks.activation_frame_stack[frame_stack_idx];
+        // Functions imported from a linked instance run against their own
+        // instance; the upgraded handle keeps it alive during dispatch.
+        let module = frame_stack
+            .frame
+            .module
+            .upgrade()
+            .ok_or(RuntimeError::InstantiateFailed)?;
+        let module_ptr: *const ModuleInst = Rc::as_ptr(&module);
 
         let current_label_idx = frame_stack.label_stack.len().saturating_sub(1);
         let (instrs_ptr, instrs_len, pc) = {
@@ -147,12 +303,18 @@ impl Runtime {
         };
         let handlers_ptr = frame_stack.handlers.as_ptr();
         let mem_ptr = frame_stack.cached_mem_ptr.unwrap_or(std::ptr::null_mut());
+        let mem_len = frame_stack.primary_mem.as_ref().map_or(0, |m| m.data_len());
         let locals_ptr = frame_stack.frame.locals.as_mut_ptr();
+        #[cfg(feature = "trace")]
+        let locals_len = frame_stack.frame.locals.len();
         let label_stack_ptr: *mut Vec<LabelStack> =
             &mut frame_stack.label_stack as *mut Vec<LabelStack>;
         let return_result_regs_ptr: *mut ArrayVec<Reg, 8> =
             &mut frame_stack.return_result_regs as *mut ArrayVec<Reg, 8>;
         let enable_checkpoint = frame_stack.enable_checkpoint;
+        let checkpoint_triggers: *const [CheckpointTrigger] =
+            self.checkpoint_config.triggers.as_slice();
+        let checkpoint_request: *const CheckpointRequest = &self.checkpoint_request;
 
         let mut state = VmState {
             reg_file: reg_file_ptr,
@@ -164,15 +326,31 @@ impl Runtime {
             label_stack: label_stack_ptr,
             current_label_idx,
             mem_ptr,
+            mem_len,
             module: module_ptr,
             trap: None,
             yielded: None,
             return_result_regs: return_result_regs_ptr,
             enable_checkpoint,
-            checkpoint_poll_counter: 0,
+            checkpoint_poll_counter: self.checkpoint_poll_counter,
+            checkpoint_triggers,
+            checkpoint_request,
+            fuel: self.fuel,
+            interruptible: enable_checkpoint || self.fuel != UNBOUNDED_FUEL,
+            #[cfg(feature = "stats")]
+            stats: stats_ptr,
+            #[cfg(feature = "trace")]
+            tracer: self
+                .tracer
+                .as_mut()
+                .map_or(std::ptr::null_mut(), |t| t as *mut Tracer),
+            #[cfg(feature = "trace")]
+            locals_len,
         };
 
         let outcome = dispatch::execute_instructions(&mut state);
+        self.fuel = state.fuel;
+        self.checkpoint_poll_counter = state.checkpoint_poll_counter;
 
         let idx = state.current_label_idx;
         if idx < state.label_stack().len() {
@@ -191,7 +369,10 @@ impl Runtime {
                 let err = state
                     .trap
                     .expect("Outcome::Trap returned without state.trap set");
-                if matches!(err, RuntimeError::CheckpointRequested) {
+                if matches!(
+                    err,
+                    RuntimeError::CheckpointRequested | RuntimeError::FuelExhausted
+                ) {
                     Ok(Err(err))
                 } else {
                     Err(err)
@@ -202,7 +383,97 @@ impl Runtime {
     }
 
     /// Executes the runtime and returns the result values.
+    ///
+    /// Also resumes a runtime paused by `run_for`, running it to completion.
     pub fn run(&mut self) -> Result<Vec<Val>, RuntimeError> {
+        match self.run_with_fuel(UNBOUNDED_FUEL)? {
+            RunStatus::Finished(values) => Ok(values),
+            RunStatus::Paused => Err(RuntimeError::FuelExhausted),
+            RunStatus::CheckpointRequested => Err(RuntimeError::CheckpointRequested),
+        }
+    }
+
+    /// Executes at most `instructions` instructions.
+    ///
+    /// Returns `RunStatus::Paused` if the guest has not finished by then.
+    /// A paused runtime continues from where it stopped on the next call to
+    /// `run_for`, `run_for_duration` or `resume`, so a scheduler can
+    /// time-slice many guests on one thread.
+    pub fn run_for(&mut self, instructions: u64) -> Result<RunStatus, RuntimeError> {
+        self.run_with_fuel(instructions)
+    }
+
+    /// Executes until the guest finishes or `duration` of host time has
+    /// elapsed.
+    ///
+    /// The deadline is checked every `DEADLINE_POLL_INTERVAL` instructions,
+    /// so a slice may overrun it by that many instructions.
+    pub fn run_for_duration(&mut self, duration: Duration) -> Result<RunStatus, RuntimeError> {
+        let deadline = Instant::now() + duration;
+        loop {
+            match self.run_with_fuel(DEADLINE_POLL_INTERVAL)? {
+                RunStatus::Paused if Instant::now() < deadline => continue,
+                status => return Ok(status),
+            }
+        }
+    }
+
+    /// Runs a paused runtime to completion.
+    pub fn resume(&mut self) -> Result<Vec<Val>, RuntimeError> {
+        self.run()
+    }
+
+    /// Returns `true` once the guest has returned from its entry function.
+    pub fn is_finished(&self) -> bool {
+        self.stacks.activation_frame_stack.is_empty()
+    }
+
+    /// Writes the state of a paused runtime and all linked instances to a
+    /// checkpoint file that `migration::restore` can resume from.
+    pub fn checkpoint<P: AsRef<Path>>(&self, path: P) -> Result<(), RuntimeError> {
+        migration::checkpoint(&self.instances(), &self.stacks, path)
+    }
+
+    /// Serializes the state of a paused runtime and all linked instances
+    /// without touching the filesystem.
+    pub fn snapshot(&self) -> Result<Vec<u8>, RuntimeError> {
+        migration::checkpoint_to_bytes(&self.instances(), &self.stacks)
+    }
+
+    /// Creates a runtime from bytes produced by `snapshot`.
+    ///
+    /// `module_inst` must be instantiated from the same module without
+    /// running its start function; its memories and globals are overwritten
+    /// with the snapshot's. Checkpointing stays enabled so the resumed guest
+    /// can request another snapshot, but no triggers are polled and no
+    /// checkpoint file is written unless `set_checkpoint_config` adds them.
+    /// Runtimes linked with other instances restore with
+    /// `migration::restore_from_bytes` and `new_restored` instead.
+    pub fn from_snapshot(module_inst: Rc<ModuleInst>, bytes: &[u8]) -> Result<Self, RuntimeError> {
+        let stacks = migration::restore_from_bytes(&[Rc::clone(&module_inst)], bytes)?;
+        let mut runtime = Self::new_restored(
+            module_inst,
+            stacks,
+            false,
+            true,
+            #[cfg(feature = "trace")]
+            None,
+        );
+        runtime.checkpoint_config.triggers.clear();
+        Ok(runtime)
+    }
+
+    /// Returns the main instance followed by the linked instances.
+    fn instances(&self) -> Vec<Rc<ModuleInst>> {
+        let mut instances = vec![Rc::clone(&self.module_inst)];
+        instances.extend(self.linked_instances.iter().cloned());
+        instances
+    }
+
+    /// Interpreter loop shared by `run` and the bounded variants.
+    fn run_with_fuel(&mut self, fuel: u64) -> Result<RunStatus, RuntimeError> {
+        self.fuel = fuel;
+
         // Setup checkpoint monitor thread (only for wasm32-wasip1-threads)
         #[cfg(all(
             target_arch = "wasm32",
@@ -211,10 +482,11 @@ impl Runtime {
             target_feature = "atomics"
         ))]
         {
-            if self.enable_checkpoint {
+            if self.enable_checkpoint && !self.checkpoint_config.triggers.is_empty() {
                 static INIT: Once = Once::new();
+                // Monitors are process-wide: the first runtime's triggers win.
                 INIT.call_once(|| {
-                    migration::setup_checkpoint_monitor();
+                    migration::setup_checkpoint_monitor(self.checkpoint_config.triggers.clone());
                 });
             }
         }
@@ -232,19 +504,16 @@ impl Runtime {
                 self.execute_frame(frame_stack_idx, &mut called_func_addr)?;
 
             match module_level_instr_result {
+                Err(RuntimeError::FuelExhausted) => {
+                    return Ok(RunStatus::Paused);
+                }
+                Err(RuntimeError::CheckpointRequested) if self.checkpoint_request.take() => {
+                    return Ok(RunStatus::CheckpointRequested);
+                }
                 Err(RuntimeError::CheckpointRequested) => {
                     println!("Runtime handling checkpoint request...");
-                    let checkpoint_path = Path::new("./checkpoint.bin");
-                    let mem_addrs = &self.module_inst.mem_addrs;
-                    let global_addrs = &self.module_inst.global_addrs;
-
-                    match migration::checkpoint(
-                        &self.module_inst,
-                        &self.stacks,
-                        mem_addrs,
-                        global_addrs,
-                        checkpoint_path,
-                    ) {
+                    self.checkpoint_config.disarm_expired();
+                    match self.checkpoint(&self.checkpoint_config.output_path) {
                         Ok(_) => {
                             println!("Checkpoint successful (Runtime).");
                             return Err(RuntimeError::CheckpointRequested);
@@ -275,8 +544,15 @@ impl Runtime {
                             params,
                             result_reg,
                         }) => {
-                            // Call WASI function directly with params from registers
-                            match self.call_wasi_function(&wasi_func_type, &params) {
+                            // Call WASI function directly with params from registers,
+                            // against the instance that imported it
+                            let module = self
+                                .stacks
+                                .activation_frame_stack
+                                .last()
+                                .and_then(|frame_stack| frame_stack.frame.module.upgrade())
+                                .ok_or(RuntimeError::InstantiateFailed)?;
+                            match Self::call_wasi_function(&module, &wasi_func_type, &params) {
                                 Ok(result) => {
                                     if let Some(reg) = result_reg {
                                         if let Some(val) = result {
@@ -301,6 +577,12 @@ impl Runtime {
                             result_regs,
                         }) => {
                             // Register-based function invocation - params already extracted
+                            #[cfg(feature = "stats")]
+                            if let Some(ref mut stats) = self.execution_stats {
+                                if let Some((instance, func)) = Self::func_location(&func_addr) {
+                                    stats.record_call(instance, func);
+                                }
+                            }
                             let func_inst_guard = func_addr.read_lock();
                             match &*func_inst_guard {
                                 FuncInst::RuntimeFunc {
@@ -308,55 +590,19 @@ impl Runtime {
                                     module: func_module_weak,
                                     code,
                                 } => {
-                                    let mut locals = params;
-                                    for v in code.locals.iter() {
-                                        for _ in 0..(v.0) {
-                                            locals.push(Val::default_value(&v.1)?);
-                                        }
-                                    }
-
-                                    if let Some(ref alloc) = code.reg_allocation {
-                                        self.stacks.reg_file.save_offsets(alloc);
-                                    }
-
                                     // Store result_regs in caller frame
                                     if let Some(caller) =
                                         self.stacks.activation_frame_stack.last_mut()
                                     {
                                         caller.result_regs = result_regs;
                                     }
-                                    // Cache primary memory address and raw pointer
-                                    let primary_mem = func_module_weak
-                                        .upgrade()
-                                        .and_then(|m| m.mem_addrs.first().cloned());
-                                    let cached_mem_ptr = primary_mem.as_ref().map(|m| m.data_ptr());
-
-                                    let new_frame = FrameStack {
-                                        frame: Frame {
-                                            locals,
-                                            module: func_module_weak.clone(),
-                                            n: type_.results.len(),
-                                        },
-                                        label_stack: vec![LabelStack {
-                                            label: Label {
-                                                is_loop: false,
-                                                return_ip: 0,
-                                            },
-                                            processed_instrs: code.body.clone(),
-                                            ip: 0,
-                                        }],
-                                        enable_checkpoint: self.enable_checkpoint,
-                                        result_regs: ArrayVec::new(),
-                                        return_result_regs: ArrayVec::new(),
-                                        primary_mem,
-                                        cached_mem_ptr,
-                                        handlers: code.handlers.clone(),
-                                    };
-                                    self.stacks.activation_frame_stack.push(new_frame);
+                                    self.push_frame(type_, func_module_weak, code, params)?;
                                 }
                                 FuncInst::HostFunc { host_code, .. } => {
-                                    // Host function with register-based params
-                                    match host_code(params) {
+                                    // Host function with register-based params, called
+                                    // with the instance of the calling frame
+                                    let caller = self.caller()?;
+                                    match host_code(&caller, params) {
                                         Ok(results) => {
                                             // Write results directly to registers
                                             for (reg, val) in result_regs.iter().zip(results.iter())
@@ -366,6 +612,13 @@ impl Runtime {
                                         }
                                         Err(e) => return Err(e),
                                     }
+                                    // Refresh cached memory pointer (the host may have grown memory)
+                                    if let Some(frame_stack) =
+                                        self.stacks.activation_frame_stack.last_mut()
+                                    {
+                                        frame_stack.cached_mem_ptr =
+                                            frame_stack.primary_mem.as_ref().map(|m| m.data_ptr());
+                                    }
                                 }
                                 FuncInst::WasiFunc { .. } => {
                                     return Err(RuntimeError::ExecutionFailed(
@@ -389,7 +642,9 @@ impl Runtime {
                                     .map(|reg| self.stacks.reg_file.get_val(reg))
                                     .collect();
                                 self.stacks.reg_file.restore_offsets();
-                                return Ok(values_to_pass.into_iter().collect());
+                                return Ok(RunStatus::Finished(
+                                    values_to_pass.into_iter().collect(),
+                                ));
                             } else {
                                 // First read values from finished frame's registers (before restore)
                                 // Use ArrayVec to avoid heap allocation
@@ -400,49 +655,175 @@ impl Runtime {
 
                                 // Restore offsets to caller's frame
                                 self.stacks.reg_file.restore_offsets();
+                                self.pass_results_to_caller(&values_to_pass);
+                            }
+                        }
+                        Some(ModuleLevelInstr::TailInvokeReg { func_addr, params }) => {
+                            #[cfg(feature = "stats")]
+                            if let Some(ref mut stats) = self.execution_stats {
+                                if let Some((instance, func)) = Self::func_location(&func_addr) {
+                                    stats.record_call(instance, func);
+                                }
+                            }
+                            // The callee replaces the current frame, so tail recursion
+                            // runs in constant activation frame stack depth.
+                            let caller = self.caller()?;
+                            self.stacks.activation_frame_stack.pop();
+                            self.stacks.reg_file.restore_offsets();
 
-                                // Write to caller's registers (after restore, in caller's coordinate system)
-                                let (reg_file, frames) = self.stacks.get_reg_file_and_frames();
-                                let caller_frame = frames.last_mut().unwrap();
-
-                                // Refresh cached memory pointer (may have changed due to memory.grow in callee)
-                                caller_frame.cached_mem_ptr =
-                                    caller_frame.primary_mem.as_ref().map(|m| m.data_ptr());
-
-                                if !caller_frame.result_regs.is_empty() {
-                                    for (caller_reg, val) in
-                                        caller_frame.result_regs.iter().zip(values_to_pass.iter())
-                                    {
-                                        reg_file.set_val(caller_reg, val);
+                            match func_addr.read_lock() {
+                                FuncInst::RuntimeFunc {
+                                    type_,
+                                    module: func_module_weak,
+                                    code,
+                                } => {
+                                    self.push_frame(type_, func_module_weak, code, params)?;
+                                }
+                                FuncInst::HostFunc { host_code, .. } => {
+                                    let results = host_code(&caller, params)?;
+                                    if self.stacks.activation_frame_stack.is_empty() {
+                                        return Ok(RunStatus::Finished(results));
                                     }
-                                    caller_frame.result_regs.clear();
+                                    self.pass_results_to_caller(&results);
+                                }
+                                FuncInst::WasiFunc { .. } => {
+                                    return Err(RuntimeError::ExecutionFailed(
+                                        "WASI function called via TailInvokeReg - use CallWasiReg",
+                                    ));
                                 }
                             }
                         }
+                        Some(ModuleLevelInstr::Throw(exn)) => self.unwind(&exn)?,
                     }
                 }
             }
         }
-        Ok(vec![])
+        Ok(RunStatus::Finished(vec![]))
+    }
+
+    /// Returns the host-call context for the instance of the current frame.
+    fn caller(&self) -> Result<Caller, RuntimeError> {
+        self.stacks
+            .activation_frame_stack
+            .last()
+            .and_then(|frame_stack| frame_stack.frame.module.upgrade())
+            .map(|module| Caller::new(module, self.checkpoint_request.clone()))
+            .ok_or(RuntimeError::InstantiateFailed)
+    }
+
+    /// Pops frames until one of them catches `exn`, leaving that frame ready
+    /// to resume at the catch clause's branch target.
+    fn unwind(&mut self, exn: &ExnAddr) -> Result<(), RuntimeError> {
+        loop {
+            self.stacks.activation_frame_stack.pop();
+            self.stacks.reg_file.restore_offsets();
+
+            let (reg_file, frames) = self.stacks.get_reg_file_and_frames();
+            let Some(frame_stack) = frames.last_mut() else {
+                return Err(RuntimeError::UncaughtException);
+            };
+            // The pending call never returns, so its result registers are dropped.
+            frame_stack.result_regs.clear();
+            frame_stack.cached_mem_ptr = frame_stack.primary_mem.as_ref().map(|m| m.data_ptr());
+
+            let module = frame_stack
+                .frame
+                .module
+                .upgrade()
+                .ok_or(RuntimeError::InstantiateFailed)?;
+            if let Some(target_ip) =
+                handlers::catch_exception(&mut frame_stack.label_stack, reg_file, &module, exn)
+            {
+                if let Some(label) = frame_stack.label_stack.last_mut() {
+                    label.ip = target_ip;
+                }
+                return Ok(());
+            }
+        }
+    }
+
+    /// Pushes a new activation frame that runs `code` with `params` as its
+    /// leading locals.
+    fn push_frame(
+        &mut self,
+        type_: &FuncType,
+        module: &Weak<ModuleInst>,
+        code: &Func,
+        params: Vec<Val>,
+    ) -> Result<(), RuntimeError> {
+        let mut locals = params;
+        for v in code.locals.iter() {
+            for _ in 0..(v.0) {
+                locals.push(Val::default_value(&v.1)?);
+            }
+        }
+
+        if let Some(ref alloc) = code.reg_allocation {
+            self.stacks.reg_file.save_offsets(alloc);
+        }
+
+        // Cache primary memory address and raw pointer
+        let primary_mem = module.upgrade().and_then(|m| m.mem_addrs.first().cloned());
+        let cached_mem_ptr = primary_mem.as_ref().map(|m| m.data_ptr());
+
+        let new_frame = FrameStack {
+            frame: Frame {
+                locals,
+                module: module.clone(),
+                n: type_.results.len(),
+            },
+            label_stack: vec![LabelStack {
+                label: Label {
+                    is_loop: false,
+                    return_ip: 0,
+                    try_ip: None,
+                },
+                processed_instrs: code.body.clone(),
+                ip: 0,
+            }],
+            enable_checkpoint: self.enable_checkpoint,
+            result_regs: ArrayVec::new(),
+            return_result_regs: ArrayVec::new(),
+            primary_mem,
+            cached_mem_ptr,
+            handlers: code.handlers.clone(),
+        };
+        self.stacks.activation_frame_stack.push(new_frame);
+        Ok(())
+    }
+
+    /// Writes returned values into the result registers of the frame on top
+    /// of the activation stack. Register offsets must already be restored to
+    /// that frame.
+    fn pass_results_to_caller(&mut self, values: &[Val]) {
+        // Write to caller's registers (after restore, in caller's coordinate system)
+        let (reg_file, frames) = self.stacks.get_reg_file_and_frames();
+        let caller_frame = frames.last_mut().unwrap();
+
+        // Refresh cached memory pointer (may have changed due to memory.grow in callee)
+        caller_frame.cached_mem_ptr = caller_frame.primary_mem.as_ref().map(|m| m.data_ptr());
+
+        if !caller_frame.result_regs.is_empty() {
+            for (caller_reg, val) in caller_frame.result_regs.iter().zip(values.iter()) {
+                reg_file.set_val(caller_reg, val);
+            }
+            caller_frame.result_regs.clear();
+        }
     }
 
     /// Calls a WASI function with the given parameters.
     fn call_wasi_function(
-        &self,
+        module_inst: &ModuleInst,
         func_type: &WasiFuncType,
         params: &[Val],
     ) -> WasiResult<Option<Val>> {
-        let wasi_impl = self
-            .module_inst
-            .wasi_impl
-            .as_ref()
-            .ok_or(WasiError::NoSys)?;
+        let wasi_impl = module_inst.wasi_impl.as_ref().ok_or(WasiError::NoSys)?;
 
         // Get memory address for WASI functions that need it
-        let memory = if self.module_inst.mem_addrs.is_empty() {
+        let memory = if module_inst.mem_addrs.is_empty() {
             return Err(WasiError::Fault);
         } else {
-            &self.module_inst.mem_addrs[0]
+            &module_inst.mem_addrs[0]
         };
 
         match func_type {
@@ -1045,7 +1426,7 @@ impl Val {
             ValueType::NumType(NumType::F32) => Ok(Val::Num(Num::F32(0.0))),
             ValueType::NumType(NumType::F64) => Ok(Val::Num(Num::F64(0.0))),
             ValueType::VecType(VecType::V128) => Ok(Val::Vec_(Vec_::V128(0))),
-            ValueType::RefType(_) => todo!("Default value for RefType"),
+            ValueType::RefType(_) => Ok(Val::Ref(Ref::RefNull)),
         }
     }
 }
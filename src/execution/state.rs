@@ -10,8 +10,14 @@ use crate::error::RuntimeError;
 use crate::execution::func::{FuncAddr, FuncInst};
 use crate::execution::ir::{Handler, ProcessedInstr};
 use crate::execution::mem::MemAddr;
+use crate::execution::migration::{CheckpointRequest, CheckpointTrigger};
 use crate::execution::module::ModuleInst;
 use crate::execution::regs::{Reg, RegFile};
+#[cfg(feature = "stats")]
+use crate::execution::stats::ExecutionStats;
+use crate::execution::tag::ExnAddr;
+#[cfg(feature = "trace")]
+use crate::execution::trace::Tracer;
 use crate::execution::value::{Num, Ref, Val, Vec_};
 use crate::structure::module::WasiFuncType;
 use crate::structure::types::{NumType, ValueType, VecType};
@@ -37,8 +43,9 @@ pub struct VmState {
     pub label_stack: *mut Vec<LabelStack>,
     pub current_label_idx: usize,
 
-    // Memory fast path (load/store)
+    // Memory fast path (load/store), bounds-checked against `mem_len`
     pub mem_ptr: *mut u8,
+    pub mem_len: usize,
 
     // Module (call/call_indirect/global access)
     pub module: *const ModuleInst,
@@ -54,6 +61,31 @@ pub struct VmState {
     /// Counter for non-atomics-target checkpoint poll throttling.
     /// Incremented by `migration::poll_checkpoint`
     pub checkpoint_poll_counter: u32,
+
+    /// Triggers polled by `migration::poll_checkpoint` (owned by the
+    /// runtime's `CheckpointConfig`).
+    pub checkpoint_triggers: *const [CheckpointTrigger],
+
+    /// In-process checkpoint request flag (owned by the runtime).
+    pub checkpoint_request: *const CheckpointRequest,
+
+    /// Instructions left before dispatch pauses with `FuelExhausted`
+    /// (`u64::MAX` for unbounded runs).
+    pub fuel: u64,
+
+    /// Set when checkpointing or a fuel budget is active. Dispatch skips
+    /// the per-instruction checkpoint poll and fuel accounting otherwise.
+    pub interruptible: bool,
+
+    // Statistics (null when statistics are disabled for this run)
+    #[cfg(feature = "stats")]
+    pub stats: *mut ExecutionStats,
+
+    // Tracing (null when tracing is disabled for this run)
+    #[cfg(feature = "trace")]
+    pub tracer: *mut Tracer,
+    #[cfg(feature = "trace")]
+    pub locals_len: usize,
 }
 
 impl VmState {
@@ -69,6 +101,17 @@ impl VmState {
         unsafe { *self.handlers.add(pc) }
     }
 
+    /// Consumes one instruction of fuel. Returns `true` when the budget is
+    /// exhausted and dispatch must pause before the instruction at `pc`.
+    #[inline(always)]
+    pub fn consume_fuel(&mut self) -> bool {
+        if self.fuel == 0 {
+            return true;
+        }
+        self.fuel -= 1;
+        false
+    }
+
     /// Shared reference to the register file.
     #[inline(always)]
     pub fn reg_file(&self) -> &RegFile {
@@ -111,6 +154,25 @@ impl VmState {
         unsafe { &*self.module }
     }
 
+    /// Checkpoint triggers of the running runtime.
+    #[inline(always)]
+    pub fn checkpoint_triggers(&self) -> &[CheckpointTrigger] {
+        unsafe { &*self.checkpoint_triggers }
+    }
+
+    /// The runtime's in-process checkpoint request flag.
+    #[inline(always)]
+    pub fn checkpoint_request(&self) -> &CheckpointRequest {
+        unsafe { &*self.checkpoint_request }
+    }
+
+    /// Locals of the current frame as a slice.
+    #[cfg(feature = "trace")]
+    #[inline(always)]
+    pub fn locals_slice(&self) -> &[Val] {
+        unsafe { std::slice::from_raw_parts(self.locals, self.locals_len) }
+    }
+
     /// Mutable reference to the return-value register slot.
     #[inline(always)]
     pub fn return_result_regs_mut(&mut self) -> &mut ArrayVec<Reg, 8> {
@@ -132,6 +194,15 @@ pub enum ModuleLevelInstr {
         params: Vec<Val>,
         result_regs: ArrayVec<Reg, 8>,
     },
+    /// Tail call: the callee replaces the current frame and returns
+    /// directly to its caller.
+    TailInvokeReg {
+        func_addr: FuncAddr,
+        params: Vec<Val>,
+    },
+    /// Exception not caught within the current frame; the runtime unwinds
+    /// caller frames until a `try_table` catches it.
+    Throw(ExnAddr),
 }
 
 /// VM execution state - holds all runtime state for WebAssembly execution.
@@ -139,6 +210,10 @@ pub enum ModuleLevelInstr {
 pub struct VMState {
     pub reg_file: RegFile,
     pub activation_frame_stack: Vec<FrameStack>,
+    /// Whether this state runs the module's start function rather than the
+    /// entry function. Checked on restore to decide if the entry function
+    /// still has to be invoked.
+    pub in_start: bool,
 }
 
 /// Type alias for backward compatibility.
@@ -189,6 +264,7 @@ impl VMState {
                         label: Label {
                             is_loop: false,
                             return_ip: 0,
+                            try_ip: None,
                         },
                         processed_instrs: code.body.clone(),
                         ip: 0,
@@ -204,6 +280,7 @@ impl VMState {
                 Ok(VMState {
                     reg_file,
                     activation_frame_stack: vec![initial_frame],
+                    in_start: false,
                 })
             }
             FuncInst::HostFunc { .. } => Err(RuntimeError::UnimplementedHostFunction),
@@ -246,6 +323,9 @@ pub struct FrameStack {
 pub struct Label {
     pub is_loop: bool,
     pub return_ip: usize,
+    /// Position of the `try_table` that opened this label, whose catch
+    /// clauses are consulted when an exception unwinds through it.
+    pub try_ip: Option<usize>,
 }
 
 /// Label stack containing instructions and program counter.
@@ -4,20 +4,53 @@ use rustc_hash::FxHashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 
 use super::handlers::*;
+use super::module::ModuleInst;
+#[cfg(feature = "stats")]
+use super::state::VmState;
 
-/// Collects per-instruction execution counts.
+/// Per-function execution counters.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FunctionStats {
+    pub instructions: u64,
+    pub calls: u64,
+}
+
+/// Identifies a function by the number of its instance and its index in
+/// that instance's function space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FuncKey {
+    pub instance: u32,
+    pub func: u32,
+}
+
+/// Collects per-instruction and per-function execution counts.
 #[derive(Debug)]
 pub struct ExecutionStats {
     total_instructions: AtomicU64,
     per_instruction: FxHashMap<usize, AtomicU64>,
+    per_function: FxHashMap<FuncKey, FunctionStats>,
+    /// Function whose instructions are currently being counted.
+    current_function: Option<FuncKey>,
+    /// Instructions executed in `current_function` not yet folded into `per_function`.
+    current_function_instructions: u64,
+    /// Function lookup keyed by the address of the function body.
+    function_key_cache: FxHashMap<usize, Option<FuncKey>>,
+    /// Addresses of the instances seen so far, indexed by instance number.
+    instances: Vec<*const ModuleInst>,
 }
 
 impl ExecutionStats {
-    /// Creates a new statistics collector.
-    pub fn new() -> Self {
+    /// Creates a new statistics collector. `main` becomes instance 0; other
+    /// instances are numbered in the order their functions are first seen.
+    pub fn new(main: *const ModuleInst) -> Self {
         Self {
             total_instructions: AtomicU64::new(0),
             per_instruction: FxHashMap::default(),
+            per_function: FxHashMap::default(),
+            current_function: None,
+            current_function_instructions: 0,
+            function_key_cache: FxHashMap::default(),
+            instances: vec![main],
         }
     }
 
@@ -28,6 +61,63 @@ impl ExecutionStats {
             .entry(handler_index)
             .or_insert_with(|| AtomicU64::new(0))
             .fetch_add(1, Ordering::Relaxed);
+        self.current_function_instructions += 1;
+    }
+
+    /// Records a call to function `func` of `instance`.
+    pub fn record_call(&mut self, instance: *const ModuleInst, func: u32) {
+        let key = self.func_key(instance, func);
+        self.per_function.entry(key).or_default().calls += 1;
+    }
+
+    /// Attributes subsequently recorded instructions to `key`.
+    pub fn enter_function(&mut self, key: Option<FuncKey>) {
+        self.flush_current_function();
+        self.current_function = key;
+    }
+
+    /// Returns the cached function for a body address, locating its instance
+    /// and index with `resolve` on first use.
+    pub fn function_key(
+        &mut self,
+        body_addr: usize,
+        resolve: impl FnOnce() -> Option<(*const ModuleInst, u32)>,
+    ) -> Option<FuncKey> {
+        if let Some(key) = self.function_key_cache.get(&body_addr) {
+            return *key;
+        }
+        let key = resolve().map(|(instance, func)| self.func_key(instance, func));
+        self.function_key_cache.insert(body_addr, key);
+        key
+    }
+
+    /// Returns the call and instruction counts of every function seen so far.
+    pub fn function_stats(&mut self) -> &FxHashMap<FuncKey, FunctionStats> {
+        self.flush_current_function();
+        &self.per_function
+    }
+
+    fn func_key(&mut self, instance: *const ModuleInst, func: u32) -> FuncKey {
+        let instance = match self.instances.iter().position(|&seen| seen == instance) {
+            Some(number) => number,
+            None => {
+                self.instances.push(instance);
+                self.instances.len() - 1
+            }
+        };
+        FuncKey {
+            instance: instance as u32,
+            func,
+        }
+    }
+
+    fn flush_current_function(&mut self) {
+        let count = std::mem::take(&mut self.current_function_instructions);
+        if let Some(key) = self.current_function {
+            if count > 0 {
+                self.per_function.entry(key).or_default().instructions += count;
+            }
+        }
     }
 
     /// Maps handler index to human-readable instruction name.
@@ -47,12 +137,18 @@ impl ExecutionStats {
             HANDLER_IDX_RETURN => "return",
             HANDLER_IDX_CALL => "call",
             HANDLER_IDX_CALL_INDIRECT => "call_indirect",
+            HANDLER_IDX_RETURN_CALL => "return_call",
+            HANDLER_IDX_RETURN_CALL_INDIRECT => "return_call_indirect",
+            HANDLER_IDX_TRY_TABLE => "try_table",
+            HANDLER_IDX_THROW => "throw",
+            HANDLER_IDX_THROW_REF => "throw_ref",
 
             // Parametric Instructions
             HANDLER_IDX_SELECT_I32
             | HANDLER_IDX_SELECT_I64
             | HANDLER_IDX_SELECT_F32
-            | HANDLER_IDX_SELECT_F64 => "select",
+            | HANDLER_IDX_SELECT_F64
+            | HANDLER_IDX_SELECT_V128 => "select",
 
             // Variable Instructions
             HANDLER_IDX_LOCAL_GET => "local.get",
@@ -61,11 +157,13 @@ impl ExecutionStats {
             HANDLER_IDX_GLOBAL_GET_I32
             | HANDLER_IDX_GLOBAL_GET_I64
             | HANDLER_IDX_GLOBAL_GET_F32
-            | HANDLER_IDX_GLOBAL_GET_F64 => "global.get",
+            | HANDLER_IDX_GLOBAL_GET_F64
+            | HANDLER_IDX_GLOBAL_GET_V128 => "global.get",
             HANDLER_IDX_GLOBAL_SET_I32
             | HANDLER_IDX_GLOBAL_SET_I64
             | HANDLER_IDX_GLOBAL_SET_F32
-            | HANDLER_IDX_GLOBAL_SET_F64 => "global.set",
+            | HANDLER_IDX_GLOBAL_SET_F64
+            | HANDLER_IDX_GLOBAL_SET_V128 => "global.set",
 
             // Memory Instructions
             HANDLER_IDX_I32_LOAD => "i32.load",
@@ -96,6 +194,7 @@ impl ExecutionStats {
             HANDLER_IDX_MEMORY_COPY => "memory.copy",
             HANDLER_IDX_MEMORY_INIT => "memory.init",
             HANDLER_IDX_MEMORY_FILL => "memory.fill",
+            HANDLER_IDX_DATA_DROP => "data.drop",
 
             // Const Instructions
             HANDLER_IDX_I32_CONST => "i32.const",
@@ -261,27 +360,36 @@ impl ExecutionStats {
             HANDLER_IDX_TABLE_GET => "table.get",
             HANDLER_IDX_TABLE_SET => "table.set",
             HANDLER_IDX_TABLE_FILL => "table.fill",
+            HANDLER_IDX_TABLE_INIT => "table.init",
+            HANDLER_IDX_ELEM_DROP => "elem.drop",
+            HANDLER_IDX_TABLE_COPY => "table.copy",
+            HANDLER_IDX_TABLE_GROW => "table.grow",
+            HANDLER_IDX_TABLE_SIZE => "table.size",
 
             // Ref Local Instructions
             HANDLER_IDX_REF_LOCAL_GET => "local.get",
             HANDLER_IDX_REF_LOCAL_SET => "local.set",
+            HANDLER_IDX_V128_LOCAL_GET => "local.get",
+            HANDLER_IDX_V128_LOCAL_SET => "local.set",
 
             // WASI Call
             HANDLER_IDX_CALL_WASI => "call_wasi",
 
             // Reserved/Unsupported ranges
-            0x06..=0x0A => "reserved", // Exception handling (unsupported)
-            0x12..=0x19 => "reserved", // Reserved opcodes
-            0x1D..=0x1F => "reserved", // Reserved opcodes
-            0x25..=0x27 => "reserved", // Old table ops/reserved
-            0xD2..=0xDF => "reserved", // Reserved range (includes unsupported ref.func)
-            0xE3..=0xEF => "reserved", // Reserved range
-            _ => "invalid_handler",
+            0x06 | 0x07 | 0x09 => "reserved", // Legacy exception handling (unsupported)
+            0x14..=0x19 => "reserved",        // Reserved opcodes
+            0x1D..=0x1E => "reserved",        // Reserved opcodes
+            0x25..=0x27 => "reserved",        // Old table ops/reserved
+            0xD2..=0xDF => "reserved",        // Reserved range (includes unsupported ref.func)
+            0xE3..=0xE7 => "reserved",        // Reserved range
+            0xEE..=0xEF => "reserved",        // Reserved range
+            idx => simd_instruction_name(idx).unwrap_or("invalid_handler"),
         }
     }
 
     /// Prints statistics summary to stderr.
-    pub fn report(&self) {
+    pub fn report(&mut self) {
+        self.flush_current_function();
         let total = self.total_instructions.load(Ordering::Relaxed);
 
         eprintln!("=== Execution Statistics ===");
@@ -318,6 +426,58 @@ impl ExecutionStats {
             eprintln!("  ... and {} more", counts.len() - top_n);
         }
 
+        // Collect and sort function counts
+        let mut functions: Vec<(FuncKey, FunctionStats)> = self
+            .per_function
+            .iter()
+            .map(|(key, stats)| (*key, *stats))
+            .collect();
+
+        functions.sort_by(|a, b| {
+            b.1.instructions
+                .cmp(&a.1.instructions)
+                .then(b.1.calls.cmp(&a.1.calls))
+                .then(a.0.cmp(&b.0))
+        });
+
+        if !functions.is_empty() {
+            eprintln!("\nTop Functions:");
+            eprintln!(
+                "  {:25} {:>12} {:>9} {:>12}",
+                "function", "instrs", "%", "calls"
+            );
+            let top_n = 20.min(functions.len());
+            for (key, stats) in functions.iter().take(top_n) {
+                let percentage = (stats.instructions as f64 / total as f64) * 100.0;
+                let name = if key.instance == 0 {
+                    format!("func[{}]:", key.func)
+                } else {
+                    format!("inst[{}].func[{}]:", key.instance, key.func)
+                };
+                eprintln!(
+                    "  {:25} {:12} ({:5.1}%) {:12}",
+                    name, stats.instructions, percentage, stats.calls
+                );
+            }
+
+            if functions.len() > top_n {
+                eprintln!("  ... and {} more", functions.len() - top_n);
+            }
+        }
+
         eprintln!("=======================");
     }
 }
+
+/// Dispatcher hook: records the instruction about to execute at `state.pc`.
+///
+/// Called by both dispatchers before each handler runs. A null
+/// `state.stats` means statistics are disabled for this run.
+#[cfg(feature = "stats")]
+#[inline(always)]
+pub fn record_step(state: &VmState) {
+    if !state.stats.is_null() && state.pc < state.instrs_len {
+        let handler_index = state.current_instr().handler_index();
+        unsafe { (*state.stats).record_instruction(handler_index) };
+    }
+}
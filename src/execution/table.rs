@@ -2,11 +2,11 @@
 
 use super::{
     func::FuncAddr,
-    module::*,
     value::{self, Val},
 };
 use crate::error::RuntimeError;
 use crate::structure::types::*;
+use serde::{Deserialize, Serialize};
 use std::cell::{Ref, RefCell};
 use std::rc::Rc;
 
@@ -19,6 +19,30 @@ pub struct TableAddr(Rc<RefCell<TableInst>>);
 pub struct TableInst {
     pub _type_: TableType,
     pub elem: Vec<Val>,
+    /// Contents right after instantiation, which checkpoints diff against.
+    pub initial: Vec<Val>,
+}
+
+/// Changes made to a table since instantiation, as stored in checkpoints.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TableDiff {
+    /// Current number of elements.
+    pub size: u32,
+    /// Runs of elements that differ from the initial contents (elements
+    /// added by `table.grow` are compared against null), as start index and
+    /// values.
+    pub runs: Vec<(u32, Vec<Val>)>,
+}
+
+impl TableDiff {
+    /// Returns true if every run lies within `size`.
+    pub fn in_bounds(&self) -> bool {
+        self.runs.iter().all(|(start, vals)| {
+            (*start as usize)
+                .checked_add(vals.len())
+                .is_some_and(|end| end <= self.size as usize)
+        })
+    }
 }
 
 impl TableAddr {
@@ -32,44 +56,184 @@ impl TableAddr {
                 vec.resize(min, Val::Ref(value::Ref::RefNull));
                 vec
             },
+            initial: Vec::new(),
         })))
     }
-    /// Initializes table elements from function indices.
-    pub fn init(&self, offset: usize, funcs: &Vec<FuncAddr>, init: &Vec<i32>) {
-        let mut addr_self = self.0.borrow_mut();
-        for (index, f) in init.iter().enumerate() {
-            addr_self.elem[index + offset] = Val::Ref(value::Ref::FuncAddr(
-                funcs.get_by_idx(FuncIdx(*f as u32)).clone(),
-            ));
+
+    /// Records the current contents as the baseline for `diff`. Called once
+    /// the element segments of an instantiation have been applied.
+    pub fn mark_initial(&self) {
+        let mut inst = self.0.borrow_mut();
+        inst.initial = inst.elem.clone();
+    }
+
+    /// Returns the changes since `mark_initial`, or None if the table still
+    /// holds its initial contents.
+    pub fn diff(&self) -> Option<TableDiff> {
+        let inst = self.0.borrow();
+        let null = Val::Ref(value::Ref::RefNull);
+        let mut runs: Vec<(u32, Vec<Val>)> = Vec::new();
+        for (i, val) in inst.elem.iter().enumerate() {
+            if inst.initial.get(i).unwrap_or(&null) == val {
+                continue;
+            }
+            match runs.last_mut() {
+                Some((start, vals)) if *start as usize + vals.len() == i => vals.push(val.clone()),
+                _ => runs.push((i as u32, vec![val.clone()])),
+            }
+        }
+        if runs.is_empty() && inst.elem.len() == inst.initial.len() {
+            return None;
+        }
+        Some(TableDiff {
+            size: inst.elem.len() as u32,
+            runs,
+        })
+    }
+
+    /// Resets the table to its initial contents and applies `diff` (used
+    /// during restore). Traps if a run lies outside the table.
+    pub fn apply_diff(&self, diff: &TableDiff) -> Result<(), RuntimeError> {
+        if !diff.in_bounds() {
+            return Err(RuntimeError::TableOutOfBounds);
         }
+        let mut inst = self.0.borrow_mut();
+        let mut elem = inst.initial.clone();
+        elem.resize(diff.size as usize, Val::Ref(value::Ref::RefNull));
+        for (start, vals) in &diff.runs {
+            let start = *start as usize;
+            elem[start..start + vals.len()].clone_from_slice(vals);
+        }
+        inst.elem = elem;
+        Ok(())
     }
-    /// Gets element at index. Out-of-bounds panics; host runtime traps.
-    pub fn get(&self, i: usize) -> Val {
-        self.0.borrow().elem[i].clone()
+    /// Returns `start..start + n` if the range lies within `len` elements.
+    fn check_range(len: usize, start: u32, n: u32) -> Result<std::ops::Range<usize>, RuntimeError> {
+        let (start, n) = (start as usize, n as usize);
+        match start.checked_add(n) {
+            Some(end) if end <= len => Ok(start..end),
+            _ => Err(RuntimeError::TableOutOfBounds),
+        }
     }
 
-    /// Sets element at index. Out-of-bounds panics; host runtime traps.
-    pub fn set(&self, i: usize, val: Val) {
-        self.0.borrow_mut().elem[i] = val;
+    /// Copies `n` references from `src[s..]` into the table at `d`
+    /// (`table.init` and active element segments).
+    /// Traps if either range is out of bounds, even when n is zero.
+    pub fn init(&self, d: u32, src: &[value::Ref], s: u32, n: u32) -> Result<(), RuntimeError> {
+        let mut inst = self.0.borrow_mut();
+        let dst_range = Self::check_range(inst.elem.len(), d, n)?;
+        let src_range = Self::check_range(src.len(), s, n)?;
+        for (slot, r) in inst.elem[dst_range].iter_mut().zip(&src[src_range]) {
+            *slot = Val::Ref(r.clone());
+        }
+        Ok(())
+    }
+
+    /// Gets element at index. Traps if out of bounds.
+    pub fn get(&self, i: u32) -> Result<Val, RuntimeError> {
+        self.0
+            .borrow()
+            .elem
+            .get(i as usize)
+            .cloned()
+            .ok_or(RuntimeError::TableOutOfBounds)
+    }
+
+    /// Sets element at index. Traps if out of bounds.
+    pub fn set(&self, i: u32, val: Val) -> Result<(), RuntimeError> {
+        let mut inst = self.0.borrow_mut();
+        let slot = inst
+            .elem
+            .get_mut(i as usize)
+            .ok_or(RuntimeError::TableOutOfBounds)?;
+        *slot = val;
+        Ok(())
     }
 
     /// Fills n elements starting at index with the given value.
-    /// Out-of-bounds panics; host runtime traps.
-    pub fn fill(&self, i: usize, val: Val, n: usize) {
+    /// Traps if the range is out of bounds, even when n is zero.
+    pub fn fill(&self, i: u32, val: Val, n: u32) -> Result<(), RuntimeError> {
         let mut inst = self.0.borrow_mut();
-        for idx in i..i + n {
-            inst.elem[idx] = val.clone();
+        let range = Self::check_range(inst.elem.len(), i, n)?;
+        inst.elem[range].fill(val);
+        Ok(())
+    }
+
+    /// Returns true if both handles refer to the same table instance.
+    pub fn ptr_eq(&self, other: &TableAddr) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+
+    /// Returns the current number of elements.
+    pub fn size(&self) -> u32 {
+        self.0.borrow().elem.len() as u32
+    }
+
+    /// Returns the type of this table, with the current size as the minimum
+    /// (used to match table imports).
+    pub fn table_type(&self) -> TableType {
+        let inst = self.0.borrow();
+        TableType(
+            Limits {
+                min: inst.elem.len() as u64,
+                max: inst._type_.0.max,
+            },
+            inst._type_.1,
+        )
+    }
+
+    /// Grows the table by n elements set to `val`. Returns the previous
+    /// size, or None if the new size exceeds the table's maximum or cannot
+    /// be allocated.
+    pub fn grow(&self, n: u32, val: Val) -> Option<u32> {
+        let mut inst = self.0.borrow_mut();
+        let prev = inst.elem.len() as u32;
+        let new = prev.checked_add(n)?;
+        if inst._type_.0.max.is_some_and(|max| new as u64 > max) {
+            return None;
         }
+        inst.elem.try_reserve_exact(n as usize).ok()?;
+        inst.elem.resize(new as usize, val);
+        Some(prev)
+    }
+
+    /// Copies n elements from `src` in `src_table` to `d` in this table.
+    /// Overlapping ranges within one table are handled like `memmove`.
+    /// Traps if either range is out of bounds, even when n is zero.
+    pub fn copy(&self, d: u32, src_table: &TableAddr, s: u32, n: u32) -> Result<(), RuntimeError> {
+        if Rc::ptr_eq(&self.0, &src_table.0) {
+            let mut inst = self.0.borrow_mut();
+            let len = inst.elem.len();
+            let dst_range = Self::check_range(len, d, n)?;
+            let src_range = Self::check_range(len, s, n)?;
+            if dst_range.start <= src_range.start {
+                for (di, si) in dst_range.zip(src_range) {
+                    inst.elem[di] = inst.elem[si].clone();
+                }
+            } else {
+                for (di, si) in dst_range.rev().zip(src_range.rev()) {
+                    inst.elem[di] = inst.elem[si].clone();
+                }
+            }
+        } else {
+            let mut inst = self.0.borrow_mut();
+            let src_inst = src_table.0.borrow();
+            let dst_range = Self::check_range(inst.elem.len(), d, n)?;
+            let src_range = Self::check_range(src_inst.elem.len(), s, n)?;
+            inst.elem[dst_range].clone_from_slice(&src_inst.elem[src_range]);
+        }
+        Ok(())
     }
 
     /// Gets function address at index for call_indirect.
-    /// Out-of-bounds panics; returns None for non-FuncAddr references (e.g. RefNull),
-    /// caller is expected to delegate the null-reference trap to the host via panic.
-    pub fn get_func_addr(&self, i: usize) -> Option<FuncAddr> {
+    /// Returns `UndefinedElement` for out-of-bounds indices and
+    /// `UninitializedElement` for non-FuncAddr references (e.g. RefNull).
+    pub fn get_func_addr(&self, i: usize) -> Result<FuncAddr, RuntimeError> {
         let inst = self.0.borrow();
-        match &inst.elem[i] {
-            Val::Ref(value::Ref::FuncAddr(func_addr)) => Some(func_addr.clone()),
-            _ => None,
+        match inst.elem.get(i) {
+            Some(Val::Ref(value::Ref::FuncAddr(func_addr))) => Ok(func_addr.clone()),
+            Some(_) => Err(RuntimeError::UninitializedElement),
+            None => Err(RuntimeError::UndefinedElement),
         }
     }
 
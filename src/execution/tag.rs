@@ -0,0 +1,105 @@
+//! Exception tag instances and exception references.
+
+use super::migration;
+use super::value::Val;
+use crate::structure::types::*;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+/// Reference-counted handle to a tag instance.
+///
+/// Tags are compared by identity: an exception is caught by a `catch` clause
+/// only if both refer to the same tag instance.
+#[derive(Clone, Debug)]
+pub struct TagAddr(Rc<TagInst>);
+
+/// Tag instance holding the payload type of its exceptions.
+#[derive(Debug)]
+pub struct TagInst {
+    pub type_: FuncType,
+}
+
+impl TagAddr {
+    /// Creates a new tag with the given payload type.
+    pub fn new(type_: &FuncType) -> TagAddr {
+        TagAddr(Rc::new(TagInst {
+            type_: type_.clone(),
+        }))
+    }
+
+    /// Returns the payload type of this tag.
+    pub fn tag_type(&self) -> &FuncType {
+        &self.0.type_
+    }
+
+    /// Returns true if both handles refer to the same tag instance.
+    pub fn ptr_eq(&self, other: &TagAddr) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// Reference-counted handle to an exception (the value of an `exnref`).
+#[derive(Clone, Debug)]
+pub struct ExnAddr(Rc<ExnInst>);
+
+/// Exception instance created by `throw`.
+///
+/// In a checkpoint the tag is stored as its position among the checkpointed
+/// instances, like function references, and rebound to the live tag
+/// instance on restore.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExnInst {
+    #[serde(
+        serialize_with = "migration::serialize_tag",
+        deserialize_with = "migration::deserialize_tag"
+    )]
+    pub tag: TagAddr,
+    pub fields: Vec<Val>,
+}
+
+impl ExnAddr {
+    /// Creates a new exception for `tag` carrying `fields`.
+    pub fn new(tag: TagAddr, fields: Vec<Val>) -> ExnAddr {
+        ExnAddr(Rc::new(ExnInst { tag, fields }))
+    }
+
+    /// Returns true if this exception was thrown with `tag`.
+    pub fn is_tag(&self, tag: &TagAddr) -> bool {
+        self.0.tag.ptr_eq(tag)
+    }
+
+    /// Returns the payload values of this exception.
+    pub fn fields(&self) -> &[Val] {
+        &self.0.fields
+    }
+
+    /// Returns true if both handles refer to the same exception.
+    pub fn ptr_eq(&self, other: &ExnAddr) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Hash for ExnAddr {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Rc::as_ptr(&self.0).hash(state);
+    }
+}
+
+impl Serialize for ExnAddr {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ExnAddr {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(ExnAddr(Rc::new(ExnInst::deserialize(deserializer)?)))
+    }
+}
@@ -6,9 +6,22 @@ use std::path::Path;
 
 use super::global::GlobalAddr;
 use super::handlers::*;
+use super::ir::ProcessedInstr;
+use super::operand;
 use super::regs::RegFile;
+use super::state::VmState;
 use super::value::Val;
 
+/// Number of bytes shown around a load/store address for the `memory` resource.
+const MEMORY_WINDOW: usize = 8;
+
+/// View of linear memory passed to the tracer for the `memory` resource.
+pub struct MemoryView<'a> {
+    pub data: &'a [u8],
+    /// Effective address of the traced load/store, if any.
+    pub access: Option<usize>,
+}
+
 /// Event types that can trigger tracing.
 #[derive(Debug, Clone, PartialEq)]
 pub enum TraceEvent {
@@ -124,6 +137,9 @@ impl TraceConfig {
                 TraceEvent::Call => {
                     if handler_index == HANDLER_IDX_CALL
                         || handler_index == HANDLER_IDX_CALL_INDIRECT
+                        || handler_index == HANDLER_IDX_CALL_WASI
+                        || handler_index == HANDLER_IDX_RETURN_CALL
+                        || handler_index == HANDLER_IDX_RETURN_CALL_INDIRECT
                     {
                         return true;
                     }
@@ -170,6 +186,7 @@ impl Tracer {
         reg_file: &RegFile,
         locals: &[Val],
         global_addrs: &[GlobalAddr],
+        memory: Option<MemoryView>,
     ) {
         if !self.config.should_trace_event(handler_index) {
             return;
@@ -204,6 +221,12 @@ impl Tracer {
             parts.push(format!("Globals:{}", globals_str));
         }
 
+        // Memory
+        if self.config.resources.contains(&TraceResource::Memory) {
+            let memory_str = self.format_memory(memory.as_ref());
+            parts.push(format!("Memory:{}", memory_str));
+        }
+
         // Write trace line
         let trace_line = format!("[{}]\n", parts.join(" | "));
         let _ = self.output.write_all(trace_line.as_bytes());
@@ -222,6 +245,27 @@ impl Tracer {
         format!("[{}]", values.join(","))
     }
 
+    fn format_memory(&self, memory: Option<&MemoryView>) -> String {
+        let Some(memory) = memory else {
+            return "none".to_string();
+        };
+
+        let mut out = format!("{}B", memory.data.len());
+        if let Some(addr) = memory.access {
+            let end = addr.saturating_add(MEMORY_WINDOW).min(memory.data.len());
+            if addr < end {
+                let bytes: Vec<String> = memory.data[addr..end]
+                    .iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect();
+                out.push_str(&format!(" @{:#x}:[{}]", addr, bytes.join(" ")));
+            } else {
+                out.push_str(&format!(" @{:#x}:out-of-bounds", addr));
+            }
+        }
+        out
+    }
+
     fn format_registers(&self, reg_file: &RegFile) -> String {
         let mut parts = Vec::new();
 
@@ -269,6 +313,28 @@ impl Tracer {
             parts.push(format!("F64[{}]", f64_vals.join(",")));
         }
 
+        // Format Ref registers
+        if !reg_file.ref_regs.is_empty() {
+            let ref_vals: Vec<String> = reg_file
+                .ref_regs
+                .iter()
+                .enumerate()
+                .map(|(i, v)| format!("r{}:{}", i, Self::format_val(&Val::Ref(v.clone()))))
+                .collect();
+            parts.push(format!("Ref[{}]", ref_vals.join(",")));
+        }
+
+        // Format V128 registers
+        if !reg_file.v128_regs.is_empty() {
+            let v128_vals: Vec<String> = reg_file
+                .v128_regs
+                .iter()
+                .enumerate()
+                .map(|(i, v)| format!("r{}:{:#034x}", i, v))
+                .collect();
+            parts.push(format!("V128[{}]", v128_vals.join(",")));
+        }
+
         if parts.is_empty() {
             "[]".to_string()
         } else {
@@ -300,6 +366,7 @@ impl Tracer {
                 super::value::Ref::RefNull => "RefNull".to_string(),
                 super::value::Ref::FuncAddr(_) => "FuncAddr".to_string(),
                 super::value::Ref::RefExtern(_) => "RefExtern".to_string(),
+                super::value::Ref::Exn(_) => "Exn".to_string(),
             },
         }
     }
@@ -320,12 +387,18 @@ impl Tracer {
             HANDLER_IDX_RETURN => "return",
             HANDLER_IDX_CALL => "call",
             HANDLER_IDX_CALL_INDIRECT => "call_indirect",
+            HANDLER_IDX_RETURN_CALL => "return_call",
+            HANDLER_IDX_RETURN_CALL_INDIRECT => "return_call_indirect",
+            HANDLER_IDX_TRY_TABLE => "try_table",
+            HANDLER_IDX_THROW => "throw",
+            HANDLER_IDX_THROW_REF => "throw_ref",
 
             // Parametric Instructions
             HANDLER_IDX_SELECT_I32
             | HANDLER_IDX_SELECT_I64
             | HANDLER_IDX_SELECT_F32
-            | HANDLER_IDX_SELECT_F64 => "select",
+            | HANDLER_IDX_SELECT_F64
+            | HANDLER_IDX_SELECT_V128 => "select",
 
             // Variable Instructions
             HANDLER_IDX_LOCAL_GET => "local.get",
@@ -334,11 +407,13 @@ impl Tracer {
             HANDLER_IDX_GLOBAL_GET_I32
             | HANDLER_IDX_GLOBAL_GET_I64
             | HANDLER_IDX_GLOBAL_GET_F32
-            | HANDLER_IDX_GLOBAL_GET_F64 => "global.get",
+            | HANDLER_IDX_GLOBAL_GET_F64
+            | HANDLER_IDX_GLOBAL_GET_V128 => "global.get",
             HANDLER_IDX_GLOBAL_SET_I32
             | HANDLER_IDX_GLOBAL_SET_I64
             | HANDLER_IDX_GLOBAL_SET_F32
-            | HANDLER_IDX_GLOBAL_SET_F64 => "global.set",
+            | HANDLER_IDX_GLOBAL_SET_F64
+            | HANDLER_IDX_GLOBAL_SET_V128 => "global.set",
 
             // Memory Instructions
             HANDLER_IDX_I32_LOAD => "i32.load",
@@ -369,6 +444,7 @@ impl Tracer {
             HANDLER_IDX_MEMORY_COPY => "memory.copy",
             HANDLER_IDX_MEMORY_INIT => "memory.init",
             HANDLER_IDX_MEMORY_FILL => "memory.fill",
+            HANDLER_IDX_DATA_DROP => "data.drop",
 
             // Const Instructions
             HANDLER_IDX_I32_CONST => "i32.const",
@@ -534,15 +610,79 @@ impl Tracer {
             HANDLER_IDX_TABLE_GET => "table.get",
             HANDLER_IDX_TABLE_SET => "table.set",
             HANDLER_IDX_TABLE_FILL => "table.fill",
+            HANDLER_IDX_TABLE_INIT => "table.init",
+            HANDLER_IDX_ELEM_DROP => "elem.drop",
+            HANDLER_IDX_TABLE_COPY => "table.copy",
+            HANDLER_IDX_TABLE_GROW => "table.grow",
+            HANDLER_IDX_TABLE_SIZE => "table.size",
 
             // Ref Local Instructions
             HANDLER_IDX_REF_LOCAL_GET => "local.get",
             HANDLER_IDX_REF_LOCAL_SET => "local.set",
+            HANDLER_IDX_V128_LOCAL_GET => "local.get",
+            HANDLER_IDX_V128_LOCAL_SET => "local.set",
 
             // WASI Call
             HANDLER_IDX_CALL_WASI => "call_wasi",
 
-            _ => "unknown",
+            idx => simd_instruction_name(idx).unwrap_or("unknown"),
         }
     }
 }
+
+/// Dispatcher hook: traces the instruction about to execute at `state.pc`.
+///
+/// Called by both dispatchers before each handler runs. A null
+/// `state.tracer` means tracing is disabled for this run.
+#[inline(always)]
+pub fn trace_step(state: &VmState) {
+    if !state.tracer.is_null() && state.pc < state.instrs_len {
+        trace_step_slow(state);
+    }
+}
+
+#[inline(never)]
+fn trace_step_slow(state: &VmState) {
+    let instr = state.current_instr();
+    let module = state.module();
+
+    // Show the memory being accessed, or memory 0 for other instructions
+    let (memory_idx, access) = match instr {
+        ProcessedInstr::MemoryLoadReg {
+            addr,
+            offset,
+            memory,
+            ..
+        }
+        | ProcessedInstr::MemoryStoreReg {
+            addr,
+            offset,
+            memory,
+            ..
+        }
+        | ProcessedInstr::SimdMemReg {
+            addr,
+            offset,
+            memory,
+            ..
+        } => (
+            *memory as usize,
+            Some(operand::read_addr(state, addr).saturating_add(*offset) as usize),
+        ),
+        _ => (0, None),
+    };
+    let memory = module.mem_addrs.get(memory_idx).map(|mem| {
+        let data = unsafe { std::slice::from_raw_parts(mem.data_ptr(), mem.data_len()) };
+        MemoryView { data, access }
+    });
+
+    let tracer = unsafe { &mut *state.tracer };
+    tracer.trace_instruction(
+        state.pc,
+        instr.handler_index(),
+        state.reg_file(),
+        state.locals_slice(),
+        &module.global_addrs,
+        memory,
+    );
+}
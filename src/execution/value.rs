@@ -1,6 +1,13 @@
 //! Runtime value types (Num, Vec, Ref) and external values.
 
-use super::{func::FuncAddr, global::GlobalAddr, mem::MemAddr, table::TableAddr};
+use super::{
+    func::FuncAddr,
+    global::GlobalAddr,
+    mem::MemAddr,
+    migration,
+    table::TableAddr,
+    tag::{ExnAddr, TagAddr},
+};
 use crate::error::RuntimeError;
 use crate::structure::module::WasiFuncType;
 use crate::structure::types::{NumType, RefType, ValueType, VecType};
@@ -56,6 +63,7 @@ impl Val {
             Val::Ref(Ref::RefNull) => ValueType::RefType(RefType::FuncRef),
             Val::Ref(Ref::FuncAddr(_)) => ValueType::RefType(RefType::FuncRef),
             Val::Ref(Ref::RefExtern(_)) => ValueType::RefType(RefType::ExternalRef),
+            Val::Ref(Ref::Exn(_)) => ValueType::RefType(RefType::ExnRef),
         }
     }
 }
@@ -98,17 +106,25 @@ pub enum Vec_ {
     V128(i128),
 }
 
-/// Reference value variants (null, function, external).
+/// Reference value variants (null, function, external, exception).
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Ref {
     /// Null reference.
     RefNull,
-    /// Function reference.
-    #[serde(skip)]
+    /// Function reference, checkpointed as an instance and function index.
+    #[serde(
+        serialize_with = "migration::serialize_func_ref",
+        deserialize_with = "migration::deserialize_func_ref"
+    )]
     FuncAddr(FuncAddr),
-    /// External reference.
-    #[serde(skip)]
+    /// External reference, checkpointed as a host-handle id.
+    #[serde(
+        serialize_with = "migration::serialize_extern_ref",
+        deserialize_with = "migration::deserialize_extern_ref"
+    )]
     RefExtern(ExternAddr),
+    /// Exception reference.
+    Exn(ExnAddr),
 }
 
 use std::hash::{Hash, Hasher};
@@ -119,14 +135,18 @@ impl Hash for Ref {
             Ref::RefNull => 0.hash(state),
             Ref::FuncAddr(addr) => {
                 1.hash(state);
-                // Hash the pointer address for FuncAddr
-                (addr as *const _ as usize).hash(state);
+                // Hash the Rc pointer address for FuncAddr
+                Rc::as_ptr(addr.get_rc()).hash(state);
             }
             Ref::RefExtern(addr) => {
                 2.hash(state);
                 // Hash the Rc pointer address for ExternAddr
                 Rc::as_ptr(&addr.0).hash(state);
             }
+            Ref::Exn(addr) => {
+                3.hash(state);
+                addr.hash(state);
+            }
         }
     }
 }
@@ -135,11 +155,9 @@ impl PartialEq for Ref {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Ref::RefNull, Ref::RefNull) => true,
-            (Ref::FuncAddr(a), Ref::FuncAddr(b)) => {
-                // Compare FuncAddr by pointer equality
-                std::ptr::eq(a as *const _, b as *const _)
-            }
+            (Ref::FuncAddr(a), Ref::FuncAddr(b)) => a.ptr_eq(b),
             (Ref::RefExtern(a), Ref::RefExtern(b)) => Rc::ptr_eq(&a.0, &b.0),
+            (Ref::Exn(a), Ref::Exn(b)) => a.ptr_eq(b),
             _ => false,
         }
     }
@@ -154,6 +172,16 @@ impl ExternAddr {
     pub fn new(externval: Externval) -> Self {
         ExternAddr(Rc::new(RefCell::new(externval)))
     }
+
+    /// Returns the external value this address refers to.
+    pub fn externval(&self) -> Externval {
+        self.0.borrow().clone()
+    }
+
+    /// Returns true if both handles refer to the same external value.
+    pub fn ptr_eq(&self, other: &ExternAddr) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
 }
 
 /// WASI function address containing the function type.
@@ -184,6 +212,9 @@ pub enum Externval {
     /// Global external value.
     #[serde(skip)]
     Global(GlobalAddr),
+    /// Tag external value.
+    #[serde(skip)]
+    Tag(TagAddr),
     /// WASI function external value.
     #[serde(skip)]
     WasiFunc(WasiFuncAddr),
@@ -199,6 +230,42 @@ impl Externval {
         }
     }
 
+    /// Extracts table address if this is a Table variant.
+    pub fn as_table(self) -> Option<TableAddr> {
+        if let Externval::Table(x) = self {
+            Some(x)
+        } else {
+            None
+        }
+    }
+
+    /// Extracts memory address if this is a Mem variant.
+    pub fn as_mem(self) -> Option<MemAddr> {
+        if let Externval::Mem(x) = self {
+            Some(x)
+        } else {
+            None
+        }
+    }
+
+    /// Extracts global address if this is a Global variant.
+    pub fn as_global(self) -> Option<GlobalAddr> {
+        if let Externval::Global(x) = self {
+            Some(x)
+        } else {
+            None
+        }
+    }
+
+    /// Extracts tag address if this is a Tag variant.
+    pub fn as_tag(self) -> Option<TagAddr> {
+        if let Externval::Tag(x) = self {
+            Some(x)
+        } else {
+            None
+        }
+    }
+
     /// Extracts WASI function address if this is a WasiFunc variant.
     pub fn as_wasi_func(self) -> Option<WasiFuncAddr> {
         if let Externval::WasiFunc(x) = self {
@@ -2,25 +2,33 @@ use anyhow::Result;
 #[cfg(feature = "trace")]
 use chiwawa::execution::trace::TraceConfig;
 use chiwawa::{
+    execution::linker::Linker,
     execution::module::*,
     execution::runtime::Runtime,
     execution::value::*,
-    execution::{migration, state::Stacks},
+    execution::{
+        migration::{self, CheckpointConfig, CheckpointTrigger},
+        state::Stacks,
+    },
     parser,
     structure::module::Module,
 };
 use clap::Parser;
 use fancy_regex::Regex;
-use rustc_hash::FxHashMap;
+use std::path::PathBuf;
 use std::rc::Rc;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
-    /// WebAssembly file to execute
+    /// WebAssembly file to execute (binary or `.wat` text)
     wasm_file: String,
     #[arg(long)]
     restore: Option<String>,
+    /// Instantiate a module before the main one and expose its exports to
+    /// later modules under NAME (repeatable, in dependency order)
+    #[arg(long = "preload", value_name = "NAME=PATH")]
+    preload: Vec<String>,
     #[arg(short, long, default_value = "_start")]
     invoke: String,
     #[arg(short, long, value_delimiter = ',', num_args = 0..)]
@@ -35,6 +43,17 @@ struct Cli {
     /// Enable checkpoint/restore
     #[arg(long = "cr", default_value = "false")]
     enable_checkpoint: bool,
+    /// File the checkpoint is written to
+    #[arg(long = "checkpoint-file", requires = "enable_checkpoint", default_value = migration::DEFAULT_CHECKPOINT_FILE)]
+    checkpoint_file: PathBuf,
+    /// What requests a checkpoint: file:PATH, fd:N (a byte arrives on fd N)
+    /// or after:SECONDS (repeatable; defaults to file:./checkpoint.trigger)
+    #[arg(
+        long = "checkpoint-trigger",
+        value_name = "KIND:ARG",
+        requires = "enable_checkpoint"
+    )]
+    checkpoint_triggers: Vec<CheckpointTrigger>,
     /// Enable trace output
     #[arg(long = "trace", default_value = "false")]
     enable_trace: bool,
@@ -145,9 +164,37 @@ fn main() -> Result<()> {
         eprintln!("         Rebuild with: cargo build --features trace");
     }
 
+    // Instantiate preloaded modules in order; their start functions have
+    // already run when restoring from a checkpoint.
+    let mut linker = Linker::new();
+    for preload in &cli.preload {
+        let (name, path) = preload.split_once('=').ok_or_else(|| {
+            anyhow::anyhow!("Invalid --preload '{}', expected NAME=PATH", preload)
+        })?;
+        let mut preload_module = Module::new(name);
+        if let Err(e) = parser::parse_bytecode(&mut preload_module, path) {
+            return Err(anyhow::anyhow!("Failed to parse {}: {}", path, e));
+        }
+        let argv = vec![path.to_string()];
+        let result = if cli.restore.is_some() {
+            linker.instantiate_without_start(name, &preload_module, argv)
+        } else {
+            linker.instantiate(name, &preload_module, argv)
+        };
+        if let Err(e) = result {
+            return Err(anyhow::anyhow!(
+                "Failed to instantiate preloaded module '{}': {:?}",
+                name,
+                e
+            ));
+        }
+    }
+    let linked_instances = linker.instances();
+
     let mut module = Module::new("test");
-    let _ = parser::parse_bytecode(&mut module, &cli.wasm_file);
-    let imports: ImportObjects = FxHashMap::default();
+    if let Err(e) = parser::parse_bytecode(&mut module, &cli.wasm_file) {
+        return Err(anyhow::anyhow!("Failed to parse {}: {}", cli.wasm_file, e));
+    }
 
     let mut wasm_argv = vec![cli.wasm_file.clone()];
     if let Some(args_string) = cli.app_args {
@@ -155,7 +202,15 @@ fn main() -> Result<()> {
         wasm_argv.extend(additional_args);
     }
 
-    let inst = ModuleInst::new(&module, imports, wasm_argv).unwrap();
+    let inst = ModuleInst::new_without_start(&module, linker.imports(), wasm_argv).unwrap();
+
+    let mut checkpoint_config = CheckpointConfig {
+        output_path: cli.checkpoint_file,
+        ..CheckpointConfig::default()
+    };
+    if !cli.checkpoint_triggers.is_empty() {
+        checkpoint_config.triggers = cli.checkpoint_triggers;
+    }
 
     // Create trace configuration if trace is enabled
     #[cfg(feature = "trace")]
@@ -172,7 +227,9 @@ fn main() -> Result<()> {
     if let Some(restore_path) = cli.restore {
         println!("Restoring from checkpoint: {}", restore_path);
 
-        let restored_stacks: Stacks = match migration::restore(Rc::clone(&inst), &restore_path) {
+        let mut instances = vec![Rc::clone(&inst)];
+        instances.extend(linked_instances.iter().cloned());
+        let restored_stacks: Stacks = match migration::restore(&instances, &restore_path) {
             Ok(stacks) => stacks,
             Err(e) => {
                 eprintln!("Failed to restore state: {:?}", e);
@@ -180,6 +237,7 @@ fn main() -> Result<()> {
             }
         };
         println!("State restored into module instance. Stacks obtained.");
+        let in_start = restored_stacks.in_start;
 
         let mut runtime = Runtime::new_restored(
             Rc::clone(&inst),
@@ -187,32 +245,47 @@ fn main() -> Result<()> {
             cli.enable_stats,
             cli.enable_checkpoint,
             #[cfg(feature = "trace")]
-            trace_config,
+            trace_config.clone(),
         );
+        runtime.set_linked_instances(linked_instances.clone());
+        runtime.set_checkpoint_config(checkpoint_config.clone());
         println!("Runtime reconstructed. Resuming execution...");
 
         let result = runtime.run();
-        handle_result(result);
-    } else {
-        let func_addr = inst.get_export_func(&cli.invoke)?;
-        let params = parse_params(cli.params.unwrap_or_default());
+        // A checkpoint taken during the start function resumes into it; the
+        // entry function still has to be invoked once it completes.
+        if !in_start || result.is_err() {
+            handle_result(result);
+            return Ok(());
+        }
+    } else if let Err(e) = inst.run_start(
+        cli.enable_checkpoint.then_some(&checkpoint_config),
+        &linked_instances,
+    ) {
+        handle_result(Err(e));
+        return Ok(());
+    }
 
-        match Runtime::new(
-            Rc::clone(&inst),
-            &func_addr,
-            params,
-            cli.enable_stats,
-            cli.enable_checkpoint,
-            #[cfg(feature = "trace")]
-            trace_config,
-        ) {
-            Ok(mut runtime) => {
-                let result = runtime.run();
-                handle_result(result);
-            }
-            Err(e) => {
-                eprintln!("Runtime initialization failed: {:?}", e);
-            }
+    let func_addr = inst.get_export_func(&cli.invoke)?;
+    let params = parse_params(cli.params.unwrap_or_default());
+
+    match Runtime::new(
+        Rc::clone(&inst),
+        &func_addr,
+        params,
+        cli.enable_stats,
+        cli.enable_checkpoint,
+        #[cfg(feature = "trace")]
+        trace_config,
+    ) {
+        Ok(mut runtime) => {
+            runtime.set_linked_instances(linked_instances);
+            runtime.set_checkpoint_config(checkpoint_config);
+            let result = runtime.run();
+            handle_result(result);
+        }
+        Err(e) => {
+            eprintln!("Runtime initialization failed: {:?}", e);
         }
     }
 
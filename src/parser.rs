@@ -41,7 +41,8 @@
 use std::fs::File;
 use std::io::Read;
 use wasmparser::{
-    ExternalKind, FunctionBody, Parser, Payload::*, SectionLimited, TypeRef, ValType,
+    BinaryReaderError, ExternalKind, FuncValidatorAllocations, FunctionBody, Parser, Payload::*,
+    SectionLimited, TypeRef, ValType, ValidPayload, Validator, WasmFeatures,
 };
 
 use crate::error::{ParserError, RuntimeError};
@@ -51,8 +52,10 @@ use crate::execution::regs::{Reg, RegAllocator};
 use crate::structure::{instructions::*, module::*, types::*};
 use itertools::Itertools;
 use rustc_hash::FxHashMap;
+use std::borrow::Cow;
 use std::rc::Rc;
 use std::sync::LazyLock;
+use twox_hash::XxHash64;
 
 /// Pending operand for peek-based operand folding.
 /// When a const or local.get instruction is followed by a foldable consumer,
@@ -103,6 +106,15 @@ fn take_i64_operand(pending: &mut Vec<PendingOperand>, reg_index: u16) -> I64Reg
     }
 }
 
+/// Extract AddrOperand for a memory access whose address was popped as `reg`
+#[inline]
+fn take_addr_operand(pending: &mut Vec<PendingOperand>, reg: Reg) -> AddrOperand {
+    match reg {
+        Reg::I64(idx) => AddrOperand::I64(take_i64_operand(pending, idx)),
+        _ => AddrOperand::I32(take_i32_operand(pending, reg.index())),
+    }
+}
+
 /// Extract F32RegOperand from pending_operands stack, falling back to register
 #[inline]
 fn take_f32_operand(pending: &mut Vec<PendingOperand>, reg_index: u16) -> F32RegOperand {
@@ -716,6 +728,8 @@ fn match_value_type(t: ValType) -> ValueType {
         ValType::Ref(ref_type) => {
             if ref_type.is_func_ref() {
                 ValueType::RefType(RefType::FuncRef)
+            } else if matches!(ref_type.heap_type(), wasmparser::HeapType::Exn) {
+                ValueType::RefType(RefType::ExnRef)
             } else {
                 ValueType::RefType(RefType::ExternalRef)
             }
@@ -893,13 +907,9 @@ fn decode_import_section(
                 }
             }
             TypeRef::Table(table_type) => {
-                let max = match table_type.maximum {
-                    Some(m) => Some(TryFrom::try_from(m).unwrap()),
-                    None => None,
-                };
                 let limits = Limits {
-                    min: TryFrom::try_from(table_type.initial).unwrap(),
-                    max,
+                    min: table_type.initial,
+                    max: table_type.maximum,
                 };
                 let reftype = if table_type.element_type.is_func_ref() {
                     RefType::FuncRef
@@ -910,22 +920,18 @@ fn decode_import_section(
                 ImportDesc::Table(TableType(limits, reftype))
             }
             TypeRef::Memory(memory) => {
-                let max = match memory.maximum {
-                    Some(m) => Some(TryFrom::try_from(m).unwrap()),
-                    None => None,
-                };
                 let limits = Limits {
-                    min: TryFrom::try_from(memory.initial).unwrap(),
-                    max,
+                    min: memory.initial,
+                    max: memory.maximum,
                 };
-                ImportDesc::Mem(MemType(limits))
+                ImportDesc::Mem(MemType(limits, match_addr_type(&memory)))
             }
             TypeRef::Global(global) => {
                 let mut_ = if global.mutable { Mut::Var } else { Mut::Const };
                 let value_type = match_value_type(global.content_type);
                 ImportDesc::Global(GlobalType(mut_, value_type))
             }
-            TypeRef::Tag(_) => todo!(),
+            TypeRef::Tag(tag_type) => ImportDesc::Tag(TypeIdx(tag_type.func_type_idx)),
         };
         module.imports.push(Import {
             module: Name(import.module.to_string()),
@@ -954,7 +960,7 @@ fn decode_export_section(
             ExternalKind::Table => ExportDesc::Table(TableIdx(index)),
             ExternalKind::Memory => ExportDesc::Mem(MemIdx(index)),
             ExternalKind::Global => ExportDesc::Global(GlobalIdx(index)),
-            ExternalKind::Tag => todo!(),
+            ExternalKind::Tag => ExportDesc::Tag(TagIdx(index)),
         };
         module.exports.push(Export {
             name: Name(export.name.to_string()),
@@ -971,21 +977,26 @@ fn decode_mem_section(
 ) -> Result<(), Box<dyn std::error::Error>> {
     for memory in body {
         let memory = memory?;
-        let max = match memory.maximum {
-            Some(m) => Some(TryFrom::try_from(m).unwrap()),
-            None => None,
-        };
         let limits = Limits {
-            min: TryFrom::try_from(memory.initial).unwrap(),
-            max,
+            min: memory.initial,
+            max: memory.maximum,
         };
         module.mems.push(Mem {
-            type_: MemType(limits),
+            type_: MemType(limits, match_addr_type(&memory)),
         });
     }
     Ok(())
 }
 
+/// Returns the address type of a memory type.
+fn match_addr_type(memory: &wasmparser::MemoryType) -> AddrType {
+    if memory.memory64 {
+        AddrType::I64
+    } else {
+        AddrType::I32
+    }
+}
+
 /// Decodes the table section.
 fn decode_table_section(
     body: SectionLimited<'_, wasmparser::Table<'_>>,
@@ -995,13 +1006,9 @@ fn decode_table_section(
         let table = table?;
         let table_type = table.ty;
 
-        let max = match table_type.maximum {
-            Some(m) => Some(TryFrom::try_from(m).unwrap()),
-            None => None,
-        };
         let limits = Limits {
-            min: TryFrom::try_from(table_type.initial).unwrap(),
-            max,
+            min: table_type.initial,
+            max: table_type.maximum,
         };
 
         let reftype = if table_type.element_type.is_func_ref() {
@@ -1016,6 +1023,20 @@ fn decode_table_section(
     Ok(())
 }
 
+/// Decodes the tag section.
+fn decode_tag_section(
+    body: SectionLimited<'_, wasmparser::TagType>,
+    module: &mut Module,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for tag in body {
+        let tag = tag?;
+        module.tags.push(Tag {
+            type_: TypeIdx(tag.func_type_idx),
+        });
+    }
+    Ok(())
+}
+
 /// Decodes the global section.
 fn decode_global_section(
     body: SectionLimited<'_, wasmparser::Global<'_>>,
@@ -1059,6 +1080,9 @@ fn parse_initexpr(expr: wasmparser::ConstExpr<'_>) -> Result<Expr, Box<dyn std::
             wasmparser::Operator::F64Const { value } => {
                 instrs.push(Instr::F64Const(f64::from_bits(value.bits())))
             }
+            wasmparser::Operator::V128Const { value } => {
+                instrs.push(Instr::V128Const(value.i128()))
+            }
             wasmparser::Operator::RefNull { .. } => {
                 instrs.push(Instr::RefNull(RefType::ExternalRef))
             }
@@ -1314,7 +1338,7 @@ fn preprocess_instructions(
         current_control_stack_pass2.clear();
         for (pc, instr) in processed.iter().enumerate().take(current_fixup_pc + 1) {
             match instr.handler_index() {
-                HANDLER_IDX_BLOCK | HANDLER_IDX_IF => {
+                HANDLER_IDX_BLOCK | HANDLER_IDX_IF | HANDLER_IDX_TRY_TABLE => {
                     let block_type = block_type_map
                         .get(&pc)
                         .cloned()
@@ -1465,7 +1489,7 @@ fn preprocess_instructions(
     for pc in 0..processed.len() {
         if let Some(instr) = processed.get(pc) {
             match instr.handler_index() {
-                HANDLER_IDX_BLOCK | HANDLER_IDX_IF => {
+                HANDLER_IDX_BLOCK | HANDLER_IDX_IF | HANDLER_IDX_TRY_TABLE => {
                     let block_type = block_type_map
                         .get(&pc)
                         .cloned()
@@ -1487,6 +1511,35 @@ fn preprocess_instructions(
                 _ => {}
             }
 
+            // Resolve try_table catch targets. The try_table itself is already on the
+            // simulated stack, so catch label N is a branch of depth N + 1.
+            if let ProcessedInstr::TryTableReg { catches } = instr {
+                let target_ips: Vec<usize> = catches
+                    .iter()
+                    .map(|clause| {
+                        let depth = clause.label as usize + 1;
+                        if current_control_stack_pass3.len() <= depth {
+                            // Catching to the function level returns from the function
+                            return processed.len();
+                        }
+                        let target_stack_level = current_control_stack_pass3.len() - 1 - depth;
+                        let (target_start_pc, is_loop, _) =
+                            current_control_stack_pass3[target_stack_level];
+                        if is_loop {
+                            target_start_pc
+                        } else {
+                            *block_end_map.get(&target_start_pc).unwrap_or(&0)
+                        }
+                    })
+                    .collect();
+                if let Some(ProcessedInstr::TryTableReg { catches }) = processed.get_mut(pc) {
+                    for (clause, target_ip) in catches.iter_mut().zip(target_ips) {
+                        clause.target_ip = target_ip;
+                    }
+                }
+                continue;
+            }
+
             // Check if it's a BrTable needing resolution *after* simulating stack for current pc
             let needs_br_table_resolution = matches!(instr, ProcessedInstr::BrTableReg { .. });
 
@@ -1809,6 +1862,63 @@ fn get_global_type(module: &Module, global_index: u32) -> ValueType {
     ValueType::NumType(NumType::I32)
 }
 
+/// Returns the parameter types of a function by index.
+///
+/// Searches imported functions first, then module-defined functions.
+fn get_func_param_types(module: &Module, function_index: u32) -> Vec<ValueType> {
+    let mut imported_func_count = 0u32;
+    for import in &module.imports {
+        let params = match &import.desc {
+            ImportDesc::Func(type_idx) => module
+                .types
+                .get(type_idx.0 as usize)
+                .map(|func_type| func_type.params.clone()),
+            ImportDesc::WasiFunc(wasi_type) => Some(wasi_type.expected_func_type().params),
+            _ => continue,
+        };
+        if imported_func_count == function_index {
+            return params.unwrap_or_default();
+        }
+        imported_func_count += 1;
+    }
+
+    let local_func_index = (function_index - imported_func_count) as usize;
+    module
+        .funcs
+        .get(local_func_index)
+        .and_then(|func| module.types.get(func.type_.0 as usize))
+        .map(|func_type| func_type.params.clone())
+        .unwrap_or_default()
+}
+
+/// Returns the payload types of a tag by index.
+///
+/// Searches imported tags first, then module-defined tags.
+fn get_tag_param_types(module: &Module, tag_index: u32) -> Vec<ValueType> {
+    let mut imported_tag_count = 0u32;
+    let mut type_idx = None;
+    for import in &module.imports {
+        if let ImportDesc::Tag(idx) = &import.desc {
+            if imported_tag_count == tag_index {
+                type_idx = Some(*idx);
+                break;
+            }
+            imported_tag_count += 1;
+        }
+    }
+
+    let type_idx = type_idx.or_else(|| {
+        module
+            .tags
+            .get((tag_index - imported_tag_count) as usize)
+            .map(|tag| tag.type_)
+    });
+    type_idx
+        .and_then(|idx| module.types.get(idx.0 as usize))
+        .map(|func_type| func_type.params.clone())
+        .unwrap_or_default()
+}
+
 /// Returns the element type of a table by index.
 ///
 /// Searches imported tables first, then module-defined tables.
@@ -1834,6 +1944,497 @@ fn get_table_element_type(module: &Module, table_index: u32) -> ValueType {
     ValueType::RefType(RefType::FuncRef)
 }
 
+/// Returns the type of addresses into a memory by index: i64 for 64-bit
+/// memories, i32 otherwise.
+///
+/// Searches imported memories first, then module-defined memories.
+fn memory_addr_type(module: &Module, mem_index: u32) -> ValueType {
+    let mut imported_mem_count = 0u32;
+    let mut mem_type = None;
+    for import in &module.imports {
+        if let ImportDesc::Mem(type_) = &import.desc {
+            if imported_mem_count == mem_index {
+                mem_type = Some(*type_);
+                break;
+            }
+            imported_mem_count += 1;
+        }
+    }
+
+    let mem_type = mem_type.or_else(|| {
+        module
+            .mems
+            .get((mem_index - imported_mem_count) as usize)
+            .map(|mem| mem.type_)
+    });
+    match mem_type {
+        Some(MemType(_, AddrType::I64)) => ValueType::NumType(NumType::I64),
+        _ => ValueType::NumType(NumType::I32),
+    }
+}
+
+/// Register shape of a fixed-width SIMD instruction.
+enum SimdShape {
+    /// [] -> [v128]
+    Const([u8; 16]),
+    /// [v128 v128] -> [v128]
+    Shuffle([u8; 16]),
+    /// [v128] -> [v128]
+    Unary,
+    /// [v128 v128] -> [v128]
+    Binary,
+    /// [v128 v128 v128] -> [v128]
+    Ternary,
+    /// [v128] -> [i32]
+    Test,
+    /// [v128 i32] -> [v128]
+    Shift,
+    /// [t] -> [v128]
+    Splat(ValueType),
+    /// [v128] -> [t]
+    ExtractLane(ValueType, u8),
+    /// [v128 t] -> [v128]
+    ReplaceLane(ValueType, u8),
+    /// [i32] -> [v128]
+    Load(wasmparser::MemArg),
+    /// [i32 v128] -> [v128]
+    LoadLane(wasmparser::MemArg, u8),
+    /// [i32 v128] -> []
+    Store(wasmparser::MemArg),
+    /// [i32 v128] -> []
+    StoreLane(wasmparser::MemArg, u8),
+}
+
+/// Lowers a fixed-width SIMD operator to `SimdReg` / `SimdMemReg`.
+/// Returns `None` if `op` is not part of the SIMD proposal.
+fn decode_simd_instr(
+    op: &wasmparser::Operator,
+    allocator: &mut RegAllocator,
+    pending_operands: &mut Vec<PendingOperand>,
+    module: &Module,
+) -> Option<ProcessedInstr> {
+    use wasmparser::Operator as Op;
+    const V128: ValueType = ValueType::VecType(VecType::V128);
+    const I32: ValueType = ValueType::NumType(NumType::I32);
+    const I64: ValueType = ValueType::NumType(NumType::I64);
+    const F32: ValueType = ValueType::NumType(NumType::F32);
+    const F64: ValueType = ValueType::NumType(NumType::F64);
+
+    let (handler_index, shape) = match op {
+        Op::V128Load { memarg } => (HANDLER_IDX_V128_LOAD, SimdShape::Load(*memarg)),
+        Op::V128Load8x8S { memarg } => (HANDLER_IDX_V128_LOAD8X8_S, SimdShape::Load(*memarg)),
+        Op::V128Load8x8U { memarg } => (HANDLER_IDX_V128_LOAD8X8_U, SimdShape::Load(*memarg)),
+        Op::V128Load16x4S { memarg } => (HANDLER_IDX_V128_LOAD16X4_S, SimdShape::Load(*memarg)),
+        Op::V128Load16x4U { memarg } => (HANDLER_IDX_V128_LOAD16X4_U, SimdShape::Load(*memarg)),
+        Op::V128Load32x2S { memarg } => (HANDLER_IDX_V128_LOAD32X2_S, SimdShape::Load(*memarg)),
+        Op::V128Load32x2U { memarg } => (HANDLER_IDX_V128_LOAD32X2_U, SimdShape::Load(*memarg)),
+        Op::V128Load8Splat { memarg } => (HANDLER_IDX_V128_LOAD8_SPLAT, SimdShape::Load(*memarg)),
+        Op::V128Load16Splat { memarg } => (HANDLER_IDX_V128_LOAD16_SPLAT, SimdShape::Load(*memarg)),
+        Op::V128Load32Splat { memarg } => (HANDLER_IDX_V128_LOAD32_SPLAT, SimdShape::Load(*memarg)),
+        Op::V128Load64Splat { memarg } => (HANDLER_IDX_V128_LOAD64_SPLAT, SimdShape::Load(*memarg)),
+        Op::V128Store { memarg } => (HANDLER_IDX_V128_STORE, SimdShape::Store(*memarg)),
+        Op::V128Const { value } => (HANDLER_IDX_V128_CONST, SimdShape::Const(*value.bytes())),
+        Op::I8x16Shuffle { lanes } => (HANDLER_IDX_I8X16_SHUFFLE, SimdShape::Shuffle(*lanes)),
+        Op::I8x16Swizzle => (HANDLER_IDX_I8X16_SWIZZLE, SimdShape::Binary),
+        Op::I8x16Splat => (HANDLER_IDX_I8X16_SPLAT, SimdShape::Splat(I32)),
+        Op::I16x8Splat => (HANDLER_IDX_I16X8_SPLAT, SimdShape::Splat(I32)),
+        Op::I32x4Splat => (HANDLER_IDX_I32X4_SPLAT, SimdShape::Splat(I32)),
+        Op::I64x2Splat => (HANDLER_IDX_I64X2_SPLAT, SimdShape::Splat(I64)),
+        Op::F32x4Splat => (HANDLER_IDX_F32X4_SPLAT, SimdShape::Splat(F32)),
+        Op::F64x2Splat => (HANDLER_IDX_F64X2_SPLAT, SimdShape::Splat(F64)),
+        Op::I8x16ExtractLaneS { lane } => (
+            HANDLER_IDX_I8X16_EXTRACT_LANE_S,
+            SimdShape::ExtractLane(I32, *lane),
+        ),
+        Op::I8x16ExtractLaneU { lane } => (
+            HANDLER_IDX_I8X16_EXTRACT_LANE_U,
+            SimdShape::ExtractLane(I32, *lane),
+        ),
+        Op::I8x16ReplaceLane { lane } => (
+            HANDLER_IDX_I8X16_REPLACE_LANE,
+            SimdShape::ReplaceLane(I32, *lane),
+        ),
+        Op::I16x8ExtractLaneS { lane } => (
+            HANDLER_IDX_I16X8_EXTRACT_LANE_S,
+            SimdShape::ExtractLane(I32, *lane),
+        ),
+        Op::I16x8ExtractLaneU { lane } => (
+            HANDLER_IDX_I16X8_EXTRACT_LANE_U,
+            SimdShape::ExtractLane(I32, *lane),
+        ),
+        Op::I16x8ReplaceLane { lane } => (
+            HANDLER_IDX_I16X8_REPLACE_LANE,
+            SimdShape::ReplaceLane(I32, *lane),
+        ),
+        Op::I32x4ExtractLane { lane } => (
+            HANDLER_IDX_I32X4_EXTRACT_LANE,
+            SimdShape::ExtractLane(I32, *lane),
+        ),
+        Op::I32x4ReplaceLane { lane } => (
+            HANDLER_IDX_I32X4_REPLACE_LANE,
+            SimdShape::ReplaceLane(I32, *lane),
+        ),
+        Op::I64x2ExtractLane { lane } => (
+            HANDLER_IDX_I64X2_EXTRACT_LANE,
+            SimdShape::ExtractLane(I64, *lane),
+        ),
+        Op::I64x2ReplaceLane { lane } => (
+            HANDLER_IDX_I64X2_REPLACE_LANE,
+            SimdShape::ReplaceLane(I64, *lane),
+        ),
+        Op::F32x4ExtractLane { lane } => (
+            HANDLER_IDX_F32X4_EXTRACT_LANE,
+            SimdShape::ExtractLane(F32, *lane),
+        ),
+        Op::F32x4ReplaceLane { lane } => (
+            HANDLER_IDX_F32X4_REPLACE_LANE,
+            SimdShape::ReplaceLane(F32, *lane),
+        ),
+        Op::F64x2ExtractLane { lane } => (
+            HANDLER_IDX_F64X2_EXTRACT_LANE,
+            SimdShape::ExtractLane(F64, *lane),
+        ),
+        Op::F64x2ReplaceLane { lane } => (
+            HANDLER_IDX_F64X2_REPLACE_LANE,
+            SimdShape::ReplaceLane(F64, *lane),
+        ),
+        Op::I8x16Eq => (HANDLER_IDX_I8X16_EQ, SimdShape::Binary),
+        Op::I8x16Ne => (HANDLER_IDX_I8X16_NE, SimdShape::Binary),
+        Op::I8x16LtS => (HANDLER_IDX_I8X16_LT_S, SimdShape::Binary),
+        Op::I8x16LtU => (HANDLER_IDX_I8X16_LT_U, SimdShape::Binary),
+        Op::I8x16GtS => (HANDLER_IDX_I8X16_GT_S, SimdShape::Binary),
+        Op::I8x16GtU => (HANDLER_IDX_I8X16_GT_U, SimdShape::Binary),
+        Op::I8x16LeS => (HANDLER_IDX_I8X16_LE_S, SimdShape::Binary),
+        Op::I8x16LeU => (HANDLER_IDX_I8X16_LE_U, SimdShape::Binary),
+        Op::I8x16GeS => (HANDLER_IDX_I8X16_GE_S, SimdShape::Binary),
+        Op::I8x16GeU => (HANDLER_IDX_I8X16_GE_U, SimdShape::Binary),
+        Op::I16x8Eq => (HANDLER_IDX_I16X8_EQ, SimdShape::Binary),
+        Op::I16x8Ne => (HANDLER_IDX_I16X8_NE, SimdShape::Binary),
+        Op::I16x8LtS => (HANDLER_IDX_I16X8_LT_S, SimdShape::Binary),
+        Op::I16x8LtU => (HANDLER_IDX_I16X8_LT_U, SimdShape::Binary),
+        Op::I16x8GtS => (HANDLER_IDX_I16X8_GT_S, SimdShape::Binary),
+        Op::I16x8GtU => (HANDLER_IDX_I16X8_GT_U, SimdShape::Binary),
+        Op::I16x8LeS => (HANDLER_IDX_I16X8_LE_S, SimdShape::Binary),
+        Op::I16x8LeU => (HANDLER_IDX_I16X8_LE_U, SimdShape::Binary),
+        Op::I16x8GeS => (HANDLER_IDX_I16X8_GE_S, SimdShape::Binary),
+        Op::I16x8GeU => (HANDLER_IDX_I16X8_GE_U, SimdShape::Binary),
+        Op::I32x4Eq => (HANDLER_IDX_I32X4_EQ, SimdShape::Binary),
+        Op::I32x4Ne => (HANDLER_IDX_I32X4_NE, SimdShape::Binary),
+        Op::I32x4LtS => (HANDLER_IDX_I32X4_LT_S, SimdShape::Binary),
+        Op::I32x4LtU => (HANDLER_IDX_I32X4_LT_U, SimdShape::Binary),
+        Op::I32x4GtS => (HANDLER_IDX_I32X4_GT_S, SimdShape::Binary),
+        Op::I32x4GtU => (HANDLER_IDX_I32X4_GT_U, SimdShape::Binary),
+        Op::I32x4LeS => (HANDLER_IDX_I32X4_LE_S, SimdShape::Binary),
+        Op::I32x4LeU => (HANDLER_IDX_I32X4_LE_U, SimdShape::Binary),
+        Op::I32x4GeS => (HANDLER_IDX_I32X4_GE_S, SimdShape::Binary),
+        Op::I32x4GeU => (HANDLER_IDX_I32X4_GE_U, SimdShape::Binary),
+        Op::F32x4Eq => (HANDLER_IDX_F32X4_EQ, SimdShape::Binary),
+        Op::F32x4Ne => (HANDLER_IDX_F32X4_NE, SimdShape::Binary),
+        Op::F32x4Lt => (HANDLER_IDX_F32X4_LT, SimdShape::Binary),
+        Op::F32x4Gt => (HANDLER_IDX_F32X4_GT, SimdShape::Binary),
+        Op::F32x4Le => (HANDLER_IDX_F32X4_LE, SimdShape::Binary),
+        Op::F32x4Ge => (HANDLER_IDX_F32X4_GE, SimdShape::Binary),
+        Op::F64x2Eq => (HANDLER_IDX_F64X2_EQ, SimdShape::Binary),
+        Op::F64x2Ne => (HANDLER_IDX_F64X2_NE, SimdShape::Binary),
+        Op::F64x2Lt => (HANDLER_IDX_F64X2_LT, SimdShape::Binary),
+        Op::F64x2Gt => (HANDLER_IDX_F64X2_GT, SimdShape::Binary),
+        Op::F64x2Le => (HANDLER_IDX_F64X2_LE, SimdShape::Binary),
+        Op::F64x2Ge => (HANDLER_IDX_F64X2_GE, SimdShape::Binary),
+        Op::V128Not => (HANDLER_IDX_V128_NOT, SimdShape::Unary),
+        Op::V128And => (HANDLER_IDX_V128_AND, SimdShape::Binary),
+        Op::V128AndNot => (HANDLER_IDX_V128_ANDNOT, SimdShape::Binary),
+        Op::V128Or => (HANDLER_IDX_V128_OR, SimdShape::Binary),
+        Op::V128Xor => (HANDLER_IDX_V128_XOR, SimdShape::Binary),
+        Op::V128Bitselect => (HANDLER_IDX_V128_BITSELECT, SimdShape::Ternary),
+        Op::V128AnyTrue => (HANDLER_IDX_V128_ANY_TRUE, SimdShape::Test),
+        Op::V128Load8Lane { memarg, lane } => (
+            HANDLER_IDX_V128_LOAD8_LANE,
+            SimdShape::LoadLane(*memarg, *lane),
+        ),
+        Op::V128Load16Lane { memarg, lane } => (
+            HANDLER_IDX_V128_LOAD16_LANE,
+            SimdShape::LoadLane(*memarg, *lane),
+        ),
+        Op::V128Load32Lane { memarg, lane } => (
+            HANDLER_IDX_V128_LOAD32_LANE,
+            SimdShape::LoadLane(*memarg, *lane),
+        ),
+        Op::V128Load64Lane { memarg, lane } => (
+            HANDLER_IDX_V128_LOAD64_LANE,
+            SimdShape::LoadLane(*memarg, *lane),
+        ),
+        Op::V128Store8Lane { memarg, lane } => (
+            HANDLER_IDX_V128_STORE8_LANE,
+            SimdShape::StoreLane(*memarg, *lane),
+        ),
+        Op::V128Store16Lane { memarg, lane } => (
+            HANDLER_IDX_V128_STORE16_LANE,
+            SimdShape::StoreLane(*memarg, *lane),
+        ),
+        Op::V128Store32Lane { memarg, lane } => (
+            HANDLER_IDX_V128_STORE32_LANE,
+            SimdShape::StoreLane(*memarg, *lane),
+        ),
+        Op::V128Store64Lane { memarg, lane } => (
+            HANDLER_IDX_V128_STORE64_LANE,
+            SimdShape::StoreLane(*memarg, *lane),
+        ),
+        Op::V128Load32Zero { memarg } => (HANDLER_IDX_V128_LOAD32_ZERO, SimdShape::Load(*memarg)),
+        Op::V128Load64Zero { memarg } => (HANDLER_IDX_V128_LOAD64_ZERO, SimdShape::Load(*memarg)),
+        Op::F32x4DemoteF64x2Zero => (HANDLER_IDX_F32X4_DEMOTE_F64X2_ZERO, SimdShape::Unary),
+        Op::F64x2PromoteLowF32x4 => (HANDLER_IDX_F64X2_PROMOTE_LOW_F32X4, SimdShape::Unary),
+        Op::I8x16Abs => (HANDLER_IDX_I8X16_ABS, SimdShape::Unary),
+        Op::I8x16Neg => (HANDLER_IDX_I8X16_NEG, SimdShape::Unary),
+        Op::I8x16Popcnt => (HANDLER_IDX_I8X16_POPCNT, SimdShape::Unary),
+        Op::I8x16AllTrue => (HANDLER_IDX_I8X16_ALL_TRUE, SimdShape::Test),
+        Op::I8x16Bitmask => (HANDLER_IDX_I8X16_BITMASK, SimdShape::Test),
+        Op::I8x16NarrowI16x8S => (HANDLER_IDX_I8X16_NARROW_I16X8_S, SimdShape::Binary),
+        Op::I8x16NarrowI16x8U => (HANDLER_IDX_I8X16_NARROW_I16X8_U, SimdShape::Binary),
+        Op::F32x4Ceil => (HANDLER_IDX_F32X4_CEIL, SimdShape::Unary),
+        Op::F32x4Floor => (HANDLER_IDX_F32X4_FLOOR, SimdShape::Unary),
+        Op::F32x4Trunc => (HANDLER_IDX_F32X4_TRUNC, SimdShape::Unary),
+        Op::F32x4Nearest => (HANDLER_IDX_F32X4_NEAREST, SimdShape::Unary),
+        Op::I8x16Shl => (HANDLER_IDX_I8X16_SHL, SimdShape::Shift),
+        Op::I8x16ShrS => (HANDLER_IDX_I8X16_SHR_S, SimdShape::Shift),
+        Op::I8x16ShrU => (HANDLER_IDX_I8X16_SHR_U, SimdShape::Shift),
+        Op::I8x16Add => (HANDLER_IDX_I8X16_ADD, SimdShape::Binary),
+        Op::I8x16AddSatS => (HANDLER_IDX_I8X16_ADD_SAT_S, SimdShape::Binary),
+        Op::I8x16AddSatU => (HANDLER_IDX_I8X16_ADD_SAT_U, SimdShape::Binary),
+        Op::I8x16Sub => (HANDLER_IDX_I8X16_SUB, SimdShape::Binary),
+        Op::I8x16SubSatS => (HANDLER_IDX_I8X16_SUB_SAT_S, SimdShape::Binary),
+        Op::I8x16SubSatU => (HANDLER_IDX_I8X16_SUB_SAT_U, SimdShape::Binary),
+        Op::F64x2Ceil => (HANDLER_IDX_F64X2_CEIL, SimdShape::Unary),
+        Op::F64x2Floor => (HANDLER_IDX_F64X2_FLOOR, SimdShape::Unary),
+        Op::I8x16MinS => (HANDLER_IDX_I8X16_MIN_S, SimdShape::Binary),
+        Op::I8x16MinU => (HANDLER_IDX_I8X16_MIN_U, SimdShape::Binary),
+        Op::I8x16MaxS => (HANDLER_IDX_I8X16_MAX_S, SimdShape::Binary),
+        Op::I8x16MaxU => (HANDLER_IDX_I8X16_MAX_U, SimdShape::Binary),
+        Op::F64x2Trunc => (HANDLER_IDX_F64X2_TRUNC, SimdShape::Unary),
+        Op::I8x16AvgrU => (HANDLER_IDX_I8X16_AVGR_U, SimdShape::Binary),
+        Op::I16x8ExtAddPairwiseI8x16S => {
+            (HANDLER_IDX_I16X8_EXTADD_PAIRWISE_I8X16_S, SimdShape::Unary)
+        }
+        Op::I16x8ExtAddPairwiseI8x16U => {
+            (HANDLER_IDX_I16X8_EXTADD_PAIRWISE_I8X16_U, SimdShape::Unary)
+        }
+        Op::I32x4ExtAddPairwiseI16x8S => {
+            (HANDLER_IDX_I32X4_EXTADD_PAIRWISE_I16X8_S, SimdShape::Unary)
+        }
+        Op::I32x4ExtAddPairwiseI16x8U => {
+            (HANDLER_IDX_I32X4_EXTADD_PAIRWISE_I16X8_U, SimdShape::Unary)
+        }
+        Op::I16x8Abs => (HANDLER_IDX_I16X8_ABS, SimdShape::Unary),
+        Op::I16x8Neg => (HANDLER_IDX_I16X8_NEG, SimdShape::Unary),
+        Op::I16x8Q15MulrSatS => (HANDLER_IDX_I16X8_Q15MULR_SAT_S, SimdShape::Binary),
+        Op::I16x8AllTrue => (HANDLER_IDX_I16X8_ALL_TRUE, SimdShape::Test),
+        Op::I16x8Bitmask => (HANDLER_IDX_I16X8_BITMASK, SimdShape::Test),
+        Op::I16x8NarrowI32x4S => (HANDLER_IDX_I16X8_NARROW_I32X4_S, SimdShape::Binary),
+        Op::I16x8NarrowI32x4U => (HANDLER_IDX_I16X8_NARROW_I32X4_U, SimdShape::Binary),
+        Op::I16x8ExtendLowI8x16S => (HANDLER_IDX_I16X8_EXTEND_LOW_I8X16_S, SimdShape::Unary),
+        Op::I16x8ExtendHighI8x16S => (HANDLER_IDX_I16X8_EXTEND_HIGH_I8X16_S, SimdShape::Unary),
+        Op::I16x8ExtendLowI8x16U => (HANDLER_IDX_I16X8_EXTEND_LOW_I8X16_U, SimdShape::Unary),
+        Op::I16x8ExtendHighI8x16U => (HANDLER_IDX_I16X8_EXTEND_HIGH_I8X16_U, SimdShape::Unary),
+        Op::I16x8Shl => (HANDLER_IDX_I16X8_SHL, SimdShape::Shift),
+        Op::I16x8ShrS => (HANDLER_IDX_I16X8_SHR_S, SimdShape::Shift),
+        Op::I16x8ShrU => (HANDLER_IDX_I16X8_SHR_U, SimdShape::Shift),
+        Op::I16x8Add => (HANDLER_IDX_I16X8_ADD, SimdShape::Binary),
+        Op::I16x8AddSatS => (HANDLER_IDX_I16X8_ADD_SAT_S, SimdShape::Binary),
+        Op::I16x8AddSatU => (HANDLER_IDX_I16X8_ADD_SAT_U, SimdShape::Binary),
+        Op::I16x8Sub => (HANDLER_IDX_I16X8_SUB, SimdShape::Binary),
+        Op::I16x8SubSatS => (HANDLER_IDX_I16X8_SUB_SAT_S, SimdShape::Binary),
+        Op::I16x8SubSatU => (HANDLER_IDX_I16X8_SUB_SAT_U, SimdShape::Binary),
+        Op::F64x2Nearest => (HANDLER_IDX_F64X2_NEAREST, SimdShape::Unary),
+        Op::I16x8Mul => (HANDLER_IDX_I16X8_MUL, SimdShape::Binary),
+        Op::I16x8MinS => (HANDLER_IDX_I16X8_MIN_S, SimdShape::Binary),
+        Op::I16x8MinU => (HANDLER_IDX_I16X8_MIN_U, SimdShape::Binary),
+        Op::I16x8MaxS => (HANDLER_IDX_I16X8_MAX_S, SimdShape::Binary),
+        Op::I16x8MaxU => (HANDLER_IDX_I16X8_MAX_U, SimdShape::Binary),
+        Op::I16x8AvgrU => (HANDLER_IDX_I16X8_AVGR_U, SimdShape::Binary),
+        Op::I16x8ExtMulLowI8x16S => (HANDLER_IDX_I16X8_EXTMUL_LOW_I8X16_S, SimdShape::Binary),
+        Op::I16x8ExtMulHighI8x16S => (HANDLER_IDX_I16X8_EXTMUL_HIGH_I8X16_S, SimdShape::Binary),
+        Op::I16x8ExtMulLowI8x16U => (HANDLER_IDX_I16X8_EXTMUL_LOW_I8X16_U, SimdShape::Binary),
+        Op::I16x8ExtMulHighI8x16U => (HANDLER_IDX_I16X8_EXTMUL_HIGH_I8X16_U, SimdShape::Binary),
+        Op::I32x4Abs => (HANDLER_IDX_I32X4_ABS, SimdShape::Unary),
+        Op::I32x4Neg => (HANDLER_IDX_I32X4_NEG, SimdShape::Unary),
+        Op::I32x4AllTrue => (HANDLER_IDX_I32X4_ALL_TRUE, SimdShape::Test),
+        Op::I32x4Bitmask => (HANDLER_IDX_I32X4_BITMASK, SimdShape::Test),
+        Op::I32x4ExtendLowI16x8S => (HANDLER_IDX_I32X4_EXTEND_LOW_I16X8_S, SimdShape::Unary),
+        Op::I32x4ExtendHighI16x8S => (HANDLER_IDX_I32X4_EXTEND_HIGH_I16X8_S, SimdShape::Unary),
+        Op::I32x4ExtendLowI16x8U => (HANDLER_IDX_I32X4_EXTEND_LOW_I16X8_U, SimdShape::Unary),
+        Op::I32x4ExtendHighI16x8U => (HANDLER_IDX_I32X4_EXTEND_HIGH_I16X8_U, SimdShape::Unary),
+        Op::I32x4Shl => (HANDLER_IDX_I32X4_SHL, SimdShape::Shift),
+        Op::I32x4ShrS => (HANDLER_IDX_I32X4_SHR_S, SimdShape::Shift),
+        Op::I32x4ShrU => (HANDLER_IDX_I32X4_SHR_U, SimdShape::Shift),
+        Op::I32x4Add => (HANDLER_IDX_I32X4_ADD, SimdShape::Binary),
+        Op::I32x4Sub => (HANDLER_IDX_I32X4_SUB, SimdShape::Binary),
+        Op::I32x4Mul => (HANDLER_IDX_I32X4_MUL, SimdShape::Binary),
+        Op::I32x4MinS => (HANDLER_IDX_I32X4_MIN_S, SimdShape::Binary),
+        Op::I32x4MinU => (HANDLER_IDX_I32X4_MIN_U, SimdShape::Binary),
+        Op::I32x4MaxS => (HANDLER_IDX_I32X4_MAX_S, SimdShape::Binary),
+        Op::I32x4MaxU => (HANDLER_IDX_I32X4_MAX_U, SimdShape::Binary),
+        Op::I32x4DotI16x8S => (HANDLER_IDX_I32X4_DOT_I16X8_S, SimdShape::Binary),
+        Op::I32x4ExtMulLowI16x8S => (HANDLER_IDX_I32X4_EXTMUL_LOW_I16X8_S, SimdShape::Binary),
+        Op::I32x4ExtMulHighI16x8S => (HANDLER_IDX_I32X4_EXTMUL_HIGH_I16X8_S, SimdShape::Binary),
+        Op::I32x4ExtMulLowI16x8U => (HANDLER_IDX_I32X4_EXTMUL_LOW_I16X8_U, SimdShape::Binary),
+        Op::I32x4ExtMulHighI16x8U => (HANDLER_IDX_I32X4_EXTMUL_HIGH_I16X8_U, SimdShape::Binary),
+        Op::I64x2Abs => (HANDLER_IDX_I64X2_ABS, SimdShape::Unary),
+        Op::I64x2Neg => (HANDLER_IDX_I64X2_NEG, SimdShape::Unary),
+        Op::I64x2AllTrue => (HANDLER_IDX_I64X2_ALL_TRUE, SimdShape::Test),
+        Op::I64x2Bitmask => (HANDLER_IDX_I64X2_BITMASK, SimdShape::Test),
+        Op::I64x2ExtendLowI32x4S => (HANDLER_IDX_I64X2_EXTEND_LOW_I32X4_S, SimdShape::Unary),
+        Op::I64x2ExtendHighI32x4S => (HANDLER_IDX_I64X2_EXTEND_HIGH_I32X4_S, SimdShape::Unary),
+        Op::I64x2ExtendLowI32x4U => (HANDLER_IDX_I64X2_EXTEND_LOW_I32X4_U, SimdShape::Unary),
+        Op::I64x2ExtendHighI32x4U => (HANDLER_IDX_I64X2_EXTEND_HIGH_I32X4_U, SimdShape::Unary),
+        Op::I64x2Shl => (HANDLER_IDX_I64X2_SHL, SimdShape::Shift),
+        Op::I64x2ShrS => (HANDLER_IDX_I64X2_SHR_S, SimdShape::Shift),
+        Op::I64x2ShrU => (HANDLER_IDX_I64X2_SHR_U, SimdShape::Shift),
+        Op::I64x2Add => (HANDLER_IDX_I64X2_ADD, SimdShape::Binary),
+        Op::I64x2Sub => (HANDLER_IDX_I64X2_SUB, SimdShape::Binary),
+        Op::I64x2Mul => (HANDLER_IDX_I64X2_MUL, SimdShape::Binary),
+        Op::I64x2Eq => (HANDLER_IDX_I64X2_EQ, SimdShape::Binary),
+        Op::I64x2Ne => (HANDLER_IDX_I64X2_NE, SimdShape::Binary),
+        Op::I64x2LtS => (HANDLER_IDX_I64X2_LT_S, SimdShape::Binary),
+        Op::I64x2GtS => (HANDLER_IDX_I64X2_GT_S, SimdShape::Binary),
+        Op::I64x2LeS => (HANDLER_IDX_I64X2_LE_S, SimdShape::Binary),
+        Op::I64x2GeS => (HANDLER_IDX_I64X2_GE_S, SimdShape::Binary),
+        Op::I64x2ExtMulLowI32x4S => (HANDLER_IDX_I64X2_EXTMUL_LOW_I32X4_S, SimdShape::Binary),
+        Op::I64x2ExtMulHighI32x4S => (HANDLER_IDX_I64X2_EXTMUL_HIGH_I32X4_S, SimdShape::Binary),
+        Op::I64x2ExtMulLowI32x4U => (HANDLER_IDX_I64X2_EXTMUL_LOW_I32X4_U, SimdShape::Binary),
+        Op::I64x2ExtMulHighI32x4U => (HANDLER_IDX_I64X2_EXTMUL_HIGH_I32X4_U, SimdShape::Binary),
+        Op::F32x4Abs => (HANDLER_IDX_F32X4_ABS, SimdShape::Unary),
+        Op::F32x4Neg => (HANDLER_IDX_F32X4_NEG, SimdShape::Unary),
+        Op::F32x4Sqrt => (HANDLER_IDX_F32X4_SQRT, SimdShape::Unary),
+        Op::F32x4Add => (HANDLER_IDX_F32X4_ADD, SimdShape::Binary),
+        Op::F32x4Sub => (HANDLER_IDX_F32X4_SUB, SimdShape::Binary),
+        Op::F32x4Mul => (HANDLER_IDX_F32X4_MUL, SimdShape::Binary),
+        Op::F32x4Div => (HANDLER_IDX_F32X4_DIV, SimdShape::Binary),
+        Op::F32x4Min => (HANDLER_IDX_F32X4_MIN, SimdShape::Binary),
+        Op::F32x4Max => (HANDLER_IDX_F32X4_MAX, SimdShape::Binary),
+        Op::F32x4PMin => (HANDLER_IDX_F32X4_PMIN, SimdShape::Binary),
+        Op::F32x4PMax => (HANDLER_IDX_F32X4_PMAX, SimdShape::Binary),
+        Op::F64x2Abs => (HANDLER_IDX_F64X2_ABS, SimdShape::Unary),
+        Op::F64x2Neg => (HANDLER_IDX_F64X2_NEG, SimdShape::Unary),
+        Op::F64x2Sqrt => (HANDLER_IDX_F64X2_SQRT, SimdShape::Unary),
+        Op::F64x2Add => (HANDLER_IDX_F64X2_ADD, SimdShape::Binary),
+        Op::F64x2Sub => (HANDLER_IDX_F64X2_SUB, SimdShape::Binary),
+        Op::F64x2Mul => (HANDLER_IDX_F64X2_MUL, SimdShape::Binary),
+        Op::F64x2Div => (HANDLER_IDX_F64X2_DIV, SimdShape::Binary),
+        Op::F64x2Min => (HANDLER_IDX_F64X2_MIN, SimdShape::Binary),
+        Op::F64x2Max => (HANDLER_IDX_F64X2_MAX, SimdShape::Binary),
+        Op::F64x2PMin => (HANDLER_IDX_F64X2_PMIN, SimdShape::Binary),
+        Op::F64x2PMax => (HANDLER_IDX_F64X2_PMAX, SimdShape::Binary),
+        Op::I32x4TruncSatF32x4S => (HANDLER_IDX_I32X4_TRUNC_SAT_F32X4_S, SimdShape::Unary),
+        Op::I32x4TruncSatF32x4U => (HANDLER_IDX_I32X4_TRUNC_SAT_F32X4_U, SimdShape::Unary),
+        Op::F32x4ConvertI32x4S => (HANDLER_IDX_F32X4_CONVERT_I32X4_S, SimdShape::Unary),
+        Op::F32x4ConvertI32x4U => (HANDLER_IDX_F32X4_CONVERT_I32X4_U, SimdShape::Unary),
+        Op::I32x4TruncSatF64x2SZero => (HANDLER_IDX_I32X4_TRUNC_SAT_F64X2_S_ZERO, SimdShape::Unary),
+        Op::I32x4TruncSatF64x2UZero => (HANDLER_IDX_I32X4_TRUNC_SAT_F64X2_U_ZERO, SimdShape::Unary),
+        Op::F64x2ConvertLowI32x4S => (HANDLER_IDX_F64X2_CONVERT_LOW_I32X4_S, SimdShape::Unary),
+        Op::F64x2ConvertLowI32x4U => (HANDLER_IDX_F64X2_CONVERT_LOW_I32X4_U, SimdShape::Unary),
+        _ => return None,
+    };
+
+    let reg = |handler_index, dst: Reg, srcs: &[Reg], imm: [u8; 16]| {
+        let mut regs = [0u16; 3];
+        for (slot, src) in regs.iter_mut().zip(srcs) {
+            *slot = src.index();
+        }
+        ProcessedInstr::SimdReg {
+            handler_index,
+            dst: dst.index(),
+            srcs: regs,
+            imm,
+        }
+    };
+    let lane_imm = |lane: u8| {
+        let mut imm = [0u8; 16];
+        imm[0] = lane;
+        imm
+    };
+
+    let instr = match shape {
+        SimdShape::Const(bytes) => reg(handler_index, allocator.push(V128), &[], bytes),
+        SimdShape::Shuffle(lanes) => {
+            let b = allocator.pop(&V128);
+            let a = allocator.pop(&V128);
+            reg(handler_index, allocator.push(V128), &[a, b], lanes)
+        }
+        SimdShape::Unary => {
+            let a = allocator.pop(&V128);
+            reg(handler_index, allocator.push(V128), &[a], [0; 16])
+        }
+        SimdShape::Binary => {
+            let b = allocator.pop(&V128);
+            let a = allocator.pop(&V128);
+            reg(handler_index, allocator.push(V128), &[a, b], [0; 16])
+        }
+        SimdShape::Ternary => {
+            let c = allocator.pop(&V128);
+            let b = allocator.pop(&V128);
+            let a = allocator.pop(&V128);
+            reg(handler_index, allocator.push(V128), &[a, b, c], [0; 16])
+        }
+        SimdShape::Test => {
+            let a = allocator.pop(&V128);
+            reg(handler_index, allocator.push(I32), &[a], [0; 16])
+        }
+        SimdShape::Shift => {
+            let s = allocator.pop(&I32);
+            let a = allocator.pop(&V128);
+            reg(handler_index, allocator.push(V128), &[a, s], [0; 16])
+        }
+        SimdShape::Splat(ty) => {
+            let x = allocator.pop(&ty);
+            reg(handler_index, allocator.push(V128), &[x], [0; 16])
+        }
+        SimdShape::ExtractLane(ty, lane) => {
+            let a = allocator.pop(&V128);
+            reg(handler_index, allocator.push(ty), &[a], lane_imm(lane))
+        }
+        SimdShape::ReplaceLane(ty, lane) => {
+            let x = allocator.pop(&ty);
+            let a = allocator.pop(&V128);
+            reg(handler_index, allocator.push(V128), &[a, x], lane_imm(lane))
+        }
+        SimdShape::Load(memarg) | SimdShape::LoadLane(memarg, _) => {
+            let (value, lane) = match shape {
+                SimdShape::LoadLane(_, lane) => (allocator.pop(&V128).index(), lane),
+                _ => (0, 0),
+            };
+            let addr_reg = allocator.pop(&memory_addr_type(module, memarg.memory));
+            let addr = take_addr_operand(pending_operands, addr_reg);
+            ProcessedInstr::SimdMemReg {
+                handler_index,
+                dst: allocator.push(V128).index(),
+                addr,
+                value,
+                offset: memarg.offset,
+                lane,
+                memory: memarg.memory,
+            }
+        }
+        SimdShape::Store(memarg) | SimdShape::StoreLane(memarg, _) => {
+            let lane = match shape {
+                SimdShape::StoreLane(_, lane) => lane,
+                _ => 0,
+            };
+            let value = allocator.pop(&V128).index();
+            let addr_reg = allocator.pop(&memory_addr_type(module, memarg.memory));
+            let addr = take_addr_operand(pending_operands, addr_reg);
+            ProcessedInstr::SimdMemReg {
+                handler_index,
+                dst: 0, // unused for stores
+                addr,
+                value,
+                offset: memarg.offset,
+                lane,
+                memory: memarg.memory,
+            }
+        }
+    };
+    Some(instr)
+}
+
 /// Decodes WebAssembly instructions into register-based processed instructions.
 ///
 /// This is the first phase of instruction preprocessing (Phase 1). It:
@@ -1892,7 +2493,7 @@ fn decode_processed_instrs_and_fixups<'a>(
     // Track allocator state at block entry for proper restoration on block exit
     let mut allocator_state_stack: Vec<crate::execution::regs::RegAllocatorState> = Vec::new();
 
-    // Track unreachable code depth (after br, return, unreachable, br_table)
+    // Track unreachable code depth (after br, return, return_call, unreachable, br_table)
     let mut unreachable_depth: usize = 0;
 
     // Pending operands for folding (stack for multiple operands)
@@ -1903,7 +2504,7 @@ fn decode_processed_instrs_and_fixups<'a>(
             break;
         }
 
-        let (op, _offset) = match ops.next() {
+        let (op, op_offset) = match ops.next() {
             Some(Ok(op_offset)) => op_offset,
             Some(Err(e)) => return Err(Box::new(e)),
             None => break,
@@ -1914,7 +2515,8 @@ fn decode_processed_instrs_and_fixups<'a>(
             match &op {
                 wasmparser::Operator::Block { .. }
                 | wasmparser::Operator::Loop { .. }
-                | wasmparser::Operator::If { .. } => {
+                | wasmparser::Operator::If { .. }
+                | wasmparser::Operator::TryTable { .. } => {
                     unreachable_depth += 1;
                 }
                 wasmparser::Operator::End => {
@@ -2034,8 +2636,17 @@ fn decode_processed_instrs_and_fixups<'a>(
                                 None,
                             )
                         }
-                        _ => {
-                            panic!("Unsupported type for LocalGet: {:?}", local_type);
+                        ValueType::VecType(_) => {
+                            let dst = allocator.push(local_type);
+                            (
+                                Some(ProcessedInstr::RefLocalReg {
+                                    handler_index: HANDLER_IDX_V128_LOCAL_GET,
+                                    dst: dst.index(),
+                                    src: 0, // unused for get
+                                    local_idx: *local_index as u16,
+                                }),
+                                None,
+                            )
                         }
                     }
                 }
@@ -2087,8 +2698,16 @@ fn decode_processed_instrs_and_fixups<'a>(
                                 None,
                             )
                         }
-                        _ => {
-                            panic!("Unsupported type for LocalSet: {:?}", local_type);
+                        ValueType::VecType(_) => {
+                            (
+                                Some(ProcessedInstr::RefLocalReg {
+                                    handler_index: HANDLER_IDX_V128_LOCAL_SET,
+                                    dst: 0, // unused for set
+                                    src: src_idx,
+                                    local_idx,
+                                }),
+                                None,
+                            )
                         }
                     }
                 }
@@ -2141,8 +2760,16 @@ fn decode_processed_instrs_and_fixups<'a>(
                                 None,
                             )
                         }
-                        _ => {
-                            panic!("Unsupported type for LocalTee: {:?}", local_type);
+                        ValueType::VecType(_) => {
+                            (
+                                Some(ProcessedInstr::RefLocalReg {
+                                    handler_index: HANDLER_IDX_V128_LOCAL_SET,
+                                    dst: 0, // unused for set
+                                    src: src_idx,
+                                    local_idx,
+                                }),
+                                None,
+                            )
                         }
                     }
                 }
@@ -2249,8 +2876,22 @@ fn decode_processed_instrs_and_fixups<'a>(
                                 )
                             }
                         }
+                        ValueType::VecType(_) => {
+                            let dst = allocator.push(global_type);
+                            (
+                                Some(ProcessedInstr::GlobalGetReg {
+                                    handler_index: HANDLER_IDX_GLOBAL_GET_V128,
+                                    dst: RegOrLocal::Reg(dst.index()),
+                                    global_index: *global_index,
+                                }),
+                                None,
+                            )
+                        }
                         _ => {
-                            panic!("Unsupported type for GlobalGet: {:?}", global_type);
+                            return Err(Box::new(ParserError::UnsupportedInstruction {
+                                offset: op_offset,
+                                op: format!("global.get of {:?}", global_type),
+                            }));
                         }
                     }
                 }
@@ -2321,8 +2962,22 @@ fn decode_processed_instrs_and_fixups<'a>(
                                 None,
                             )
                         }
+                        ValueType::VecType(_) => {
+                            let src_reg = allocator.pop(&global_type);
+                            (
+                                Some(ProcessedInstr::GlobalSetReg {
+                                    handler_index: HANDLER_IDX_GLOBAL_SET_V128,
+                                    src: RegOrLocal::Reg(src_reg.index()),
+                                    global_index: *global_index,
+                                }),
+                                None,
+                            )
+                        }
                         _ => {
-                            panic!("Unsupported type for GlobalSet: {:?}", global_type);
+                            return Err(Box::new(ParserError::UnsupportedInstruction {
+                                offset: op_offset,
+                                op: format!("global.set of {:?}", global_type),
+                            }));
                         }
                     }
                 }
@@ -5282,6 +5937,86 @@ fn decode_processed_instrs_and_fixups<'a>(
                     });
                     (Some(instr), fixup)
                 }
+                wasmparser::Operator::TryTable { try_table } => {
+                    let blockty = &try_table.ty;
+                    // Catch labels refer to blocks enclosing the try_table, so
+                    // resolve their result registers before pushing its own label.
+                    let catches: Vec<CatchClause> = try_table
+                        .catches
+                        .iter()
+                        .map(|catch| {
+                            let (kind, label) = match *catch {
+                                wasmparser::Catch::One { tag, label } => {
+                                    (CatchKind::Tag(TagIdx(tag)), label)
+                                }
+                                wasmparser::Catch::OneRef { tag, label } => {
+                                    (CatchKind::TagRef(TagIdx(tag)), label)
+                                }
+                                wasmparser::Catch::All { label } => (CatchKind::All, label),
+                                wasmparser::Catch::AllRef { label } => (CatchKind::AllRef, label),
+                            };
+                            let (_, target_result_regs) =
+                                compute_branch_regs(&control_info_stack, label as usize, None);
+                            CatchClause {
+                                kind,
+                                label,
+                                target_ip: usize::MAX, // Resolved in preprocess_instructions
+                                target_result_regs: target_result_regs.into_boxed_slice(),
+                            }
+                        })
+                        .collect();
+
+                    let param_types = get_block_param_types(blockty, module);
+                    for vtype in param_types.iter().rev() {
+                        allocator.pop(vtype);
+                    }
+
+                    let saved_state = allocator.save_state();
+                    allocator_state_stack.push(saved_state.clone());
+
+                    let result_types = get_block_result_types(blockty, module);
+                    let result_regs: Vec<Reg> = {
+                        let mut state = saved_state;
+                        result_types
+                            .iter()
+                            .map(|vtype| state.next_reg_for_type(vtype))
+                            .collect()
+                    };
+
+                    for vtype in param_types.iter() {
+                        allocator.push(*vtype);
+                    }
+
+                    control_info_stack.push(ControlBlockInfo {
+                        block_type: *blockty,
+                        is_loop: false,
+                        result_regs,
+                        param_regs: Vec::new(),
+                    });
+
+                    let instr = ProcessedInstr::TryTableReg {
+                        catches: catches.into_boxed_slice(),
+                    };
+                    (Some(instr), None)
+                }
+                wasmparser::Operator::Throw { tag_index } => {
+                    let param_types = get_tag_param_types(module, *tag_index);
+                    let param_regs = allocator.peek_regs_for_types(&param_types);
+                    for param_type in param_types.iter().rev() {
+                        allocator.pop(param_type);
+                    }
+                    (
+                        Some(ProcessedInstr::ThrowReg {
+                            tag_idx: TagIdx(*tag_index),
+                            param_regs: param_regs.into_boxed_slice(),
+                        }),
+                        None,
+                    )
+                }
+                wasmparser::Operator::ThrowRef => {
+                    let exn_reg = allocator.pop(&ValueType::RefType(RefType::ExnRef));
+                    (Some(ProcessedInstr::ThrowRefReg { exn_reg }), None)
+                }
                 wasmparser::Operator::Else => {
                     if let Some(state) = allocator_state_stack.last() {
                         allocator.restore_state(state);
@@ -5475,6 +6210,89 @@ fn decode_processed_instrs_and_fixups<'a>(
                     (Some(instr), None)
                 }
 
+                wasmparser::Operator::ReturnCall { function_index } => {
+                    let param_types = get_func_param_types(module, *function_index);
+                    let param_regs = allocator.peek_regs_for_types(&param_types);
+                    for param_type in param_types.iter().rev() {
+                        allocator.pop(param_type);
+                    }
+
+                    let wasi_func_type = if (*function_index as usize) < module.num_imported_funcs {
+                        match module
+                            .imports
+                            .get(*function_index as usize)
+                            .map(|i| &i.desc)
+                        {
+                            Some(ImportDesc::WasiFunc(wasi_type)) => Some(*wasi_type),
+                            _ => None,
+                        }
+                    } else {
+                        None
+                    };
+
+                    if let Some(wasi_type) = wasi_func_type {
+                        // WASI functions run inside the caller's frame, so the
+                        // tail call is lowered to a call followed by a return.
+                        let result_reg = wasi_type
+                            .expected_func_type()
+                            .results
+                            .first()
+                            .map(|result_type| allocator.push(*result_type));
+                        initial_processed_instrs.push(ProcessedInstr::CallWasiReg {
+                            wasi_func_type: wasi_type,
+                            param_regs: param_regs.into_boxed_slice(),
+                            result_reg,
+                        });
+                        current_processed_pc += 1;
+
+                        let result_regs = allocator.peek_regs_for_types(result_types);
+                        for result_type in result_types.iter().rev() {
+                            allocator.pop(result_type);
+                        }
+                        (
+                            Some(ProcessedInstr::ReturnReg {
+                                result_regs: result_regs.into_boxed_slice(),
+                            }),
+                            None,
+                        )
+                    } else {
+                        (
+                            Some(ProcessedInstr::ReturnCallReg {
+                                func_idx: FuncIdx(*function_index),
+                                param_regs: param_regs.into_boxed_slice(),
+                            }),
+                            None,
+                        )
+                    }
+                }
+
+                wasmparser::Operator::ReturnCallIndirect {
+                    type_index,
+                    table_index,
+                } => {
+                    let param_types = module
+                        .types
+                        .get(*type_index as usize)
+                        .map(|func_type| func_type.params.clone())
+                        .unwrap_or_default();
+
+                    let index_reg = allocator.peek(&ValueType::NumType(NumType::I32)).unwrap();
+                    allocator.pop(&ValueType::NumType(NumType::I32));
+
+                    let param_regs = allocator.peek_regs_for_types(&param_types);
+                    for param_type in param_types.iter().rev() {
+                        allocator.pop(param_type);
+                    }
+
+                    let instr = ProcessedInstr::ReturnCallIndirectReg {
+                        type_idx: TypeIdx(*type_index),
+                        table_idx: TableIdx(*table_index),
+                        index_reg,
+                        param_regs: param_regs.into_boxed_slice(),
+                    };
+                    (Some(instr), None)
+                }
+
                 wasmparser::Operator::Br { relative_depth } => {
                     // Compute source and target registers for branch
                     let (source_regs, target_result_regs) = compute_branch_regs(
@@ -6434,8 +7252,8 @@ fn decode_processed_instrs_and_fixups<'a>(
                 }
                 // Memory Load instructions
                 wasmparser::Operator::I32Load { memarg } => {
-                    let addr_reg = allocator.pop(&ValueType::NumType(NumType::I32));
-                    let addr = take_i32_operand(&mut pending_operands, addr_reg.index());
+                    let addr_reg = allocator.pop(&memory_addr_type(module, memarg.memory));
+                    let addr = take_addr_operand(&mut pending_operands, addr_reg);
                     if let Some(local_idx) = try_fold_dst_i32(&mut ops, param_types, locals) {
                         let _ = ops.next();
                         let _dst = allocator.push(ValueType::NumType(NumType::I32));
@@ -6445,6 +7263,7 @@ fn decode_processed_instrs_and_fixups<'a>(
                             dst: RegOrLocal::Local(local_idx),
                             addr,
                             offset: memarg.offset,
+                            memory: memarg.memory,
                         });
                         current_processed_pc += 1;
                         (None, None)
@@ -6456,14 +7275,15 @@ fn decode_processed_instrs_and_fixups<'a>(
                                 dst: RegOrLocal::Reg(dst.index()),
                                 addr,
                                 offset: memarg.offset,
+                                memory: memarg.memory,
                             }),
                             None,
                         )
                     }
                 }
                 wasmparser::Operator::I64Load { memarg } => {
-                    let addr_reg = allocator.pop(&ValueType::NumType(NumType::I32));
-                    let addr = take_i32_operand(&mut pending_operands, addr_reg.index());
+                    let addr_reg = allocator.pop(&memory_addr_type(module, memarg.memory));
+                    let addr = take_addr_operand(&mut pending_operands, addr_reg);
                     if let Some(local_idx) = try_fold_dst_i64(&mut ops, param_types, locals) {
                         let _ = ops.next();
                         let _dst = allocator.push(ValueType::NumType(NumType::I64));
@@ -6473,6 +7293,7 @@ fn decode_processed_instrs_and_fixups<'a>(
                             dst: RegOrLocal::Local(local_idx),
                             addr,
                             offset: memarg.offset,
+                            memory: memarg.memory,
                         });
                         current_processed_pc += 1;
                         (None, None)
@@ -6484,14 +7305,15 @@ fn decode_processed_instrs_and_fixups<'a>(
                                 dst: RegOrLocal::Reg(dst.index()),
                                 addr,
                                 offset: memarg.offset,
+                                memory: memarg.memory,
                             }),
                             None,
                         )
                     }
                 }
                 wasmparser::Operator::F32Load { memarg } => {
-                    let addr_reg = allocator.pop(&ValueType::NumType(NumType::I32));
-                    let addr = take_i32_operand(&mut pending_operands, addr_reg.index());
+                    let addr_reg = allocator.pop(&memory_addr_type(module, memarg.memory));
+                    let addr = take_addr_operand(&mut pending_operands, addr_reg);
                     if let Some(local_idx) = try_fold_dst_f32(&mut ops, param_types, locals) {
                         let _ = ops.next();
                         let _dst = allocator.push(ValueType::NumType(NumType::F32));
@@ -6501,6 +7323,7 @@ fn decode_processed_instrs_and_fixups<'a>(
                             dst: RegOrLocal::Local(local_idx),
                             addr,
                             offset: memarg.offset,
+                            memory: memarg.memory,
                         });
                         current_processed_pc += 1;
                         (None, None)
@@ -6512,14 +7335,15 @@ fn decode_processed_instrs_and_fixups<'a>(
                                 dst: RegOrLocal::Reg(dst.index()),
                                 addr,
                                 offset: memarg.offset,
+                                memory: memarg.memory,
                             }),
                             None,
                         )
                     }
                 }
                 wasmparser::Operator::F64Load { memarg } => {
-                    let addr_reg = allocator.pop(&ValueType::NumType(NumType::I32));
-                    let addr = take_i32_operand(&mut pending_operands, addr_reg.index());
+                    let addr_reg = allocator.pop(&memory_addr_type(module, memarg.memory));
+                    let addr = take_addr_operand(&mut pending_operands, addr_reg);
                     if let Some(local_idx) = try_fold_dst_f64(&mut ops, param_types, locals) {
                         let _ = ops.next();
                         let _dst = allocator.push(ValueType::NumType(NumType::F64));
@@ -6529,6 +7353,7 @@ fn decode_processed_instrs_and_fixups<'a>(
                             dst: RegOrLocal::Local(local_idx),
                             addr,
                             offset: memarg.offset,
+                            memory: memarg.memory,
                         });
                         current_processed_pc += 1;
                         (None, None)
@@ -6540,14 +7365,15 @@ fn decode_processed_instrs_and_fixups<'a>(
                                 dst: RegOrLocal::Reg(dst.index()),
                                 addr,
                                 offset: memarg.offset,
+                                memory: memarg.memory,
                             }),
                             None,
                         )
                     }
                 }
                 wasmparser::Operator::I32Load8S { memarg } => {
-                    let addr_reg = allocator.pop(&ValueType::NumType(NumType::I32));
-                    let addr = take_i32_operand(&mut pending_operands, addr_reg.index());
+                    let addr_reg = allocator.pop(&memory_addr_type(module, memarg.memory));
+                    let addr = take_addr_operand(&mut pending_operands, addr_reg);
                     if let Some(local_idx) = try_fold_dst_i32(&mut ops, param_types, locals) {
                         let _ = ops.next();
                         let _dst = allocator.push(ValueType::NumType(NumType::I32));
@@ -6557,6 +7383,7 @@ fn decode_processed_instrs_and_fixups<'a>(
                             dst: RegOrLocal::Local(local_idx),
                             addr,
                             offset: memarg.offset,
+                            memory: memarg.memory,
                         });
                         current_processed_pc += 1;
                         (None, None)
@@ -6568,14 +7395,15 @@ fn decode_processed_instrs_and_fixups<'a>(
                                 dst: RegOrLocal::Reg(dst.index()),
                                 addr,
                                 offset: memarg.offset,
+                                memory: memarg.memory,
                             }),
                             None,
                         )
                     }
                 }
                 wasmparser::Operator::I32Load8U { memarg } => {
-                    let addr_reg = allocator.pop(&ValueType::NumType(NumType::I32));
-                    let addr = take_i32_operand(&mut pending_operands, addr_reg.index());
+                    let addr_reg = allocator.pop(&memory_addr_type(module, memarg.memory));
+                    let addr = take_addr_operand(&mut pending_operands, addr_reg);
                     if let Some(local_idx) = try_fold_dst_i32(&mut ops, param_types, locals) {
                         let _ = ops.next();
                         let _dst = allocator.push(ValueType::NumType(NumType::I32));
@@ -6585,6 +7413,7 @@ fn decode_processed_instrs_and_fixups<'a>(
                             dst: RegOrLocal::Local(local_idx),
                             addr,
                             offset: memarg.offset,
+                            memory: memarg.memory,
                         });
                         current_processed_pc += 1;
                         (None, None)
@@ -6596,14 +7425,15 @@ fn decode_processed_instrs_and_fixups<'a>(
                                 dst: RegOrLocal::Reg(dst.index()),
                                 addr,
                                 offset: memarg.offset,
+                                memory: memarg.memory,
                             }),
                             None,
                         )
                     }
                 }
                 wasmparser::Operator::I32Load16S { memarg } => {
-                    let addr_reg = allocator.pop(&ValueType::NumType(NumType::I32));
-                    let addr = take_i32_operand(&mut pending_operands, addr_reg.index());
+                    let addr_reg = allocator.pop(&memory_addr_type(module, memarg.memory));
+                    let addr = take_addr_operand(&mut pending_operands, addr_reg);
                     if let Some(local_idx) = try_fold_dst_i32(&mut ops, param_types, locals) {
                         let _ = ops.next();
                         let _dst = allocator.push(ValueType::NumType(NumType::I32));
@@ -6613,6 +7443,7 @@ fn decode_processed_instrs_and_fixups<'a>(
                             dst: RegOrLocal::Local(local_idx),
                             addr,
                             offset: memarg.offset,
+                            memory: memarg.memory,
                         });
                         current_processed_pc += 1;
                         (None, None)
@@ -6624,14 +7455,15 @@ fn decode_processed_instrs_and_fixups<'a>(
                                 dst: RegOrLocal::Reg(dst.index()),
                                 addr,
                                 offset: memarg.offset,
+                                memory: memarg.memory,
                             }),
                             None,
                         )
                     }
                 }
                 wasmparser::Operator::I32Load16U { memarg } => {
-                    let addr_reg = allocator.pop(&ValueType::NumType(NumType::I32));
-                    let addr = take_i32_operand(&mut pending_operands, addr_reg.index());
+                    let addr_reg = allocator.pop(&memory_addr_type(module, memarg.memory));
+                    let addr = take_addr_operand(&mut pending_operands, addr_reg);
                     if let Some(local_idx) = try_fold_dst_i32(&mut ops, param_types, locals) {
                         let _ = ops.next();
                         let _dst = allocator.push(ValueType::NumType(NumType::I32));
@@ -6641,6 +7473,7 @@ fn decode_processed_instrs_and_fixups<'a>(
                             dst: RegOrLocal::Local(local_idx),
                             addr,
                             offset: memarg.offset,
+                            memory: memarg.memory,
                         });
                         current_processed_pc += 1;
                         (None, None)
@@ -6652,14 +7485,15 @@ fn decode_processed_instrs_and_fixups<'a>(
                                 dst: RegOrLocal::Reg(dst.index()),
                                 addr,
                                 offset: memarg.offset,
+                                memory: memarg.memory,
                             }),
                             None,
                         )
                     }
                 }
                 wasmparser::Operator::I64Load8S { memarg } => {
-                    let addr_reg = allocator.pop(&ValueType::NumType(NumType::I32));
-                    let addr = take_i32_operand(&mut pending_operands, addr_reg.index());
+                    let addr_reg = allocator.pop(&memory_addr_type(module, memarg.memory));
+                    let addr = take_addr_operand(&mut pending_operands, addr_reg);
                     if let Some(local_idx) = try_fold_dst_i64(&mut ops, param_types, locals) {
                         let _ = ops.next();
                         let _dst = allocator.push(ValueType::NumType(NumType::I64));
@@ -6669,6 +7503,7 @@ fn decode_processed_instrs_and_fixups<'a>(
                             dst: RegOrLocal::Local(local_idx),
                             addr,
                             offset: memarg.offset,
+                            memory: memarg.memory,
                         });
                         current_processed_pc += 1;
                         (None, None)
@@ -6680,14 +7515,15 @@ fn decode_processed_instrs_and_fixups<'a>(
                                 dst: RegOrLocal::Reg(dst.index()),
                                 addr,
                                 offset: memarg.offset,
+                                memory: memarg.memory,
                             }),
                             None,
                         )
                     }
                 }
                 wasmparser::Operator::I64Load8U { memarg } => {
-                    let addr_reg = allocator.pop(&ValueType::NumType(NumType::I32));
-                    let addr = take_i32_operand(&mut pending_operands, addr_reg.index());
+                    let addr_reg = allocator.pop(&memory_addr_type(module, memarg.memory));
+                    let addr = take_addr_operand(&mut pending_operands, addr_reg);
                     if let Some(local_idx) = try_fold_dst_i64(&mut ops, param_types, locals) {
                         let _ = ops.next();
                         let _dst = allocator.push(ValueType::NumType(NumType::I64));
@@ -6697,6 +7533,7 @@ fn decode_processed_instrs_and_fixups<'a>(
                             dst: RegOrLocal::Local(local_idx),
                             addr,
                             offset: memarg.offset,
+                            memory: memarg.memory,
                         });
                         current_processed_pc += 1;
                         (None, None)
@@ -6708,14 +7545,15 @@ fn decode_processed_instrs_and_fixups<'a>(
                                 dst: RegOrLocal::Reg(dst.index()),
                                 addr,
                                 offset: memarg.offset,
+                                memory: memarg.memory,
                             }),
                             None,
                         )
                     }
                 }
                 wasmparser::Operator::I64Load16S { memarg } => {
-                    let addr_reg = allocator.pop(&ValueType::NumType(NumType::I32));
-                    let addr = take_i32_operand(&mut pending_operands, addr_reg.index());
+                    let addr_reg = allocator.pop(&memory_addr_type(module, memarg.memory));
+                    let addr = take_addr_operand(&mut pending_operands, addr_reg);
                     if let Some(local_idx) = try_fold_dst_i64(&mut ops, param_types, locals) {
                         let _ = ops.next();
                         let _dst = allocator.push(ValueType::NumType(NumType::I64));
@@ -6725,6 +7563,7 @@ fn decode_processed_instrs_and_fixups<'a>(
                             dst: RegOrLocal::Local(local_idx),
                             addr,
                             offset: memarg.offset,
+                            memory: memarg.memory,
                         });
                         current_processed_pc += 1;
                         (None, None)
@@ -6736,14 +7575,15 @@ fn decode_processed_instrs_and_fixups<'a>(
                                 dst: RegOrLocal::Reg(dst.index()),
                                 addr,
                                 offset: memarg.offset,
+                                memory: memarg.memory,
                             }),
                             None,
                         )
                     }
                 }
                 wasmparser::Operator::I64Load16U { memarg } => {
-                    let addr_reg = allocator.pop(&ValueType::NumType(NumType::I32));
-                    let addr = take_i32_operand(&mut pending_operands, addr_reg.index());
+                    let addr_reg = allocator.pop(&memory_addr_type(module, memarg.memory));
+                    let addr = take_addr_operand(&mut pending_operands, addr_reg);
                     if let Some(local_idx) = try_fold_dst_i64(&mut ops, param_types, locals) {
                         let _ = ops.next();
                         let _dst = allocator.push(ValueType::NumType(NumType::I64));
@@ -6753,6 +7593,7 @@ fn decode_processed_instrs_and_fixups<'a>(
                             dst: RegOrLocal::Local(local_idx),
                             addr,
                             offset: memarg.offset,
+                            memory: memarg.memory,
                         });
                         current_processed_pc += 1;
                         (None, None)
@@ -6764,14 +7605,15 @@ fn decode_processed_instrs_and_fixups<'a>(
                                 dst: RegOrLocal::Reg(dst.index()),
                                 addr,
                                 offset: memarg.offset,
+                                memory: memarg.memory,
                             }),
                             None,
                         )
                     }
                 }
                 wasmparser::Operator::I64Load32S { memarg } => {
-                    let addr_reg = allocator.pop(&ValueType::NumType(NumType::I32));
-                    let addr = take_i32_operand(&mut pending_operands, addr_reg.index());
+                    let addr_reg = allocator.pop(&memory_addr_type(module, memarg.memory));
+                    let addr = take_addr_operand(&mut pending_operands, addr_reg);
                     if let Some(local_idx) = try_fold_dst_i64(&mut ops, param_types, locals) {
                         let _ = ops.next();
                         let _dst = allocator.push(ValueType::NumType(NumType::I64));
@@ -6781,6 +7623,7 @@ fn decode_processed_instrs_and_fixups<'a>(
                             dst: RegOrLocal::Local(local_idx),
                             addr,
                             offset: memarg.offset,
+                            memory: memarg.memory,
                         });
                         current_processed_pc += 1;
                         (None, None)
@@ -6792,14 +7635,15 @@ fn decode_processed_instrs_and_fixups<'a>(
                                 dst: RegOrLocal::Reg(dst.index()),
                                 addr,
                                 offset: memarg.offset,
+                                memory: memarg.memory,
                             }),
                             None,
                         )
                     }
                 }
                 wasmparser::Operator::I64Load32U { memarg } => {
-                    let addr_reg = allocator.pop(&ValueType::NumType(NumType::I32));
-                    let addr = take_i32_operand(&mut pending_operands, addr_reg.index());
+                    let addr_reg = allocator.pop(&memory_addr_type(module, memarg.memory));
+                    let addr = take_addr_operand(&mut pending_operands, addr_reg);
                     if let Some(local_idx) = try_fold_dst_i64(&mut ops, param_types, locals) {
                         let _ = ops.next();
                         let _dst = allocator.push(ValueType::NumType(NumType::I64));
@@ -6809,6 +7653,7 @@ fn decode_processed_instrs_and_fixups<'a>(
                             dst: RegOrLocal::Local(local_idx),
                             addr,
                             offset: memarg.offset,
+                            memory: memarg.memory,
                         });
                         current_processed_pc += 1;
                         (None, None)
@@ -6820,6 +7665,7 @@ fn decode_processed_instrs_and_fixups<'a>(
                                 dst: RegOrLocal::Reg(dst.index()),
                                 addr,
                                 offset: memarg.offset,
+                                memory: memarg.memory,
                             }),
                             None,
                         )
@@ -6828,195 +7674,224 @@ fn decode_processed_instrs_and_fixups<'a>(
                 // Memory Store instructions
                 wasmparser::Operator::I32Store { memarg } => {
                     let value = allocator.pop(&ValueType::NumType(NumType::I32));
-                    let addr_reg = allocator.pop(&ValueType::NumType(NumType::I32));
-                    let addr = take_i32_operand(&mut pending_operands, addr_reg.index());
+                    let addr_reg = allocator.pop(&memory_addr_type(module, memarg.memory));
+                    let addr = take_addr_operand(&mut pending_operands, addr_reg);
                     (
                         Some(ProcessedInstr::MemoryStoreReg {
                             handler_index: HANDLER_IDX_I32_STORE,
                             addr,
                             value,
                             offset: memarg.offset,
+                            memory: memarg.memory,
                         }),
                         None,
                     )
                 }
                 wasmparser::Operator::I64Store { memarg } => {
                     let value = allocator.pop(&ValueType::NumType(NumType::I64));
-                    let addr_reg = allocator.pop(&ValueType::NumType(NumType::I32));
-                    let addr = take_i32_operand(&mut pending_operands, addr_reg.index());
+                    let addr_reg = allocator.pop(&memory_addr_type(module, memarg.memory));
+                    let addr = take_addr_operand(&mut pending_operands, addr_reg);
                     (
                         Some(ProcessedInstr::MemoryStoreReg {
                             handler_index: HANDLER_IDX_I64_STORE,
                             addr,
                             value,
                             offset: memarg.offset,
+                            memory: memarg.memory,
                         }),
                         None,
                     )
                 }
                 wasmparser::Operator::F32Store { memarg } => {
                     let value = allocator.pop(&ValueType::NumType(NumType::F32));
-                    let addr_reg = allocator.pop(&ValueType::NumType(NumType::I32));
-                    let addr = take_i32_operand(&mut pending_operands, addr_reg.index());
+                    let addr_reg = allocator.pop(&memory_addr_type(module, memarg.memory));
+                    let addr = take_addr_operand(&mut pending_operands, addr_reg);
                     (
                         Some(ProcessedInstr::MemoryStoreReg {
                             handler_index: HANDLER_IDX_F32_STORE,
                             addr,
                             value,
                             offset: memarg.offset,
+                            memory: memarg.memory,
                         }),
                         None,
                     )
                 }
                 wasmparser::Operator::F64Store { memarg } => {
                     let value = allocator.pop(&ValueType::NumType(NumType::F64));
-                    let addr_reg = allocator.pop(&ValueType::NumType(NumType::I32));
-                    let addr = take_i32_operand(&mut pending_operands, addr_reg.index());
+                    let addr_reg = allocator.pop(&memory_addr_type(module, memarg.memory));
+                    let addr = take_addr_operand(&mut pending_operands, addr_reg);
                     (
                         Some(ProcessedInstr::MemoryStoreReg {
                             handler_index: HANDLER_IDX_F64_STORE,
                             addr,
                             value,
                             offset: memarg.offset,
+                            memory: memarg.memory,
                         }),
                         None,
                     )
                 }
                 wasmparser::Operator::I32Store8 { memarg } => {
                     let value = allocator.pop(&ValueType::NumType(NumType::I32));
-                    let addr_reg = allocator.pop(&ValueType::NumType(NumType::I32));
-                    let addr = take_i32_operand(&mut pending_operands, addr_reg.index());
+                    let addr_reg = allocator.pop(&memory_addr_type(module, memarg.memory));
+                    let addr = take_addr_operand(&mut pending_operands, addr_reg);
                     (
                         Some(ProcessedInstr::MemoryStoreReg {
                             handler_index: HANDLER_IDX_I32_STORE8,
                             addr,
                             value,
                             offset: memarg.offset,
+                            memory: memarg.memory,
                         }),
                         None,
                     )
                 }
                 wasmparser::Operator::I32Store16 { memarg } => {
                     let value = allocator.pop(&ValueType::NumType(NumType::I32));
-                    let addr_reg = allocator.pop(&ValueType::NumType(NumType::I32));
-                    let addr = take_i32_operand(&mut pending_operands, addr_reg.index());
+                    let addr_reg = allocator.pop(&memory_addr_type(module, memarg.memory));
+                    let addr = take_addr_operand(&mut pending_operands, addr_reg);
                     (
                         Some(ProcessedInstr::MemoryStoreReg {
                             handler_index: HANDLER_IDX_I32_STORE16,
                             addr,
                             value,
                             offset: memarg.offset,
+                            memory: memarg.memory,
                         }),
                         None,
                     )
                 }
                 wasmparser::Operator::I64Store8 { memarg } => {
                     let value = allocator.pop(&ValueType::NumType(NumType::I64));
-                    let addr_reg = allocator.pop(&ValueType::NumType(NumType::I32));
-                    let addr = take_i32_operand(&mut pending_operands, addr_reg.index());
+                    let addr_reg = allocator.pop(&memory_addr_type(module, memarg.memory));
+                    let addr = take_addr_operand(&mut pending_operands, addr_reg);
                     (
                         Some(ProcessedInstr::MemoryStoreReg {
                             handler_index: HANDLER_IDX_I64_STORE8,
                             addr,
                             value,
                             offset: memarg.offset,
+                            memory: memarg.memory,
                         }),
                         None,
                     )
                 }
                 wasmparser::Operator::I64Store16 { memarg } => {
                     let value = allocator.pop(&ValueType::NumType(NumType::I64));
-                    let addr_reg = allocator.pop(&ValueType::NumType(NumType::I32));
-                    let addr = take_i32_operand(&mut pending_operands, addr_reg.index());
+                    let addr_reg = allocator.pop(&memory_addr_type(module, memarg.memory));
+                    let addr = take_addr_operand(&mut pending_operands, addr_reg);
                     (
                         Some(ProcessedInstr::MemoryStoreReg {
                             handler_index: HANDLER_IDX_I64_STORE16,
                             addr,
                             value,
                             offset: memarg.offset,
+                            memory: memarg.memory,
                         }),
                         None,
                     )
                 }
                 wasmparser::Operator::I64Store32 { memarg } => {
                     let value = allocator.pop(&ValueType::NumType(NumType::I64));
-                    let addr_reg = allocator.pop(&ValueType::NumType(NumType::I32));
-                    let addr = take_i32_operand(&mut pending_operands, addr_reg.index());
+                    let addr_reg = allocator.pop(&memory_addr_type(module, memarg.memory));
+                    let addr = take_addr_operand(&mut pending_operands, addr_reg);
                     (
                         Some(ProcessedInstr::MemoryStoreReg {
                             handler_index: HANDLER_IDX_I64_STORE32,
                             addr,
                             value,
                             offset: memarg.offset,
+                            memory: memarg.memory,
                         }),
                         None,
                     )
                 }
 
                 // Memory Ops instructions (size, grow, copy, init, fill)
-                wasmparser::Operator::MemorySize { .. } => {
-                    let dst = allocator.push(ValueType::NumType(NumType::I32));
+                wasmparser::Operator::MemorySize { mem } => {
+                    let dst = allocator.push(memory_addr_type(module, *mem));
                     (
                         Some(ProcessedInstr::MemoryOpsReg {
                             handler_index: HANDLER_IDX_MEMORY_SIZE,
                             dst: Some(dst),
                             args: Box::new([]),
                             data_index: 0,
+                            memory: *mem,
+                            src_memory: 0,
                         }),
                         None,
                     )
                 }
-                wasmparser::Operator::MemoryGrow { .. } => {
-                    let delta = allocator.pop(&ValueType::NumType(NumType::I32));
-                    let dst = allocator.push(ValueType::NumType(NumType::I32));
+                wasmparser::Operator::MemoryGrow { mem } => {
+                    let addr_type = memory_addr_type(module, *mem);
+                    let delta = allocator.pop(&addr_type);
+                    let dst = allocator.push(addr_type);
                     (
                         Some(ProcessedInstr::MemoryOpsReg {
                             handler_index: HANDLER_IDX_MEMORY_GROW,
                             dst: Some(dst),
                             args: Box::new([delta]),
                             data_index: 0,
+                            memory: *mem,
+                            src_memory: 0,
                         }),
                         None,
                     )
                 }
-                wasmparser::Operator::MemoryCopy { .. } => {
-                    let len = allocator.pop(&ValueType::NumType(NumType::I32));
-                    let src = allocator.pop(&ValueType::NumType(NumType::I32));
-                    let dest = allocator.pop(&ValueType::NumType(NumType::I32));
+                wasmparser::Operator::MemoryCopy { dst_mem, src_mem } => {
+                    let dst_type = memory_addr_type(module, *dst_mem);
+                    let src_type = memory_addr_type(module, *src_mem);
+                    // The length is i64 only if both memories are 64-bit
+                    let len_type = if dst_type == src_type {
+                        dst_type
+                    } else {
+                        ValueType::NumType(NumType::I32)
+                    };
+                    let len = allocator.pop(&len_type);
+                    let src = allocator.pop(&src_type);
+                    let dest = allocator.pop(&dst_type);
                     (
                         Some(ProcessedInstr::MemoryOpsReg {
                             handler_index: HANDLER_IDX_MEMORY_COPY,
                             dst: None,
                             args: Box::new([dest, src, len]),
                             data_index: 0,
+                            memory: *dst_mem,
+                            src_memory: *src_mem,
                         }),
                         None,
                     )
                 }
-                wasmparser::Operator::MemoryInit { data_index, .. } => {
+                wasmparser::Operator::MemoryInit { data_index, mem } => {
                     let len = allocator.pop(&ValueType::NumType(NumType::I32));
                     let offset = allocator.pop(&ValueType::NumType(NumType::I32));
-                    let dest = allocator.pop(&ValueType::NumType(NumType::I32));
+                    let dest = allocator.pop(&memory_addr_type(module, *mem));
                     (
                         Some(ProcessedInstr::MemoryOpsReg {
                             handler_index: HANDLER_IDX_MEMORY_INIT,
                             dst: None,
                             args: Box::new([dest, offset, len]),
                             data_index: *data_index,
+                            memory: *mem,
+                            src_memory: 0,
                         }),
                         None,
                     )
                 }
-                wasmparser::Operator::MemoryFill { .. } => {
-                    let size = allocator.pop(&ValueType::NumType(NumType::I32));
+                wasmparser::Operator::MemoryFill { mem } => {
+                    let addr_type = memory_addr_type(module, *mem);
+                    let size = allocator.pop(&addr_type);
                     let val = allocator.pop(&ValueType::NumType(NumType::I32));
-                    let dest = allocator.pop(&ValueType::NumType(NumType::I32));
+                    let dest = allocator.pop(&addr_type);
                     (
                         Some(ProcessedInstr::MemoryOpsReg {
                             handler_index: HANDLER_IDX_MEMORY_FILL,
                             dst: None,
                             args: Box::new([dest, val, size]),
                             data_index: 0,
+                            memory: *mem,
+                            src_memory: 0,
                         }),
                         None,
                     )
@@ -7043,7 +7918,7 @@ fn decode_processed_instrs_and_fixups<'a>(
                         ValueType::NumType(NumType::F32) => HANDLER_IDX_SELECT_F32,
                         ValueType::NumType(NumType::F64) => HANDLER_IDX_SELECT_F64,
                         ValueType::RefType(_) => HANDLER_IDX_SELECT_I64,
-                        ValueType::VecType(_) => panic!("VecType not supported for Select"),
+                        ValueType::VecType(_) => HANDLER_IDX_SELECT_V128,
                     };
 
                     (
@@ -7058,7 +7933,7 @@ fn decode_processed_instrs_and_fixups<'a>(
                     )
                 }
                 wasmparser::Operator::Select => {
-                    // Untyped Select: only supports i32/i64/f32/f64 (not reftype)
+                    // Untyped Select: only supports i32/i64/f32/f64/v128 (not reftype)
                     let cond = allocator.pop(&ValueType::NumType(NumType::I32));
 
                     // Use peek_type to determine the type of val2 (top of stack after cond)
@@ -7071,7 +7946,13 @@ fn decode_processed_instrs_and_fixups<'a>(
                         ValueType::NumType(NumType::I64) => HANDLER_IDX_SELECT_I64,
                         ValueType::NumType(NumType::F32) => HANDLER_IDX_SELECT_F32,
                         ValueType::NumType(NumType::F64) => HANDLER_IDX_SELECT_F64,
-                        _ => panic!("Select requires numeric values on stack"),
+                        ValueType::VecType(_) => HANDLER_IDX_SELECT_V128,
+                        _ => {
+                            return Err(Box::new(ParserError::UnsupportedInstruction {
+                                offset: op_offset,
+                                op: format!("select of {:?}", val_type),
+                            }))
+                        }
                     };
 
                     let val2 = allocator.pop(&val_type);
@@ -7093,6 +7974,7 @@ fn decode_processed_instrs_and_fixups<'a>(
                     let ref_type = match hty {
                         wasmparser::HeapType::Func => RefType::FuncRef,
                         wasmparser::HeapType::Extern => RefType::ExternalRef,
+                        wasmparser::HeapType::Exn => RefType::ExnRef,
                         _ => RefType::ExternalRef,
                     };
                     let dst = allocator.push(ValueType::RefType(ref_type));
@@ -7175,12 +8057,93 @@ fn decode_processed_instrs_and_fixups<'a>(
                     )
                 }
 
-                _ => {
-                    panic!("Unsupported instruction: {:?}", op);
+                wasmparser::Operator::TableSize { table } => {
+                    // table.size: [] -> [i32]
+                    let dst = allocator.push(ValueType::NumType(NumType::I32));
+                    (
+                        Some(ProcessedInstr::TableOpsReg {
+                            handler_index: HANDLER_IDX_TABLE_SIZE,
+                            table_idx: *table,
+                            src_idx: 0,
+                            regs: [dst.index(), 0, 0],
+                        }),
+                        None,
+                    )
+                }
+
+                wasmparser::Operator::TableGrow { table } => {
+                    // table.grow: [ref, i32] -> [i32]
+                    let ref_type_vt = get_table_element_type(module, *table);
+                    let n = allocator.pop(&ValueType::NumType(NumType::I32));
+                    let val = allocator.pop(&ref_type_vt);
+                    let dst = allocator.push(ValueType::NumType(NumType::I32));
+                    (
+                        Some(ProcessedInstr::TableOpsReg {
+                            handler_index: HANDLER_IDX_TABLE_GROW,
+                            table_idx: *table,
+                            src_idx: 0,
+                            regs: [dst.index(), val.index(), n.index()],
+                        }),
+                        None,
+                    )
                 }
+
+                wasmparser::Operator::TableCopy {
+                    dst_table,
+                    src_table,
+                } => {
+                    // table.copy: [i32, i32, i32] -> []
+                    let n = allocator.pop(&ValueType::NumType(NumType::I32));
+                    let s = allocator.pop(&ValueType::NumType(NumType::I32));
+                    let d = allocator.pop(&ValueType::NumType(NumType::I32));
+                    (
+                        Some(ProcessedInstr::TableOpsReg {
+                            handler_index: HANDLER_IDX_TABLE_COPY,
+                            table_idx: *dst_table,
+                            src_idx: *src_table,
+                            regs: [d.index(), s.index(), n.index()],
+                        }),
+                        None,
+                    )
+                }
+
+                wasmparser::Operator::TableInit { elem_index, table } => {
+                    // table.init: [i32, i32, i32] -> []
+                    let n = allocator.pop(&ValueType::NumType(NumType::I32));
+                    let s = allocator.pop(&ValueType::NumType(NumType::I32));
+                    let d = allocator.pop(&ValueType::NumType(NumType::I32));
+                    (
+                        Some(ProcessedInstr::TableOpsReg {
+                            handler_index: HANDLER_IDX_TABLE_INIT,
+                            table_idx: *table,
+                            src_idx: *elem_index,
+                            regs: [d.index(), s.index(), n.index()],
+                        }),
+                        None,
+                    )
+                }
+
+                wasmparser::Operator::ElemDrop { elem_index } => (
+                    Some(ProcessedInstr::ElemDropReg {
+                        elem_index: *elem_index,
+                    }),
+                    None,
+                ),
+
+                _ => match decode_simd_instr(&op, allocator, &mut pending_operands, module) {
+                    Some(instr) => (Some(instr), None),
+                    None => {
+                        return Err(Box::new(ParserError::UnsupportedInstruction {
+                            offset: op_offset,
+                            op: format!("{:?}", op),
+                        }))
+                    }
+                },
             }
         } else {
-            panic!("Register allocator is required");
+            return Err(Box::new(RuntimeError::InvalidWasm(
+                "Internal Error: Register allocator is required",
+            )));
         };
 
         let processed_instr_template = processed_instr;
@@ -7191,6 +8154,10 @@ fn decode_processed_instrs_and_fixups<'a>(
                 control_stack_for_map_building.push((current_processed_pc, false, None));
                 block_type_map.insert(current_processed_pc, blockty);
             }
+            wasmparser::Operator::TryTable { ref try_table } => {
+                control_stack_for_map_building.push((current_processed_pc, false, None));
+                block_type_map.insert(current_processed_pc, try_table.ty);
+            }
             wasmparser::Operator::Loop { blockty } => {
                 control_stack_for_map_building.push((current_processed_pc, false, None));
                 block_type_map.insert(current_processed_pc, blockty);
@@ -7236,7 +8203,7 @@ fn decode_processed_instrs_and_fixups<'a>(
 
             // Update control_info_stack and block_result_regs_map
             match op {
-                wasmparser::Operator::Block { .. } => {
+                wasmparser::Operator::Block { .. } | wasmparser::Operator::TryTable { .. } => {
                     // Register for BrTable resolution (always needed)
                     if let Some(block_info) = control_info_stack.last() {
                         block_result_regs_map.insert(
@@ -7273,6 +8240,10 @@ fn decode_processed_instrs_and_fixups<'a>(
                 wasmparser::Operator::Br { .. }
                 | wasmparser::Operator::BrTable { .. }
                 | wasmparser::Operator::Return
+                | wasmparser::Operator::ReturnCall { .. }
+                | wasmparser::Operator::ReturnCallIndirect { .. }
+                | wasmparser::Operator::Throw { .. }
+                | wasmparser::Operator::ThrowRef
                 | wasmparser::Operator::Unreachable => {
                     unreachable_depth = 1;
                 }
@@ -7377,30 +8348,133 @@ fn compute_branch_regs(
     (source_regs, target_result_regs)
 }
 
-/// Parses a WebAssembly binary file and populates the module structure.
+/// Parses a WebAssembly file and populates the module structure.
+///
+/// This is the main entry point for loading a WebAssembly module from disk.
+/// It reads the file (binary or `.wat` text) and hands its contents to
+/// `parse_bytes`.
+///
+/// # Arguments
+///
+/// * `module` - The module structure to populate
+/// * `path` - Path to the WebAssembly binary or text file
+pub fn parse_bytecode(module: &mut Module, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let file = File::open(path)?;
+    parse_reader(module, file)
+}
+
+/// Reads a WebAssembly module (binary or text) from `reader` to the end and
+/// populates the module structure.
+///
+/// # Arguments
+///
+/// * `module` - The module structure to populate
+/// * `reader` - Source of the WebAssembly module (e.g. a socket or pipe)
+pub fn parse_reader<R: Read>(
+    module: &mut Module,
+    mut reader: R,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut buf = Vec::new();
+    reader.read_to_end(&mut buf)?;
+    parse_bytes(module, &buf)
+}
+
+/// Converts a module in the text format (`.wat`) to a binary. Binaries are
+/// returned unchanged.
 ///
-/// This is the main entry point for loading a WebAssembly module. It reads
-/// the binary file, parses all sections using wasmparser, and preprocesses
-/// instructions for efficient interpretation.
+/// Text parse errors are reported with 1-based line and column numbers.
+pub fn wat_to_binary(bytes: &[u8]) -> Result<Cow<'_, [u8]>, ParserError> {
+    if wat::Detect::from_bytes(bytes) != wat::Detect::WasmText {
+        return Ok(Cow::Borrowed(bytes));
+    }
+    // Text is only detected in valid UTF-8
+    let text = std::str::from_utf8(bytes).unwrap_or_default();
+    let to_error = |e: wast::Error| {
+        let (line, column) = e.span().linecol_in(text);
+        ParserError::WatError {
+            line: line + 1,
+            column: column + 1,
+            message: e.message(),
+        }
+    };
+
+    let buf = wast::parser::ParseBuffer::new(text).map_err(to_error)?;
+    let mut wat = wast::parser::parse::<wast::Wat>(&buf).map_err(to_error)?;
+    wat.encode().map(Cow::Owned).map_err(to_error)
+}
+
+/// Proposals chiwawa implements. Spelled out rather than derived from
+/// `WasmFeatures::default()`, which also enables threads, relaxed SIMD,
+/// extended constant expressions and the component model.
+const SUPPORTED_FEATURES: WasmFeatures = WasmFeatures::MUTABLE_GLOBAL
+    .union(WasmFeatures::SATURATING_FLOAT_TO_INT)
+    .union(WasmFeatures::SIGN_EXTENSION)
+    .union(WasmFeatures::REFERENCE_TYPES)
+    .union(WasmFeatures::MULTI_VALUE)
+    .union(WasmFeatures::BULK_MEMORY)
+    .union(WasmFeatures::SIMD)
+    .union(WasmFeatures::TAIL_CALL)
+    .union(WasmFeatures::FLOATS)
+    .union(WasmFeatures::MULTI_MEMORY)
+    .union(WasmFeatures::EXCEPTIONS)
+    .union(WasmFeatures::MEMORY64);
+
+/// Validates a WebAssembly binary against the proposals chiwawa implements.
+///
+/// Runs wasmparser's validator over every section and function body, so
+/// malformed or ill-typed modules are rejected before any instruction is
+/// preprocessed. Errors in a function body carry its function index.
+pub fn validate(bytes: &[u8]) -> Result<(), ParserError> {
+    let features = SUPPORTED_FEATURES;
+    let to_error = |e: BinaryReaderError| ParserError::ValidationError {
+        offset: e.offset(),
+        message: e.message().to_string(),
+    };
+
+    let mut validator = Validator::new_with_features(features);
+    let mut allocs = FuncValidatorAllocations::default();
+    for payload in Parser::new(0).parse_all(bytes) {
+        let payload = payload.map_err(to_error)?;
+        if let ValidPayload::Func(func, body) = validator.payload(&payload).map_err(to_error)? {
+            let func_index = func.index;
+            let mut func_validator = func.into_validator(std::mem::take(&mut allocs));
+            func_validator
+                .validate(&body)
+                .map_err(|e| ParserError::FuncValidationError {
+                    func_index,
+                    offset: e.offset(),
+                    message: e.message().to_string(),
+                })?;
+            allocs = func_validator.into_allocations();
+        }
+    }
+    Ok(())
+}
+
+/// Parses an in-memory WebAssembly module and populates the module structure.
+///
+/// Accepts both the binary and the text format; text is converted with
+/// `wat_to_binary` first. The module is checked with `validate`, then all
+/// sections are parsed using wasmparser and instructions are preprocessed for
+/// efficient interpretation.
 ///
 /// # Arguments
 ///
 /// * `module` - The module structure to populate
-/// * `path` - Path to the WebAssembly binary file
-pub fn parse_bytecode(
+/// * `bytes` - The WebAssembly binary or text
+pub fn parse_bytes(
     mut module: &mut Module,
-    path: &str,
+    bytes: &[u8],
 ) -> Result<(), Box<dyn std::error::Error>> {
     let mut current_func_index = module.num_imported_funcs;
     let mut arity_cache = BlockArityCache::new();
 
-    let mut buf = Vec::new();
+    let bytes = wat_to_binary(bytes)?;
+    validate(&bytes)?;
+    module.fingerprint = XxHash64::oneshot(0, &bytes);
     let parser = Parser::new(0);
 
-    let mut file = File::open(path)?;
-    file.read_to_end(&mut buf)?;
-
-    for payload in parser.parse_all(&buf) {
+    for payload in parser.parse_all(&bytes) {
         match payload? {
             Version {
                 num,
@@ -7436,7 +8510,9 @@ pub fn parse_bytecode(
                 decode_mem_section(body, &mut module)?;
             }
 
-            TagSection(_) => { /* ... */ }
+            TagSection(body) => {
+                decode_tag_section(body, module)?;
+            }
 
             GlobalSection(body) => {
                 decode_global_section(body, &mut module)?;
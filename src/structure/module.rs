@@ -11,6 +11,7 @@
 //! - **Tables**: Collections of function references for indirect calls
 //! - **Memories**: Linear memory regions
 //! - **Globals**: Global variables
+//! - **Tags**: Exception tags used by `throw` and `try_table`
 //! - **Elements**: Table initialization data
 //! - **Data**: Memory initialization data
 //! - **Imports/Exports**: Module interface
@@ -53,6 +54,15 @@ pub struct Global {
     pub init: Expr,
 }
 
+/// Exception tag definition.
+///
+/// The referenced function type describes the exception's payload; its
+/// results are always empty.
+#[derive(Clone)]
+pub struct Tag {
+    pub type_: TypeIdx,
+}
+
 /// Element segment for table initialization.
 pub struct Elem {
     pub type_: RefType,
@@ -105,6 +115,7 @@ pub enum ImportDesc {
     Table(TableType),
     Mem(MemType),
     Global(GlobalType),
+    Tag(TypeIdx),
     WasiFunc(WasiFuncType),
 }
 
@@ -561,6 +572,7 @@ pub enum ExportDesc {
     Table(TableIdx),
     Mem(MemIdx),
     Global(GlobalIdx),
+    Tag(TagIdx),
 }
 
 /// A parsed WebAssembly module.
@@ -578,6 +590,8 @@ pub struct Module {
     pub mems: Vec<Mem>,
     /// Global variable definitions.
     pub globals: Vec<Global>,
+    /// Exception tag definitions.
+    pub tags: Vec<Tag>,
     /// Element segments.
     pub elems: Vec<Elem>,
     /// Data segments.
@@ -592,6 +606,10 @@ pub struct Module {
     pub code_index: usize,
     /// Export declarations.
     pub exports: Vec<Export>,
+    /// XXH64 hash of the binary the module was parsed from (0 if not
+    /// parsed). Checkpoints record it to refuse restoring into another
+    /// module.
+    pub fingerprint: u64,
 }
 
 impl Module {
@@ -603,6 +621,7 @@ impl Module {
             tables: Vec::new(),
             mems: Vec::new(),
             globals: Vec::new(),
+            tags: Vec::new(),
             elems: Vec::new(),
             datas: Vec::new(),
             start: None,
@@ -610,6 +629,7 @@ impl Module {
             num_imported_funcs: 0,
             code_index: 0,
             exports: Vec::new(),
+            fingerprint: 0,
         }
     }
 }
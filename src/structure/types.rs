@@ -44,6 +44,7 @@ pub enum VecType {
 pub enum RefType {
     FuncRef,
     ExternalRef,
+    ExnRef,
 }
 
 /// Function type signature.
@@ -125,6 +126,16 @@ impl Into<u32> for GlobalIdx {
 }
 impl GetIdx for GlobalIdx {}
 
+/// Index into the tag section.
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TagIdx(pub u32);
+impl From<TagIdx> for u32 {
+    fn from(idx: TagIdx) -> u32 {
+        idx.0
+    }
+}
+impl GetIdx for TagIdx {}
+
 /// Index into local variables within a function.
 #[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct LocalIdx(pub u32);
@@ -174,14 +185,34 @@ pub struct TableType(pub Limits, pub RefType);
 #[derive(PartialEq, Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct Limits {
     /// Minimum size (in pages for memory, elements for tables).
-    pub min: u32,
+    pub min: u64,
     /// Optional maximum size.
-    pub max: Option<u32>,
+    pub max: Option<u64>,
+}
+
+impl Limits {
+    /// Returns true if these limits (of an external value) satisfy the
+    /// limits `other` declared by an import.
+    pub fn type_match(&self, other: &Limits) -> bool {
+        self.min >= other.min
+            && match (self.max, other.max) {
+                (_, None) => true,
+                (Some(max), Some(other_max)) => max <= other_max,
+                (None, Some(_)) => false,
+            }
+    }
+}
+
+/// Address type of a memory: `I32` for 32-bit memories, `I64` for memory64.
+#[derive(PartialEq, Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum AddrType {
+    I32,
+    I64,
 }
 
-/// Memory type specifying size limits.
+/// Memory type specifying size limits and address type.
 #[derive(PartialEq, Clone, Copy, Debug, Serialize, Deserialize)]
-pub struct MemType(pub Limits);
+pub struct MemType(pub Limits, pub AddrType);
 
 /// Global type specifying mutability and value type.
 #[derive(PartialEq, Clone, Copy, Debug, Serialize, Deserialize)]
@@ -23,10 +23,12 @@
 //! ## Module Organization
 //!
 //! - [`passthrough`]: WASI function implementations delegating to wasi-libc
+//! - [`fd_table`]: guest file-descriptor bookkeeping for checkpoint/restore
 //! - [`types`]: WASI type definitions
 //! - [`error`]: WASI error codes and handling
 
 pub mod error;
+pub mod fd_table;
 pub mod passthrough;
 pub mod types;
 
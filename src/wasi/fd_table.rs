@@ -0,0 +1,106 @@
+//! Guest file-descriptor bookkeeping for checkpoint/restore.
+//!
+//! The passthrough layer hands host fd numbers straight to the guest, so an
+//! fd opened with `path_open` only exists in the host process. [`FdTable`]
+//! records how each such fd was opened, which is enough to reopen it on
+//! another host and renumber it to the same fd. It is kept in sync by the
+//! `path_open`, `fd_close`, `fd_renumber`, `fd_fdstat_set_flags` and
+//! `fd_fdstat_set_rights` calls of [`PassthroughWasiImpl`].
+//!
+//! [`PassthroughWasiImpl`]: super::passthrough::PassthroughWasiImpl
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// `oflags` bit requesting a directory.
+pub const OFLAGS_DIRECTORY: u32 = 1 << 1;
+
+/// How a guest file descriptor was opened.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FdEntry {
+    pub fd: u32,
+    /// Name of the preopened directory `path` is relative to.
+    pub preopen: String,
+    /// Path relative to `preopen`.
+    pub path: String,
+    pub dirflags: u32,
+    pub oflags: u32,
+    pub rights_base: u64,
+    pub rights_inheriting: u64,
+    pub fdflags: u32,
+    /// Seek offset, filled in when the state is saved. `None` for
+    /// descriptors that cannot seek, such as directories.
+    pub offset: Option<u64>,
+}
+
+/// WASI state of one instance carried across a checkpoint.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct WasiState {
+    /// Preopened directories of the host, as fd and name. The guest refers
+    /// to them by fd, so the restoring host must provide the same mapping.
+    pub preopens: Vec<(u32, String)>,
+    /// Descriptors opened by the guest, in ascending fd order.
+    pub fds: Vec<FdEntry>,
+}
+
+/// Descriptors opened by the guest, keyed by fd.
+#[derive(Debug, Default)]
+pub struct FdTable {
+    entries: BTreeMap<u32, FdEntry>,
+}
+
+impl FdTable {
+    /// Returns the preopen name and preopen-relative path of `path` opened
+    /// relative to `dirfd`, or None if `dirfd` is neither a preopen nor a
+    /// tracked descriptor.
+    pub fn resolve(
+        &self,
+        preopens: &[(u32, String)],
+        dirfd: u32,
+        path: &str,
+    ) -> Option<(String, String)> {
+        if let Some((_, name)) = preopens.iter().find(|(fd, _)| *fd == dirfd) {
+            return Some((name.clone(), path.to_string()));
+        }
+        self.entries
+            .get(&dirfd)
+            .map(|dir| (dir.preopen.clone(), format!("{}/{}", dir.path, path)))
+    }
+
+    /// Records an opened descriptor, replacing any stale entry for its fd.
+    pub fn insert(&mut self, entry: FdEntry) {
+        self.entries.insert(entry.fd, entry);
+    }
+
+    /// Forgets a closed descriptor.
+    pub fn remove(&mut self, fd: u32) {
+        self.entries.remove(&fd);
+    }
+
+    /// Moves the entry of `fd` to `to`, which was closed by the renumber.
+    pub fn renumber(&mut self, fd: u32, to: u32) {
+        self.entries.remove(&to);
+        if let Some(mut entry) = self.entries.remove(&fd) {
+            entry.fd = to;
+            self.entries.insert(to, entry);
+        }
+    }
+
+    pub fn set_fdflags(&mut self, fd: u32, fdflags: u32) {
+        if let Some(entry) = self.entries.get_mut(&fd) {
+            entry.fdflags = fdflags;
+        }
+    }
+
+    pub fn set_rights(&mut self, fd: u32, rights_base: u64, rights_inheriting: u64) {
+        if let Some(entry) = self.entries.get_mut(&fd) {
+            entry.rights_base = rights_base;
+            entry.rights_inheriting = rights_inheriting;
+        }
+    }
+
+    /// Tracked descriptors in ascending fd order.
+    pub fn entries(&self) -> impl Iterator<Item = &FdEntry> {
+        self.entries.values()
+    }
+}
@@ -7,10 +7,19 @@
 //! Each public method on [`PassthroughWasiImpl`] corresponds to a WASI function
 //! and translates between guest memory addresses and host pointers.
 
+use super::fd_table::{FdEntry, FdTable, WasiState, OFLAGS_DIRECTORY};
 use super::*;
 use crate::execution::mem::MemAddr;
+use std::sync::{Mutex, OnceLock};
 use WasiError;
 
+/// Preopened directories of the host process, as fd and name. Captured by
+/// the first `PassthroughWasiImpl`, before any guest can close one.
+static HOST_PREOPENS: OnceLock<Vec<(u32, String)>> = OnceLock::new();
+
+/// `whence` value of `fd_seek` for absolute offsets.
+const WHENCE_SET: u32 = 0;
+
 /// WASI iovec structure that matches wasi-libc layout.
 #[repr(C)]
 struct WasiIovec {
@@ -142,13 +151,218 @@ extern "C" {
 /// This struct holds state needed for WASI operations (such as command-line arguments)
 /// and provides methods for each WASI Preview 1 function. Each method reads from or
 /// writes to guest linear memory and calls the corresponding `__wasi_*` function.
+///
+/// Descriptors the guest opens are tracked in an [`FdTable`] so that
+/// `save_state` and `restore_state` can carry them across a checkpoint.
 pub struct PassthroughWasiImpl {
     argv: Vec<String>,
+    fds: Mutex<FdTable>,
 }
 
 impl PassthroughWasiImpl {
     pub fn new(argv: Vec<String>) -> Self {
-        PassthroughWasiImpl { argv }
+        Self::host_preopens();
+        PassthroughWasiImpl {
+            argv,
+            fds: Mutex::new(FdTable::default()),
+        }
+    }
+
+    /// Preopened directories of the host, enumerated from fd 3 up to the
+    /// first `EBADF` like wasi-libc does at startup.
+    pub fn host_preopens() -> &'static [(u32, String)] {
+        HOST_PREOPENS.get_or_init(|| {
+            let mut preopens = Vec::new();
+            for fd in 3.. {
+                // prestat: tag (u8, 0 = dir) and name length (u32 at offset 4)
+                let mut prestat: [u8; 8] = [0; 8];
+                let wasi_errno = unsafe { __wasi_fd_prestat_get(fd, prestat.as_mut_ptr()) };
+                if wasi_errno != 0 {
+                    break;
+                }
+                if prestat[0] != 0 {
+                    continue;
+                }
+                let name_len = u32::from_le_bytes([prestat[4], prestat[5], prestat[6], prestat[7]]);
+                let mut name = vec![0u8; name_len as usize];
+                let wasi_errno =
+                    unsafe { __wasi_fd_prestat_dir_name(fd, name.as_mut_ptr(), name_len) };
+                if wasi_errno == 0 {
+                    preopens.push((fd, String::from_utf8_lossy(&name).into_owned()));
+                }
+            }
+            preopens
+        })
+    }
+
+    /// Captures the preopen mapping and the descriptors opened by the guest,
+    /// with their current seek offsets.
+    pub fn save_state(&self) -> WasiState {
+        let fds = self.fds.lock().unwrap();
+        WasiState {
+            preopens: Self::host_preopens().to_vec(),
+            fds: fds
+                .entries()
+                .map(|entry| {
+                    let mut offset: u64 = 0;
+                    let wasi_errno = unsafe { __wasi_fd_tell(entry.fd, &mut offset) };
+                    FdEntry {
+                        offset: (wasi_errno == 0).then_some(offset),
+                        ..entry.clone()
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Reopens the descriptors of `state` under their original fd numbers
+    /// and seeks them back to their saved offsets.
+    ///
+    /// Fails if the host's preopens differ from the saved ones, if a file
+    /// can no longer be opened, or if an original fd number is already in
+    /// use. Descriptors reopened before the failure are closed again.
+    pub fn restore_state(&self, state: &WasiState) -> Result<(), String> {
+        let preopens = Self::host_preopens();
+        for (fd, name) in &state.preopens {
+            if !preopens
+                .iter()
+                .any(|(host_fd, host_name)| host_fd == fd && host_name == name)
+            {
+                return Err(format!(
+                    "Preopen {:?} (fd {}) is not available on this host",
+                    name, fd
+                ));
+            }
+        }
+
+        let mut reopened = Vec::new();
+        for entry in &state.fds {
+            match Self::reopen(preopens, entry) {
+                Ok(()) => reopened.push(entry.fd),
+                Err(e) => {
+                    for fd in reopened {
+                        unsafe { __wasi_fd_close(fd) };
+                    }
+                    return Err(e);
+                }
+            }
+        }
+
+        let mut fds = self.fds.lock().unwrap();
+        for entry in &state.fds {
+            fds.insert(FdEntry {
+                offset: None,
+                ..entry.clone()
+            });
+        }
+        Ok(())
+    }
+
+    /// Closes the descriptors reopened by a successful `restore_state` and
+    /// forgets their entries, for when a later step of the restore fails.
+    pub fn discard_state(&self, state: &WasiState) {
+        let mut fds = self.fds.lock().unwrap();
+        for entry in &state.fds {
+            unsafe { __wasi_fd_close(entry.fd) };
+            fds.remove(entry.fd);
+        }
+    }
+
+    /// Reopens one saved descriptor at `entry.fd`.
+    fn reopen(preopens: &[(u32, String)], entry: &FdEntry) -> Result<(), String> {
+        let target = entry.fd;
+        let mut stat: [u8; 24] = [0; 24];
+        if unsafe { __wasi_fd_fdstat_get(target, stat.as_mut_ptr()) } == 0 {
+            return Err(format!("fd {} is already open on this host", target));
+        }
+        let dirfd = preopens
+            .iter()
+            .find(|(_, name)| *name == entry.preopen)
+            .map(|(fd, _)| *fd)
+            .ok_or_else(|| format!("Preopen {:?} is not available on this host", entry.preopen))?;
+        let mut path = entry.path.as_bytes().to_vec();
+        path.push(0);
+        // Reopen without O_CREAT, O_EXCL or O_TRUNC: the file must already
+        // exist and keep its contents
+        let open = || {
+            let mut fd: u32 = 0;
+            let wasi_errno = unsafe {
+                __wasi_path_open(
+                    dirfd,
+                    entry.dirflags,
+                    path.as_ptr(),
+                    (entry.oflags & OFLAGS_DIRECTORY) as u16,
+                    entry.rights_base,
+                    entry.rights_inheriting,
+                    entry.fdflags as u16,
+                    &mut fd,
+                )
+            };
+            if wasi_errno == 0 {
+                Ok(fd)
+            } else {
+                Err(format!(
+                    "Cannot reopen {:?} in {:?}: {}",
+                    entry.path,
+                    entry.preopen,
+                    WasiError::from_errno(wasi_errno)
+                ))
+            }
+        };
+
+        // The host hands out the lowest free fd, so open placeholders until
+        // one lands on the target and renumber the reopened file onto it
+        let fd = open()?;
+        if fd != target {
+            let mut placeholders = Vec::new();
+            let renumbered = loop {
+                match open() {
+                    Ok(placeholder) if placeholder == target => {
+                        let wasi_errno = unsafe { __wasi_fd_renumber(fd as i32, target as i32) };
+                        break if wasi_errno == 0 {
+                            Ok(())
+                        } else {
+                            placeholders.push(placeholder);
+                            Err(format!(
+                                "Cannot renumber fd {} to {}: {}",
+                                fd,
+                                target,
+                                WasiError::from_errno(wasi_errno)
+                            ))
+                        };
+                    }
+                    Ok(placeholder) if placeholder < target => placeholders.push(placeholder),
+                    Ok(placeholder) => {
+                        placeholders.push(placeholder);
+                        break Err(format!("fd {} cannot be allocated on this host", target));
+                    }
+                    Err(e) => break Err(e),
+                }
+            };
+            for placeholder in placeholders {
+                unsafe { __wasi_fd_close(placeholder) };
+            }
+            if let Err(e) = renumbered {
+                unsafe { __wasi_fd_close(fd) };
+                return Err(e);
+            }
+        }
+
+        if let Some(offset) = entry.offset.filter(|&offset| offset != 0) {
+            let mut new_offset: u64 = 0;
+            let wasi_errno =
+                unsafe { __wasi_fd_seek(target, offset as i64, WHENCE_SET, &mut new_offset) };
+            if wasi_errno != 0 {
+                unsafe { __wasi_fd_close(target) };
+                return Err(format!(
+                    "Cannot seek {:?} to {}: {}",
+                    entry.path,
+                    offset,
+                    WasiError::from_errno(wasi_errno)
+                ));
+            }
+        }
+        Ok(())
     }
 
     /// Check if a file exists without memory allocation
@@ -175,6 +389,83 @@ impl PassthroughWasiImpl {
         wasi_errno == 0
     }
 
+    /// Consume one byte from `fd` if one is ready, without blocking
+    /// Used for checkpoint trigger detection
+    pub fn take_fd_byte(fd: u32) -> bool {
+        // Two subscriptions (48 bytes each): fd_read on `fd` (userdata 1)
+        // and a relative clock timeout of 0 (userdata 0) so the call returns
+        // immediately
+        let mut subscriptions: [u8; 96] = [0; 96];
+        subscriptions[0..8].copy_from_slice(&1u64.to_le_bytes());
+        subscriptions[8] = 1; // eventtype: fd_read
+        subscriptions[16..20].copy_from_slice(&fd.to_le_bytes());
+        subscriptions[48 + 8] = 0; // eventtype: clock
+        subscriptions[48 + 16..48 + 20].copy_from_slice(&1u32.to_le_bytes()); // monotonic
+
+        let mut events: [u8; 64] = [0; 64];
+        let mut nevents: u32 = 0;
+        let wasi_errno = unsafe {
+            __wasi_poll_oneoff(subscriptions.as_ptr(), events.as_mut_ptr(), 2, &mut nevents)
+        };
+        if wasi_errno != 0 {
+            return false;
+        }
+
+        // Each event is 32 bytes: userdata (u64), error (u16), type (u8), ...
+        let ready = events
+            .chunks_exact(32)
+            .take(nevents as usize)
+            .any(|event| event[0] == 1 && event[8] == 0 && event[9] == 0);
+        if !ready {
+            return false;
+        }
+
+        let mut byte: [u8; 1] = [0];
+        let iov = WasiIovec {
+            buf: byte.as_mut_ptr(),
+            buf_len: 1,
+        };
+        let mut nread: u32 = 0;
+        let wasi_errno = unsafe { __wasi_fd_read(fd, &iov, 1, &mut nread) };
+        wasi_errno == 0 && nread == 1
+    }
+
+    /// Translates the guest range `[ptr, ptr + len)` into a host pointer.
+    /// Fails with `WasiError::Fault` if the range is outside guest memory.
+    fn guest_ptr(memory: &MemAddr, ptr: Ptr, len: u64) -> WasiResult<*mut u8> {
+        let memory_len = memory.get_memory_direct_access().data.len();
+        let pos = MemAddr::check_range(memory_len, ptr as u64, 0, len).ok_or(WasiError::Fault)?;
+        Ok(unsafe { memory.data_ptr().add(pos) })
+    }
+
+    /// Borrows the guest bytes `[ptr, ptr + len)`, such as a path.
+    fn guest_bytes(memory: &MemAddr, ptr: Ptr, len: Size) -> WasiResult<&[u8]> {
+        let data = &memory.get_memory_direct_access().data;
+        let pos =
+            MemAddr::check_range(data.len(), ptr as u64, 0, len as u64).ok_or(WasiError::Fault)?;
+        Ok(&data[pos..pos + len as usize])
+    }
+
+    /// Reads `iovs_len` guest iovecs at `iovs_ptr` and translates each
+    /// buffer into a host pointer, checking that it lies in guest memory.
+    fn guest_iovecs(memory: &MemAddr, iovs_ptr: Ptr, iovs_len: Size) -> WasiResult<Vec<WasiIovec>> {
+        // Each iovec is 8 bytes: buf_ptr (4 bytes) + buf_len (4 bytes)
+        Self::guest_ptr(memory, iovs_ptr, iovs_len as u64 * 8)?;
+        let mut iovecs = Vec::with_capacity(iovs_len as usize);
+        for i in 0..iovs_len {
+            let iovec_ptr = iovs_ptr as u64 + i as u64 * 8;
+            let buf_ptr: u32 = memory.load(0, iovec_ptr).map_err(|_| WasiError::Fault)?;
+            let buf_len: u32 = memory.load(4, iovec_ptr).map_err(|_| WasiError::Fault)?;
+            let buf = if buf_len == 0 {
+                std::ptr::null()
+            } else {
+                Self::guest_ptr(memory, buf_ptr, buf_len as u64)? as *const u8
+            };
+            iovecs.push(WasiIovec { buf, buf_len });
+        }
+        Ok(iovecs)
+    }
+
     pub fn fd_write(
         &self,
         memory: &MemAddr,
@@ -183,51 +474,7 @@ impl PassthroughWasiImpl {
         iovs_len: Size,
         nwritten_ptr: Ptr,
     ) -> WasiResult<i32> {
-        let memory_guard = memory.get_memory_direct_access();
-        let memory_base = memory_guard.data.as_ptr();
-        let memory_len = memory_guard.data.len();
-
-        let mut iovecs = Vec::with_capacity(iovs_len as usize);
-
-        for i in 0..iovs_len {
-            // Each iovec is 8 bytes: buf_ptr (4 bytes) + buf_len (4 bytes)
-            let iovec_offset = iovs_ptr as usize + (i as usize * 8);
-
-            if iovec_offset + 8 > memory_len {
-                return Err(WasiError::Fault);
-            }
-
-            let buf_ptr = u32::from_le_bytes([
-                memory_guard.data[iovec_offset],
-                memory_guard.data[iovec_offset + 1],
-                memory_guard.data[iovec_offset + 2],
-                memory_guard.data[iovec_offset + 3],
-            ]);
-
-            let buf_len = u32::from_le_bytes([
-                memory_guard.data[iovec_offset + 4],
-                memory_guard.data[iovec_offset + 5],
-                memory_guard.data[iovec_offset + 6],
-                memory_guard.data[iovec_offset + 7],
-            ]);
-
-            if buf_len == 0 {
-                iovecs.push(WasiIovec {
-                    buf: std::ptr::null(),
-                    buf_len: 0,
-                });
-                continue;
-            }
-
-            if buf_ptr as usize + buf_len as usize > memory_len {
-                return Err(WasiError::Fault);
-            }
-
-            iovecs.push(WasiIovec {
-                buf: unsafe { memory_base.add(buf_ptr as usize) },
-                buf_len,
-            });
-        }
+        let iovecs = Self::guest_iovecs(memory, iovs_ptr, iovs_len)?;
 
         // Call wasi-libc fd_write function
         let mut nwritten: u32 = 0;
@@ -241,7 +488,9 @@ impl PassthroughWasiImpl {
         };
 
         if wasi_errno == 0 {
-            memory.store(0, nwritten_ptr as i32, nwritten);
+            memory
+                .store(0, nwritten_ptr as u64, nwritten)
+                .map_err(|_| WasiError::Fault)?;
         }
 
         Ok(wasi_errno as i32)
@@ -255,47 +504,16 @@ impl PassthroughWasiImpl {
         iovs_len: Size,
         nread_ptr: Ptr,
     ) -> WasiResult<i32> {
-        let memory_guard = memory.get_memory_direct_access();
-        let memory_base = memory_guard.data.as_ptr();
-
-        let mut iovecs = Vec::with_capacity(iovs_len as usize);
-
-        for i in 0..iovs_len {
-            let iovec_offset = iovs_ptr as usize + (i as usize * 8);
-
-            let buf_ptr = u32::from_le_bytes([
-                memory_guard.data[iovec_offset],
-                memory_guard.data[iovec_offset + 1],
-                memory_guard.data[iovec_offset + 2],
-                memory_guard.data[iovec_offset + 3],
-            ]);
-
-            let buf_len = u32::from_le_bytes([
-                memory_guard.data[iovec_offset + 4],
-                memory_guard.data[iovec_offset + 5],
-                memory_guard.data[iovec_offset + 6],
-                memory_guard.data[iovec_offset + 7],
-            ]);
-
-            if buf_len == 0 {
-                iovecs.push(WasiIovec {
-                    buf: std::ptr::null(),
-                    buf_len: 0,
-                });
-            } else {
-                iovecs.push(WasiIovec {
-                    buf: unsafe { memory_base.add(buf_ptr as usize) },
-                    buf_len,
-                });
-            }
-        }
+        let iovecs = Self::guest_iovecs(memory, iovs_ptr, iovs_len)?;
 
         let mut nread: u32 = 0;
         let wasi_errno =
             unsafe { __wasi_fd_read(fd as u32, iovecs.as_ptr(), iovs_len, &mut nread as *mut u32) };
 
         if wasi_errno == 0 {
-            memory.store(0, nread_ptr as i32, nread);
+            memory
+                .store(0, nread_ptr as u64, nread)
+                .map_err(|_| WasiError::Fault)?;
         }
 
         Ok(wasi_errno as i32)
@@ -313,17 +531,18 @@ impl PassthroughWasiImpl {
             return Ok(0);
         }
 
-        let memory_guard = memory.get_memory_direct_access();
-        let memory_base = memory_guard.data.as_ptr();
-
-        let wasi_errno =
-            unsafe { __wasi_random_get(memory_base.add(buf_ptr as usize) as *mut u8, buf_len) };
+        let wasi_errno = unsafe {
+            __wasi_random_get(Self::guest_ptr(memory, buf_ptr, buf_len as u64)?, buf_len)
+        };
 
         Ok(wasi_errno as i32)
     }
 
     pub fn fd_close(&self, fd: Fd) -> WasiResult<i32> {
         let wasi_errno = unsafe { __wasi_fd_close(fd as u32) };
+        if wasi_errno == 0 {
+            self.fds.lock().unwrap().remove(fd as u32);
+        }
 
         Ok(wasi_errno as i32)
     }
@@ -371,10 +590,14 @@ impl PassthroughWasiImpl {
         ptr_data.extend_from_slice(&0u32.to_le_bytes());
 
         // Write pointer array to WebAssembly memory
-        memory.store_bytes(environ_ptr as i32, &ptr_data);
+        memory
+            .store_bytes(environ_ptr as u64, &ptr_data)
+            .map_err(|_| WasiError::Fault)?;
 
         // Write environment strings to WebAssembly memory
-        memory.store_bytes(environ_buf_ptr as i32, &environ_buf);
+        memory
+            .store_bytes(environ_buf_ptr as u64, &environ_buf)
+            .map_err(|_| WasiError::Fault)?;
 
         Ok(0)
     }
@@ -396,10 +619,14 @@ impl PassthroughWasiImpl {
         }
 
         // Write environment variable count
-        memory.store(0, environ_count_ptr as i32, environ_count);
+        memory
+            .store(0, environ_count_ptr as u64, environ_count)
+            .map_err(|_| WasiError::Fault)?;
 
         // Write total buffer size needed
-        memory.store(0, environ_buf_size_ptr as i32, environ_buf_size);
+        memory
+            .store(0, environ_buf_size_ptr as u64, environ_buf_size)
+            .map_err(|_| WasiError::Fault)?;
 
         Ok(0)
     }
@@ -428,10 +655,14 @@ impl PassthroughWasiImpl {
         ptr_data.extend_from_slice(&0u32.to_le_bytes());
 
         // Write pointer array to WebAssembly memory
-        memory.store_bytes(argv_ptr as i32, &ptr_data);
+        memory
+            .store_bytes(argv_ptr as u64, &ptr_data)
+            .map_err(|_| WasiError::Fault)?;
 
         // Write argument strings to WebAssembly memory
-        memory.store_bytes(argv_buf_ptr as i32, &argv_buf);
+        memory
+            .store_bytes(argv_buf_ptr as u64, &argv_buf)
+            .map_err(|_| WasiError::Fault)?;
 
         Ok(0)
     }
@@ -451,10 +682,14 @@ impl PassthroughWasiImpl {
         let argv_buf_size: u32 = args.iter().map(|arg| arg.len() + 1).sum::<usize>() as u32;
 
         // Write argument count to WebAssembly memory
-        memory.store(0, argc_ptr as i32, argc);
+        memory
+            .store(0, argc_ptr as u64, argc)
+            .map_err(|_| WasiError::Fault)?;
 
         // Write total buffer size needed to WebAssembly memory
-        memory.store(0, argv_buf_size_ptr as i32, argv_buf_size);
+        memory
+            .store(0, argv_buf_size_ptr as u64, argv_buf_size)
+            .map_err(|_| WasiError::Fault)?;
 
         Ok(0)
     }
@@ -476,7 +711,9 @@ impl PassthroughWasiImpl {
         }
 
         // Write timestamp (64-bit nanoseconds) to memory using store_bytes
-        memory.store_bytes(time_ptr as i32, &time.to_le_bytes());
+        memory
+            .store_bytes(time_ptr as u64, &time.to_le_bytes())
+            .map_err(|_| WasiError::Fault)?;
 
         Ok(wasi_errno as i32)
     }
@@ -496,18 +733,16 @@ impl PassthroughWasiImpl {
         }
 
         // Write resolution (64-bit nanoseconds) to memory using store_bytes
-        memory.store_bytes(resolution_ptr as i32, &resolution.to_le_bytes());
+        memory
+            .store_bytes(resolution_ptr as u64, &resolution.to_le_bytes())
+            .map_err(|_| WasiError::Fault)?;
 
         Ok(wasi_errno as i32)
     }
 
     pub fn fd_prestat_get(&self, memory: &MemAddr, fd: Fd, prestat_ptr: Ptr) -> WasiResult<i32> {
-        let memory_guard = memory.get_memory_direct_access();
-        let memory_base = memory_guard.data.as_ptr();
-
-        let wasi_errno = unsafe {
-            __wasi_fd_prestat_get(fd as u32, memory_base.add(prestat_ptr as usize) as *mut u8)
-        };
+        let wasi_errno =
+            unsafe { __wasi_fd_prestat_get(fd as u32, Self::guest_ptr(memory, prestat_ptr, 8)?) };
 
         Ok(wasi_errno as i32)
     }
@@ -519,13 +754,10 @@ impl PassthroughWasiImpl {
         path_ptr: Ptr,
         path_len: Size,
     ) -> WasiResult<i32> {
-        let memory_guard = memory.get_memory_direct_access();
-        let memory_base = memory_guard.data.as_ptr();
-
         let wasi_errno = unsafe {
             __wasi_fd_prestat_dir_name(
                 fd as u32,
-                memory_base.add(path_ptr as usize) as *mut u8,
+                Self::guest_ptr(memory, path_ptr, path_len as u64)?,
                 path_len,
             )
         };
@@ -540,12 +772,8 @@ impl PassthroughWasiImpl {
     }
 
     pub fn fd_fdstat_get(&self, memory: &MemAddr, fd: Fd, stat_ptr: Ptr) -> WasiResult<i32> {
-        let memory_guard = memory.get_memory_direct_access();
-        let memory_base = memory_guard.data.as_ptr();
-
-        let wasi_errno = unsafe {
-            __wasi_fd_fdstat_get(fd as u32, memory_base.add(stat_ptr as usize) as *mut u8)
-        };
+        let wasi_errno =
+            unsafe { __wasi_fd_fdstat_get(fd as u32, Self::guest_ptr(memory, stat_ptr, 24)?) };
 
         Ok(wasi_errno as i32)
     }
@@ -563,16 +791,12 @@ impl PassthroughWasiImpl {
         fdflags: u32,
         opened_fd_ptr: Ptr,
     ) -> WasiResult<i32> {
-        let memory_guard = memory.get_memory_direct_access();
-        let memory_base = memory_guard.data.as_ptr();
-
         // Create null-terminated string from path
-        let path_slice = unsafe {
-            std::slice::from_raw_parts(memory_base.add(path_ptr as usize), path_len as usize)
-        };
+        let path_slice = Self::guest_bytes(memory, path_ptr, path_len)?;
         let mut path_vec = path_slice.to_vec();
         path_vec.push(0); // Add null terminator
 
+        let mut opened_fd: u32 = 0;
         let wasi_errno = unsafe {
             __wasi_path_open(
                 fd as u32,
@@ -582,10 +806,39 @@ impl PassthroughWasiImpl {
                 fs_rights_base,
                 fs_rights_inheriting,
                 fdflags as u16,
-                memory_base.add(opened_fd_ptr as usize) as *mut u32,
+                &mut opened_fd,
             )
         };
 
+        if wasi_errno == 0 {
+            if memory.store(0, opened_fd_ptr as u64, opened_fd).is_err() {
+                // The guest never sees the fd, so close it rather than leak it
+                unsafe { __wasi_fd_close(opened_fd) };
+                return Err(WasiError::Fault);
+            }
+            let mut fds = self.fds.lock().unwrap();
+            let resolved = fds.resolve(
+                Self::host_preopens(),
+                fd as u32,
+                &String::from_utf8_lossy(&path_vec[..path_len as usize]),
+            );
+            match resolved {
+                Some((preopen, path)) => fds.insert(FdEntry {
+                    fd: opened_fd,
+                    preopen,
+                    path,
+                    dirflags,
+                    oflags,
+                    rights_base: fs_rights_base,
+                    rights_inheriting: fs_rights_inheriting,
+                    fdflags,
+                    offset: None,
+                }),
+                // Not reachable from a preopen, so it cannot be reopened
+                None => fds.remove(opened_fd),
+            }
+        }
+
         Ok(wasi_errno as i32)
     }
 
@@ -597,15 +850,12 @@ impl PassthroughWasiImpl {
         whence: u32,
         newoffset_ptr: Ptr,
     ) -> WasiResult<i32> {
-        let memory_guard = memory.get_memory_direct_access();
-        let memory_base = memory_guard.data.as_ptr();
-
         let wasi_errno = unsafe {
             __wasi_fd_seek(
                 fd as u32,
                 offset,
                 whence,
-                memory_base.add(newoffset_ptr as usize) as *mut u64,
+                Self::guest_ptr(memory, newoffset_ptr, 8)? as *mut u64,
             )
         };
 
@@ -613,11 +863,12 @@ impl PassthroughWasiImpl {
     }
 
     pub fn fd_tell(&self, memory: &MemAddr, fd: Fd, offset_ptr: Ptr) -> WasiResult<i32> {
-        let memory_guard = memory.get_memory_direct_access();
-        let memory_base = memory_guard.data.as_ptr();
-
-        let wasi_errno =
-            unsafe { __wasi_fd_tell(fd as u32, memory_base.add(offset_ptr as usize) as *mut u64) };
+        let wasi_errno = unsafe {
+            __wasi_fd_tell(
+                fd as u32,
+                Self::guest_ptr(memory, offset_ptr, 8)? as *mut u64,
+            )
+        };
 
         Ok(wasi_errno as i32)
     }
@@ -629,11 +880,8 @@ impl PassthroughWasiImpl {
     }
 
     pub fn fd_filestat_get(&self, memory: &MemAddr, fd: Fd, filestat_ptr: Ptr) -> WasiResult<i32> {
-        let memory_guard = memory.get_memory_direct_access();
-        let memory_base = memory_guard.data.as_ptr();
-
         let wasi_errno = unsafe {
-            __wasi_fd_filestat_get(fd as u32, memory_base.add(filestat_ptr as usize) as *mut u8)
+            __wasi_fd_filestat_get(fd as u32, Self::guest_ptr(memory, filestat_ptr, 64)?)
         };
 
         Ok(wasi_errno as i32)
@@ -648,16 +896,13 @@ impl PassthroughWasiImpl {
         cookie: u64,
         buf_used_ptr: Ptr,
     ) -> WasiResult<i32> {
-        let memory_guard = memory.get_memory_direct_access();
-        let memory_base = memory_guard.data.as_ptr();
-
         let wasi_errno = unsafe {
             __wasi_fd_readdir(
                 fd as u32,
-                memory_base.add(buf_ptr as usize) as *mut u8,
+                Self::guest_ptr(memory, buf_ptr, buf_len as u64)?,
                 buf_len,
                 cookie,
-                memory_base.add(buf_used_ptr as usize) as *mut u32,
+                Self::guest_ptr(memory, buf_used_ptr, 4)? as *mut u32,
             )
         };
 
@@ -673,41 +918,7 @@ impl PassthroughWasiImpl {
         offset: u64,
         nread_ptr: Ptr,
     ) -> WasiResult<i32> {
-        let memory_guard = memory.get_memory_direct_access();
-        let memory_base = memory_guard.data.as_ptr();
-
-        let mut iovecs = Vec::with_capacity(iovs_len as usize);
-
-        for i in 0..iovs_len {
-            // Each iovec is 8 bytes: buf_ptr (4 bytes) + buf_len (4 bytes)
-            let iovec_offset = iovs_ptr as usize + (i as usize * 8);
-
-            let buf_ptr = u32::from_le_bytes([
-                memory_guard.data[iovec_offset],
-                memory_guard.data[iovec_offset + 1],
-                memory_guard.data[iovec_offset + 2],
-                memory_guard.data[iovec_offset + 3],
-            ]);
-
-            let buf_len = u32::from_le_bytes([
-                memory_guard.data[iovec_offset + 4],
-                memory_guard.data[iovec_offset + 5],
-                memory_guard.data[iovec_offset + 6],
-                memory_guard.data[iovec_offset + 7],
-            ]);
-
-            if buf_len == 0 {
-                iovecs.push(WasiIovec {
-                    buf: std::ptr::null(),
-                    buf_len: 0,
-                });
-            } else {
-                iovecs.push(WasiIovec {
-                    buf: unsafe { memory_base.add(buf_ptr as usize) },
-                    buf_len,
-                });
-            }
-        }
+        let iovecs = Self::guest_iovecs(memory, iovs_ptr, iovs_len)?;
 
         let mut nread: u32 = 0;
         let wasi_errno = unsafe {
@@ -724,7 +935,9 @@ impl PassthroughWasiImpl {
             return Ok(wasi_errno as i32);
         }
 
-        memory.store(0, nread_ptr as i32, nread);
+        memory
+            .store(0, nread_ptr as u64, nread)
+            .map_err(|_| WasiError::Fault)?;
 
         Ok(0)
     }
@@ -737,6 +950,9 @@ impl PassthroughWasiImpl {
 
     pub fn fd_fdstat_set_flags(&self, fd: Fd, flags: u32) -> WasiResult<i32> {
         let wasi_errno = unsafe { __wasi_fd_fdstat_set_flags(fd as u32, flags) };
+        if wasi_errno == 0 {
+            self.fds.lock().unwrap().set_fdflags(fd as u32, flags);
+        }
 
         Ok(wasi_errno as i32)
     }
@@ -756,41 +972,7 @@ impl PassthroughWasiImpl {
         offset: u64,
         nwritten_ptr: Ptr,
     ) -> WasiResult<i32> {
-        let memory_guard = memory.get_memory_direct_access();
-        let memory_base = memory_guard.data.as_ptr();
-
-        let mut iovecs = Vec::with_capacity(iovs_len as usize);
-
-        for i in 0..iovs_len {
-            // Each iovec is 8 bytes: buf_ptr (4 bytes) + buf_len (4 bytes)
-            let iovec_offset = iovs_ptr as usize + (i as usize * 8);
-
-            let buf_ptr = u32::from_le_bytes([
-                memory_guard.data[iovec_offset],
-                memory_guard.data[iovec_offset + 1],
-                memory_guard.data[iovec_offset + 2],
-                memory_guard.data[iovec_offset + 3],
-            ]);
-
-            let buf_len = u32::from_le_bytes([
-                memory_guard.data[iovec_offset + 4],
-                memory_guard.data[iovec_offset + 5],
-                memory_guard.data[iovec_offset + 6],
-                memory_guard.data[iovec_offset + 7],
-            ]);
-
-            if buf_len == 0 {
-                iovecs.push(WasiIovec {
-                    buf: std::ptr::null(),
-                    buf_len: 0,
-                });
-            } else {
-                iovecs.push(WasiIovec {
-                    buf: unsafe { memory_base.add(buf_ptr as usize) },
-                    buf_len,
-                });
-            }
-        }
+        let iovecs = Self::guest_iovecs(memory, iovs_ptr, iovs_len)?;
 
         let mut nwritten: u32 = 0;
         let wasi_errno = unsafe {
@@ -807,7 +989,9 @@ impl PassthroughWasiImpl {
             return Ok(wasi_errno as i32);
         }
 
-        memory.store(0, nwritten_ptr as i32, nwritten);
+        memory
+            .store(0, nwritten_ptr as u64, nwritten)
+            .map_err(|_| WasiError::Fault)?;
 
         Ok(0)
     }
@@ -819,13 +1003,8 @@ impl PassthroughWasiImpl {
         path_ptr: Ptr,
         path_len: Size,
     ) -> WasiResult<i32> {
-        let memory_guard = memory.get_memory_direct_access();
-        let memory_base = memory_guard.data.as_ptr();
-
         // Create null-terminated string from path
-        let path_slice = unsafe {
-            std::slice::from_raw_parts(memory_base.add(path_ptr as usize), path_len as usize)
-        };
+        let path_slice = Self::guest_bytes(memory, path_ptr, path_len)?;
         let mut path_vec = path_slice.to_vec();
         path_vec.push(0); // Add null terminator
 
@@ -843,13 +1022,8 @@ impl PassthroughWasiImpl {
         path_len: Size,
         filestat_ptr: Ptr,
     ) -> WasiResult<i32> {
-        let memory_guard = memory.get_memory_direct_access();
-        let memory_base = memory_guard.data.as_ptr();
-
         // Create null-terminated string from path
-        let path_slice = unsafe {
-            std::slice::from_raw_parts(memory_base.add(path_ptr as usize), path_len as usize)
-        };
+        let path_slice = Self::guest_bytes(memory, path_ptr, path_len)?;
         let mut path_vec = path_slice.to_vec();
         path_vec.push(0); // null terminate
 
@@ -858,7 +1032,7 @@ impl PassthroughWasiImpl {
                 fd as u32,
                 flags,
                 path_vec.as_ptr(),
-                memory_base.add(filestat_ptr as usize) as *mut u8,
+                Self::guest_ptr(memory, filestat_ptr, 64)?,
             )
         };
 
@@ -876,13 +1050,8 @@ impl PassthroughWasiImpl {
         mtim: u64,
         fst_flags: u32,
     ) -> WasiResult<i32> {
-        let memory_guard = memory.get_memory_direct_access();
-        let memory_base = memory_guard.data.as_ptr();
-
         // Create null-terminated string from path
-        let path_slice = unsafe {
-            std::slice::from_raw_parts(memory_base.add(path_ptr as usize), path_len as usize)
-        };
+        let path_slice = Self::guest_bytes(memory, path_ptr, path_len)?;
         let mut path_vec = path_slice.to_vec();
         path_vec.push(0);
 
@@ -910,13 +1079,8 @@ impl PassthroughWasiImpl {
         buf_len: Size,
         buf_used_ptr: Ptr,
     ) -> WasiResult<i32> {
-        let memory_guard = memory.get_memory_direct_access();
-        let memory_base = memory_guard.data.as_ptr();
-
         // Create null-terminated string from path
-        let path_slice = unsafe {
-            std::slice::from_raw_parts(memory_base.add(path_ptr as usize), path_len as usize)
-        };
+        let path_slice = Self::guest_bytes(memory, path_ptr, path_len)?;
         let mut path_vec = path_slice.to_vec();
         path_vec.push(0);
 
@@ -924,9 +1088,9 @@ impl PassthroughWasiImpl {
             __wasi_path_readlink(
                 fd as u32,
                 path_vec.as_ptr(),
-                memory_base.add(buf_ptr as usize) as *mut u8,
+                Self::guest_ptr(memory, buf_ptr, buf_len as u64)?,
                 buf_len,
-                memory_base.add(buf_used_ptr as usize) as *mut u32,
+                Self::guest_ptr(memory, buf_used_ptr, 4)? as *mut u32,
             )
         };
 
@@ -940,13 +1104,8 @@ impl PassthroughWasiImpl {
         path_ptr: Ptr,
         path_len: Size,
     ) -> WasiResult<i32> {
-        let memory_guard = memory.get_memory_direct_access();
-        let memory_base = memory_guard.data.as_ptr();
-
         // Create null-terminated string from path
-        let path_slice = unsafe {
-            std::slice::from_raw_parts(memory_base.add(path_ptr as usize), path_len as usize)
-        };
+        let path_slice = Self::guest_bytes(memory, path_ptr, path_len)?;
         let mut path_vec = path_slice.to_vec();
         path_vec.push(0); // Add null terminator
 
@@ -962,12 +1121,7 @@ impl PassthroughWasiImpl {
         path_ptr: Ptr,
         path_len: Size,
     ) -> WasiResult<i32> {
-        let memory_guard = memory.get_memory_direct_access();
-        let memory_base = memory_guard.data.as_ptr();
-
-        let path_slice = unsafe {
-            std::slice::from_raw_parts(memory_base.add(path_ptr as usize), path_len as usize)
-        };
+        let path_slice = Self::guest_bytes(memory, path_ptr, path_len)?;
         let mut path_vec = path_slice.to_vec();
         path_vec.push(0);
 
@@ -984,15 +1138,12 @@ impl PassthroughWasiImpl {
         nsubscriptions: Size,
         nevents_ptr: Ptr,
     ) -> WasiResult<i32> {
-        let memory_guard = memory.get_memory_direct_access();
-        let memory_base = memory_guard.data.as_ptr();
-
         let wasi_errno = unsafe {
             __wasi_poll_oneoff(
-                memory_base.add(in_ptr as usize),
-                memory_base.add(out_ptr as usize) as *mut u8,
+                Self::guest_ptr(memory, in_ptr, nsubscriptions as u64 * 48)?,
+                Self::guest_ptr(memory, out_ptr, nsubscriptions as u64 * 32)?,
                 nsubscriptions,
-                memory_base.add(nevents_ptr as usize) as *mut u32,
+                Self::guest_ptr(memory, nevents_ptr, 4)? as *mut u32,
             )
         };
 
@@ -1039,12 +1190,21 @@ impl PassthroughWasiImpl {
     ) -> WasiResult<i32> {
         let wasi_errno =
             unsafe { __wasi_fd_fdstat_set_rights(fd, fs_rights_base, fs_rights_inheriting) };
+        if wasi_errno == 0 {
+            self.fds
+                .lock()
+                .unwrap()
+                .set_rights(fd, fs_rights_base, fs_rights_inheriting);
+        }
 
         Ok(wasi_errno as i32)
     }
 
     pub fn fd_renumber(&self, _memory: &MemAddr, fd: u32, to: u32) -> WasiResult<i32> {
         let wasi_errno = unsafe { __wasi_fd_renumber(fd as i32, to as i32) };
+        if wasi_errno == 0 {
+            self.fds.lock().unwrap().renumber(fd, to);
+        }
 
         Ok(wasi_errno as i32)
     }
@@ -1073,25 +1233,12 @@ impl PassthroughWasiImpl {
         new_path_ptr: Ptr,
         new_path_len: Size,
     ) -> WasiResult<i32> {
-        let memory_guard = memory.get_memory_direct_access();
-        let memory_base = memory_guard.data.as_ptr();
-
         // Create null-terminated strings from paths
-        let old_path_slice = unsafe {
-            std::slice::from_raw_parts(
-                memory_base.add(old_path_ptr as usize),
-                old_path_len as usize,
-            )
-        };
+        let old_path_slice = Self::guest_bytes(memory, old_path_ptr, old_path_len)?;
         let mut old_path_vec = old_path_slice.to_vec();
         old_path_vec.push(0); // Add null terminator
 
-        let new_path_slice = unsafe {
-            std::slice::from_raw_parts(
-                memory_base.add(new_path_ptr as usize),
-                new_path_len as usize,
-            )
-        };
+        let new_path_slice = Self::guest_bytes(memory, new_path_ptr, new_path_len)?;
         let mut new_path_vec = new_path_slice.to_vec();
         new_path_vec.push(0); // Add null terminator
 
@@ -1118,22 +1265,9 @@ impl PassthroughWasiImpl {
         new_path_ptr: Ptr,
         new_path_len: Size,
     ) -> WasiResult<i32> {
-        let memory_guard = memory.get_memory_direct_access();
-        let memory_base = memory_guard.data.as_ptr();
-
         // Create null-terminated strings for old and new paths
-        let old_path_slice = unsafe {
-            std::slice::from_raw_parts(
-                memory_base.add(old_path_ptr as usize),
-                old_path_len as usize,
-            )
-        };
-        let new_path_slice = unsafe {
-            std::slice::from_raw_parts(
-                memory_base.add(new_path_ptr as usize),
-                new_path_len as usize,
-            )
-        };
+        let old_path_slice = Self::guest_bytes(memory, old_path_ptr, old_path_len)?;
+        let new_path_slice = Self::guest_bytes(memory, new_path_ptr, new_path_len)?;
 
         let mut old_path_cstr = old_path_slice.to_vec();
         old_path_cstr.push(0);
@@ -1161,21 +1295,8 @@ impl PassthroughWasiImpl {
         new_path_ptr: Ptr,
         new_path_len: Size,
     ) -> WasiResult<i32> {
-        let memory_guard = memory.get_memory_direct_access();
-        let memory_base = memory_guard.data.as_ptr();
-
-        let old_path_slice = unsafe {
-            std::slice::from_raw_parts(
-                memory_base.add(old_path_ptr as usize),
-                old_path_len as usize,
-            )
-        };
-        let new_path_slice = unsafe {
-            std::slice::from_raw_parts(
-                memory_base.add(new_path_ptr as usize),
-                new_path_len as usize,
-            )
-        };
+        let old_path_slice = Self::guest_bytes(memory, old_path_ptr, old_path_len)?;
+        let new_path_slice = Self::guest_bytes(memory, new_path_ptr, new_path_len)?;
 
         // null terminate
         let mut old_path_vec = old_path_slice.to_vec();
@@ -1196,11 +1317,9 @@ impl PassthroughWasiImpl {
         flags: u32,
         fd_ptr: Ptr,
     ) -> WasiResult<i32> {
-        let memory_guard = memory.get_memory_direct_access();
-        let memory_base = memory_guard.data.as_ptr();
-
-        let wasi_errno =
-            unsafe { __wasi_sock_accept(fd, flags, memory_base.add(fd_ptr as usize) as *mut u32) };
+        let wasi_errno = unsafe {
+            __wasi_sock_accept(fd, flags, Self::guest_ptr(memory, fd_ptr, 4)? as *mut u32)
+        };
 
         Ok(wasi_errno as i32)
     }
@@ -1215,17 +1334,15 @@ impl PassthroughWasiImpl {
         ro_datalen_ptr: Ptr,
         ro_flags_ptr: Ptr,
     ) -> WasiResult<i32> {
-        let memory_guard = memory.get_memory_direct_access();
-        let memory_base = memory_guard.data.as_ptr();
-
+        let ri_data = Self::guest_iovecs(memory, ri_data_ptr, ri_data_len)?;
         let wasi_errno = unsafe {
             __wasi_sock_recv(
                 fd,
-                memory_base.add(ri_data_ptr as usize) as *const WasiIovec,
+                ri_data.as_ptr(),
                 ri_data_len,
                 ri_flags,
-                memory_base.add(ro_datalen_ptr as usize) as *mut u32,
-                memory_base.add(ro_flags_ptr as usize) as *mut u32,
+                Self::guest_ptr(memory, ro_datalen_ptr, 4)? as *mut u32,
+                Self::guest_ptr(memory, ro_flags_ptr, 4)? as *mut u32,
             )
         };
 
@@ -1241,16 +1358,14 @@ impl PassthroughWasiImpl {
         si_flags: u32,
         so_datalen_ptr: Ptr,
     ) -> WasiResult<i32> {
-        let memory_guard = memory.get_memory_direct_access();
-        let memory_base = memory_guard.data.as_ptr();
-
+        let si_data = Self::guest_iovecs(memory, si_data_ptr, si_data_len)?;
         let wasi_errno = unsafe {
             __wasi_sock_send(
                 fd,
-                memory_base.add(si_data_ptr as usize) as *const WasiIovec,
+                si_data.as_ptr(),
                 si_data_len,
                 si_flags,
-                memory_base.add(so_datalen_ptr as usize) as *mut u32,
+                Self::guest_ptr(memory, so_datalen_ptr, 4)? as *mut u32,
             )
         };
 
@@ -161,6 +161,27 @@ mod tests {
             vec![Val::Num(Num::I32(5)), Val::Num(Num::I64(123))],
         );
         assert_eq!(ret.unwrap().last().unwrap().to_i64().unwrap(), 123);
+        let ret = call_function(
+            &inst,
+            "dispatch",
+            vec![Val::Num(Num::I32(0)), Val::Num(Num::I64(2))],
+        );
+        assert_eq!(
+            ret,
+            Err(chiwawa::error::RuntimeError::IndirectCallTypeMismatch)
+        );
+        let ret = call_function(
+            &inst,
+            "dispatch",
+            vec![Val::Num(Num::I32(32)), Val::Num(Num::I64(2))],
+        );
+        assert_eq!(ret, Err(chiwawa::error::RuntimeError::UndefinedElement));
+        let ret = call_function(
+            &inst,
+            "dispatch",
+            vec![Val::Num(Num::I32(-1)), Val::Num(Num::I64(2))],
+        );
+        assert_eq!(ret, Err(chiwawa::error::RuntimeError::UndefinedElement));
     }
 
     #[test]
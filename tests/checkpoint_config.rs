@@ -0,0 +1,82 @@
+use chiwawa::{
+    error::RuntimeError,
+    execution::migration::{self, CheckpointConfig, CheckpointTrigger},
+    execution::module::*,
+    execution::runtime::Runtime,
+    execution::value::*,
+    parser,
+    structure::module::Module,
+};
+use rustc_hash::FxHashMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::Instant;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_instance(wasm_path: &str) -> Rc<ModuleInst> {
+        let mut module = Module::new("test");
+        let _ = parser::parse_bytecode(&mut module, wasm_path);
+        let imports: ImportObjects = FxHashMap::default();
+        ModuleInst::new(&module, imports, Vec::new()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_triggers() {
+        assert_eq!(
+            "file:/tmp/guest-a.trigger".parse(),
+            Ok(CheckpointTrigger::File(PathBuf::from(
+                "/tmp/guest-a.trigger"
+            )))
+        );
+        assert_eq!("fd:7".parse(), Ok(CheckpointTrigger::Fd(7)));
+        let before = Instant::now();
+        match "after:2.5".parse::<CheckpointTrigger>() {
+            Ok(CheckpointTrigger::Deadline(deadline)) => {
+                assert!(deadline >= before + std::time::Duration::from_millis(2500))
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!("fd:x".parse::<CheckpointTrigger>().is_err());
+        assert!("after:-1".parse::<CheckpointTrigger>().is_err());
+        assert!("signal:USR1".parse::<CheckpointTrigger>().is_err());
+        assert!("checkpoint.trigger".parse::<CheckpointTrigger>().is_err());
+    }
+
+    #[test]
+    fn test_deadline_writes_configured_file() {
+        let path = "temp_deadline_checkpoint.bin";
+        let inst = load_instance("tests/wasm/steps.wasm");
+        let func_addr = inst.get_export_func("count-to").unwrap();
+        let params = vec![Val::Num(Num::I32(20000))];
+        let mut runtime = Runtime::new(Rc::clone(&inst), &func_addr, params, false, true).unwrap();
+        runtime.set_checkpoint_config(CheckpointConfig {
+            output_path: PathBuf::from(path),
+            triggers: vec![CheckpointTrigger::Deadline(Instant::now())],
+        });
+
+        assert_eq!(runtime.run(), Err(RuntimeError::CheckpointRequested));
+        assert!(Path::new(path).exists());
+        assert!(!Path::new(migration::DEFAULT_CHECKPOINT_FILE).exists());
+        let partial = inst.global_addrs[0].get().to_i32().unwrap();
+        assert!(partial < 20000);
+
+        // The expired deadline is disarmed, so the runtime can finish
+        let ret = runtime.resume().unwrap();
+        assert_eq!(ret.last().unwrap().to_i32().unwrap(), 20000);
+
+        let restored_inst = load_instance("tests/wasm/steps.wasm");
+        let stacks = migration::restore(&[Rc::clone(&restored_inst)], path).unwrap();
+        assert_eq!(
+            restored_inst.global_addrs[0].get().to_i32().unwrap(),
+            partial
+        );
+        let mut runtime = Runtime::new_restored(Rc::clone(&restored_inst), stacks, false, false);
+        let ret = runtime.run().unwrap();
+        assert_eq!(ret.last().unwrap().to_i32().unwrap(), 20000);
+
+        let _ = std::fs::remove_file(path);
+    }
+}
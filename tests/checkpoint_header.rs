@@ -0,0 +1,138 @@
+use chiwawa::{
+    error::RuntimeError,
+    execution::migration::{self, CheckpointHeader, CHECKPOINT_FORMAT_VERSION, CHECKPOINT_MAGIC},
+    execution::module::*,
+    execution::runtime::{RunStatus, Runtime},
+    execution::value::*,
+    parser,
+    structure::module::Module,
+};
+use rustc_hash::FxHashMap;
+use std::rc::Rc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instantiate(bytes: &[u8]) -> Rc<ModuleInst> {
+        let mut module = Module::new("test");
+        parser::parse_bytes(&mut module, bytes).unwrap();
+        let imports: ImportObjects = FxHashMap::default();
+        ModuleInst::new(&module, imports, Vec::new()).unwrap()
+    }
+
+    fn steps_wasm() -> Vec<u8> {
+        std::fs::read("tests/wasm/steps.wasm").unwrap()
+    }
+
+    /// Checkpoint of `count-to 500` paused after 1000 instructions.
+    fn paused_snapshot() -> (Rc<ModuleInst>, Vec<u8>) {
+        let inst = instantiate(&steps_wasm());
+        let func_addr = inst.get_export_func("count-to").unwrap();
+        let params = vec![Val::Num(Num::I32(500))];
+        let mut runtime = Runtime::new(Rc::clone(&inst), &func_addr, params, false, false).unwrap();
+        assert_eq!(runtime.run_for(1000).unwrap(), RunStatus::Paused);
+        let bytes = runtime.snapshot().unwrap();
+        (inst, bytes)
+    }
+
+    /// Re-encodes `bytes` with the header changed by `edit`.
+    fn with_header(bytes: &[u8], edit: impl FnOnce(&mut CheckpointHeader)) -> Vec<u8> {
+        let (mut header, state) = migration::read_checkpoint_header(bytes).unwrap();
+        edit(&mut header);
+        let mut encoded = bytes[..CHECKPOINT_MAGIC.len() + 4].to_vec();
+        encoded.extend(bincode::serialize(&header).unwrap());
+        encoded.extend_from_slice(state);
+        encoded
+    }
+
+    fn load_error(result: Result<Runtime, RuntimeError>) -> String {
+        match result {
+            Err(RuntimeError::CheckpointLoadError(message)) => message,
+            Err(e) => panic!("expected CheckpointLoadError, got {:?}", e),
+            Ok(_) => panic!("expected CheckpointLoadError, got a runtime"),
+        }
+    }
+
+    #[test]
+    fn test_header_contents() {
+        let (inst, bytes) = paused_snapshot();
+        assert_eq!(bytes[..CHECKPOINT_MAGIC.len()], CHECKPOINT_MAGIC);
+        let (header, _) = migration::read_checkpoint_header(&bytes).unwrap();
+        assert_eq!(header.chiwawa_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(
+            header.features.contains(&"tco".to_string()),
+            cfg!(feature = "tco")
+        );
+        assert_ne!(inst.fingerprint, 0);
+        assert_eq!(header.module_fingerprints, vec![inst.fingerprint]);
+    }
+
+    #[test]
+    fn test_restore_into_same_module_from_text() {
+        let (_, bytes) = paused_snapshot();
+        let text = std::fs::read("tests/wasm/steps.wat").unwrap();
+        let inst = instantiate(&text);
+        let mut runtime = Runtime::from_snapshot(inst, &bytes).unwrap();
+        let ret = runtime.run().unwrap();
+        assert_eq!(ret.last().unwrap().to_i32().unwrap(), 500);
+    }
+
+    #[test]
+    fn test_refuses_other_module() {
+        let (_, bytes) = paused_snapshot();
+        let mut text = std::fs::read_to_string("tests/wasm/steps.wat").unwrap();
+        text.insert_str(text.rfind(')').unwrap(), "(func (export \"extra\"))\n");
+        let other = instantiate(text.as_bytes());
+        let message = load_error(Runtime::from_snapshot(Rc::clone(&other), &bytes));
+        assert!(message.contains("different module"), "{}", message);
+        // Nothing was applied to the instance
+        assert_eq!(other.global_addrs[0].get().to_i32().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_refuses_bad_magic_and_version() {
+        let (_, bytes) = paused_snapshot();
+        let inst = instantiate(&steps_wasm());
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] ^= 0xff;
+        let message = load_error(Runtime::from_snapshot(Rc::clone(&inst), &bad_magic));
+        assert!(message.contains("magic"), "{}", message);
+        let message = load_error(Runtime::from_snapshot(Rc::clone(&inst), &bytes[..4]));
+        assert!(message.contains("magic"), "{}", message);
+
+        let mut bad_version = bytes.clone();
+        let offset = CHECKPOINT_MAGIC.len();
+        bad_version[offset..offset + 4]
+            .copy_from_slice(&(CHECKPOINT_FORMAT_VERSION + 1).to_le_bytes());
+        let message = load_error(Runtime::from_snapshot(inst, &bad_version));
+        assert!(message.contains("format version"), "{}", message);
+    }
+
+    #[test]
+    fn test_refuses_other_build() {
+        let (_, bytes) = paused_snapshot();
+        let inst = instantiate(&steps_wasm());
+
+        let other_version = with_header(&bytes, |h| h.chiwawa_version = "0.0.0-other".into());
+        let message = load_error(Runtime::from_snapshot(Rc::clone(&inst), &other_version));
+        assert!(message.contains("0.0.0-other"), "{}", message);
+
+        let other_ir = with_header(&bytes, |h| h.ir_layout_version += 1);
+        let message = load_error(Runtime::from_snapshot(Rc::clone(&inst), &other_ir));
+        assert!(message.contains("IR layout"), "{}", message);
+
+        let other_features = with_header(&bytes, |h| h.features = vec!["other".into()]);
+        let message = load_error(Runtime::from_snapshot(Rc::clone(&inst), &other_features));
+        assert!(message.contains("features"), "{}", message);
+
+        match migration::restore_from_bytes(&[Rc::clone(&inst), inst], &bytes) {
+            Err(RuntimeError::CheckpointLoadError(message)) => {
+                assert!(message.contains("instances"), "{}", message)
+            }
+            Err(e) => panic!("expected CheckpointLoadError, got {:?}", e),
+            Ok(_) => panic!("expected CheckpointLoadError, got restored stacks"),
+        }
+    }
+}
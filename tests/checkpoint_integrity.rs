@@ -0,0 +1,193 @@
+use chiwawa::{
+    error::RuntimeError,
+    execution::migration::{self, Section, SectionKind, CHECKPOINT_MAGIC},
+    execution::module::*,
+    execution::runtime::{RunStatus, Runtime},
+    execution::value::*,
+    parser,
+    structure::module::Module,
+};
+use rustc_hash::FxHashMap;
+use std::path::Path;
+use std::rc::Rc;
+use twox_hash::XxHash64;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_instance() -> Rc<ModuleInst> {
+        let mut module = Module::new("test");
+        let _ = parser::parse_bytecode(&mut module, "tests/wasm/fill.wasm");
+        let imports: ImportObjects = FxHashMap::default();
+        ModuleInst::new(&module, imports, Vec::new()).unwrap()
+    }
+
+    /// Runtime for `fill 400` paused after 2000 instructions.
+    fn paused_runtime() -> Runtime {
+        let inst = load_instance();
+        let func_addr = inst.get_export_func("fill").unwrap();
+        let params = vec![Val::Num(Num::I32(400))];
+        let mut runtime = Runtime::new(inst, &func_addr, params, false, false).unwrap();
+        assert_eq!(runtime.run_for(2000).unwrap(), RunStatus::Paused);
+        runtime
+    }
+
+    /// Splits an image into its prefix (magic, version, header) and sections.
+    fn split(bytes: &[u8]) -> (Vec<u8>, Vec<Section>) {
+        let (_, rest) = migration::read_checkpoint_header(bytes).unwrap();
+        let prefix = bytes[..bytes.len() - rest.len()].to_vec();
+        (prefix, bincode::deserialize(rest).unwrap())
+    }
+
+    fn join(mut prefix: Vec<u8>, sections: &[Section]) -> Vec<u8> {
+        prefix.extend(bincode::serialize(sections).unwrap());
+        prefix
+    }
+
+    fn assert_untouched(inst: &Rc<ModuleInst>) {
+        assert_eq!(inst.global_addrs[0].get().to_i32().unwrap(), 0);
+        assert!(inst.mem_addrs[0].get_data().iter().all(|&b| b == 0));
+    }
+
+    fn load_error(inst: &Rc<ModuleInst>, bytes: &[u8]) -> String {
+        match Runtime::from_snapshot(Rc::clone(inst), bytes) {
+            Err(RuntimeError::CheckpointLoadError(message)) => message,
+            Err(e) => panic!("expected CheckpointLoadError, got {:?}", e),
+            Ok(_) => panic!("expected CheckpointLoadError, got a runtime"),
+        }
+    }
+
+    #[test]
+    fn test_sections_round_trip() {
+        let bytes = paused_runtime().snapshot().unwrap();
+        let (_, sections) = split(&bytes);
+        let kinds: Vec<SectionKind> = sections.iter().map(|section| section.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                SectionKind::Frames,
+                SectionKind::RegFile,
+                SectionKind::Memory { memory: 0 },
+                SectionKind::Globals,
+                SectionKind::Layout { instance: 0 },
+                SectionKind::Externs,
+            ]
+        );
+
+        let inst = load_instance();
+        let mut runtime = Runtime::from_snapshot(inst, &bytes).unwrap();
+        let ret = runtime.run().unwrap();
+        assert_eq!(ret.last().unwrap().to_i32().unwrap(), 399);
+    }
+
+    #[test]
+    fn test_bit_flip_in_each_section_is_detected() {
+        let bytes = paused_runtime().snapshot().unwrap();
+        let (prefix, sections) = split(&bytes);
+        for idx in 0..sections.len() {
+            let (_, mut corrupted) = split(&bytes);
+            let data = &mut corrupted[idx].data;
+            let mid = data.len() / 2;
+            data[mid] ^= 0x01;
+
+            let inst = load_instance();
+            let message = load_error(&inst, &join(prefix.clone(), &corrupted));
+            assert_eq!(
+                message,
+                format!("Checksum mismatch in section {:?}", sections[idx].kind)
+            );
+            assert_untouched(&inst);
+        }
+    }
+
+    #[test]
+    fn test_truncated_and_padded_images() {
+        let bytes = paused_runtime().snapshot().unwrap();
+        let inst = load_instance();
+
+        for len in [bytes.len() - 1, bytes.len() / 2, CHECKPOINT_MAGIC.len() + 8] {
+            let message = load_error(&inst, &bytes[..len]);
+            assert!(
+                message.contains("Truncated") || message.contains("header"),
+                "{}",
+                message
+            );
+        }
+
+        let mut padded = bytes.clone();
+        padded.push(0);
+        let message = load_error(&inst, &padded);
+        assert!(message.contains("trailing"), "{}", message);
+        assert_untouched(&inst);
+    }
+
+    #[test]
+    fn test_missing_section() {
+        let bytes = paused_runtime().snapshot().unwrap();
+        let (prefix, mut sections) = split(&bytes);
+        sections.retain(|section| !matches!(section.kind, SectionKind::Globals));
+        let inst = load_instance();
+        let message = load_error(&inst, &join(prefix, &sections));
+        assert!(message.contains("Missing section Globals"), "{}", message);
+        assert_untouched(&inst);
+    }
+
+    #[test]
+    fn test_global_mismatch_is_refused_before_restoring_memory() {
+        let bytes = paused_runtime().snapshot().unwrap();
+        let (prefix, sections) = split(&bytes);
+        let globals_idx = sections
+            .iter()
+            .position(|section| matches!(section.kind, SectionKind::Globals))
+            .unwrap();
+        let globals: Vec<Val> = bincode::deserialize(&sections[globals_idx].data).unwrap();
+
+        let wrong_type: Vec<Val> = globals
+            .iter()
+            .map(|value| match value {
+                Val::Num(Num::F64(_)) => Val::Num(Num::I32(0)),
+                _ => Val::Num(Num::F64(0.5)),
+            })
+            .collect();
+        let mut extra = globals.clone();
+        extra.push(Val::Num(Num::I32(0)));
+        for (values, expected) in [(wrong_type, "has type"), (extra, "global variable count")] {
+            let (_, mut tampered) = split(&bytes);
+            let data = bincode::serialize(&values).unwrap();
+            tampered[globals_idx].checksum = XxHash64::oneshot(0, &data);
+            tampered[globals_idx].data = data;
+
+            let inst = load_instance();
+            let message = load_error(&inst, &join(prefix.clone(), &tampered));
+            assert!(message.contains(expected), "{}", message);
+            assert_untouched(&inst);
+        }
+    }
+
+    #[test]
+    fn test_checkpoint_replaces_file_atomically() {
+        let path = "temp_integrity_checkpoint.bin";
+        std::fs::write(path, b"previous checkpoint").unwrap();
+
+        let runtime = paused_runtime();
+        runtime.checkpoint(path).unwrap();
+        assert!(!Path::new("temp_integrity_checkpoint.bin.tmp").exists());
+        let inst = load_instance();
+        let stacks = migration::restore(&[Rc::clone(&inst)], path).unwrap();
+        let ret = Runtime::new_restored(inst, stacks, false, false)
+            .run()
+            .unwrap();
+        assert_eq!(ret.last().unwrap().to_i32().unwrap(), 399);
+
+        // A failed write leaves neither the checkpoint nor a temporary file
+        let missing_dir = "temp_integrity_missing_dir/checkpoint.bin";
+        assert!(matches!(
+            runtime.checkpoint(missing_dir),
+            Err(RuntimeError::CheckpointSaveError(_))
+        ));
+        assert!(!Path::new(missing_dir).exists());
+
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,139 @@
+use chiwawa::{
+    error::RuntimeError,
+    execution::func::FuncAddr,
+    execution::host::Caller,
+    execution::linker::Linker,
+    execution::module::*,
+    execution::runtime::{RunStatus, Runtime},
+    execution::value::*,
+    parser,
+    structure::module::Module,
+};
+use std::rc::Rc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instantiate() -> Rc<ModuleInst> {
+        let mut module = Module::new("test");
+        let _ = parser::parse_bytecode(&mut module, "tests/wasm/refs.wasm");
+        let mut linker = Linker::new();
+        linker.func_wrap("env", "yield", |caller: &Caller| {
+            caller.request_checkpoint()
+        });
+        linker
+            .instantiate_without_start("refs", &module, Vec::new())
+            .unwrap()
+    }
+
+    /// Runs `name` up to its yield and returns a snapshot taken there.
+    fn snapshot_at_yield(inst: &Rc<ModuleInst>, name: &str, params: Vec<Val>) -> Runtime {
+        let func_addr = inst.get_export_func(name).unwrap();
+        let mut runtime = Runtime::new(Rc::clone(inst), &func_addr, params, false, true).unwrap();
+        assert_eq!(
+            runtime.run_for(u64::MAX).unwrap(),
+            RunStatus::CheckpointRequested
+        );
+        runtime
+    }
+
+    fn func_ref(val: &Val) -> FuncAddr {
+        match val {
+            Val::Ref(Ref::FuncAddr(func_addr)) => func_addr.clone(),
+            other => panic!("expected a funcref, got {:?}", other),
+        }
+    }
+
+    fn extern_ref(val: &Val) -> ExternAddr {
+        match val {
+            Val::Ref(Ref::RefExtern(extern_addr)) => extern_addr.clone(),
+            other => panic!("expected an externref, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_funcref_survives_restore() {
+        for (which, expected) in [(0, 20), (1, 50)] {
+            let inst = instantiate();
+            let params = vec![Val::Num(Num::I32(5)), Val::Num(Num::I32(which))];
+            let bytes = snapshot_at_yield(&inst, "apply", params)
+                .snapshot()
+                .unwrap();
+
+            let restored_inst = instantiate();
+            let mut restored = Runtime::from_snapshot(Rc::clone(&restored_inst), &bytes).unwrap();
+            let ret = restored.run().unwrap();
+            assert_eq!(ret.last().unwrap().to_i32().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_funcref_local_in_callee() {
+        for (which, expected) in [(0, 10), (1, 25)] {
+            let inst = instantiate();
+            let params = vec![Val::Num(Num::I32(5)), Val::Num(Num::I32(which))];
+            let mut runtime = snapshot_at_yield(&inst, "apply-in-callee", params);
+            let bytes = runtime.snapshot().unwrap();
+            let ret = runtime.resume().unwrap();
+            assert_eq!(ret.last().unwrap().to_i32().unwrap(), expected);
+
+            let restored_inst = instantiate();
+            let mut restored = Runtime::from_snapshot(Rc::clone(&restored_inst), &bytes).unwrap();
+            let ret = restored.run().unwrap();
+            assert_eq!(ret.last().unwrap().to_i32().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_funcref_global_rebinds_to_restored_instance() {
+        let inst = instantiate();
+        let double = inst.get_export_func("double").unwrap();
+        inst.global_addrs[0]
+            .set(Val::Ref(Ref::FuncAddr(double)))
+            .unwrap();
+        let params = vec![Val::Num(Num::I32(1)), Val::Num(Num::I32(0))];
+        let bytes = snapshot_at_yield(&inst, "apply", params)
+            .snapshot()
+            .unwrap();
+
+        // A fresh instance starts with `$square` in the global
+        let restored_inst = instantiate();
+        Runtime::from_snapshot(Rc::clone(&restored_inst), &bytes).unwrap();
+        let restored_double = restored_inst.get_export_func("double").unwrap();
+        assert!(func_ref(&restored_inst.global_addrs[0].get()).ptr_eq(&restored_double));
+    }
+
+    #[test]
+    fn test_externref_rebinds_to_restored_instance() {
+        let inst = instantiate();
+        let double = inst.get_export_func("double").unwrap();
+        let handle = ExternAddr::new(Externval::Func(double));
+        let params = vec![Val::Ref(Ref::RefExtern(handle))];
+        let bytes = snapshot_at_yield(&inst, "keep", params).snapshot().unwrap();
+
+        let restored_inst = instantiate();
+        let mut restored = Runtime::from_snapshot(Rc::clone(&restored_inst), &bytes).unwrap();
+        let ret = restored.run().unwrap();
+        assert_eq!(ret.len(), 2);
+        let held = extern_ref(&ret[0]);
+        assert!(held.ptr_eq(&extern_ref(&ret[1])));
+        let restored_double = restored_inst.get_export_func("double").unwrap();
+        match held.externval() {
+            Externval::Func(func_addr) => assert!(func_addr.ptr_eq(&restored_double)),
+            other => panic!("expected a function, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_externref_outside_instances_is_refused() {
+        let inst = instantiate();
+        let handle = ExternAddr::new(Externval::Func(FuncAddr::alloc_empty()));
+        let params = vec![Val::Ref(Ref::RefExtern(handle))];
+        let runtime = snapshot_at_yield(&inst, "keep", params);
+        assert!(matches!(
+            runtime.snapshot(),
+            Err(RuntimeError::SerializationError(_))
+        ));
+    }
+}
@@ -0,0 +1,124 @@
+use chiwawa::{
+    execution::host::Caller,
+    execution::linker::Linker,
+    execution::migration::{self, Section, SectionKind, TableDiff},
+    execution::module::*,
+    execution::runtime::{RunStatus, Runtime},
+    execution::value::*,
+    parser,
+    structure::module::Module,
+};
+use std::rc::Rc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instantiate() -> Rc<ModuleInst> {
+        let mut module = Module::new("test");
+        let _ = parser::parse_bytecode(&mut module, "tests/wasm/tables.wasm");
+        let mut linker = Linker::new();
+        linker.func_wrap("env", "yield", |caller: &Caller| {
+            caller.request_checkpoint()
+        });
+        linker
+            .instantiate_without_start("tables", &module, Vec::new())
+            .unwrap()
+    }
+
+    fn mutate(inst: &Rc<ModuleInst>) -> Runtime {
+        let func_addr = inst.get_export_func("mutate").unwrap();
+        let params = vec![Val::Num(Num::I32(3))];
+        Runtime::new(Rc::clone(inst), &func_addr, params, false, true).unwrap()
+    }
+
+    fn table_sections(bytes: &[u8]) -> Vec<Section> {
+        let (_, rest) = migration::read_checkpoint_header(bytes).unwrap();
+        let sections: Vec<Section> = bincode::deserialize(rest).unwrap();
+        sections
+            .into_iter()
+            .filter(|section| matches!(section.kind, SectionKind::Table { .. }))
+            .collect()
+    }
+
+    #[test]
+    fn test_unmodified_table_is_not_stored() {
+        let inst = instantiate();
+        let mut runtime = mutate(&inst);
+        assert_eq!(runtime.run_for(1).unwrap(), RunStatus::Paused);
+        assert!(inst.table_addrs[0].diff().is_none());
+        assert!(table_sections(&runtime.snapshot().unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_diff_covers_changed_and_grown_elements() {
+        let inst = instantiate();
+        let mut runtime = mutate(&inst);
+        assert_eq!(
+            runtime.run_for(u64::MAX).unwrap(),
+            RunStatus::CheckpointRequested
+        );
+        let table = &inst.table_addrs[0];
+        let square = table.get(1).unwrap();
+        assert_eq!(
+            table.diff(),
+            Some(TableDiff {
+                size: 6,
+                runs: vec![
+                    (0, vec![Val::Ref(Ref::RefNull)]),
+                    (3, vec![square.clone(), square.clone(), square]),
+                ],
+            })
+        );
+        assert_eq!(table_sections(&runtime.snapshot().unwrap()).len(), 1);
+    }
+
+    #[test]
+    fn test_importing_table_keeps_owner_baseline() {
+        let inst = instantiate();
+        let mut runtime = mutate(&inst);
+        assert_eq!(
+            runtime.run_for(u64::MAX).unwrap(),
+            RunStatus::CheckpointRequested
+        );
+        let table = &inst.table_addrs[0];
+        let diff = table.diff();
+        assert!(diff.is_some());
+
+        // Instantiating a module that imports the table must not reset the
+        // baseline of the instance that defines it
+        let mut module = Module::new("test");
+        let _ = parser::parse_bytecode(&mut module, "tests/wasm/table_importer.wasm");
+        let mut linker = Linker::new();
+        linker.define("tables", "table", Externval::Table(table.clone()));
+        linker
+            .instantiate_without_start("importer", &module, Vec::new())
+            .unwrap();
+        assert_eq!(table.diff(), diff);
+    }
+
+    #[test]
+    fn test_modified_table_survives_restore() {
+        let inst = instantiate();
+        let mut runtime = mutate(&inst);
+        assert_eq!(
+            runtime.run_for(u64::MAX).unwrap(),
+            RunStatus::CheckpointRequested
+        );
+        let bytes = runtime.snapshot().unwrap();
+
+        let restored_inst = instantiate();
+        let mut restored = Runtime::from_snapshot(Rc::clone(&restored_inst), &bytes).unwrap();
+        let table = &restored_inst.table_addrs[0];
+        assert_eq!(table.size(), 6);
+        assert_eq!(table.get(0).unwrap(), Val::Ref(Ref::RefNull));
+        let square = table.get(1).unwrap();
+        for i in 3..6 {
+            assert_eq!(table.get(i).unwrap(), square);
+        }
+        assert!(table.diff().is_some());
+
+        let ret = restored.run().unwrap();
+        assert_eq!(ret.last().unwrap().to_i32().unwrap(), 15);
+    }
+}
@@ -0,0 +1,207 @@
+use chiwawa::{
+    error::RuntimeError,
+    execution::host::Caller,
+    execution::linker::Linker,
+    execution::migration::{self, Section, SectionKind},
+    execution::module::*,
+    execution::runtime::{RunStatus, Runtime},
+    execution::value::*,
+    parser,
+    structure::module::Module,
+};
+use std::rc::Rc;
+use twox_hash::XxHash64;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = "tests/testdir/fd_state.txt";
+
+    fn instantiate() -> Rc<ModuleInst> {
+        let mut module = Module::new("test");
+        let _ = parser::parse_bytecode(&mut module, "tests/wasm/fd_state.wasm");
+        let mut linker = Linker::new();
+        linker.func_wrap("env", "yield", |caller: &Caller| {
+            caller.request_checkpoint()
+        });
+        linker
+            .instantiate_without_start("fd_state", &module, Vec::new())
+            .unwrap()
+    }
+
+    /// Runs the guest up to its yield, with the file open at offset 2.
+    fn run_to_yield(inst: &Rc<ModuleInst>) -> Runtime {
+        std::fs::write(FILE, b"abcdefgh").unwrap();
+        let func_addr = inst.get_export_func("run").unwrap();
+        let mut runtime =
+            Runtime::new(Rc::clone(inst), &func_addr, Vec::new(), false, true).unwrap();
+        assert_eq!(
+            runtime.run_for(u64::MAX).unwrap(),
+            RunStatus::CheckpointRequested
+        );
+        runtime
+    }
+
+    fn guest_fd(inst: &Rc<ModuleInst>) -> i32 {
+        inst.global_addrs[0].get().to_i32().unwrap()
+    }
+
+    /// Opens the file with the fd stored at `ptr`, returning the errno.
+    fn open_at(inst: &Rc<ModuleInst>, ptr: i32) -> Result<i32, RuntimeError> {
+        let func_addr = inst.get_export_func("open-at").unwrap();
+        let params = vec![Val::Num(Num::I32(ptr))];
+        let mut runtime = Runtime::new(Rc::clone(inst), &func_addr, params, false, false)?;
+        runtime.run()?.last().unwrap().to_i32()
+    }
+
+    #[test]
+    fn test_open_fd_is_reopened_at_same_number_and_offset() {
+        let inst = instantiate();
+        let runtime = run_to_yield(&inst);
+        let fd = guest_fd(&inst);
+        let wasi = inst.wasi_impl.as_ref().unwrap();
+        let state = wasi.save_state();
+        assert_eq!(state.fds.len(), 1);
+        assert_eq!(state.fds[0].fd, fd as u32);
+        assert_eq!(state.fds[0].path, FILE);
+        assert_eq!(state.fds[0].offset, Some(2));
+        let bytes = runtime.snapshot().unwrap();
+
+        // Free the fd on this host, leaving a lower fd free as well, so the
+        // reopened file has to be renumbered into place
+        assert_eq!(wasi.fd_close(fd).unwrap(), 0);
+
+        let restored_inst = instantiate();
+        let mut restored = Runtime::from_snapshot(Rc::clone(&restored_inst), &bytes).unwrap();
+        let restored_state = restored_inst.wasi_impl.as_ref().unwrap().save_state();
+        assert_eq!(restored_state.fds, state.fds);
+
+        let ret = restored.run().unwrap();
+        assert_eq!(ret.last().unwrap().to_i32().unwrap(), 0x66656463);
+        std::fs::remove_file(FILE).unwrap();
+    }
+
+    #[test]
+    fn test_missing_file_is_refused() {
+        let inst = instantiate();
+        let runtime = run_to_yield(&inst);
+        let fd = guest_fd(&inst);
+        let bytes = runtime.snapshot().unwrap();
+        assert_eq!(inst.wasi_impl.as_ref().unwrap().fd_close(fd).unwrap(), 0);
+        std::fs::remove_file(FILE).unwrap();
+
+        let restored_inst = instantiate();
+        let result = Runtime::from_snapshot(Rc::clone(&restored_inst), &bytes);
+        assert!(matches!(result, Err(RuntimeError::CheckpointLoadError(_))));
+        assert_eq!(guest_fd(&restored_inst), -1);
+        assert!(restored_inst
+            .wasi_impl
+            .as_ref()
+            .unwrap()
+            .save_state()
+            .fds
+            .is_empty());
+    }
+
+    #[test]
+    fn test_failed_restore_rolls_back_earlier_instances() {
+        let first = instantiate();
+        let second = instantiate();
+        let mut runtime = run_to_yield(&first);
+        run_to_yield(&second);
+        runtime.set_linked_instances(vec![Rc::clone(&second)]);
+        let bytes = runtime.snapshot().unwrap();
+        let (first_fd, second_fd) = (guest_fd(&first), guest_fd(&second));
+        assert!(first_fd < second_fd);
+        for inst in [&first, &second] {
+            let wasi = inst.wasi_impl.as_ref().unwrap();
+            assert_eq!(wasi.fd_close(guest_fd(inst)).unwrap(), 0);
+        }
+
+        // Occupy the second instance's fd so that only the first one reopens
+        let wasi = first.wasi_impl.as_ref().unwrap();
+        let mut placeholders = Vec::new();
+        while placeholders.last() != Some(&second_fd) {
+            assert_eq!(open_at(&first, 96).unwrap(), 0);
+            placeholders.push(first.mem_addrs[0].load::<i32>(0, 96).unwrap());
+        }
+        for &fd in &placeholders[..placeholders.len() - 1] {
+            assert_eq!(wasi.fd_close(fd).unwrap(), 0);
+        }
+
+        let restored = [instantiate(), instantiate()];
+        let result = migration::restore_from_bytes(&restored, &bytes);
+        assert!(matches!(result, Err(RuntimeError::CheckpointLoadError(_))));
+        let restored_wasi = restored[0].wasi_impl.as_ref().unwrap();
+        assert!(restored_wasi.save_state().fds.is_empty());
+
+        // The fd reopened for the first instance was closed again
+        assert_eq!(open_at(&first, 96).unwrap(), 0);
+        let fd = first.mem_addrs[0].load::<i32>(0, 96).unwrap();
+        assert!(fd <= first_fd);
+        assert_eq!(wasi.fd_close(fd).unwrap(), 0);
+        assert_eq!(wasi.fd_close(second_fd).unwrap(), 0);
+        std::fs::remove_file(FILE).unwrap();
+    }
+
+    #[test]
+    fn test_global_mismatch_does_not_reopen_fds() {
+        let inst = instantiate();
+        let runtime = run_to_yield(&inst);
+        let fd = guest_fd(&inst);
+        let bytes = runtime.snapshot().unwrap();
+        let wasi = inst.wasi_impl.as_ref().unwrap();
+        assert_eq!(wasi.fd_close(fd).unwrap(), 0);
+
+        // Turn the i32 fd global into an i64, keeping the checksum valid
+        let (_, rest) = migration::read_checkpoint_header(&bytes).unwrap();
+        let mut tampered = bytes[..bytes.len() - rest.len()].to_vec();
+        let mut sections: Vec<Section> = bincode::deserialize(rest).unwrap();
+        let globals = sections
+            .iter_mut()
+            .find(|section| section.kind == SectionKind::Globals)
+            .unwrap();
+        let mut values: Vec<Val> = bincode::deserialize(&globals.data).unwrap();
+        values[0] = Val::Num(Num::I64(fd as i64));
+        globals.data = bincode::serialize(&values).unwrap();
+        globals.checksum = XxHash64::oneshot(0, &globals.data);
+        tampered.extend(bincode::serialize(&sections).unwrap());
+
+        let restored_inst = instantiate();
+        let result = Runtime::from_snapshot(Rc::clone(&restored_inst), &tampered);
+        assert!(matches!(result, Err(RuntimeError::CheckpointLoadError(_))));
+        let restored_wasi = restored_inst.wasi_impl.as_ref().unwrap();
+        assert!(restored_wasi.save_state().fds.is_empty());
+
+        // No descriptor was left open on this host
+        assert_eq!(open_at(&inst, 96).unwrap(), 0);
+        let reopened = inst.mem_addrs[0].load::<i32>(0, 96).unwrap();
+        assert!(reopened <= fd);
+        assert_eq!(wasi.fd_close(reopened).unwrap(), 0);
+        std::fs::remove_file(FILE).unwrap();
+    }
+
+    #[test]
+    fn test_path_open_fault_does_not_leak_fd() {
+        let inst = instantiate();
+        std::fs::write(FILE, b"abcdefgh").unwrap();
+        let wasi = inst.wasi_impl.as_ref().unwrap();
+        assert_eq!(open_at(&inst, 96).unwrap(), 0);
+        let fd = inst.mem_addrs[0].load::<i32>(0, 96).unwrap();
+        assert_eq!(wasi.fd_close(fd).unwrap(), 0);
+
+        // The result pointer is out of bounds, so the guest never sees the fd
+        assert!(matches!(
+            open_at(&inst, 0x10000),
+            Err(RuntimeError::ExecutionFailed(_))
+        ));
+        assert!(wasi.save_state().fds.is_empty());
+
+        // The fd was closed, so the next open gets the same number back
+        assert_eq!(open_at(&inst, 96).unwrap(), 0);
+        assert_eq!(inst.mem_addrs[0].load::<i32>(0, 96).unwrap(), fd);
+        assert_eq!(wasi.fd_close(fd).unwrap(), 0);
+        std::fs::remove_file(FILE).unwrap();
+    }
+}
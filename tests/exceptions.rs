@@ -0,0 +1,132 @@
+use chiwawa::{
+    error::RuntimeError, execution::module::*, execution::runtime::Runtime, execution::value::*,
+    parser, structure::module::Module,
+};
+use rustc_hash::FxHashMap;
+use std::rc::Rc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_instance(wasm_path: &str) -> Rc<ModuleInst> {
+        let mut module = Module::new("test");
+        let _ = parser::parse_bytecode(&mut module, wasm_path);
+        let imports: ImportObjects = FxHashMap::default();
+        ModuleInst::new(&module, imports, Vec::new()).unwrap()
+    }
+
+    fn load_instance_with_tag(
+        wasm_path: &str,
+        tag: Externval,
+    ) -> Result<Rc<ModuleInst>, RuntimeError> {
+        let mut module = Module::new("test");
+        let _ = parser::parse_bytecode(&mut module, wasm_path);
+        let mut env = FxHashMap::default();
+        env.insert("e0".to_string(), tag);
+        let mut imports: ImportObjects = FxHashMap::default();
+        imports.insert("env".to_string(), env);
+        ModuleInst::new(&module, imports, Vec::new())
+    }
+
+    fn exported(inst: &Rc<ModuleInst>, name: &str) -> Externval {
+        inst.exports
+            .iter()
+            .find(|export| export.name == name)
+            .map(|export| export.value.clone())
+            .unwrap()
+    }
+
+    fn call_function(
+        inst: &Rc<ModuleInst>,
+        func_name: &str,
+        params: Vec<Val>,
+    ) -> Result<Vec<Val>, RuntimeError> {
+        let func_addr = inst.get_export_func(func_name)?;
+        let mut runtime = Runtime::new(Rc::clone(inst), &func_addr, params, true, false)?;
+        runtime.run()
+    }
+
+    #[test]
+    fn test_catch_payload() {
+        let inst = load_instance("tests/wasm/exceptions.wasm");
+        let ret = call_function(&inst, "catch-local", vec![Val::Num(Num::I32(42))]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 42);
+        let ret = call_function(&inst, "catch-multi", vec![]).unwrap();
+        assert_eq!(ret[0].to_i64().unwrap(), 0x100000000);
+        assert_eq!(ret[1].to_i32().unwrap(), 42);
+    }
+
+    #[test]
+    fn test_try_table_without_throw() {
+        let inst = load_instance("tests/wasm/exceptions.wasm");
+        let ret = call_function(&inst, "catch-no-throw", vec![Val::Num(Num::I32(4))]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 104);
+        let ret = call_function(&inst, "catch-no-throw", vec![Val::Num(Num::I32(5))]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 5);
+    }
+
+    #[test]
+    fn test_catch_across_frames() {
+        let inst = load_instance("tests/wasm/exceptions.wasm");
+        let ret = call_function(&inst, "catch-across-frames", vec![Val::Num(Num::I32(9))]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 9);
+        let ret = call_function(&inst, "catch-nested", vec![]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_catch_all() {
+        let inst = load_instance("tests/wasm/exceptions.wasm");
+        let ret = call_function(&inst, "catch-all", vec![]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_catch_ref_and_throw_ref() {
+        let inst = load_instance("tests/wasm/exceptions.wasm");
+        let ret = call_function(&inst, "catch-ref-rethrow", vec![]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 12);
+        let ret = call_function(&inst, "catch-all-ref-rethrow", vec![]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 40);
+    }
+
+    #[test]
+    fn test_exnref_local_in_callee() {
+        let inst = load_instance("tests/wasm/exceptions.wasm");
+        let ret = call_function(&inst, "catch-from-local", vec![Val::Num(Num::I32(7))]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 7);
+    }
+
+    #[test]
+    fn test_uncaught_exception() {
+        let inst = load_instance("tests/wasm/exceptions.wasm");
+        let ret = call_function(&inst, "uncaught", vec![]);
+        assert!(matches!(ret, Err(RuntimeError::UncaughtException)));
+    }
+
+    #[test]
+    fn test_throw_ref_null() {
+        let inst = load_instance("tests/wasm/exceptions.wasm");
+        let ret = call_function(&inst, "throw-ref-null", vec![]);
+        assert!(matches!(ret, Err(RuntimeError::NullExceptionReference)));
+    }
+
+    #[test]
+    fn test_imported_tag() {
+        let exporter = load_instance("tests/wasm/exceptions.wasm");
+        let inst = load_instance_with_tag(
+            "tests/wasm/exceptions_import.wasm",
+            exported(&exporter, "e0"),
+        )
+        .unwrap();
+        let ret = call_function(&inst, "catch-imported", vec![Val::Num(Num::I32(7))]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 7);
+
+        let ret = load_instance_with_tag(
+            "tests/wasm/exceptions_import.wasm",
+            exported(&exporter, "e1"),
+        );
+        assert!(matches!(ret, Err(RuntimeError::LinkError)));
+    }
+}
@@ -0,0 +1,147 @@
+use chiwawa::{
+    error::RuntimeError,
+    execution::host::Caller,
+    execution::linker::Linker,
+    execution::module::*,
+    execution::runtime::Runtime,
+    execution::value::*,
+    parser,
+    structure::module::Module,
+    structure::types::{FuncType, NumType, ValueType},
+};
+use std::rc::Rc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_module(wasm_path: &str) -> Module {
+        let mut module = Module::new("test");
+        let _ = parser::parse_bytecode(&mut module, wasm_path);
+        module
+    }
+
+    fn host_linker() -> Linker {
+        let mut linker = Linker::new();
+        linker
+            .func_wrap("env", "mul", |a: i32, b: i64| a as f64 * b as f64)
+            .func_wrap("env", "divmod", |a: i32, b: i32| {
+                if b == 0 {
+                    return Err(RuntimeError::DivisionByZero);
+                }
+                Ok((a / b, a % b))
+            })
+            .func_wrap("env", "peek", |caller: &Caller, ptr: i32| {
+                caller
+                    .memory(0)
+                    .ok_or(RuntimeError::MemoryNotFound)?
+                    .load::<i32>(0, ptr as u64)
+            })
+            .func_wrap("env", "bump", |caller: &Caller| {
+                let counter = caller.global(0).ok_or(RuntimeError::LinkError)?;
+                counter.set(Val::Num(Num::I32(counter.get().to_i32()? + 1)))
+            })
+            .func_wrap("env", "check", |x: i32| x * 2)
+            .func_wrap("env", "grow", |caller: &Caller, delta: i32| {
+                let mem = caller.memory(0).ok_or(RuntimeError::MemoryNotFound)?;
+                Ok(mem.mem_grow(delta as u64).map_or(-1, |prev| prev as i32))
+            });
+        linker
+    }
+
+    fn instantiate(linker: &mut Linker) -> Result<Rc<ModuleInst>, RuntimeError> {
+        linker.instantiate("host", &load_module("tests/wasm/host.wasm"), Vec::new())
+    }
+
+    fn call_function(
+        inst: &Rc<ModuleInst>,
+        func_name: &str,
+        params: Vec<Val>,
+    ) -> Result<Vec<Val>, RuntimeError> {
+        let func_addr = inst.get_export_func(func_name)?;
+        let mut runtime = Runtime::new(Rc::clone(inst), &func_addr, params, true, false)?;
+        runtime.run()
+    }
+
+    #[test]
+    fn test_typed_params_and_result() {
+        let inst = instantiate(&mut host_linker()).unwrap();
+        let params = vec![Val::Num(Num::I32(3)), Val::Num(Num::I64(7))];
+        let ret = call_function(&inst, "mul", params.clone());
+        assert_eq!(ret.unwrap().last().unwrap().to_f64().unwrap(), 21.0);
+        let ret = call_function(&inst, "tail-mul", params);
+        assert_eq!(ret.unwrap().last().unwrap().to_f64().unwrap(), 21.0);
+    }
+
+    #[test]
+    fn test_multiple_results() {
+        let inst = instantiate(&mut host_linker()).unwrap();
+        let params = vec![Val::Num(Num::I32(17)), Val::Num(Num::I32(5))];
+        let ret = call_function(&inst, "divmod", params).unwrap();
+        assert_eq!(ret, vec![Val::Num(Num::I32(3)), Val::Num(Num::I32(2))]);
+    }
+
+    #[test]
+    fn test_host_error_traps() {
+        let inst = instantiate(&mut host_linker()).unwrap();
+        let params = vec![Val::Num(Num::I32(17)), Val::Num(Num::I32(0))];
+        let ret = call_function(&inst, "divmod", params);
+        assert_eq!(ret, Err(RuntimeError::DivisionByZero));
+    }
+
+    #[test]
+    fn test_caller_memory_and_globals() {
+        let inst = instantiate(&mut host_linker()).unwrap();
+        let ret = call_function(&inst, "peek", vec![Val::Num(Num::I32(16))]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 42);
+        let ret = call_function(&inst, "peek", vec![Val::Num(Num::I32(0x10000))]);
+        assert_eq!(ret, Err(RuntimeError::MemoryOutOfBounds));
+
+        let ret = call_function(&inst, "bump-twice", vec![]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_host_memory_grow() {
+        // The guest stores past the old end of memory after the host grew it
+        let inst = instantiate(&mut host_linker()).unwrap();
+        let ret = call_function(&inst, "grow-and-store", vec![Val::Num(Num::I32(77))]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 77);
+        assert_eq!(inst.mem_addrs[0].mem_size(), 2);
+    }
+
+    #[test]
+    fn test_signature_mismatch() {
+        let mut linker = host_linker();
+        linker.func_wrap("env", "check", |x: i64| x);
+        assert!(matches!(
+            instantiate(&mut linker),
+            Err(RuntimeError::LinkError)
+        ));
+    }
+
+    #[test]
+    fn test_func_new() {
+        let mut linker = host_linker();
+        let i32_type = ValueType::NumType(NumType::I32);
+        let type_ = FuncType {
+            params: vec![i32_type.clone()],
+            results: vec![i32_type],
+        };
+        linker.func_new("env", "check", type_.clone(), |_, params| {
+            Ok(vec![Val::Num(Num::I32(params[0].to_i32()? + 10))])
+        });
+        let inst = instantiate(&mut linker).unwrap();
+        let ret = call_function(&inst, "check", vec![Val::Num(Num::I32(5))]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 16);
+
+        // Results that do not match the declared type are rejected
+        let mut linker = host_linker();
+        linker.func_new("env", "check", type_, |_, _| {
+            Ok(vec![Val::Num(Num::I64(1))])
+        });
+        let inst = instantiate(&mut linker).unwrap();
+        let ret = call_function(&inst, "check", vec![Val::Num(Num::I32(5))]);
+        assert_eq!(ret, Err(RuntimeError::TypeMismatch));
+    }
+}
@@ -107,6 +107,21 @@ mod tests {
     #[test]
     fn test_i32_div_s() {
         let inst = load_instance("tests/wasm/i32.wasm");
+        let ret = call_function(
+            &inst,
+            "div_s",
+            vec![Val::Num(Num::I32(1)), Val::Num(Num::I32(0))],
+        );
+        assert_eq!(ret, Err(chiwawa::error::RuntimeError::DivisionByZero));
+        let ret = call_function(
+            &inst,
+            "div_s",
+            vec![
+                Val::Num(Num::I32(0x80000000u32 as i32)),
+                Val::Num(Num::I32(-1)),
+            ],
+        );
+        assert_eq!(ret, Err(chiwawa::error::RuntimeError::IntegerOverflow));
         let ret = call_function(
             &inst,
             "div_s",
@@ -166,6 +181,12 @@ mod tests {
     #[test]
     fn test_i32_div_u() {
         let inst = load_instance("tests/wasm/i32.wasm");
+        let ret = call_function(
+            &inst,
+            "div_u",
+            vec![Val::Num(Num::I32(1)), Val::Num(Num::I32(0))],
+        );
+        assert_eq!(ret, Err(chiwawa::error::RuntimeError::DivisionByZero));
         let ret = call_function(
             &inst,
             "div_u",
@@ -231,6 +252,12 @@ mod tests {
     #[test]
     fn test_i32_rem_s() {
         let inst = load_instance("tests/wasm/i32.wasm");
+        let ret = call_function(
+            &inst,
+            "rem_s",
+            vec![Val::Num(Num::I32(1)), Val::Num(Num::I32(0))],
+        );
+        assert_eq!(ret, Err(chiwawa::error::RuntimeError::DivisionByZero));
         let ret = call_function(
             &inst,
             "rem_s",
@@ -290,6 +317,12 @@ mod tests {
     #[test]
     fn test_i32_rem_u() {
         let inst = load_instance("tests/wasm/i32.wasm");
+        let ret = call_function(
+            &inst,
+            "rem_u",
+            vec![Val::Num(Num::I32(1)), Val::Num(Num::I32(0))],
+        );
+        assert_eq!(ret, Err(chiwawa::error::RuntimeError::DivisionByZero));
         let ret = call_function(
             &inst,
             "rem_u",
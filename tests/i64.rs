@@ -398,6 +398,21 @@ mod tests {
     #[test]
     fn test_i64_div_s() {
         let inst = load_instance("tests/wasm/i64.wasm");
+        let ret = call_function(
+            &inst,
+            "div_s",
+            vec![Val::Num(Num::I64(1)), Val::Num(Num::I64(0))],
+        );
+        assert_eq!(ret, Err(chiwawa::error::RuntimeError::DivisionByZero));
+        let ret = call_function(
+            &inst,
+            "div_s",
+            vec![
+                Val::Num(Num::I64(0x8000000000000000u64 as i64)),
+                Val::Num(Num::I64(-1)),
+            ],
+        );
+        assert_eq!(ret, Err(chiwawa::error::RuntimeError::IntegerOverflow));
 
         assert_eq!(
             call_function(
@@ -618,6 +633,12 @@ mod tests {
     #[test]
     fn test_i64_div_u() {
         let inst = load_instance("tests/wasm/i64.wasm");
+        let ret = call_function(
+            &inst,
+            "div_u",
+            vec![Val::Num(Num::I64(1)), Val::Num(Num::I64(0))],
+        );
+        assert_eq!(ret, Err(chiwawa::error::RuntimeError::DivisionByZero));
 
         assert_eq!(
             call_function(
@@ -818,6 +839,12 @@ mod tests {
     #[test]
     fn test_i64_rem_s() {
         let inst = load_instance("tests/wasm/i64.wasm");
+        let ret = call_function(
+            &inst,
+            "rem_s",
+            vec![Val::Num(Num::I64(1)), Val::Num(Num::I64(0))],
+        );
+        assert_eq!(ret, Err(chiwawa::error::RuntimeError::DivisionByZero));
 
         assert_eq!(
             call_function(
@@ -1054,6 +1081,12 @@ mod tests {
     #[test]
     fn test_i64_rem_u() {
         let inst = load_instance("tests/wasm/i64.wasm");
+        let ret = call_function(
+            &inst,
+            "rem_u",
+            vec![Val::Num(Num::I64(1)), Val::Num(Num::I64(0))],
+        );
+        assert_eq!(ret, Err(chiwawa::error::RuntimeError::DivisionByZero));
 
         assert_eq!(
             call_function(
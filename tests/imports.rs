@@ -0,0 +1,165 @@
+use chiwawa::{
+    error::RuntimeError, execution::module::*, execution::runtime::Runtime, execution::value::*,
+    parser, structure::module::Module,
+};
+use rustc_hash::FxHashMap;
+use std::rc::Rc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_instance(wasm_path: &str) -> Rc<ModuleInst> {
+        let mut module = Module::new("test");
+        let _ = parser::parse_bytecode(&mut module, wasm_path);
+        let imports: ImportObjects = FxHashMap::default();
+        ModuleInst::new(&module, imports, Vec::new()).unwrap()
+    }
+
+    fn load_instance_with_env(
+        wasm_path: &str,
+        env: Vec<(&str, Externval)>,
+    ) -> Result<Rc<ModuleInst>, RuntimeError> {
+        let mut module = Module::new("test");
+        let _ = parser::parse_bytecode(&mut module, wasm_path);
+        let env = env
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect();
+        let mut imports: ImportObjects = FxHashMap::default();
+        imports.insert("env".to_string(), env);
+        ModuleInst::new(&module, imports, Vec::new())
+    }
+
+    fn exported(inst: &Rc<ModuleInst>, name: &str) -> Externval {
+        inst.exports
+            .iter()
+            .find(|export| export.name == name)
+            .map(|export| export.value.clone())
+            .unwrap()
+    }
+
+    fn exporter_env(exporter: &Rc<ModuleInst>) -> Vec<(&'static str, Externval)> {
+        ["table", "memory", "__stack_pointer", "const"]
+            .into_iter()
+            .map(|name| (name, exported(exporter, name)))
+            .collect()
+    }
+
+    fn call_function(
+        inst: &Rc<ModuleInst>,
+        func_name: &str,
+        params: Vec<Val>,
+    ) -> Result<Vec<Val>, RuntimeError> {
+        let func_addr = inst.get_export_func(func_name)?;
+        let mut runtime = Runtime::new(Rc::clone(inst), &func_addr, params, true, false)?;
+        runtime.run()
+    }
+
+    fn call_i32(inst: &Rc<ModuleInst>, func_name: &str, params: Vec<Val>) -> i32 {
+        let ret = call_function(inst, func_name, params);
+        ret.unwrap().last().unwrap().to_i32().unwrap()
+    }
+
+    #[test]
+    fn test_imported_memory_is_shared() {
+        let exporter = load_instance("tests/wasm/import_exporter.wasm");
+        let inst =
+            load_instance_with_env("tests/wasm/import_importer.wasm", exporter_env(&exporter))
+                .unwrap();
+
+        let ret = call_function(
+            &inst,
+            "store",
+            vec![Val::Num(Num::I32(8)), Val::Num(Num::I32(0x1234))],
+        );
+        assert!(ret.is_ok());
+        assert_eq!(
+            call_i32(&exporter, "load", vec![Val::Num(Num::I32(8))]),
+            0x1234
+        );
+
+        assert_eq!(call_i32(&inst, "grow", vec![Val::Num(Num::I32(2))]), 1);
+        assert_eq!(call_i32(&exporter, "size", vec![]), 3);
+        // The exporter's maximum of 4 pages still applies
+        assert_eq!(call_i32(&inst, "grow", vec![Val::Num(Num::I32(2))]), -1);
+    }
+
+    #[test]
+    fn test_imported_global() {
+        let exporter = load_instance("tests/wasm/import_exporter.wasm");
+        let inst =
+            load_instance_with_env("tests/wasm/import_importer.wasm", exporter_env(&exporter))
+                .unwrap();
+
+        assert_eq!(call_i32(&inst, "push", vec![]), 1008);
+        assert_eq!(call_i32(&inst, "push", vec![]), 992);
+        assert_eq!(call_i32(&exporter, "sp", vec![]), 992);
+        assert_eq!(call_i32(&inst, "derived", vec![]), 7);
+    }
+
+    #[test]
+    fn test_imported_table() {
+        let exporter = load_instance("tests/wasm/import_exporter.wasm");
+        let inst =
+            load_instance_with_env("tests/wasm/import_importer.wasm", exporter_env(&exporter))
+                .unwrap();
+
+        assert_eq!(call_i32(&inst, "table-size", vec![]), 4);
+        assert_eq!(call_i32(&inst, "call", vec![Val::Num(Num::I32(0))]), 11);
+        // Written by the importer's active element segment
+        assert_eq!(call_i32(&inst, "call", vec![Val::Num(Num::I32(1))]), 22);
+        let ret = call_function(&inst, "call", vec![Val::Num(Num::I32(2))]);
+        assert!(matches!(ret, Err(RuntimeError::UninitializedElement)));
+    }
+
+    #[test]
+    fn test_import_kind_mismatch() {
+        let exporter = load_instance("tests/wasm/import_exporter.wasm");
+
+        let mut env = exporter_env(&exporter);
+        env[1].1 = exported(&exporter, "table");
+        let ret = load_instance_with_env("tests/wasm/import_importer.wasm", env);
+        assert!(matches!(ret, Err(RuntimeError::LinkError)));
+
+        let mut env = exporter_env(&exporter);
+        env.remove(0);
+        let ret = load_instance_with_env("tests/wasm/import_importer.wasm", env);
+        assert!(matches!(ret, Err(RuntimeError::LinkError)));
+    }
+
+    #[test]
+    fn test_import_global_type_mismatch() {
+        let exporter = load_instance("tests/wasm/import_exporter.wasm");
+
+        // Immutable global supplied for a mutable import
+        let mut env = exporter_env(&exporter);
+        env[2].1 = exported(&exporter, "const");
+        let ret = load_instance_with_env("tests/wasm/import_importer.wasm", env);
+        assert!(matches!(ret, Err(RuntimeError::LinkError)));
+
+        // Mutable global supplied for an immutable import
+        let mut env = exporter_env(&exporter);
+        env[3].1 = exported(&exporter, "__stack_pointer");
+        let ret = load_instance_with_env("tests/wasm/import_importer.wasm", env);
+        assert!(matches!(ret, Err(RuntimeError::LinkError)));
+    }
+
+    #[test]
+    fn test_import_memory_limits() {
+        let exporter = load_instance("tests/wasm/import_exporter.wasm");
+
+        // One page is fewer than the two required
+        let env = vec![("memory", exported(&exporter, "memory"))];
+        let ret = load_instance_with_env("tests/wasm/import_limits.wasm", env);
+        assert!(matches!(ret, Err(RuntimeError::LinkError)));
+
+        // Limits are matched against the current size
+        assert_eq!(call_i32(&exporter, "size", vec![]), 1);
+        let exporter_mem = exported(&exporter, "memory").as_mem().unwrap();
+        assert_eq!(exporter_mem.mem_grow(1), Some(1));
+        let env = vec![("memory", exported(&exporter, "memory"))];
+        let inst = load_instance_with_env("tests/wasm/import_limits.wasm", env).unwrap();
+        assert_eq!(call_i32(&inst, "size", vec![]), 2);
+    }
+}
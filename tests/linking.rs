@@ -0,0 +1,230 @@
+use chiwawa::{
+    error::RuntimeError,
+    execution::host::Caller,
+    execution::linker::Linker,
+    execution::migration::{self, Section, SectionKind},
+    execution::module::*,
+    execution::runtime::{RunStatus, Runtime},
+    execution::state::Stacks,
+    execution::value::*,
+    parser,
+    structure::module::Module,
+};
+use std::rc::Rc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_module(wasm_path: &str) -> Module {
+        let mut module = Module::new("test");
+        let _ = parser::parse_bytecode(&mut module, wasm_path);
+        module
+    }
+
+    /// Instantiates `link_lib` as "lib" and `link_main` as "app".
+    fn link() -> (Linker, Rc<ModuleInst>, Rc<ModuleInst>) {
+        let mut linker = Linker::new();
+        let lib = linker
+            .instantiate("lib", &load_module("tests/wasm/link_lib.wasm"), Vec::new())
+            .unwrap();
+        let app = linker
+            .instantiate("app", &load_module("tests/wasm/link_main.wasm"), Vec::new())
+            .unwrap();
+        (linker, lib, app)
+    }
+
+    fn call_function(
+        inst: &Rc<ModuleInst>,
+        func_name: &str,
+        params: Vec<Val>,
+    ) -> Result<Vec<Val>, RuntimeError> {
+        let func_addr = inst.get_export_func(func_name)?;
+        let mut runtime = Runtime::new(Rc::clone(inst), &func_addr, params, true, false)?;
+        runtime.run()
+    }
+
+    fn call_i32(inst: &Rc<ModuleInst>, func_name: &str, params: Vec<Val>) -> i32 {
+        let ret = call_function(inst, func_name, params);
+        ret.unwrap().last().unwrap().to_i32().unwrap()
+    }
+
+    #[test]
+    fn test_cross_module_call() {
+        let (_linker, _lib, app) = link();
+        let params = vec![Val::Num(Num::I32(2)), Val::Num(Num::I32(3))];
+        assert_eq!(call_i32(&app, "sum", params.clone()), 5);
+        assert_eq!(call_i32(&app, "sum-tail", params), 5);
+    }
+
+    #[test]
+    fn test_callee_uses_own_instance() {
+        let (_linker, lib, app) = link();
+
+        // `bump` reads and writes the globals of `lib`, not those of `app`
+        assert_eq!(call_i32(&app, "bump-twice", vec![]), 102);
+        assert_eq!(call_i32(&lib, "count", vec![]), 2);
+        assert_eq!(call_i32(&app, "own", vec![]), 5);
+        // ... and stores into the memory shared with `app`
+        assert_eq!(call_i32(&app, "load", vec![]), 2);
+    }
+
+    #[test]
+    fn test_transitive_imports() {
+        let (mut linker, _lib, _app) = link();
+        let client = linker
+            .instantiate(
+                "client",
+                &load_module("tests/wasm/link_client.wasm"),
+                Vec::new(),
+            )
+            .unwrap();
+        assert_eq!(call_i32(&client, "triple", vec![Val::Num(Num::I32(7))]), 21);
+        assert!(linker.get("client").is_some());
+        assert_eq!(linker.instances().len(), 3);
+    }
+
+    #[test]
+    fn test_missing_dependency() {
+        let mut linker = Linker::new();
+        let ret = linker.instantiate("app", &load_module("tests/wasm/link_main.wasm"), Vec::new());
+        assert!(matches!(ret, Err(RuntimeError::LinkError)));
+        assert!(linker.get("app").is_none());
+    }
+
+    #[test]
+    fn test_define_individual_values() {
+        let lib_linker = link().0;
+        let lib = lib_linker.get("lib").unwrap();
+
+        // Provide each import individually under the expected names
+        let mut linker = Linker::new();
+        for name in ["add", "bump", "memory"] {
+            let value = lib
+                .exports
+                .iter()
+                .find(|export| export.name == name)
+                .map(|export| export.value.clone())
+                .unwrap();
+            linker.define("lib", name, value);
+        }
+        let app = linker
+            .instantiate("app", &load_module("tests/wasm/link_main.wasm"), Vec::new())
+            .unwrap();
+        let params = vec![Val::Num(Num::I32(4)), Val::Num(Num::I32(5))];
+        assert_eq!(call_i32(&app, "sum", params), 9);
+    }
+
+    #[test]
+    fn test_checkpoint_covers_linked_instances() {
+        let (_linker, lib, app) = link();
+        let path = "temp_linking_checkpoint.bin";
+
+        assert_eq!(call_i32(&app, "bump-twice", vec![]), 102);
+
+        // Checkpoint at the entry of a function defined in `lib`
+        let func_addr = lib.get_export_func("bump").unwrap();
+        let stacks = Stacks::new(&func_addr, Vec::new()).unwrap();
+        let instances = vec![Rc::clone(&app), Rc::clone(&lib)];
+        migration::checkpoint(&instances, &stacks, path).unwrap();
+
+        assert_eq!(call_i32(&app, "bump-twice", vec![]), 104);
+        assert_eq!(call_i32(&app, "load", vec![]), 4);
+
+        let restored = migration::restore(&instances, path).unwrap();
+        assert_eq!(call_i32(&lib, "count", vec![]), 2);
+        assert_eq!(call_i32(&app, "load", vec![]), 2);
+
+        let mut runtime = Runtime::new_restored(Rc::clone(&app), restored, false, false);
+        runtime.set_linked_instances(vec![Rc::clone(&lib)]);
+        let ret = runtime.run();
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 103);
+        assert_eq!(call_i32(&lib, "count", vec![]), 3);
+
+        // The checkpoint must be restored into the same set of instances
+        let ret = migration::restore(&[Rc::clone(&app)], path);
+        assert!(matches!(ret, Err(RuntimeError::CheckpointLoadError(_))));
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_checkpoint_stores_shared_memory_once() {
+        let (_linker, lib, app) = link();
+        assert_eq!(call_i32(&app, "bump-twice", vec![]), 102);
+
+        let func_addr = app.get_export_func("load").unwrap();
+        let stacks = Stacks::new(&func_addr, Vec::new()).unwrap();
+        let instances = vec![Rc::clone(&app), Rc::clone(&lib)];
+        let bytes = migration::checkpoint_to_bytes(&instances, &stacks).unwrap();
+
+        // `app` imports the memory of `lib`, so both refer to memory 0
+        let (_, rest) = migration::read_checkpoint_header(&bytes).unwrap();
+        let sections: Vec<Section> = bincode::deserialize(rest).unwrap();
+        let memories = sections
+            .iter()
+            .filter(|section| matches!(section.kind, SectionKind::Memory { .. }))
+            .count();
+        assert_eq!(memories, 1);
+        for instance in 0..2 {
+            let layout = sections
+                .iter()
+                .find(|section| section.kind == SectionKind::Layout { instance })
+                .unwrap();
+            let (memories, _): (Vec<u32>, Vec<u32>) = bincode::deserialize(&layout.data).unwrap();
+            assert_eq!(memories, vec![0]);
+        }
+
+        let (_linker, lib, app) = link();
+        let instances = vec![Rc::clone(&app), Rc::clone(&lib)];
+        let restored = migration::restore_from_bytes(&instances, &bytes).unwrap();
+        assert_eq!(call_i32(&lib, "count", vec![]), 2);
+        let ret = Runtime::new_restored(Rc::clone(&app), restored, false, false).run();
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 2);
+
+        // Instances that do not share the memory cannot take the checkpoint
+        let (_linker, _lib, app) = link();
+        let (_linker, lib, _app) = link();
+        let ret = migration::restore_from_bytes(&[app, lib], &bytes);
+        assert!(matches!(ret, Err(RuntimeError::CheckpointLoadError(_))));
+    }
+
+    /// Instantiates `exn_lib` as "lib" and `exn_app` as "app".
+    fn link_exceptions() -> (Rc<ModuleInst>, Rc<ModuleInst>) {
+        let mut linker = Linker::new();
+        linker.func_wrap("env", "yield", |caller: &Caller| {
+            caller.request_checkpoint()
+        });
+        let lib = linker
+            .instantiate("lib", &load_module("tests/wasm/exn_lib.wasm"), Vec::new())
+            .unwrap();
+        let app = linker
+            .instantiate_without_start("app", &load_module("tests/wasm/exn_app.wasm"), Vec::new())
+            .unwrap();
+        (lib, app)
+    }
+
+    #[test]
+    fn test_checkpoint_keeps_tags_of_linked_exceptions() {
+        let (lib, app) = link_exceptions();
+        let func_addr = app.get_export_func("run").unwrap();
+        let mut runtime =
+            Runtime::new(Rc::clone(&app), &func_addr, Vec::new(), false, true).unwrap();
+        runtime.set_linked_instances(vec![Rc::clone(&lib)]);
+        assert_eq!(
+            runtime.run_for(u64::MAX).unwrap(),
+            RunStatus::CheckpointRequested
+        );
+        let bytes = runtime.snapshot().unwrap();
+
+        // The exception was thrown by `lib`, so its tag must be resolved
+        // there rather than in the frame's instance
+        let (lib, app) = link_exceptions();
+        let instances = vec![Rc::clone(&app), Rc::clone(&lib)];
+        let stacks = migration::restore_from_bytes(&instances, &bytes).unwrap();
+        let mut runtime = Runtime::new_restored(Rc::clone(&app), stacks, false, false);
+        runtime.set_linked_instances(vec![Rc::clone(&lib)]);
+        let ret = runtime.run();
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 42);
+    }
+}
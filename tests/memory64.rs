@@ -0,0 +1,125 @@
+use chiwawa::{
+    error::RuntimeError, execution::module::*, execution::runtime::Runtime, execution::value::*,
+    parser, structure::module::Module,
+};
+use rustc_hash::FxHashMap;
+use std::rc::Rc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_instance(wasm_path: &str) -> Rc<ModuleInst> {
+        let mut module = Module::new("test");
+        let _ = parser::parse_bytecode(&mut module, wasm_path);
+        let imports: ImportObjects = FxHashMap::default();
+        ModuleInst::new(&module, imports, Vec::new()).unwrap()
+    }
+
+    fn call_function(
+        inst: &Rc<ModuleInst>,
+        func_name: &str,
+        params: Vec<Val>,
+    ) -> Result<Vec<Val>, RuntimeError> {
+        let func_addr = inst.get_export_func(func_name)?;
+        let mut runtime = Runtime::new(Rc::clone(inst), &func_addr, params, true, false)?;
+        runtime.run()
+    }
+
+    fn i64(v: i64) -> Val {
+        Val::Num(Num::I64(v))
+    }
+
+    fn i32(v: i32) -> Val {
+        Val::Num(Num::I32(v))
+    }
+
+    #[test]
+    fn test_load_store() {
+        let inst = load_instance("tests/wasm/memory64.wasm");
+        let ret = call_function(&inst, "load", vec![i64(0x100)]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i64().unwrap(), 42);
+
+        call_function(&inst, "store", vec![i64(0xFFF8), i64(-2)]).unwrap();
+        let ret = call_function(&inst, "load", vec![i64(0xFFF8)]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i64().unwrap(), -2);
+    }
+
+    #[test]
+    fn test_out_of_bounds() {
+        let inst = load_instance("tests/wasm/memory64.wasm");
+        // Addresses above 4GiB must not wrap around to low memory
+        let ret = call_function(&inst, "load8", vec![i64(0x1_0000_0000)]);
+        assert!(matches!(ret, Err(RuntimeError::MemoryOutOfBounds)));
+        let ret = call_function(&inst, "load8", vec![i64(-1)]);
+        assert!(matches!(ret, Err(RuntimeError::MemoryOutOfBounds)));
+        let ret = call_function(&inst, "load-offset", vec![i64(0)]);
+        assert!(matches!(ret, Err(RuntimeError::MemoryOutOfBounds)));
+        let ret = call_function(&inst, "simd-lane", vec![i64(0xFFF8)]);
+        assert!(matches!(ret, Err(RuntimeError::MemoryOutOfBounds)));
+    }
+
+    #[test]
+    fn test_size_grow() {
+        let inst = load_instance("tests/wasm/memory64.wasm");
+        let ret = call_function(&inst, "size", vec![]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i64().unwrap(), 1);
+        let ret = call_function(&inst, "grow", vec![i64(2)]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i64().unwrap(), 1);
+        let ret = call_function(&inst, "size", vec![]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i64().unwrap(), 3);
+
+        // Beyond the declared maximum, and a delta that overflows
+        let ret = call_function(&inst, "grow", vec![i64(1)]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i64().unwrap(), -1);
+        let ret = call_function(&inst, "grow", vec![i64(-1)]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i64().unwrap(), -1);
+
+        let ret = call_function(&inst, "load-offset", vec![i64(0)]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_bulk_memory() {
+        let inst = load_instance("tests/wasm/memory64.wasm");
+        call_function(&inst, "fill", vec![i64(0x200), i32(0x11), i64(8)]).unwrap();
+        let ret = call_function(&inst, "load", vec![i64(0x200)]);
+        assert_eq!(
+            ret.unwrap().last().unwrap().to_i64().unwrap(),
+            0x1111111111111111
+        );
+
+        call_function(&inst, "copy", vec![i64(0x300), i64(0x100), i64(8)]).unwrap();
+        let ret = call_function(&inst, "load", vec![i64(0x300)]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i64().unwrap(), 42);
+
+        call_function(&inst, "copy-to-small", vec![i32(4), i64(0x100), i32(4)]).unwrap();
+        let ret = call_function(&inst, "load-small", vec![i32(4)]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 42);
+
+        call_function(&inst, "init", vec![i64(0x400)]).unwrap();
+        let ret = call_function(&inst, "load8", vec![i64(0x405)]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), b'4' as i32);
+
+        let ret = call_function(&inst, "fill", vec![i64(0x1_0000_0000), i32(0), i64(0)]);
+        assert!(matches!(ret, Err(RuntimeError::MemoryOutOfBounds)));
+    }
+
+    #[test]
+    fn test_oversized_minimum() {
+        // 2^48 pages overflows the size in bytes on every host
+        let wat = "(module (memory i64 281474976710656))";
+        let mut module = Module::new("test");
+        parser::parse_bytes(&mut module, &wat::parse_str(wat).unwrap()).unwrap();
+        let imports: ImportObjects = FxHashMap::default();
+        let ret = ModuleInst::new(&module, imports, Vec::new());
+        assert!(matches!(ret, Err(RuntimeError::MemoryAllocationFailed)));
+    }
+
+    #[test]
+    fn test_simd_load() {
+        let inst = load_instance("tests/wasm/memory64.wasm");
+        let ret = call_function(&inst, "simd-lane", vec![i64(0x100)]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 42);
+    }
+}
@@ -0,0 +1,159 @@
+use chiwawa::{
+    error::RuntimeError, execution::module::*, execution::runtime::Runtime, execution::value::*,
+    parser, structure::module::Module, wasi::passthrough::PassthroughWasiImpl, wasi::WasiError,
+};
+use rustc_hash::FxHashMap;
+use std::rc::Rc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_instance(wasm_path: &str) -> Rc<ModuleInst> {
+        let mut module = Module::new("test");
+        let _ = parser::parse_bytecode(&mut module, wasm_path);
+        let imports: ImportObjects = FxHashMap::default();
+        ModuleInst::new(&module, imports, Vec::new()).unwrap()
+    }
+
+    fn call_function(
+        inst: &Rc<ModuleInst>,
+        func_name: &str,
+        params: Vec<Val>,
+    ) -> Result<Vec<Val>, RuntimeError> {
+        let func_addr = inst.get_export_func(func_name)?;
+        let mut runtime = Runtime::new(Rc::clone(inst), &func_addr, params, true, false)?;
+        runtime.run()
+    }
+
+    #[test]
+    fn test_memory_trap_load_store_in_bounds() {
+        let inst = load_instance("tests/wasm/memorytrap.wasm");
+
+        let result = call_function(
+            &inst,
+            "store",
+            vec![Val::Num(Num::I32(-4)), Val::Num(Num::I32(42))],
+        );
+        assert!(result.is_ok());
+
+        let result = call_function(&inst, "load", vec![Val::Num(Num::I32(-4))]);
+        assert_eq!(result.unwrap().last().unwrap().to_i32().unwrap(), 42);
+    }
+
+    #[test]
+    fn test_memory_trap_load_store_out_of_bounds() {
+        let inst = load_instance("tests/wasm/memorytrap.wasm");
+
+        for addr in [-3, -2, -1, 0, i32::MIN] {
+            let result = call_function(
+                &inst,
+                "store",
+                vec![Val::Num(Num::I32(addr)), Val::Num(Num::I32(13))],
+            );
+            assert_eq!(result, Err(RuntimeError::MemoryOutOfBounds));
+
+            let result = call_function(&inst, "load", vec![Val::Num(Num::I32(addr))]);
+            assert_eq!(result, Err(RuntimeError::MemoryOutOfBounds));
+        }
+
+        let result = call_function(&inst, "memory.grow", vec![Val::Num(Num::I32(0x10001))]);
+        assert_eq!(result.unwrap().last().unwrap().to_i32().unwrap(), -1);
+    }
+
+    #[test]
+    fn test_memory_trap_fill_out_of_bounds() {
+        let inst = load_instance("tests/wasm/memoryfill-5.wasm");
+
+        let result = call_function(
+            &inst,
+            "run",
+            vec![
+                Val::Num(Num::I32(65280)),
+                Val::Num(Num::I32(37)),
+                Val::Num(Num::I32(257)),
+            ],
+        );
+        assert_eq!(result, Err(RuntimeError::MemoryOutOfBounds));
+
+        // No partial write before the trap
+        let result = call_function(
+            &inst,
+            "checkRange",
+            vec![
+                Val::Num(Num::I32(0)),
+                Val::Num(Num::I32(65536)),
+                Val::Num(Num::I32(0)),
+            ],
+        );
+        assert_eq!(result.unwrap().last().unwrap().to_i32().unwrap(), -1);
+    }
+
+    #[test]
+    fn test_memory_trap_copy_out_of_bounds() {
+        let inst = load_instance("tests/wasm/memorycopy-10.wasm");
+
+        let result = call_function(
+            &inst,
+            "run",
+            vec![
+                Val::Num(Num::I32(65516)),
+                Val::Num(Num::I32(0)),
+                Val::Num(Num::I32(40)),
+            ],
+        );
+        assert_eq!(result, Err(RuntimeError::MemoryOutOfBounds));
+
+        let result = call_function(&inst, "load8_u", vec![Val::Num(Num::I32(65516))]);
+        assert_eq!(result.unwrap().last().unwrap().to_i32().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_memory_trap_init_out_of_bounds() {
+        let inst = load_instance("tests/wasm/memoryinit-5.wasm");
+
+        let result = call_function(
+            &inst,
+            "run",
+            vec![Val::Num(Num::I32(65528)), Val::Num(Num::I32(16))],
+        );
+        assert_eq!(result, Err(RuntimeError::MemoryOutOfBounds));
+
+        let result = call_function(
+            &inst,
+            "run",
+            vec![Val::Num(Num::I32(0)), Val::Num(Num::I32(-4))],
+        );
+        assert_eq!(result, Err(RuntimeError::MemoryOutOfBounds));
+    }
+
+    #[test]
+    fn test_memory_trap_wasi_out_of_bounds() {
+        let inst = load_instance("tests/wasm/memorytrap.wasm");
+        let memory = &inst.mem_addrs[0];
+        let wasi = PassthroughWasiImpl::new(Vec::new());
+
+        // Paths, buffers and result pointers reaching past the 64KiB memory
+        let ret = wasi.path_open(memory, 3, 0, 65530, 16, 0, 0, 0, 0, 96);
+        assert!(matches!(ret, Err(WasiError::Fault)));
+        let ret = wasi.path_create_directory(memory, 3, 0xffff_fff0, 0x20);
+        assert!(matches!(ret, Err(WasiError::Fault)));
+        let ret = wasi.random_get(memory, 65535, 2);
+        assert!(matches!(ret, Err(WasiError::Fault)));
+        let ret = wasi.fd_fdstat_get(memory, 1, 65530);
+        assert!(matches!(ret, Err(WasiError::Fault)));
+        let ret = wasi.poll_oneoff(memory, 0, 0, 0x1000_0000, 0);
+        assert!(matches!(ret, Err(WasiError::Fault)));
+
+        // An iovec array that does not fit, and an in-bounds iovec whose
+        // buffer does not
+        let ret = wasi.fd_write(memory, 1, 0, 0x2000_0000, 8);
+        assert!(matches!(ret, Err(WasiError::Fault)));
+        memory.store(0, 0, 65530u32).unwrap();
+        memory.store(0, 4, 16u32).unwrap();
+        let ret = wasi.fd_read(memory, 0, 0, 1, 8);
+        assert!(matches!(ret, Err(WasiError::Fault)));
+        let ret = wasi.fd_pwrite(memory, 1, 0, 1, 0, 8);
+        assert!(matches!(ret, Err(WasiError::Fault)));
+    }
+}
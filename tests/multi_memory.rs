@@ -0,0 +1,174 @@
+use chiwawa::{
+    error::RuntimeError, execution::module::*, execution::runtime::Runtime, execution::value::*,
+    parser, structure::module::Module,
+};
+use rustc_hash::FxHashMap;
+use std::rc::Rc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_instance(wasm_path: &str) -> Rc<ModuleInst> {
+        let mut module = Module::new("test");
+        let _ = parser::parse_bytecode(&mut module, wasm_path);
+        let imports: ImportObjects = FxHashMap::default();
+        ModuleInst::new(&module, imports, Vec::new()).unwrap()
+    }
+
+    fn call_function(
+        inst: &Rc<ModuleInst>,
+        func_name: &str,
+        params: Vec<Val>,
+    ) -> Result<Vec<Val>, RuntimeError> {
+        let func_addr = inst.get_export_func(func_name)?;
+        let mut runtime = Runtime::new(Rc::clone(inst), &func_addr, params, true, false)?;
+        runtime.run()
+    }
+
+    fn i32s(values: &[i32]) -> Vec<Val> {
+        values.iter().map(|v| Val::Num(Num::I32(*v))).collect()
+    }
+
+    fn call_i32(inst: &Rc<ModuleInst>, func_name: &str, params: &[i32]) -> i32 {
+        let ret = call_function(inst, func_name, i32s(params));
+        ret.unwrap().last().unwrap().to_i32().unwrap()
+    }
+
+    #[test]
+    fn test_active_data_segment() {
+        let inst = load_instance("tests/wasm/multi_memory.wasm");
+        assert_eq!(call_i32(&inst, "load1", &[16]), 42);
+        assert_eq!(call_i32(&inst, "load0", &[16]), 0);
+    }
+
+    #[test]
+    fn test_load_store() {
+        let inst = load_instance("tests/wasm/multi_memory.wasm");
+        call_function(&inst, "store1", i32s(&[8, 1234])).unwrap();
+        call_function(&inst, "store0", i32s(&[8, 5678])).unwrap();
+        assert_eq!(call_i32(&inst, "load1", &[8]), 1234);
+        assert_eq!(call_i32(&inst, "load0", &[8]), 5678);
+    }
+
+    #[test]
+    fn test_size_grow() {
+        let inst = load_instance("tests/wasm/multi_memory.wasm");
+        assert_eq!(call_i32(&inst, "size1", &[]), 1);
+        assert_eq!(call_i32(&inst, "grow1", &[1]), 1);
+        assert_eq!(call_i32(&inst, "size1", &[]), 2);
+        // Maximum of memory 1 is two pages
+        assert_eq!(call_i32(&inst, "grow1", &[1]), -1);
+
+        call_function(&inst, "store1", i32s(&[65536, 7])).unwrap();
+        assert_eq!(call_i32(&inst, "load1", &[65536]), 7);
+        // Memory 0 is unaffected by the growth of memory 1
+        let ret = call_function(&inst, "load0", i32s(&[65536]));
+        assert!(matches!(ret, Err(RuntimeError::MemoryOutOfBounds)));
+    }
+
+    #[test]
+    fn test_fill_init() {
+        let inst = load_instance("tests/wasm/multi_memory.wasm");
+        call_function(&inst, "fill1", i32s(&[100, 0xAB, 4])).unwrap();
+        assert_eq!(call_i32(&inst, "load1", &[100]), 0xABABABABu32 as i32);
+        assert_eq!(call_i32(&inst, "load0", &[100]), 0);
+
+        call_function(&inst, "init1", i32s(&[200])).unwrap();
+        assert_eq!(call_i32(&inst, "load8-1", &[200]), b'h' as i32);
+        assert_eq!(call_i32(&inst, "load8-1", &[204]), b'o' as i32);
+        assert_eq!(call_i32(&inst, "load0", &[200]), 0);
+    }
+
+    #[test]
+    fn test_copy_between_memories() {
+        let inst = load_instance("tests/wasm/multi_memory.wasm");
+        call_function(&inst, "store0", i32s(&[0, 0x11223344])).unwrap();
+        call_function(&inst, "copy0to1", i32s(&[32, 0, 4])).unwrap();
+        assert_eq!(call_i32(&inst, "load1", &[32]), 0x11223344);
+
+        // Overlapping copy within memory 1
+        call_function(&inst, "copy1to1", i32s(&[33, 32, 4])).unwrap();
+        assert_eq!(call_i32(&inst, "load1", &[33]), 0x11223344);
+    }
+
+    #[test]
+    fn test_out_of_bounds() {
+        let inst = load_instance("tests/wasm/multi_memory.wasm");
+        let ret = call_function(&inst, "load1", i32s(&[65534]));
+        assert!(matches!(ret, Err(RuntimeError::MemoryOutOfBounds)));
+        let ret = call_function(&inst, "store1", i32s(&[65536, 0]));
+        assert!(matches!(ret, Err(RuntimeError::MemoryOutOfBounds)));
+        let ret = call_function(&inst, "copy0to1", i32s(&[65536, 0, 1]));
+        assert!(matches!(ret, Err(RuntimeError::MemoryOutOfBounds)));
+        let ret = call_function(&inst, "copy0to1", i32s(&[0, 65537, 0]));
+        assert!(matches!(ret, Err(RuntimeError::MemoryOutOfBounds)));
+    }
+
+    #[test]
+    fn test_simd_load_store() {
+        let inst = load_instance("tests/wasm/multi_memory.wasm");
+        call_function(&inst, "store0", i32s(&[12, -9])).unwrap();
+        assert_eq!(call_i32(&inst, "simd-roundtrip1", &[0, 48]), -9);
+        assert_eq!(call_i32(&inst, "load1", &[60]), -9);
+        let ret = call_function(&inst, "simd-roundtrip1", i32s(&[0, 65530]));
+        assert!(matches!(ret, Err(RuntimeError::MemoryOutOfBounds)));
+    }
+
+    /// Instantiates a module importing one memory as both index 0 and 1.
+    fn load_aliased_instance() -> Rc<ModuleInst> {
+        let exporter_wat = r#"(module (memory (export "memory") 1))"#;
+        let mut exporter_module = Module::new("exporter");
+        parser::parse_bytes(&mut exporter_module, &wat::parse_str(exporter_wat).unwrap()).unwrap();
+        let exporter = ModuleInst::new(&exporter_module, FxHashMap::default(), Vec::new()).unwrap();
+        let memory = exporter.exports[0].value.clone();
+
+        let wat = r#"
+            (module
+              (import "env" "a" (memory $a 1))
+              (import "env" "b" (memory $b 1))
+              (func (export "store-a") (param i32 i32)
+                (i32.store $a (local.get 0) (local.get 1)))
+              (func (export "load-a") (param i32) (result i32)
+                (i32.load $a (local.get 0)))
+              (func (export "copy-b-to-a") (param i32 i32 i32)
+                (memory.copy $a $b (local.get 0) (local.get 1) (local.get 2)))
+              ;; Grows through index 1, then stores and loads through index 0
+              (func (export "grow-b-store-load-a") (param i32 i32) (result i32)
+                (drop (memory.grow $b (i32.const 1)))
+                (i32.store $a (local.get 0) (local.get 1))
+                (i32.load $a (local.get 0))))
+        "#;
+        let mut module = Module::new("test");
+        parser::parse_bytes(&mut module, &wat::parse_str(wat).unwrap()).unwrap();
+        let env = [("a", memory.clone()), ("b", memory)]
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect();
+        let mut imports: ImportObjects = FxHashMap::default();
+        imports.insert("env".to_string(), env);
+        ModuleInst::new(&module, imports, Vec::new()).unwrap()
+    }
+
+    #[test]
+    fn test_copy_between_aliased_indices() {
+        let inst = load_aliased_instance();
+        assert!(inst.mem_addrs[0].ptr_eq(&inst.mem_addrs[1]));
+        call_function(&inst, "store-a", i32s(&[0, 0x04030201])).unwrap();
+        call_function(&inst, "store-a", i32s(&[4, 0x08070605])).unwrap();
+
+        // Overlapping copy between the two indices of one memory
+        call_function(&inst, "copy-b-to-a", i32s(&[1, 0, 8])).unwrap();
+        assert_eq!(call_i32(&inst, "load-a", &[1]), 0x04030201);
+        assert_eq!(call_i32(&inst, "load-a", &[5]), 0x08070605);
+    }
+
+    #[test]
+    fn test_grow_through_aliased_index() {
+        let inst = load_aliased_instance();
+        call_function(&inst, "store-a", i32s(&[100, 42])).unwrap();
+        assert_eq!(call_i32(&inst, "grow-b-store-load-a", &[65540, 7]), 7);
+        assert_eq!(call_i32(&inst, "load-a", &[100]), 42);
+        assert_eq!(call_i32(&inst, "load-a", &[65540]), 7);
+    }
+}
@@ -0,0 +1,107 @@
+use chiwawa::{
+    error::{ParserError, RuntimeError},
+    execution::module::*,
+    execution::runtime::Runtime,
+    execution::value::*,
+    parser,
+    structure::module::Module,
+};
+use rustc_hash::FxHashMap;
+use std::rc::Rc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instantiate(module: &Module) -> Rc<ModuleInst> {
+        let imports: ImportObjects = FxHashMap::default();
+        ModuleInst::new(module, imports, Vec::new()).unwrap()
+    }
+
+    fn call_function(
+        inst: &Rc<ModuleInst>,
+        func_name: &str,
+        params: Vec<Val>,
+    ) -> Result<Vec<Val>, RuntimeError> {
+        let func_addr = inst.get_export_func(func_name)?;
+        let mut runtime = Runtime::new(Rc::clone(inst), &func_addr, params, true, false)?;
+        runtime.run()
+    }
+
+    fn check_add(inst: &Rc<ModuleInst>) {
+        let params = vec![Val::Num(Num::I32(20)), Val::Num(Num::I32(22))];
+        let ret = call_function(inst, "add", params);
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 42);
+    }
+
+    #[test]
+    fn test_parse_bytes() {
+        let bytes = std::fs::read("tests/wasm/link_lib.wasm").unwrap();
+        let mut module = Module::new("test");
+        parser::parse_bytes(&mut module, &bytes).unwrap();
+        check_add(&instantiate(&module));
+    }
+
+    #[test]
+    fn test_parse_reader() {
+        let bytes = std::fs::read("tests/wasm/link_lib.wasm").unwrap();
+        let mut module = Module::new("test");
+        parser::parse_reader(&mut module, std::io::Cursor::new(bytes)).unwrap();
+        check_add(&instantiate(&module));
+    }
+
+    #[test]
+    fn test_parse_bytecode_missing_file() {
+        let mut module = Module::new("test");
+        let ret = parser::parse_bytecode(&mut module, "tests/wasm/does_not_exist.wasm");
+        assert!(ret.is_err());
+    }
+
+    #[test]
+    fn test_parse_bytes_invalid() {
+        let mut module = Module::new("test");
+        assert!(parser::parse_bytes(&mut module, b"not wasm").is_err());
+
+        // Truncated after the header and section id
+        let mut module = Module::new("test");
+        let bytes = [0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01];
+        assert!(parser::parse_bytes(&mut module, &bytes).is_err());
+    }
+
+    #[test]
+    fn test_parse_bytes_text() {
+        let bytes = std::fs::read("tests/wasm/link_lib.wat").unwrap();
+        let mut module = Module::new("test");
+        parser::parse_bytes(&mut module, &bytes).unwrap();
+        check_add(&instantiate(&module));
+    }
+
+    #[test]
+    fn test_parse_bytecode_text_file() {
+        let mut module = Module::new("test");
+        parser::parse_bytecode(&mut module, "tests/wasm/link_lib.wat").unwrap();
+        check_add(&instantiate(&module));
+    }
+
+    #[test]
+    fn test_wat_to_binary_passes_binary_through() {
+        let bytes = std::fs::read("tests/wasm/link_lib.wasm").unwrap();
+        let binary = parser::wat_to_binary(&bytes).unwrap();
+        assert!(matches!(binary, std::borrow::Cow::Borrowed(_)));
+        assert_eq!(&*binary, &bytes[..]);
+    }
+
+    #[test]
+    fn test_parse_bytes_text_error_location() {
+        let wat = "(module\n  (func (result i32)\n    i32.bogus))";
+        let mut module = Module::new("test");
+        let err = parser::parse_bytes(&mut module, wat.as_bytes()).unwrap_err();
+        match err.downcast_ref::<ParserError>() {
+            Some(ParserError::WatError { line, column, .. }) => {
+                assert_eq!(*line, 3);
+                assert_eq!(*column, 5);
+            }
+            other => panic!("expected WatError, got {:?}", other),
+        }
+    }
+}
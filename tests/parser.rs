@@ -10,8 +10,8 @@ mod tests {
         let wat = r#"
         (module
             (func (param i32 i32 i64))
-            (func (result i32 i32))
-            (func (param i32 i32) (result i32))
+            (func (result i32 i32) (i32.const 0) (i32.const 0))
+            (func (param i32 i32) (result i32) (local.get 0))
         )"#;
 
         let binary = wat::parse_str(wat).unwrap();
@@ -45,8 +45,8 @@ mod tests {
             (import "test" "test" (func (param i32))) 
             (import "test" "test" (func (param i32 i32) (result i32))) 
             (func (param i32 i32 i64))
-            (func (result i32 i32)) 
-            (func (param i32 i32) (result i32))
+            (func (result i32 i32) (i32.const 0) (i32.const 0))
+            (func (param i32 i32) (result i32) (local.get 0))
         )"#;
 
         let binary = wat::parse_str(wat).unwrap();
@@ -218,7 +218,7 @@ mod tests {
     fn decode_global_section() {
         let wat = r#"
         (module
-            (global $f32 f32)
+            (global $f32 f32 (f32.const 1.5))
             (global $f64 (mut i64)(i64.const 2024))
         )"#;
 
@@ -240,7 +240,8 @@ mod tests {
         let mut init = &module.globals[0].init;
         assert!(matches!(type_.0, Mut::Const));
         assert!(matches!(type_.1, ValueType::NumType(NumType::F32)));
-        assert_eq!(init.0.len(), 0);
+        assert_eq!(init.0.len(), 1);
+        assert!(matches!(init.0[0], Instr::F32Const(v) if v == 1.5));
 
         type_ = &module.globals[1].type_;
         init = &module.globals[1].init;
@@ -0,0 +1,103 @@
+use chiwawa::{
+    error::RuntimeError, execution::module::*, execution::runtime::Runtime, execution::value::*,
+    parser, structure::module::Module,
+};
+use rustc_hash::FxHashMap;
+use std::rc::Rc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_instance(wasm_path: &str) -> Rc<ModuleInst> {
+        let mut module = Module::new("test");
+        let _ = parser::parse_bytecode(&mut module, wasm_path);
+        let imports: ImportObjects = FxHashMap::default();
+        ModuleInst::new(&module, imports, Vec::new()).unwrap()
+    }
+
+    fn call_function(
+        inst: &Rc<ModuleInst>,
+        func_name: &str,
+        params: Vec<Val>,
+    ) -> Result<Vec<Val>, RuntimeError> {
+        let func_addr = inst.get_export_func(func_name)?;
+        let mut runtime = Runtime::new(Rc::clone(inst), &func_addr, params, true, false)?;
+        runtime.run()
+    }
+
+    #[test]
+    fn test_return_call_types() {
+        let inst = load_instance("tests/wasm/return_call.wasm");
+        let ret = call_function(&inst, "type-i32", vec![]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 0x132);
+        let ret = call_function(&inst, "type-i64", vec![]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i64().unwrap(), 0x164);
+        let ret = call_function(&inst, "type-first-i32", vec![]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 32);
+        let ret = call_function(&inst, "type-second-i64", vec![]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i64().unwrap(), 64);
+    }
+
+    #[test]
+    fn test_return_call_recursion() {
+        let inst = load_instance("tests/wasm/return_call.wasm");
+        let ret = call_function(
+            &inst,
+            "fac-acc",
+            vec![Val::Num(Num::I64(25)), Val::Num(Num::I64(1))],
+        );
+        assert_eq!(
+            ret.unwrap().last().unwrap().to_i64().unwrap(),
+            7034535277573963776
+        );
+
+        let ret = call_function(&inst, "even", vec![Val::Num(Num::I64(77))]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i64().unwrap(), 99);
+        let ret = call_function(&inst, "odd", vec![Val::Num(Num::I64(200))]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i64().unwrap(), 99);
+        let ret = call_function(&inst, "nested", vec![Val::Num(Num::I64(10))]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i64().unwrap(), 45);
+    }
+
+    #[test]
+    fn test_return_call_deep_recursion() {
+        let inst = load_instance("tests/wasm/return_call.wasm");
+        let ret = call_function(&inst, "count", vec![Val::Num(Num::I64(1_000_000))]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i64().unwrap(), 0);
+        let ret = call_function(&inst, "even", vec![Val::Num(Num::I64(1_000_001))]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i64().unwrap(), 99);
+    }
+
+    #[test]
+    fn test_return_call_indirect() {
+        let inst = load_instance("tests/wasm/return_call.wasm");
+        let ret = call_function(
+            &inst,
+            "dispatch-i32",
+            vec![Val::Num(Num::I32(0)), Val::Num(Num::I32(7))],
+        );
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 7);
+        let ret = call_function(&inst, "dispatch-fac", vec![Val::Num(Num::I64(5))]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i64().unwrap(), 120);
+        let ret = call_function(&inst, "even-indirect", vec![Val::Num(Num::I64(100_001))]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i64().unwrap(), 99);
+    }
+
+    #[test]
+    fn test_return_call_indirect_traps() {
+        let inst = load_instance("tests/wasm/return_call.wasm");
+        let ret = call_function(
+            &inst,
+            "dispatch-i32",
+            vec![Val::Num(Num::I32(1)), Val::Num(Num::I32(7))],
+        );
+        assert_eq!(ret, Err(RuntimeError::IndirectCallTypeMismatch));
+        let ret = call_function(
+            &inst,
+            "dispatch-i32",
+            vec![Val::Num(Num::I32(5)), Val::Num(Num::I32(7))],
+        );
+        assert_eq!(ret, Err(RuntimeError::UndefinedElement));
+    }
+}
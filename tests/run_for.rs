@@ -0,0 +1,131 @@
+use chiwawa::{
+    execution::migration,
+    execution::module::*,
+    execution::runtime::{RunStatus, Runtime},
+    execution::value::*,
+    parser,
+    structure::module::Module,
+};
+use rustc_hash::FxHashMap;
+use std::rc::Rc;
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_instance(wasm_path: &str) -> Rc<ModuleInst> {
+        let mut module = Module::new("test");
+        let _ = parser::parse_bytecode(&mut module, wasm_path);
+        let imports: ImportObjects = FxHashMap::default();
+        ModuleInst::new(&module, imports, Vec::new()).unwrap()
+    }
+
+    fn count_to(inst: &Rc<ModuleInst>, n: i32) -> Runtime {
+        let func_addr = inst.get_export_func("count-to").unwrap();
+        let params = vec![Val::Num(Num::I32(n))];
+        Runtime::new(Rc::clone(inst), &func_addr, params, false, false).unwrap()
+    }
+
+    fn count(inst: &Rc<ModuleInst>) -> i32 {
+        inst.global_addrs[0].get().to_i32().unwrap()
+    }
+
+    fn finished_i32(status: RunStatus) -> i32 {
+        match status {
+            RunStatus::Finished(values) => values.last().unwrap().to_i32().unwrap(),
+            status => panic!("expected the guest to finish, got {:?}", status),
+        }
+    }
+
+    #[test]
+    fn test_run_for_pauses_and_resumes() {
+        let inst = load_instance("tests/wasm/steps.wasm");
+        let mut runtime = count_to(&inst, 1000);
+
+        assert_eq!(runtime.run_for(100).unwrap(), RunStatus::Paused);
+        assert!(!runtime.is_finished());
+        let partial = count(&inst);
+        assert!(partial > 0 && partial < 1000);
+
+        assert_eq!(runtime.run_for(0).unwrap(), RunStatus::Paused);
+        assert_eq!(count(&inst), partial);
+
+        let ret = runtime.resume().unwrap();
+        assert_eq!(ret.last().unwrap().to_i32().unwrap(), 1000);
+        assert!(runtime.is_finished());
+    }
+
+    #[test]
+    fn test_run_for_slices_match_unbounded_run() {
+        let inst = load_instance("tests/wasm/steps.wasm");
+        let mut runtime = count_to(&inst, 500);
+        let mut slices = 0;
+        let result = loop {
+            slices += 1;
+            match runtime.run_for(64).unwrap() {
+                RunStatus::Paused => continue,
+                status => break finished_i32(status),
+            }
+        };
+        assert_eq!(result, 500);
+        assert!(slices > 10);
+
+        let inst = load_instance("tests/wasm/steps.wasm");
+        let ret = count_to(&inst, 500).run().unwrap();
+        assert_eq!(ret.last().unwrap().to_i32().unwrap(), 500);
+    }
+
+    #[test]
+    fn test_time_slice_two_guests() {
+        let first = load_instance("tests/wasm/steps.wasm");
+        let second = load_instance("tests/wasm/steps.wasm");
+        let mut guests = vec![count_to(&first, 300), count_to(&second, 200)];
+        let mut results = vec![None, None];
+
+        while results.iter().any(Option::is_none) {
+            for (guest, result) in guests.iter_mut().zip(results.iter_mut()) {
+                if result.is_none() {
+                    if let RunStatus::Finished(values) = guest.run_for(50).unwrap() {
+                        *result = Some(values.last().unwrap().to_i32().unwrap());
+                    }
+                }
+            }
+        }
+        assert_eq!(results, vec![Some(300), Some(200)]);
+    }
+
+    #[test]
+    fn test_run_for_duration() {
+        let inst = load_instance("tests/wasm/steps.wasm");
+        let mut runtime = count_to(&inst, 100);
+        let status = runtime.run_for_duration(Duration::from_secs(60)).unwrap();
+        assert_eq!(finished_i32(status), 100);
+
+        let inst = load_instance("tests/wasm/steps.wasm");
+        let mut runtime = count_to(&inst, 1_000_000);
+        let status = runtime.run_for_duration(Duration::ZERO).unwrap();
+        assert_eq!(status, RunStatus::Paused);
+        assert!(count(&inst) < 1_000_000);
+    }
+
+    #[test]
+    fn test_checkpoint_paused_runtime() {
+        let path = "temp_run_for_checkpoint.bin";
+        let inst = load_instance("tests/wasm/steps.wasm");
+        let mut runtime = count_to(&inst, 400);
+        assert_eq!(runtime.run_for(1000).unwrap(), RunStatus::Paused);
+        let partial = count(&inst);
+        runtime.checkpoint(path).unwrap();
+        drop(runtime);
+
+        let restored_inst = load_instance("tests/wasm/steps.wasm");
+        let stacks = migration::restore(&[Rc::clone(&restored_inst)], path).unwrap();
+        assert_eq!(count(&restored_inst), partial);
+        let mut runtime = Runtime::new_restored(Rc::clone(&restored_inst), stacks, false, false);
+        let ret = runtime.run().unwrap();
+        assert_eq!(ret.last().unwrap().to_i32().unwrap(), 400);
+
+        let _ = std::fs::remove_file(path);
+    }
+}
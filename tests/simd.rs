@@ -0,0 +1,335 @@
+use chiwawa::{
+    error::RuntimeError, execution::module::*, execution::runtime::Runtime, execution::value::*,
+    parser, structure::module::Module,
+};
+use rustc_hash::FxHashMap;
+use std::rc::Rc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_instance(wasm_path: &str) -> Rc<ModuleInst> {
+        let mut module = Module::new("test");
+        let _ = parser::parse_bytecode(&mut module, wasm_path);
+        let imports: ImportObjects = FxHashMap::default();
+        ModuleInst::new(&module, imports, Vec::new()).unwrap()
+    }
+
+    fn call_function(
+        inst: &Rc<ModuleInst>,
+        func_name: &str,
+        params: Vec<Val>,
+    ) -> Result<Vec<Val>, RuntimeError> {
+        let func_addr = inst.get_export_func(func_name)?;
+        let mut runtime = Runtime::new(Rc::clone(inst), &func_addr, params, true, false)?;
+        runtime.run()
+    }
+
+    fn v128(v: i128) -> Val {
+        Val::Vec_(Vec_::V128(v))
+    }
+
+    fn from_bytes(chunks: impl Iterator<Item = Vec<u8>>) -> Val {
+        let bytes: Vec<u8> = chunks.flatten().collect();
+        v128(i128::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    fn u8x16(lanes: [u8; 16]) -> Val {
+        v128(i128::from_le_bytes(lanes))
+    }
+
+    fn i16x8(lanes: [i16; 8]) -> Val {
+        from_bytes(lanes.iter().map(|x| x.to_le_bytes().to_vec()))
+    }
+
+    fn i32x4(lanes: [i32; 4]) -> Val {
+        from_bytes(lanes.iter().map(|x| x.to_le_bytes().to_vec()))
+    }
+
+    fn i64x2(lanes: [i64; 2]) -> Val {
+        from_bytes(lanes.iter().map(|x| x.to_le_bytes().to_vec()))
+    }
+
+    fn f32x4(lanes: [f32; 4]) -> Val {
+        from_bytes(lanes.iter().map(|x| x.to_le_bytes().to_vec()))
+    }
+
+    fn f64x2(lanes: [f64; 2]) -> Val {
+        from_bytes(lanes.iter().map(|x| x.to_le_bytes().to_vec()))
+    }
+
+    fn result(ret: Result<Vec<Val>, RuntimeError>) -> Val {
+        ret.unwrap().last().unwrap().clone()
+    }
+
+    #[test]
+    fn test_simd_const() {
+        let inst = load_instance("tests/wasm/simd.wasm");
+        let ret = call_function(&inst, "v128.const", vec![]);
+        assert_eq!(result(ret), i32x4([1, 2, 3, 4]));
+    }
+
+    #[test]
+    fn test_simd_integer_arithmetic() {
+        let inst = load_instance("tests/wasm/simd.wasm");
+        let ret = call_function(
+            &inst,
+            "i32x4.add",
+            vec![i32x4([1, 2, 3, i32::MAX]), i32x4([10, 20, 30, 1])],
+        );
+        assert_eq!(result(ret), i32x4([11, 22, 33, i32::MIN]));
+
+        let ret = call_function(
+            &inst,
+            "i8x16.add_sat_u",
+            vec![u8x16([250; 16]), u8x16([3; 16])],
+        );
+        assert_eq!(result(ret), u8x16([253; 16]));
+        let ret = call_function(
+            &inst,
+            "i8x16.add_sat_u",
+            vec![u8x16([250; 16]), u8x16([10; 16])],
+        );
+        assert_eq!(result(ret), u8x16([255; 16]));
+
+        let ret = call_function(
+            &inst,
+            "i16x8.mul",
+            vec![
+                i16x8([1, 2, 3, 4, 5, 6, 0x4000, -8]),
+                i16x8([3, 3, 3, 3, 3, 3, 4, 3]),
+            ],
+        );
+        assert_eq!(result(ret), i16x8([3, 6, 9, 12, 15, 18, 0, -24]));
+
+        let ret = call_function(
+            &inst,
+            "i64x2.sub",
+            vec![i64x2([5, i64::MIN]), i64x2([7, 1])],
+        );
+        assert_eq!(result(ret), i64x2([-2, i64::MAX]));
+
+        let ret = call_function(
+            &inst,
+            "i32x4.dot_i16x8_s",
+            vec![
+                i16x8([-32768, -32768, 1, 2, 3, 4, 5, 6]),
+                i16x8([-32768, -32768, 1, 1, 1, 1, 1, 1]),
+            ],
+        );
+        assert_eq!(result(ret), i32x4([i32::MIN, 3, 7, 11]));
+
+        let ret = call_function(
+            &inst,
+            "i16x8.shl",
+            vec![i16x8([1; 8]), Val::Num(Num::I32(17))],
+        );
+        assert_eq!(result(ret), i16x8([2; 8]));
+
+        let ret = call_function(
+            &inst,
+            "i32x4.gt_s",
+            vec![i32x4([1, -1, 5, 0]), i32x4([0, 0, 5, -1])],
+        );
+        assert_eq!(result(ret), i32x4([-1, 0, 0, -1]));
+    }
+
+    #[test]
+    fn test_simd_float_arithmetic() {
+        let inst = load_instance("tests/wasm/simd.wasm");
+        let ret = call_function(
+            &inst,
+            "f32x4.min",
+            vec![
+                f32x4([1.0, -0.0, f32::NAN, 3.0]),
+                f32x4([2.0, 0.0, 1.0, f32::NEG_INFINITY]),
+            ],
+        );
+        let Val::Vec_(Vec_::V128(v)) = result(ret) else {
+            panic!("expected v128");
+        };
+        let lanes: Vec<f32> = v
+            .to_le_bytes()
+            .chunks(4)
+            .map(|c| f32::from_le_bytes(c.try_into().unwrap()))
+            .collect();
+        assert_eq!(lanes[0], 1.0);
+        assert!(lanes[1] == 0.0 && lanes[1].is_sign_negative());
+        assert!(lanes[2].is_nan());
+        assert_eq!(lanes[3], f32::NEG_INFINITY);
+
+        let ret = call_function(
+            &inst,
+            "f64x2.mul",
+            vec![f64x2([1.5, -2.0]), f64x2([2.0, 4.0])],
+        );
+        assert_eq!(result(ret), f64x2([3.0, -8.0]));
+    }
+
+    #[test]
+    fn test_simd_conversions() {
+        let inst = load_instance("tests/wasm/simd.wasm");
+        let ret = call_function(
+            &inst,
+            "i16x8.narrow_i32x4_s",
+            vec![i32x4([1, 40000, -40000, 5]), i32x4([-1, 32767, -32768, 0])],
+        );
+        assert_eq!(
+            result(ret),
+            i16x8([1, 32767, -32768, 5, -1, 32767, -32768, 0])
+        );
+
+        let ret = call_function(
+            &inst,
+            "i32x4.extend_high_i16x8_u",
+            vec![i16x8([0, 0, 0, 0, -1, 1, 2, 3])],
+        );
+        assert_eq!(result(ret), i32x4([65535, 1, 2, 3]));
+
+        let ret = call_function(
+            &inst,
+            "i32x4.trunc_sat_f32x4_s",
+            vec![f32x4([1.9, -1.9, f32::NAN, 3e10])],
+        );
+        assert_eq!(result(ret), i32x4([1, -1, 0, i32::MAX]));
+    }
+
+    #[test]
+    fn test_simd_bitwise() {
+        let inst = load_instance("tests/wasm/simd.wasm");
+        let mask = 0x00ff_00ff_00ff_00ff_00ff_00ff_00ff_00ff_i128;
+        let ret = call_function(&inst, "v128.bitselect", vec![v128(-1), v128(0), v128(mask)]);
+        assert_eq!(result(ret), v128(mask));
+
+        let ret = call_function(&inst, "v128.any_true", vec![v128(0)]);
+        assert_eq!(result(ret).to_i32().unwrap(), 0);
+        let ret = call_function(&inst, "v128.any_true", vec![v128(1 << 100)]);
+        assert_eq!(result(ret).to_i32().unwrap(), 1);
+
+        let ret = call_function(&inst, "i32x4.all_true", vec![i32x4([1, 2, 3, 0])]);
+        assert_eq!(result(ret).to_i32().unwrap(), 0);
+        let ret = call_function(&inst, "i32x4.all_true", vec![i32x4([1, 2, 3, 4])]);
+        assert_eq!(result(ret).to_i32().unwrap(), 1);
+
+        let mut lanes = [1u8; 16];
+        lanes[0] = 0x80;
+        lanes[15] = 0xff;
+        let ret = call_function(&inst, "i8x16.bitmask", vec![u8x16(lanes)]);
+        assert_eq!(result(ret).to_i32().unwrap(), 0x8001);
+    }
+
+    #[test]
+    fn test_simd_lanes() {
+        let inst = load_instance("tests/wasm/simd.wasm");
+        let a: [u8; 16] = std::array::from_fn(|i| i as u8);
+        let b: [u8; 16] = std::array::from_fn(|i| i as u8 + 16);
+        let ret = call_function(&inst, "i8x16.shuffle", vec![u8x16(a), u8x16(b)]);
+        let expected: [u8; 16] = std::array::from_fn(|i| (i / 2) as u8 + 16 * (i % 2) as u8);
+        assert_eq!(result(ret), u8x16(expected));
+
+        let a: [u8; 16] = std::array::from_fn(|i| i as u8 + 100);
+        let mut s: [u8; 16] = std::array::from_fn(|i| 15 - i as u8);
+        s[15] = 200;
+        let ret = call_function(&inst, "i8x16.swizzle", vec![u8x16(a), u8x16(s)]);
+        let mut expected: [u8; 16] = std::array::from_fn(|i| 115 - i as u8);
+        expected[15] = 0;
+        assert_eq!(result(ret), u8x16(expected));
+
+        let ret = call_function(&inst, "i32x4.splat", vec![Val::Num(Num::I32(7))]);
+        assert_eq!(result(ret), i32x4([7; 4]));
+
+        let mut lanes = [0u8; 16];
+        lanes[15] = 0xff;
+        let ret = call_function(&inst, "i8x16.extract_lane_s", vec![u8x16(lanes)]);
+        assert_eq!(result(ret).to_i32().unwrap(), -1);
+
+        let ret = call_function(
+            &inst,
+            "i64x2.replace_lane",
+            vec![i64x2([1, 2]), Val::Num(Num::I64(99))],
+        );
+        assert_eq!(result(ret), i64x2([1, 99]));
+
+        let ret = call_function(&inst, "f64x2.extract_lane", vec![f64x2([0.5, -2.25])]);
+        assert_eq!(result(ret).to_f64().unwrap(), -2.25);
+    }
+
+    #[test]
+    fn test_simd_memory() {
+        let inst = load_instance("tests/wasm/simd.wasm");
+        let data: [u8; 16] = [
+            0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0xf8, 0xf9, 0xfa, 0xfb, 0xfc, 0xfd,
+            0xfe, 0xff,
+        ];
+        let ret = call_function(&inst, "v128.load", vec![Val::Num(Num::I32(0))]);
+        assert_eq!(result(ret), u8x16(data));
+
+        let ret = call_function(&inst, "v128.load8x8_s", vec![Val::Num(Num::I32(8))]);
+        assert_eq!(result(ret), i16x8([-8, -7, -6, -5, -4, -3, -2, -1]));
+
+        let ret = call_function(&inst, "v128.load32_splat", vec![Val::Num(Num::I32(4))]);
+        assert_eq!(result(ret), i32x4([0x08070605; 4]));
+
+        let ret = call_function(&inst, "v128.load64_zero", vec![Val::Num(Num::I32(0))]);
+        assert_eq!(result(ret), i64x2([0x0807060504030201, 0]));
+
+        let ret = call_function(
+            &inst,
+            "v128.load16_lane",
+            vec![Val::Num(Num::I32(0)), v128(0)],
+        );
+        assert_eq!(result(ret), i16x8([0, 0, 0, 0, 0, 0, 0, 0x0201]));
+
+        let value = i32x4([1, 2, 3, 4]);
+        let ret = call_function(
+            &inst,
+            "store-load",
+            vec![Val::Num(Num::I32(100)), value.clone()],
+        );
+        assert_eq!(result(ret), value);
+
+        let ret = call_function(&inst, "store32_lane", vec![Val::Num(Num::I32(200)), value]);
+        assert_eq!(result(ret).to_i32().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_simd_memory_out_of_bounds() {
+        let inst = load_instance("tests/wasm/simd.wasm");
+        let ret = call_function(&inst, "v128.load", vec![Val::Num(Num::I32(65520))]);
+        assert!(ret.is_ok());
+        let ret = call_function(&inst, "v128.load", vec![Val::Num(Num::I32(65521))]);
+        assert_eq!(ret, Err(RuntimeError::MemoryOutOfBounds));
+        let ret = call_function(&inst, "v128.load", vec![Val::Num(Num::I32(-1))]);
+        assert_eq!(ret, Err(RuntimeError::MemoryOutOfBounds));
+        let ret = call_function(
+            &inst,
+            "store-load",
+            vec![Val::Num(Num::I32(65528)), v128(0)],
+        );
+        assert_eq!(ret, Err(RuntimeError::MemoryOutOfBounds));
+    }
+
+    #[test]
+    fn test_simd_locals_select_globals() {
+        let inst = load_instance("tests/wasm/simd.wasm");
+        let ret = call_function(&inst, "local-tee", vec![i32x4([1, 2, 3, 4])]);
+        assert_eq!(result(ret), i32x4([2, 4, 6, 8]));
+
+        let (a, b) = (i32x4([1, 1, 1, 1]), i32x4([2, 2, 2, 2]));
+        let ret = call_function(
+            &inst,
+            "select",
+            vec![a.clone(), b.clone(), Val::Num(Num::I32(1))],
+        );
+        assert_eq!(result(ret), a);
+        let ret = call_function(&inst, "select", vec![a, b.clone(), Val::Num(Num::I32(0))]);
+        assert_eq!(result(ret), b);
+
+        let ret = call_function(&inst, "global", vec![i64x2([-1, 42])]);
+        assert_eq!(result(ret), i64x2([-1, 42]));
+
+        let ret = call_function(&inst, "call", vec![i32x4([1, 2, 3, 4])]);
+        assert_eq!(result(ret), i32x4([2, 4, 6, 8]));
+    }
+}
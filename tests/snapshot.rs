@@ -0,0 +1,133 @@
+use chiwawa::{
+    error::RuntimeError,
+    execution::host::Caller,
+    execution::linker::Linker,
+    execution::module::*,
+    execution::runtime::{RunStatus, Runtime},
+    execution::value::*,
+    parser,
+    structure::module::Module,
+};
+use std::path::Path;
+use std::rc::Rc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instantiate() -> Rc<ModuleInst> {
+        let mut module = Module::new("test");
+        let _ = parser::parse_bytecode(&mut module, "tests/wasm/snapshot.wasm");
+        let mut linker = Linker::new();
+        linker.func_wrap("env", "yield", |caller: &Caller| {
+            caller.request_checkpoint()
+        });
+        linker
+            .instantiate_without_start("snapshot", &module, Vec::new())
+            .unwrap()
+    }
+
+    fn count_to(inst: &Rc<ModuleInst>, n: i32, yield_at: i32) -> Runtime {
+        let func_addr = inst.get_export_func("count-to").unwrap();
+        let params = vec![Val::Num(Num::I32(n)), Val::Num(Num::I32(yield_at))];
+        Runtime::new(Rc::clone(inst), &func_addr, params, false, true).unwrap()
+    }
+
+    fn count(inst: &Rc<ModuleInst>) -> i32 {
+        inst.global_addrs[0].get().to_i32().unwrap()
+    }
+
+    #[test]
+    fn test_host_requested_snapshot() {
+        let inst = instantiate();
+        let mut runtime = count_to(&inst, 300, 120);
+        assert_eq!(
+            runtime.run_for(u64::MAX).unwrap(),
+            RunStatus::CheckpointRequested
+        );
+        assert_eq!(count(&inst), 120);
+        assert!(!Path::new("./checkpoint.bin").exists());
+
+        let bytes = runtime.snapshot().unwrap();
+
+        // The original runtime keeps its state and can finish
+        let ret = runtime.resume().unwrap();
+        assert_eq!(ret.last().unwrap().to_i32().unwrap(), 300);
+
+        // A fresh instance resumes from the snapshot
+        let restored_inst = instantiate();
+        let mut restored = Runtime::from_snapshot(Rc::clone(&restored_inst), &bytes).unwrap();
+        assert_eq!(count(&restored_inst), 120);
+        let ret = restored.run().unwrap();
+        assert_eq!(ret.last().unwrap().to_i32().unwrap(), 300);
+    }
+
+    #[test]
+    fn test_requested_checkpoint_from_run() {
+        let inst = instantiate();
+        let mut runtime = count_to(&inst, 50, 10);
+        assert_eq!(runtime.run(), Err(RuntimeError::CheckpointRequested));
+        assert!(!Path::new("./checkpoint.bin").exists());
+        let ret = runtime.resume().unwrap();
+        assert_eq!(ret.last().unwrap().to_i32().unwrap(), 50);
+    }
+
+    #[test]
+    fn test_snapshot_of_paused_runtime() {
+        let inst = instantiate();
+        let mut runtime = count_to(&inst, 500, -1);
+        assert_eq!(runtime.run_for(1000).unwrap(), RunStatus::Paused);
+        let partial = count(&inst);
+        let bytes = runtime.snapshot().unwrap();
+
+        let restored_inst = instantiate();
+        let mut restored = Runtime::from_snapshot(Rc::clone(&restored_inst), &bytes).unwrap();
+        assert_eq!(count(&restored_inst), partial);
+        let ret = restored.run().unwrap();
+        assert_eq!(ret.last().unwrap().to_i32().unwrap(), 500);
+    }
+
+    #[test]
+    fn test_restored_runtime_ignores_trigger_file() {
+        let inst = instantiate();
+        let mut runtime = count_to(&inst, 2000, 10);
+        assert_eq!(
+            runtime.run_for(u64::MAX).unwrap(),
+            RunStatus::CheckpointRequested
+        );
+        let bytes = runtime.snapshot().unwrap();
+
+        // A trigger file in the working directory must not stop the restored guest
+        std::fs::write("./checkpoint.trigger", b"").unwrap();
+        let restored_inst = instantiate();
+        let mut restored = Runtime::from_snapshot(Rc::clone(&restored_inst), &bytes).unwrap();
+        let ret = restored.run();
+        let _ = std::fs::remove_file("./checkpoint.trigger");
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 2000);
+        assert!(!Path::new("./checkpoint.bin").exists());
+    }
+
+    #[test]
+    fn test_checkpoint_requests_are_per_runtime() {
+        let inst = instantiate();
+        let mut requested = count_to(&inst, 50, -1);
+        let other_inst = instantiate();
+        let mut other = count_to(&other_inst, 50, -1);
+
+        requested.checkpoint_request().request();
+        let ret = other.run().unwrap();
+        assert_eq!(ret.last().unwrap().to_i32().unwrap(), 50);
+        assert_eq!(
+            requested.run_for(u64::MAX).unwrap(),
+            RunStatus::CheckpointRequested
+        );
+        assert_eq!(count(&inst), 0);
+    }
+
+    #[test]
+    fn test_from_snapshot_rejects_garbage() {
+        let inst = instantiate();
+        let ret = Runtime::from_snapshot(inst, &[0xff; 8]);
+        assert!(matches!(ret, Err(RuntimeError::CheckpointLoadError(_))));
+    }
+}
@@ -0,0 +1,73 @@
+use chiwawa::{
+    error::RuntimeError, execution::module::*, execution::runtime::Runtime, execution::value::*,
+    parser, structure::module::Module,
+};
+use rustc_hash::FxHashMap;
+use std::rc::Rc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_module(wasm_path: &str) -> Module {
+        let mut module = Module::new("test");
+        let _ = parser::parse_bytecode(&mut module, wasm_path);
+        module
+    }
+
+    fn call_function(
+        inst: &Rc<ModuleInst>,
+        func_name: &str,
+        params: Vec<Val>,
+    ) -> Result<Vec<Val>, RuntimeError> {
+        let func_addr = inst.get_export_func(func_name)?;
+        let mut runtime = Runtime::new(Rc::clone(inst), &func_addr, params, true, false)?;
+        runtime.run()
+    }
+
+    #[test]
+    fn test_start_runs_on_instantiation() {
+        let module = load_module("tests/wasm/start.wasm");
+        let imports: ImportObjects = FxHashMap::default();
+        let inst = ModuleInst::new(&module, imports, Vec::new()).unwrap();
+
+        let ret = call_function(&inst, "get", vec![]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 2);
+        let ret = call_function(&inst, "load", vec![]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 42);
+    }
+
+    #[test]
+    fn test_start_deferred() {
+        let module = load_module("tests/wasm/start.wasm");
+        let imports: ImportObjects = FxHashMap::default();
+        let inst = ModuleInst::new_without_start(&module, imports, Vec::new()).unwrap();
+
+        let ret = call_function(&inst, "get", vec![]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 0);
+
+        inst.run_start(None, &[]).unwrap();
+        let ret = call_function(&inst, "get", vec![]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 2);
+        let ret = call_function(&inst, "load", vec![]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 42);
+    }
+
+    #[test]
+    fn test_start_trap() {
+        let module = load_module("tests/wasm/start_trap.wasm");
+        let imports: ImportObjects = FxHashMap::default();
+        let ret = ModuleInst::new(&module, imports, Vec::new());
+        assert!(matches!(ret, Err(RuntimeError::Unreachable)));
+
+        // Effects before the trap remain visible in the instance.
+        let imports: ImportObjects = FxHashMap::default();
+        let inst = ModuleInst::new_without_start(&module, imports, Vec::new()).unwrap();
+        assert!(matches!(
+            inst.run_start(None, &[]),
+            Err(RuntimeError::Unreachable)
+        ));
+        let ret = call_function(&inst, "load", vec![]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 1);
+    }
+}
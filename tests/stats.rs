@@ -0,0 +1,77 @@
+#![cfg(feature = "stats")]
+
+use chiwawa::{
+    execution::linker::Linker,
+    execution::module::*,
+    execution::runtime::Runtime,
+    execution::stats::{FuncKey, FunctionStats},
+    parser,
+    structure::module::Module,
+};
+use std::rc::Rc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_module(wasm_path: &str) -> Module {
+        let mut module = Module::new("test");
+        let _ = parser::parse_bytecode(&mut module, wasm_path);
+        module
+    }
+
+    /// Instantiates `link_lib` as "lib" and `link_main` as "app".
+    fn link() -> (Rc<ModuleInst>, Rc<ModuleInst>) {
+        let mut linker = Linker::new();
+        let lib = linker
+            .instantiate("lib", &load_module("tests/wasm/link_lib.wasm"), Vec::new())
+            .unwrap();
+        let app = linker
+            .instantiate("app", &load_module("tests/wasm/link_main.wasm"), Vec::new())
+            .unwrap();
+        (lib, app)
+    }
+
+    /// Runs `func_name` with statistics enabled and returns the counts of
+    /// function `func` of instance `instance`.
+    fn run_with_stats(
+        inst: &Rc<ModuleInst>,
+        func_name: &str,
+        instance: u32,
+        func: u32,
+    ) -> FunctionStats {
+        let func_addr = inst.get_export_func(func_name).unwrap();
+        let mut runtime =
+            Runtime::new(Rc::clone(inst), &func_addr, Vec::new(), true, false).unwrap();
+        runtime.run().unwrap();
+        let stats = runtime.execution_stats().unwrap().function_stats();
+        stats
+            .get(&FuncKey { instance, func })
+            .copied()
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_counts_functions_of_linked_instances() {
+        let (lib, app) = link();
+
+        // `bump` is function 1 of `lib`
+        let bump = run_with_stats(&lib, "bump", 0, 1);
+        assert_eq!(bump.calls, 1);
+        assert!(bump.instructions > 0);
+
+        // `bump-twice` is function 4 of `app` (after the two imported
+        // functions); calls into `lib` are counted against `lib`, the
+        // second instance seen
+        let bump_twice = run_with_stats(&app, "bump-twice", 0, 4);
+        assert_eq!(bump_twice.calls, 1);
+        assert!(bump_twice.instructions > 0);
+        let linked_bump = run_with_stats(&app, "bump-twice", 1, 1);
+        assert_eq!(linked_bump.calls, 2);
+        assert_eq!(linked_bump.instructions, 2 * bump.instructions);
+
+        // The import's slot in `app` is not counted separately
+        let imported_slot = run_with_stats(&app, "bump-twice", 0, 1);
+        assert_eq!(imported_slot.calls, 0);
+    }
+}
@@ -0,0 +1,177 @@
+use chiwawa::{
+    error::RuntimeError, execution::module::*, execution::runtime::Runtime, execution::value::*,
+    parser, structure::module::Module,
+};
+use rustc_hash::FxHashMap;
+use std::rc::Rc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_instance(wasm_path: &str) -> Rc<ModuleInst> {
+        let mut module = Module::new("test");
+        let _ = parser::parse_bytecode(&mut module, wasm_path);
+        let imports: ImportObjects = FxHashMap::default();
+        ModuleInst::new(&module, imports, Vec::new()).unwrap()
+    }
+
+    fn call_function(
+        inst: &Rc<ModuleInst>,
+        func_name: &str,
+        params: Vec<Val>,
+    ) -> Result<Vec<Val>, RuntimeError> {
+        let func_addr = inst.get_export_func(func_name)?;
+        let mut runtime = Runtime::new(Rc::clone(inst), &func_addr, params, true, false)?;
+        runtime.run()
+    }
+
+    fn check(inst: &Rc<ModuleInst>, func_name: &str, i: i32) -> Result<i32, RuntimeError> {
+        let ret = call_function(inst, func_name, vec![Val::Num(Num::I32(i))])?;
+        Ok(ret.last().unwrap().to_i32().unwrap())
+    }
+
+    fn copy(
+        inst: &Rc<ModuleInst>,
+        func_name: &str,
+        d: i32,
+        s: i32,
+        n: i32,
+    ) -> Result<Vec<Val>, RuntimeError> {
+        call_function(
+            inst,
+            func_name,
+            vec![
+                Val::Num(Num::I32(d)),
+                Val::Num(Num::I32(s)),
+                Val::Num(Num::I32(n)),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_table_copy_initial() {
+        let inst = load_instance("tests/wasm/table_copy.wasm");
+
+        // (assert_trap (invoke "check-t0" (i32.const 1)) "uninitialized element")
+        let ret = check(&inst, "check-t0", 1);
+        assert!(matches!(ret, Err(RuntimeError::UninitializedElement)));
+        // (assert_return (invoke "check-t0" (i32.const 2)) (i32.const 3))
+        assert_eq!(check(&inst, "check-t0", 2).unwrap(), 3);
+        // (assert_return (invoke "check-t0" (i32.const 3)) (i32.const 1))
+        assert_eq!(check(&inst, "check-t0", 3).unwrap(), 1);
+        // (assert_return (invoke "check-t0" (i32.const 4)) (i32.const 4))
+        assert_eq!(check(&inst, "check-t0", 4).unwrap(), 4);
+        // (assert_return (invoke "check-t0" (i32.const 5)) (i32.const 1))
+        assert_eq!(check(&inst, "check-t0", 5).unwrap(), 1);
+        // (assert_trap (invoke "check-t0" (i32.const 6)) "uninitialized element")
+        let ret = check(&inst, "check-t0", 6);
+        assert!(matches!(ret, Err(RuntimeError::UninitializedElement)));
+    }
+
+    #[test]
+    fn test_table_copy_overlap_forward() {
+        let inst = load_instance("tests/wasm/table_copy.wasm");
+
+        // (invoke "copy" (i32.const 3) (i32.const 2) (i32.const 4))
+        assert!(copy(&inst, "copy", 3, 2, 4).is_ok());
+
+        // (assert_trap (invoke "check-t0" (i32.const 1)) "uninitialized element")
+        let ret = check(&inst, "check-t0", 1);
+        assert!(matches!(ret, Err(RuntimeError::UninitializedElement)));
+        // (assert_return (invoke "check-t0" (i32.const 2)) (i32.const 3))
+        assert_eq!(check(&inst, "check-t0", 2).unwrap(), 3);
+        // (assert_return (invoke "check-t0" (i32.const 3)) (i32.const 3))
+        assert_eq!(check(&inst, "check-t0", 3).unwrap(), 3);
+        // (assert_return (invoke "check-t0" (i32.const 4)) (i32.const 1))
+        assert_eq!(check(&inst, "check-t0", 4).unwrap(), 1);
+        // (assert_return (invoke "check-t0" (i32.const 5)) (i32.const 4))
+        assert_eq!(check(&inst, "check-t0", 5).unwrap(), 4);
+        // (assert_return (invoke "check-t0" (i32.const 6)) (i32.const 1))
+        assert_eq!(check(&inst, "check-t0", 6).unwrap(), 1);
+        // (assert_trap (invoke "check-t0" (i32.const 7)) "uninitialized element")
+        let ret = check(&inst, "check-t0", 7);
+        assert!(matches!(ret, Err(RuntimeError::UninitializedElement)));
+    }
+
+    #[test]
+    fn test_table_copy_overlap_backward() {
+        let inst = load_instance("tests/wasm/table_copy.wasm");
+
+        // (invoke "copy" (i32.const 1) (i32.const 2) (i32.const 4))
+        assert!(copy(&inst, "copy", 1, 2, 4).is_ok());
+
+        // (assert_trap (invoke "check-t0" (i32.const 0)) "uninitialized element")
+        let ret = check(&inst, "check-t0", 0);
+        assert!(matches!(ret, Err(RuntimeError::UninitializedElement)));
+        // (assert_return (invoke "check-t0" (i32.const 1)) (i32.const 3))
+        assert_eq!(check(&inst, "check-t0", 1).unwrap(), 3);
+        // (assert_return (invoke "check-t0" (i32.const 2)) (i32.const 1))
+        assert_eq!(check(&inst, "check-t0", 2).unwrap(), 1);
+        // (assert_return (invoke "check-t0" (i32.const 3)) (i32.const 4))
+        assert_eq!(check(&inst, "check-t0", 3).unwrap(), 4);
+        // (assert_return (invoke "check-t0" (i32.const 4)) (i32.const 1))
+        assert_eq!(check(&inst, "check-t0", 4).unwrap(), 1);
+        // (assert_return (invoke "check-t0" (i32.const 5)) (i32.const 1))
+        assert_eq!(check(&inst, "check-t0", 5).unwrap(), 1);
+        // (assert_trap (invoke "check-t0" (i32.const 6)) "uninitialized element")
+        let ret = check(&inst, "check-t0", 6);
+        assert!(matches!(ret, Err(RuntimeError::UninitializedElement)));
+    }
+
+    #[test]
+    fn test_table_copy_between_tables() {
+        let inst = load_instance("tests/wasm/table_copy.wasm");
+
+        // (invoke "copy-t1-to-t0" (i32.const 0) (i32.const 6) (i32.const 2))
+        assert!(copy(&inst, "copy-t1-to-t0", 0, 6, 2).is_ok());
+
+        // (assert_return (invoke "check-t0" (i32.const 0)) (i32.const 2))
+        assert_eq!(check(&inst, "check-t0", 0).unwrap(), 2);
+        // (assert_return (invoke "check-t0" (i32.const 1)) (i32.const 4))
+        assert_eq!(check(&inst, "check-t0", 1).unwrap(), 4);
+        // (assert_return (invoke "check-t0" (i32.const 2)) (i32.const 3))
+        assert_eq!(check(&inst, "check-t0", 2).unwrap(), 3);
+        // (assert_return (invoke "check-t1" (i32.const 6)) (i32.const 2))
+        assert_eq!(check(&inst, "check-t1", 6).unwrap(), 2);
+        // (assert_return (invoke "check-t1" (i32.const 7)) (i32.const 4))
+        assert_eq!(check(&inst, "check-t1", 7).unwrap(), 4);
+    }
+
+    #[test]
+    fn test_table_copy_out_of_bounds() {
+        let inst = load_instance("tests/wasm/table_copy.wasm");
+
+        // (assert_trap (invoke "copy" (i32.const 8) (i32.const 0) (i32.const 3)) "out of bounds table access")
+        let ret = copy(&inst, "copy", 8, 0, 3);
+        assert!(matches!(ret, Err(RuntimeError::TableOutOfBounds)));
+
+        // (assert_trap (invoke "copy" (i32.const 0) (i32.const 8) (i32.const 3)) "out of bounds table access")
+        let ret = copy(&inst, "copy", 0, 8, 3);
+        assert!(matches!(ret, Err(RuntimeError::TableOutOfBounds)));
+
+        // (assert_trap (invoke "copy-t1-to-t0" (i32.const 0) (i32.const 9) (i32.const 2)) "out of bounds table access")
+        let ret = copy(&inst, "copy-t1-to-t0", 0, 9, 2);
+        assert!(matches!(ret, Err(RuntimeError::TableOutOfBounds)));
+
+        // (assert_return (invoke "copy" (i32.const 10) (i32.const 0) (i32.const 0)))
+        assert!(copy(&inst, "copy", 10, 0, 0).is_ok());
+
+        // (assert_return (invoke "copy" (i32.const 0) (i32.const 10) (i32.const 0)))
+        assert!(copy(&inst, "copy", 0, 10, 0).is_ok());
+
+        // (assert_trap (invoke "copy" (i32.const 11) (i32.const 0) (i32.const 0)) "out of bounds table access")
+        let ret = copy(&inst, "copy", 11, 0, 0);
+        assert!(matches!(ret, Err(RuntimeError::TableOutOfBounds)));
+
+        // (assert_trap (invoke "copy" (i32.const 0) (i32.const 11) (i32.const 0)) "out of bounds table access")
+        let ret = copy(&inst, "copy", 0, 11, 0);
+        assert!(matches!(ret, Err(RuntimeError::TableOutOfBounds)));
+
+        // (assert_return (invoke "check-t0" (i32.const 2)) (i32.const 3))
+        assert_eq!(check(&inst, "check-t0", 2).unwrap(), 3);
+        // (assert_trap (invoke "check-t0" (i32.const 8)) "uninitialized element")
+        let ret = check(&inst, "check-t0", 8);
+        assert!(matches!(ret, Err(RuntimeError::UninitializedElement)));
+    }
+}
@@ -223,4 +223,39 @@ mod tests {
         let ret = call_function(&inst, "get", vec![Val::Num(Num::I32(9))]);
         assert_eq!(ret.unwrap().last().unwrap(), &Val::Ref(Ref::RefNull));
     }
+
+    #[test]
+    fn test_table_fill_out_of_bounds() {
+        let inst = load_instance("tests/wasm/table_fill.wasm");
+
+        // (assert_trap (invoke "fill" (i32.const 8) (ref.null extern) (i32.const 3)) "out of bounds table access")
+        let ret = call_function(
+            &inst,
+            "fill",
+            vec![
+                Val::Num(Num::I32(8)),
+                Val::Ref(Ref::RefNull),
+                Val::Num(Num::I32(3)),
+            ],
+        );
+        assert!(matches!(
+            ret,
+            Err(chiwawa::error::RuntimeError::TableOutOfBounds)
+        ));
+
+        // (assert_trap (invoke "fill" (i32.const 11) (ref.null extern) (i32.const 0)) "out of bounds table access")
+        let ret = call_function(
+            &inst,
+            "fill",
+            vec![
+                Val::Num(Num::I32(11)),
+                Val::Ref(Ref::RefNull),
+                Val::Num(Num::I32(0)),
+            ],
+        );
+        assert!(matches!(
+            ret,
+            Err(chiwawa::error::RuntimeError::TableOutOfBounds)
+        ));
+    }
 }
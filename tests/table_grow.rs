@@ -0,0 +1,193 @@
+use chiwawa::{
+    error::RuntimeError, execution::func::FuncAddr, execution::module::*,
+    execution::runtime::Runtime, execution::value::*, parser, structure::module::Module,
+};
+use rustc_hash::FxHashMap;
+use std::rc::Rc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_instance(wasm_path: &str) -> Rc<ModuleInst> {
+        let mut module = Module::new("test");
+        let _ = parser::parse_bytecode(&mut module, wasm_path);
+        let imports: ImportObjects = FxHashMap::default();
+        ModuleInst::new(&module, imports, Vec::new()).unwrap()
+    }
+
+    fn call_function(
+        inst: &Rc<ModuleInst>,
+        func_name: &str,
+        params: Vec<Val>,
+    ) -> Result<Vec<Val>, RuntimeError> {
+        let func_addr = inst.get_export_func(func_name)?;
+        let mut runtime = Runtime::new(Rc::clone(inst), &func_addr, params, true, false)?;
+        runtime.run()
+    }
+
+    fn i32_result(ret: Result<Vec<Val>, RuntimeError>) -> i32 {
+        ret.unwrap().last().unwrap().to_i32().unwrap()
+    }
+
+    #[test]
+    fn test_table_grow() {
+        let inst = load_instance("tests/wasm/table_grow.wasm");
+
+        let extern_2 = Val::Ref(Ref::RefExtern(ExternAddr::new(Externval::Func(
+            FuncAddr::alloc_empty(),
+        ))));
+        let extern_3 = Val::Ref(Ref::RefExtern(ExternAddr::new(Externval::Func(
+            FuncAddr::alloc_empty(),
+        ))));
+        let extern_4 = Val::Ref(Ref::RefExtern(ExternAddr::new(Externval::Func(
+            FuncAddr::alloc_empty(),
+        ))));
+
+        // (assert_return (invoke "size") (i32.const 0))
+        assert_eq!(i32_result(call_function(&inst, "size", vec![])), 0);
+
+        // (assert_trap (invoke "set" (i32.const 0) (ref.extern 2)) "out of bounds table access")
+        let ret = call_function(&inst, "set", vec![Val::Num(Num::I32(0)), extern_2.clone()]);
+        assert!(matches!(ret, Err(RuntimeError::TableOutOfBounds)));
+
+        // (assert_trap (invoke "get" (i32.const 0)) "out of bounds table access")
+        let ret = call_function(&inst, "get", vec![Val::Num(Num::I32(0))]);
+        assert!(matches!(ret, Err(RuntimeError::TableOutOfBounds)));
+
+        // (assert_return (invoke "grow" (i32.const 1) (ref.null extern)) (i32.const 0))
+        let ret = call_function(
+            &inst,
+            "grow",
+            vec![Val::Num(Num::I32(1)), Val::Ref(Ref::RefNull)],
+        );
+        assert_eq!(i32_result(ret), 0);
+
+        // (assert_return (invoke "size") (i32.const 1))
+        assert_eq!(i32_result(call_function(&inst, "size", vec![])), 1);
+
+        // (assert_return (invoke "get" (i32.const 0)) (ref.null extern))
+        let ret = call_function(&inst, "get", vec![Val::Num(Num::I32(0))]);
+        assert_eq!(ret.unwrap().last().unwrap(), &Val::Ref(Ref::RefNull));
+
+        // (assert_return (invoke "set" (i32.const 0) (ref.extern 2)))
+        let ret = call_function(&inst, "set", vec![Val::Num(Num::I32(0)), extern_2.clone()]);
+        assert!(ret.is_ok());
+
+        // (assert_return (invoke "get" (i32.const 0)) (ref.extern 2))
+        let ret = call_function(&inst, "get", vec![Val::Num(Num::I32(0))]);
+        assert_eq!(ret.unwrap().last().unwrap(), &extern_2);
+
+        // (assert_trap (invoke "set" (i32.const 1) (ref.extern 2)) "out of bounds table access")
+        let ret = call_function(&inst, "set", vec![Val::Num(Num::I32(1)), extern_2.clone()]);
+        assert!(matches!(ret, Err(RuntimeError::TableOutOfBounds)));
+
+        // (assert_trap (invoke "get" (i32.const 1)) "out of bounds table access")
+        let ret = call_function(&inst, "get", vec![Val::Num(Num::I32(1))]);
+        assert!(matches!(ret, Err(RuntimeError::TableOutOfBounds)));
+
+        // (assert_return (invoke "grow-abbrev" (i32.const 4) (ref.extern 3)) (i32.const 1))
+        let ret = call_function(
+            &inst,
+            "grow-abbrev",
+            vec![Val::Num(Num::I32(4)), extern_3.clone()],
+        );
+        assert_eq!(i32_result(ret), 1);
+
+        // (assert_return (invoke "size") (i32.const 5))
+        assert_eq!(i32_result(call_function(&inst, "size", vec![])), 5);
+
+        // (assert_return (invoke "get" (i32.const 0)) (ref.extern 2))
+        let ret = call_function(&inst, "get", vec![Val::Num(Num::I32(0))]);
+        assert_eq!(ret.unwrap().last().unwrap(), &extern_2);
+
+        // (assert_return (invoke "get" (i32.const 1)) (ref.extern 3))
+        let ret = call_function(&inst, "get", vec![Val::Num(Num::I32(1))]);
+        assert_eq!(ret.unwrap().last().unwrap(), &extern_3);
+
+        // (assert_return (invoke "get" (i32.const 4)) (ref.extern 3))
+        let ret = call_function(&inst, "get", vec![Val::Num(Num::I32(4))]);
+        assert_eq!(ret.unwrap().last().unwrap(), &extern_3);
+
+        // (assert_return (invoke "set" (i32.const 4) (ref.extern 4)))
+        let ret = call_function(&inst, "set", vec![Val::Num(Num::I32(4)), extern_4.clone()]);
+        assert!(ret.is_ok());
+
+        // (assert_return (invoke "get" (i32.const 4)) (ref.extern 4))
+        let ret = call_function(&inst, "get", vec![Val::Num(Num::I32(4))]);
+        assert_eq!(ret.unwrap().last().unwrap(), &extern_4);
+
+        // (assert_trap (invoke "set" (i32.const 5) (ref.extern 2)) "out of bounds table access")
+        let ret = call_function(&inst, "set", vec![Val::Num(Num::I32(5)), extern_2.clone()]);
+        assert!(matches!(ret, Err(RuntimeError::TableOutOfBounds)));
+
+        // (assert_trap (invoke "get" (i32.const 5)) "out of bounds table access")
+        let ret = call_function(&inst, "get", vec![Val::Num(Num::I32(5))]);
+        assert!(matches!(ret, Err(RuntimeError::TableOutOfBounds)));
+    }
+
+    #[test]
+    fn test_table_grow_huge() {
+        let inst = load_instance("tests/wasm/table_grow.wasm");
+
+        // (assert_return (invoke "grow-huge") (i32.const -1))
+        assert_eq!(i32_result(call_function(&inst, "grow-huge", vec![])), -1);
+
+        // Within u32 range but far above the declared maximum, so it is
+        // refused before anything is allocated
+        let ret = call_function(&inst, "grow-max", vec![Val::Num(Num::I32(0x1000_0000))]);
+        assert_eq!(i32_result(ret), -1);
+        let ret = call_function(&inst, "grow-max", vec![Val::Num(Num::I32(0))]);
+        assert_eq!(i32_result(ret), 0);
+    }
+
+    /// On 32-bit hosts (such as wasm32-wasip1) 0x1000_0000 elements always
+    /// exceed `isize::MAX` bytes, so the reservation fails deterministically.
+    #[cfg(target_pointer_width = "32")]
+    #[test]
+    fn test_table_grow_allocation_failure() {
+        let inst = load_instance("tests/wasm/table_grow.wasm");
+
+        // Within u32 range and without a maximum, but too large to allocate
+        let ret = call_function(
+            &inst,
+            "grow",
+            vec![Val::Num(Num::I32(0x1000_0000)), Val::Ref(Ref::RefNull)],
+        );
+        assert_eq!(i32_result(ret), -1);
+        assert_eq!(i32_result(call_function(&inst, "size", vec![])), 0);
+    }
+
+    #[test]
+    fn test_table_grow_max() {
+        let inst = load_instance("tests/wasm/table_grow.wasm");
+
+        // (assert_return (invoke "grow-max" (i32.const 1)) (i32.const 0))
+        let ret = call_function(&inst, "grow-max", vec![Val::Num(Num::I32(1))]);
+        assert_eq!(i32_result(ret), 0);
+
+        // (assert_return (invoke "grow-max" (i32.const 1)) (i32.const 1))
+        let ret = call_function(&inst, "grow-max", vec![Val::Num(Num::I32(1))]);
+        assert_eq!(i32_result(ret), 1);
+
+        // (assert_return (invoke "grow-max" (i32.const 2)) (i32.const 2))
+        let ret = call_function(&inst, "grow-max", vec![Val::Num(Num::I32(2))]);
+        assert_eq!(i32_result(ret), 2);
+
+        // (assert_return (invoke "grow-max" (i32.const 6)) (i32.const 4))
+        let ret = call_function(&inst, "grow-max", vec![Val::Num(Num::I32(6))]);
+        assert_eq!(i32_result(ret), 4);
+
+        // (assert_return (invoke "grow-max" (i32.const 0)) (i32.const 10))
+        let ret = call_function(&inst, "grow-max", vec![Val::Num(Num::I32(0))]);
+        assert_eq!(i32_result(ret), 10);
+
+        // (assert_return (invoke "grow-max" (i32.const 1)) (i32.const -1))
+        let ret = call_function(&inst, "grow-max", vec![Val::Num(Num::I32(1))]);
+        assert_eq!(i32_result(ret), -1);
+
+        // (assert_return (invoke "grow-max" (i32.const 0x10000)) (i32.const -1))
+        let ret = call_function(&inst, "grow-max", vec![Val::Num(Num::I32(0x10000))]);
+        assert_eq!(i32_result(ret), -1);
+    }
+}
@@ -0,0 +1,150 @@
+use chiwawa::{
+    error::RuntimeError, execution::module::*, execution::runtime::Runtime, execution::value::*,
+    parser, structure::module::Module,
+};
+use rustc_hash::FxHashMap;
+use std::rc::Rc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_instance(wasm_path: &str) -> Rc<ModuleInst> {
+        let mut module = Module::new("test");
+        let _ = parser::parse_bytecode(&mut module, wasm_path);
+        let imports: ImportObjects = FxHashMap::default();
+        ModuleInst::new(&module, imports, Vec::new()).unwrap()
+    }
+
+    fn call_function(
+        inst: &Rc<ModuleInst>,
+        func_name: &str,
+        params: Vec<Val>,
+    ) -> Result<Vec<Val>, RuntimeError> {
+        let func_addr = inst.get_export_func(func_name)?;
+        let mut runtime = Runtime::new(Rc::clone(inst), &func_addr, params, true, false)?;
+        runtime.run()
+    }
+
+    fn check(inst: &Rc<ModuleInst>, i: i32) -> Result<i32, RuntimeError> {
+        let ret = call_function(inst, "check", vec![Val::Num(Num::I32(i))])?;
+        Ok(ret.last().unwrap().to_i32().unwrap())
+    }
+
+    fn init(
+        inst: &Rc<ModuleInst>,
+        func_name: &str,
+        d: i32,
+        s: i32,
+        n: i32,
+    ) -> Result<Vec<Val>, RuntimeError> {
+        call_function(
+            inst,
+            func_name,
+            vec![
+                Val::Num(Num::I32(d)),
+                Val::Num(Num::I32(s)),
+                Val::Num(Num::I32(n)),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_table_init_active() {
+        let inst = load_instance("tests/wasm/table_init.wasm");
+
+        // (assert_return (invoke "check" (i32.const 0)) (i32.const 0))
+        assert_eq!(check(&inst, 0).unwrap(), 0);
+        // (assert_trap (invoke "check" (i32.const 1)) "uninitialized element")
+        let ret = check(&inst, 1);
+        assert!(matches!(ret, Err(RuntimeError::UninitializedElement)));
+
+        // Active segments are dropped after instantiation.
+        // (assert_return (invoke "init-active" (i32.const 0) (i32.const 0) (i32.const 0)))
+        assert!(init(&inst, "init-active", 0, 0, 0).is_ok());
+        // (assert_trap (invoke "init-active" (i32.const 0) (i32.const 0) (i32.const 1)) "out of bounds table access")
+        let ret = init(&inst, "init-active", 0, 0, 1);
+        assert!(matches!(ret, Err(RuntimeError::TableOutOfBounds)));
+    }
+
+    #[test]
+    fn test_table_init_passive() {
+        let inst = load_instance("tests/wasm/table_init.wasm");
+
+        // (assert_return (invoke "init-exprs" (i32.const 2) (i32.const 0) (i32.const 3)))
+        assert!(init(&inst, "init-exprs", 2, 0, 3).is_ok());
+        // (assert_return (invoke "init-funcs" (i32.const 5) (i32.const 0) (i32.const 2)))
+        assert!(init(&inst, "init-funcs", 5, 0, 2).is_ok());
+
+        // (assert_return (invoke "check" (i32.const 0)) (i32.const 0))
+        assert_eq!(check(&inst, 0).unwrap(), 0);
+        // (assert_return (invoke "check" (i32.const 2)) (i32.const 1))
+        assert_eq!(check(&inst, 2).unwrap(), 1);
+        // (assert_trap (invoke "check" (i32.const 3)) "uninitialized element")
+        let ret = check(&inst, 3);
+        assert!(matches!(ret, Err(RuntimeError::UninitializedElement)));
+        // (assert_return (invoke "check" (i32.const 4)) (i32.const 2))
+        assert_eq!(check(&inst, 4).unwrap(), 2);
+        // (assert_return (invoke "check" (i32.const 5)) (i32.const 3))
+        assert_eq!(check(&inst, 5).unwrap(), 3);
+        // (assert_return (invoke "check" (i32.const 6)) (i32.const 4))
+        assert_eq!(check(&inst, 6).unwrap(), 4);
+        // (assert_trap (invoke "check" (i32.const 7)) "uninitialized element")
+        let ret = check(&inst, 7);
+        assert!(matches!(ret, Err(RuntimeError::UninitializedElement)));
+
+        // (assert_return (invoke "init-funcs" (i32.const 8) (i32.const 1) (i32.const 1)))
+        assert!(init(&inst, "init-funcs", 8, 1, 1).is_ok());
+        // (assert_return (invoke "check" (i32.const 8)) (i32.const 4))
+        assert_eq!(check(&inst, 8).unwrap(), 4);
+    }
+
+    #[test]
+    fn test_table_init_out_of_bounds() {
+        let inst = load_instance("tests/wasm/table_init.wasm");
+
+        // (assert_trap (invoke "init-exprs" (i32.const 8) (i32.const 0) (i32.const 3)) "out of bounds table access")
+        let ret = init(&inst, "init-exprs", 8, 0, 3);
+        assert!(matches!(ret, Err(RuntimeError::TableOutOfBounds)));
+        // (assert_trap (invoke "init-exprs" (i32.const 0) (i32.const 1) (i32.const 3)) "out of bounds table access")
+        let ret = init(&inst, "init-exprs", 0, 1, 3);
+        assert!(matches!(ret, Err(RuntimeError::TableOutOfBounds)));
+        // (assert_return (invoke "init-exprs" (i32.const 10) (i32.const 0) (i32.const 0)))
+        assert!(init(&inst, "init-exprs", 10, 0, 0).is_ok());
+        // (assert_return (invoke "init-exprs" (i32.const 0) (i32.const 3) (i32.const 0)))
+        assert!(init(&inst, "init-exprs", 0, 3, 0).is_ok());
+        // (assert_trap (invoke "init-exprs" (i32.const 11) (i32.const 0) (i32.const 0)) "out of bounds table access")
+        let ret = init(&inst, "init-exprs", 11, 0, 0);
+        assert!(matches!(ret, Err(RuntimeError::TableOutOfBounds)));
+        // (assert_trap (invoke "init-exprs" (i32.const 0) (i32.const 4) (i32.const 0)) "out of bounds table access")
+        let ret = init(&inst, "init-exprs", 0, 4, 0);
+        assert!(matches!(ret, Err(RuntimeError::TableOutOfBounds)));
+
+        // A trapping init leaves the table untouched.
+        // (assert_trap (invoke "check" (i32.const 8)) "uninitialized element")
+        let ret = check(&inst, 8);
+        assert!(matches!(ret, Err(RuntimeError::UninitializedElement)));
+    }
+
+    #[test]
+    fn test_elem_drop() {
+        let inst = load_instance("tests/wasm/table_init.wasm");
+
+        // (assert_return (invoke "drop-exprs"))
+        assert!(call_function(&inst, "drop-exprs", vec![]).is_ok());
+        // (assert_return (invoke "drop-exprs"))
+        assert!(call_function(&inst, "drop-exprs", vec![]).is_ok());
+
+        // (assert_return (invoke "init-exprs" (i32.const 0) (i32.const 0) (i32.const 0)))
+        assert!(init(&inst, "init-exprs", 0, 0, 0).is_ok());
+        // (assert_trap (invoke "init-exprs" (i32.const 0) (i32.const 0) (i32.const 1)) "out of bounds table access")
+        let ret = init(&inst, "init-exprs", 0, 0, 1);
+        assert!(matches!(ret, Err(RuntimeError::TableOutOfBounds)));
+
+        // Other segments are unaffected.
+        // (assert_return (invoke "init-funcs" (i32.const 1) (i32.const 0) (i32.const 2)))
+        assert!(init(&inst, "init-funcs", 1, 0, 2).is_ok());
+        // (assert_return (invoke "check" (i32.const 1)) (i32.const 3))
+        assert_eq!(check(&inst, 1).unwrap(), 3);
+    }
+}
@@ -0,0 +1,134 @@
+use chiwawa::{
+    error::RuntimeError, execution::module::*, execution::runtime::Runtime, execution::value::*,
+    parser, structure::module::Module,
+};
+use rustc_hash::FxHashMap;
+use std::rc::Rc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_instance(wasm_path: &str) -> Rc<ModuleInst> {
+        let mut module = Module::new("test");
+        let _ = parser::parse_bytecode(&mut module, wasm_path);
+        let imports: ImportObjects = FxHashMap::default();
+        ModuleInst::new(&module, imports, Vec::new()).unwrap()
+    }
+
+    fn call_function(
+        inst: &Rc<ModuleInst>,
+        func_name: &str,
+        params: Vec<Val>,
+    ) -> Result<Vec<Val>, RuntimeError> {
+        let func_addr = inst.get_export_func(func_name)?;
+        let mut runtime = Runtime::new(Rc::clone(inst), &func_addr, params, true, false)?;
+        runtime.run()
+    }
+
+    fn size(inst: &Rc<ModuleInst>, func_name: &str) -> i32 {
+        let ret = call_function(inst, func_name, vec![]);
+        ret.unwrap().last().unwrap().to_i32().unwrap()
+    }
+
+    fn grow(inst: &Rc<ModuleInst>, func_name: &str, delta: i32) {
+        let ret = call_function(inst, func_name, vec![Val::Num(Num::I32(delta))]);
+        assert!(ret.is_ok());
+    }
+
+    #[test]
+    fn test_table_size_t0() {
+        let inst = load_instance("tests/wasm/table_size.wasm");
+
+        // (assert_return (invoke "size-t0") (i32.const 0))
+        assert_eq!(size(&inst, "size-t0"), 0);
+        // (assert_return (invoke "grow-t0" (i32.const 1)))
+        grow(&inst, "grow-t0", 1);
+        // (assert_return (invoke "size-t0") (i32.const 1))
+        assert_eq!(size(&inst, "size-t0"), 1);
+        // (assert_return (invoke "grow-t0" (i32.const 4)))
+        grow(&inst, "grow-t0", 4);
+        // (assert_return (invoke "size-t0") (i32.const 5))
+        assert_eq!(size(&inst, "size-t0"), 5);
+        // (assert_return (invoke "grow-t0" (i32.const 0)))
+        grow(&inst, "grow-t0", 0);
+        // (assert_return (invoke "size-t0") (i32.const 5))
+        assert_eq!(size(&inst, "size-t0"), 5);
+    }
+
+    #[test]
+    fn test_table_size_t1() {
+        let inst = load_instance("tests/wasm/table_size.wasm");
+
+        // (assert_return (invoke "size-t1") (i32.const 1))
+        assert_eq!(size(&inst, "size-t1"), 1);
+        // (assert_return (invoke "grow-t1" (i32.const 1)))
+        grow(&inst, "grow-t1", 1);
+        // (assert_return (invoke "size-t1") (i32.const 2))
+        assert_eq!(size(&inst, "size-t1"), 2);
+        // (assert_return (invoke "grow-t1" (i32.const 4)))
+        grow(&inst, "grow-t1", 4);
+        // (assert_return (invoke "size-t1") (i32.const 6))
+        assert_eq!(size(&inst, "size-t1"), 6);
+        // (assert_return (invoke "grow-t1" (i32.const 0)))
+        grow(&inst, "grow-t1", 0);
+        // (assert_return (invoke "size-t1") (i32.const 6))
+        assert_eq!(size(&inst, "size-t1"), 6);
+    }
+
+    #[test]
+    fn test_table_size_t2() {
+        let inst = load_instance("tests/wasm/table_size.wasm");
+
+        // (assert_return (invoke "size-t2") (i32.const 0))
+        assert_eq!(size(&inst, "size-t2"), 0);
+        // (assert_return (invoke "grow-t2" (i32.const 3)))
+        grow(&inst, "grow-t2", 3);
+        // (assert_return (invoke "size-t2") (i32.const 0))
+        assert_eq!(size(&inst, "size-t2"), 0);
+        // (assert_return (invoke "grow-t2" (i32.const 1)))
+        grow(&inst, "grow-t2", 1);
+        // (assert_return (invoke "size-t2") (i32.const 1))
+        assert_eq!(size(&inst, "size-t2"), 1);
+        // (assert_return (invoke "grow-t2" (i32.const 0)))
+        grow(&inst, "grow-t2", 0);
+        // (assert_return (invoke "size-t2") (i32.const 1))
+        assert_eq!(size(&inst, "size-t2"), 1);
+        // (assert_return (invoke "grow-t2" (i32.const 4)))
+        grow(&inst, "grow-t2", 4);
+        // (assert_return (invoke "size-t2") (i32.const 1))
+        assert_eq!(size(&inst, "size-t2"), 1);
+        // (assert_return (invoke "grow-t2" (i32.const 1)))
+        grow(&inst, "grow-t2", 1);
+        // (assert_return (invoke "size-t2") (i32.const 2))
+        assert_eq!(size(&inst, "size-t2"), 2);
+    }
+
+    #[test]
+    fn test_table_size_t3() {
+        let inst = load_instance("tests/wasm/table_size.wasm");
+
+        // (assert_return (invoke "size-t3") (i32.const 3))
+        assert_eq!(size(&inst, "size-t3"), 3);
+        // (assert_return (invoke "grow-t3" (i32.const 1)))
+        grow(&inst, "grow-t3", 1);
+        // (assert_return (invoke "size-t3") (i32.const 4))
+        assert_eq!(size(&inst, "size-t3"), 4);
+        // (assert_return (invoke "grow-t3" (i32.const 3)))
+        grow(&inst, "grow-t3", 3);
+        // (assert_return (invoke "size-t3") (i32.const 7))
+        assert_eq!(size(&inst, "size-t3"), 7);
+        // (assert_return (invoke "grow-t3" (i32.const 0)))
+        grow(&inst, "grow-t3", 0);
+        // (assert_return (invoke "size-t3") (i32.const 7))
+        assert_eq!(size(&inst, "size-t3"), 7);
+        // (assert_return (invoke "grow-t3" (i32.const 2)))
+        grow(&inst, "grow-t3", 2);
+        // (assert_return (invoke "size-t3") (i32.const 7))
+        assert_eq!(size(&inst, "size-t3"), 7);
+        // (assert_return (invoke "grow-t3" (i32.const 1)))
+        grow(&inst, "grow-t3", 1);
+        // (assert_return (invoke "size-t3") (i32.const 8))
+        assert_eq!(size(&inst, "size-t3"), 8);
+    }
+}
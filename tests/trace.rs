@@ -0,0 +1,121 @@
+#![cfg(feature = "trace")]
+
+use chiwawa::{
+    error::RuntimeError, execution::module::*, execution::runtime::Runtime,
+    execution::trace::TraceConfig, execution::value::*, parser, structure::module::Module,
+};
+use rustc_hash::FxHashMap;
+use std::fs;
+use std::rc::Rc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sums `1..=n` through a loop, a call, and a store/load round trip.
+    const WAT: &str = r#"
+        (module
+            (memory 1)
+            (global $acc (mut i32) (i32.const 0))
+            (func $add (param i32 i32) (result i32)
+                local.get 0
+                local.get 1
+                i32.add)
+            (func (export "sum") (param $n i32) (result i32)
+                (local $i i32)
+                (block $done
+                    (loop $next
+                        local.get $i
+                        local.get $n
+                        i32.ge_u
+                        br_if $done
+                        local.get $i
+                        i32.const 1
+                        i32.add
+                        local.set $i
+                        global.get $acc
+                        local.get $i
+                        call $add
+                        global.set $acc
+                        br $next))
+                i32.const 16
+                global.get $acc
+                i32.store
+                i32.const 16
+                i32.load))
+    "#;
+
+    /// Trace of `sum(2)`. Both dispatchers must produce exactly these
+    /// lines; run with `--features trace` and `--features trace,tco`.
+    const EXPECTED: &str = "\
+[PC:0000 | Instr:block | Locals:[I32(2),I32(0)] | Globals:[I32(0)]]
+[PC:0001 | Instr:loop | Locals:[I32(2),I32(0)] | Globals:[I32(0)]]
+[PC:0002 | Instr:i32.ge_u | Locals:[I32(2),I32(0)] | Globals:[I32(0)]]
+[PC:0003 | Instr:br_if | Locals:[I32(2),I32(0)] | Globals:[I32(0)]]
+[PC:0004 | Instr:i32.add | Locals:[I32(2),I32(0)] | Globals:[I32(0)]]
+[PC:0005 | Instr:global.get | Locals:[I32(2),I32(1)] | Globals:[I32(0)]]
+[PC:0006 | Instr:local.get | Locals:[I32(2),I32(1)] | Globals:[I32(0)]]
+[PC:0007 | Instr:call | Locals:[I32(2),I32(1)] | Globals:[I32(0)]]
+[PC:0000 | Instr:i32.add | Locals:[I32(0),I32(1)] | Globals:[I32(0)]]
+[PC:0001 | Instr:end | Locals:[I32(0),I32(1)] | Globals:[I32(0)]]
+[PC:0008 | Instr:global.set | Locals:[I32(2),I32(1)] | Globals:[I32(0)]]
+[PC:0009 | Instr:br | Locals:[I32(2),I32(1)] | Globals:[I32(1)]]
+[PC:0001 | Instr:loop | Locals:[I32(2),I32(1)] | Globals:[I32(1)]]
+[PC:0002 | Instr:i32.ge_u | Locals:[I32(2),I32(1)] | Globals:[I32(1)]]
+[PC:0003 | Instr:br_if | Locals:[I32(2),I32(1)] | Globals:[I32(1)]]
+[PC:0004 | Instr:i32.add | Locals:[I32(2),I32(1)] | Globals:[I32(1)]]
+[PC:0005 | Instr:global.get | Locals:[I32(2),I32(2)] | Globals:[I32(1)]]
+[PC:0006 | Instr:local.get | Locals:[I32(2),I32(2)] | Globals:[I32(1)]]
+[PC:0007 | Instr:call | Locals:[I32(2),I32(2)] | Globals:[I32(1)]]
+[PC:0000 | Instr:i32.add | Locals:[I32(1),I32(2)] | Globals:[I32(1)]]
+[PC:0001 | Instr:end | Locals:[I32(1),I32(2)] | Globals:[I32(1)]]
+[PC:0008 | Instr:global.set | Locals:[I32(2),I32(2)] | Globals:[I32(1)]]
+[PC:0009 | Instr:br | Locals:[I32(2),I32(2)] | Globals:[I32(3)]]
+[PC:0001 | Instr:loop | Locals:[I32(2),I32(2)] | Globals:[I32(3)]]
+[PC:0002 | Instr:i32.ge_u | Locals:[I32(2),I32(2)] | Globals:[I32(3)]]
+[PC:0003 | Instr:br_if | Locals:[I32(2),I32(2)] | Globals:[I32(3)]]
+[PC:0012 | Instr:i32.const | Locals:[I32(2),I32(2)] | Globals:[I32(3)]]
+[PC:0013 | Instr:global.get | Locals:[I32(2),I32(2)] | Globals:[I32(3)]]
+[PC:0014 | Instr:i32.store | Locals:[I32(2),I32(2)] | Globals:[I32(3)]]
+[PC:0015 | Instr:i32.load | Locals:[I32(2),I32(2)] | Globals:[I32(3)]]
+[PC:0016 | Instr:end | Locals:[I32(2),I32(2)] | Globals:[I32(3)]]
+";
+
+    fn run_traced(path: &str) -> Result<Val, RuntimeError> {
+        let mut module = Module::new("test");
+        parser::parse_bytes(&mut module, &wat::parse_str(WAT).unwrap()).unwrap();
+        let imports: ImportObjects = FxHashMap::default();
+        let inst = ModuleInst::new(&module, imports, Vec::new()).unwrap();
+        let func_addr = inst.get_export_func("sum").unwrap();
+        let config = TraceConfig::new(
+            None,
+            Some(vec![
+                "pc".to_string(),
+                "locals".to_string(),
+                "globals".to_string(),
+            ]),
+            Some(path.to_string()),
+        );
+        let mut runtime = Runtime::new(
+            Rc::clone(&inst),
+            &func_addr,
+            vec![Val::Num(Num::I32(2))],
+            false,
+            false,
+            Some(config),
+        )?;
+        let mut ret = runtime.run()?;
+        Ok(ret.pop().unwrap())
+    }
+
+    #[test]
+    fn test_trace_events() {
+        let path = "temp_trace_events.log";
+        let ret = run_traced(path).unwrap();
+        assert_eq!(ret, Val::Num(Num::I32(3)));
+
+        let trace = fs::read_to_string(path).unwrap();
+        let _ = fs::remove_file(path);
+        assert_eq!(trace, EXPECTED);
+    }
+}
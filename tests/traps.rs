@@ -0,0 +1,91 @@
+use chiwawa::{
+    error::RuntimeError, execution::module::*, execution::runtime::Runtime, execution::value::*,
+    parser, structure::module::Module,
+};
+use rustc_hash::FxHashMap;
+use std::rc::Rc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_instance(wasm_path: &str) -> Rc<ModuleInst> {
+        let mut module = Module::new("test");
+        let _ = parser::parse_bytecode(&mut module, wasm_path);
+        let imports: ImportObjects = FxHashMap::default();
+        ModuleInst::new(&module, imports, Vec::new()).unwrap()
+    }
+
+    fn call_function(
+        inst: &Rc<ModuleInst>,
+        func_name: &str,
+        params: Vec<Val>,
+    ) -> Result<Vec<Val>, RuntimeError> {
+        let func_addr = inst.get_export_func(func_name)?;
+        let mut runtime = Runtime::new(Rc::clone(inst), &func_addr, params, true, false)?;
+        runtime.run()
+    }
+
+    #[test]
+    fn test_trap_unreachable() {
+        let inst = load_instance("tests/wasm/traps.wasm");
+        for name in [
+            "unreachable",
+            "unreachable-in-block",
+            "unreachable-after-call",
+        ] {
+            let ret = call_function(&inst, name, vec![]);
+            assert_eq!(ret, Err(RuntimeError::Unreachable), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_trap_call_indirect() {
+        let inst = load_instance("tests/wasm/traps.wasm");
+        let ret = call_function(&inst, "call-indirect", vec![Val::Num(Num::I32(0))]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 42);
+        let ret = call_function(&inst, "call-indirect", vec![Val::Num(Num::I32(1))]);
+        assert_eq!(ret, Err(RuntimeError::UninitializedElement));
+        let ret = call_function(&inst, "call-indirect", vec![Val::Num(Num::I32(2))]);
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 42);
+        let ret = call_function(&inst, "call-indirect", vec![Val::Num(Num::I32(3))]);
+        assert_eq!(ret, Err(RuntimeError::UndefinedElement));
+    }
+
+    #[test]
+    fn test_trap_division() {
+        let inst = load_instance("tests/wasm/traps.wasm");
+        let ret = call_function(
+            &inst,
+            "i32.div_s",
+            vec![Val::Num(Num::I32(1)), Val::Num(Num::I32(0))],
+        );
+        assert_eq!(ret, Err(RuntimeError::DivisionByZero));
+        let ret = call_function(
+            &inst,
+            "i32.div_s",
+            vec![Val::Num(Num::I32(i32::MIN)), Val::Num(Num::I32(-1))],
+        );
+        assert_eq!(ret, Err(RuntimeError::IntegerOverflow));
+        let ret = call_function(
+            &inst,
+            "i64.rem_u",
+            vec![Val::Num(Num::I64(1)), Val::Num(Num::I64(0))],
+        );
+        assert_eq!(ret, Err(RuntimeError::DivisionByZero));
+    }
+
+    #[test]
+    fn test_trap_instance_reusable() {
+        // A trap must not poison the instance for subsequent invocations.
+        let inst = load_instance("tests/wasm/traps.wasm");
+        let ret = call_function(&inst, "unreachable", vec![]);
+        assert_eq!(ret, Err(RuntimeError::Unreachable));
+        let ret = call_function(
+            &inst,
+            "i32.div_s",
+            vec![Val::Num(Num::I32(7)), Val::Num(Num::I32(2))],
+        );
+        assert_eq!(ret.unwrap().last().unwrap().to_i32().unwrap(), 3);
+    }
+}
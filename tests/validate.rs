@@ -0,0 +1,96 @@
+use chiwawa::{error::ParserError, parser, structure::module::Module};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_error(wat: &str) -> ParserError {
+        let binary = wat::parse_str(wat).unwrap();
+        let mut module = Module::new("test");
+        let err = parser::parse_bytes(&mut module, &binary).unwrap_err();
+        err.downcast_ref::<ParserError>()
+            .expect("expected a ParserError")
+            .clone()
+    }
+
+    #[test]
+    fn test_validate_accepts_valid_module() {
+        let bytes = std::fs::read("tests/wasm/link_lib.wasm").unwrap();
+        assert_eq!(parser::validate(&bytes), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_type_mismatch_reports_func_index() {
+        let err = parse_error(
+            r#"
+            (module
+                (import "env" "f" (func))
+                (func (result i32) (i32.const 1))
+                (func (result i32) (i32.add (i32.const 1) (i64.const 2))))"#,
+        );
+        match err {
+            ParserError::FuncValidationError {
+                func_index, offset, ..
+            } => {
+                assert_eq!(func_index, 2);
+                assert!(offset > 0);
+            }
+            other => panic!("expected FuncValidationError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validate_untyped_select_on_refs() {
+        let err = parse_error(
+            r#"
+            (module
+                (func (param funcref funcref i32) (result funcref)
+                    (select (local.get 0) (local.get 1) (local.get 2))))"#,
+        );
+        assert!(matches!(
+            err,
+            ParserError::FuncValidationError { func_index: 0, .. }
+        ));
+    }
+
+    #[test]
+    fn test_validate_unknown_export() {
+        let err = parse_error(r#"(module (func) (export "f" (func 5)))"#);
+        assert!(matches!(err, ParserError::ValidationError { .. }));
+    }
+
+    #[test]
+    fn test_validate_rejects_unsupported_proposals() {
+        // Shared memories and atomics (threads)
+        let err = parse_error(r#"(module (memory 1 1 shared))"#);
+        assert!(matches!(err, ParserError::ValidationError { .. }));
+        let err = parse_error(
+            r#"
+            (module (memory 1)
+                (func (result i32) (i32.atomic.load (i32.const 0))))"#,
+        );
+        assert!(matches!(err, ParserError::FuncValidationError { .. }));
+
+        // Relaxed SIMD
+        let err = parse_error(
+            r#"
+            (module
+                (func (param v128 v128) (result v128)
+                    (i8x16.relaxed_swizzle (local.get 0) (local.get 1))))"#,
+        );
+        assert!(matches!(err, ParserError::FuncValidationError { .. }));
+
+        // Extended constant expressions
+        let err = parse_error(r#"(module (global i32 (i32.add (i32.const 1) (i32.const 2))))"#);
+        assert!(matches!(err, ParserError::ValidationError { .. }));
+    }
+
+    #[test]
+    fn test_validate_unknown_local() {
+        let err = parse_error(r#"(module (func (result i32) (local.get 3)))"#);
+        assert!(matches!(
+            err,
+            ParserError::FuncValidationError { func_index: 0, .. }
+        ));
+    }
+}
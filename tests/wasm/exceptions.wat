@@ -0,0 +1,102 @@
+(module
+  (tag $e0 (export "e0") (param i32))
+  (tag $e1 (export "e1") (param i64 i32))
+  (tag $empty (export "empty"))
+
+  (func $throw-e0 (param i32)
+    (throw $e0 (local.get 0)))
+
+  (func $throw-if-odd (param i32) (result i32)
+    (if (i32.and (local.get 0) (i32.const 1))
+      (then (throw $e0 (local.get 0))))
+    (local.get 0))
+
+  (func (export "catch-local") (param i32) (result i32)
+    (block $h (result i32)
+      (try_table (result i32) (catch $e0 $h)
+        (throw $e0 (local.get 0)))))
+
+  (func (export "catch-multi") (result i64 i32)
+    (block $h (result i64 i32)
+      (try_table (catch $e1 $h)
+        (throw $e1 (i64.const 0x100000000) (i32.const 42)))
+      (i64.const 0)
+      (i32.const 0)))
+
+  (func (export "catch-no-throw") (param i32) (result i32)
+    (block $h (result i32)
+      (try_table (result i32) (catch $e0 $h)
+        (call $throw-if-odd (local.get 0)))
+      (i32.const 100)
+      (i32.add)))
+
+  (func (export "catch-across-frames") (param i32) (result i32)
+    (block $h (result i32)
+      (try_table (catch $e0 $h)
+        (call $throw-e0 (local.get 0)))
+      (i32.const -1)))
+
+  (func (export "catch-nested") (result i32)
+    (block $outer (result i32)
+      (try_table (catch $e0 $outer)
+        (block $inner
+          (try_table (catch $empty $inner)
+            (throw $e0 (i32.const 3)))))
+      (i32.const -1)))
+
+  (func (export "catch-all") (result i32)
+    (block $h
+      (try_table (catch_all $h)
+        (throw $e1 (i64.const 1) (i32.const 2)))
+      (return (i32.const 0)))
+    (i32.const 1))
+
+  (func (export "catch-ref-rethrow") (result i32)
+    (block $outer (result i32)
+      (try_table (catch $e0 $outer)
+        (block $inner (result i32 exnref)
+          (try_table (catch_ref $e0 $inner)
+            (call $throw-e0 (i32.const 11)))
+          (unreachable))
+        (throw_ref))
+      (unreachable))
+    (i32.const 1)
+    (i32.add))
+
+  (func (export "catch-all-ref-rethrow") (result i32)
+    (block $outer (result i32)
+      (try_table (catch $e0 $outer)
+        (block $inner (result exnref)
+          (try_table (catch_all_ref $inner)
+            (throw $e0 (i32.const 20)))
+          (unreachable))
+        (throw_ref))
+      (unreachable))
+    (i32.const 2)
+    (i32.mul))
+
+  ;; Keeps the caught exception in an exnref local before rethrowing it
+  (func $rethrow-from-local (param i32)
+    (local $e exnref)
+    (local.set $e
+      (block $h (result exnref)
+        (try_table (catch_all_ref $h)
+          (throw $e0 (local.get 0)))
+        (unreachable)))
+    (throw_ref (local.get $e)))
+
+  (func (export "catch-from-local") (param i32) (result i32)
+    (block $h (result i32)
+      (try_table (catch $e0 $h)
+        (call $rethrow-from-local (local.get 0)))
+      (i32.const -1)))
+
+  (func (export "uncaught") (result i32)
+    (block $h
+      (try_table (catch $empty $h)
+        (call $throw-e0 (i32.const 5))))
+    (i32.const 0))
+
+  (func (export "throw-ref-null")
+    (throw_ref (ref.null exn)))
+)
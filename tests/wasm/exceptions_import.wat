@@ -0,0 +1,8 @@
+(module
+  (import "env" "e0" (tag $e0 (param i32)))
+
+  (func (export "catch-imported") (param i32) (result i32)
+    (block $h (result i32)
+      (try_table (result i32) (catch $e0 $h)
+        (throw $e0 (local.get 0)))))
+)
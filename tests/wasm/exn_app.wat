@@ -0,0 +1,20 @@
+(module
+  (import "lib" "throw" (func $throw (param i32)))
+  (import "env" "yield" (func $yield))
+  (import "lib" "e" (tag $e (param i32)))
+
+  ;; Holds an exception thrown by lib across the yield, then rethrows it
+  ;; and returns its payload. The tag is index 1 in lib but index 0 here.
+  (func (export "run") (result i32)
+    (local $exn exnref)
+    (local.set $exn
+      (block $caught (result exnref)
+        (try_table (catch_all_ref $caught)
+          (call $throw (i32.const 42)))
+        (unreachable)))
+    (call $yield)
+    (block $payload (result i32)
+      (try_table (catch $e $payload)
+        (throw_ref (local.get $exn)))
+      (unreachable)))
+)
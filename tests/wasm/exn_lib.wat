@@ -0,0 +1,7 @@
+(module
+  (tag $pad)
+  (tag $e (export "e") (param i32))
+
+  (func (export "throw") (param i32)
+    (throw $e (local.get 0)))
+)
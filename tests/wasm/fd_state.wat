@@ -0,0 +1,57 @@
+(module
+    (import "wasi_snapshot_preview1" "path_open"
+        (func $path_open (param i32 i32 i32 i32 i32 i64 i64 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "fd_read"
+        (func $fd_read (param i32 i32 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "fd_close"
+        (func $fd_close (param i32) (result i32)))
+    (import "env" "yield" (func $yield))
+
+    (memory 1)
+    (data (i32.const 0) "tests/testdir/fd_state.txt")
+
+    (global $fd (export "fd") (mut i32) (i32.const -1))
+
+    ;; Opens the file relative to the first preopen and stores the fd at 96
+    (func $open (result i32)
+        (call $path_open
+            (i32.const 3) (i32.const 0) (i32.const 0) (i32.const 26) (i32.const 0)
+            (i64.const 0x26) (i64.const 0) (i32.const 0) (i32.const 96))
+        if
+            unreachable
+        end
+        (i32.load (i32.const 96))
+    )
+
+    ;; Opens the file storing the fd at $ptr and returns the errno
+    (func (export "open-at") (param $ptr i32) (result i32)
+        (call $path_open
+            (i32.const 3) (i32.const 0) (i32.const 0) (i32.const 26) (i32.const 0)
+            (i64.const 0x26) (i64.const 0) (i32.const 0) (local.get $ptr))
+    )
+
+    ;; Reads $len bytes into $buf through the iovec at 64
+    (func $read (param $buf i32) (param $len i32)
+        (i32.store (i32.const 64) (local.get $buf))
+        (i32.store (i32.const 68) (local.get $len))
+        (call $fd_read (global.get $fd) (i32.const 64) (i32.const 1) (i32.const 80))
+        if
+            unreachable
+        end
+    )
+
+    ;; Opens the file twice and closes the first copy, so that the kept fd
+    ;; is not the lowest free one. Reads 2 bytes, yields, then returns the
+    ;; next 4 bytes.
+    (func (export "run") (result i32)
+        (local $first i32)
+        (local.set $first (call $open))
+        (global.set $fd (call $open))
+        (call $fd_close (local.get $first))
+        drop
+        (call $read (i32.const 128) (i32.const 2))
+        call $yield
+        (call $read (i32.const 136) (i32.const 4))
+        (i32.load (i32.const 136))
+    )
+)
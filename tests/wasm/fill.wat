@@ -0,0 +1,15 @@
+(module
+  (memory (export "mem") 1)
+  (global $count (export "count") (mut i32) (i32.const 0))
+
+  (func (export "fill") (param $n i32) (result i32)
+    (local $i i32)
+    (block $done
+      (loop $next
+        (br_if $done (i32.ge_u (local.get $i) (local.get $n)))
+        (i32.store (i32.shl (local.get $i) (i32.const 2)) (local.get $i))
+        (global.set $count (i32.add (global.get $count) (i32.const 1)))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $next)))
+    (i32.load (i32.shl (i32.sub (local.get $n) (i32.const 1)) (i32.const 2))))
+)
@@ -0,0 +1,30 @@
+(module
+  (import "env" "mul" (func $mul (param i32 i64) (result f64)))
+  (import "env" "divmod" (func $divmod (param i32 i32) (result i32 i32)))
+  (import "env" "peek" (func $peek (param i32) (result i32)))
+  (import "env" "bump" (func $bump))
+  (import "env" "check" (func $check (param i32) (result i32)))
+  (import "env" "grow" (func $grow (param i32) (result i32)))
+  (memory (export "memory") 1)
+  (global $counter (export "counter") (mut i32) (i32.const 0))
+  (data (i32.const 16) "\2a\00\00\00")
+
+  (func (export "mul") (param i32 i64) (result f64)
+    (call $mul (local.get 0) (local.get 1)))
+  (func (export "tail-mul") (param i32 i64) (result f64)
+    (return_call $mul (local.get 0) (local.get 1)))
+  (func (export "divmod") (param i32 i32) (result i32 i32)
+    (call $divmod (local.get 0) (local.get 1)))
+  (func (export "peek") (param i32) (result i32)
+    (call $peek (local.get 0)))
+  (func (export "bump-twice") (result i32)
+    (call $bump)
+    (call $bump)
+    (global.get $counter))
+  (func (export "check") (param i32) (result i32)
+    (i32.add (call $check (local.get 0)) (i32.const 1)))
+  (func (export "grow-and-store") (param i32) (result i32)
+    (drop (call $grow (i32.const 1)))
+    (i32.store (i32.const 0x10010) (local.get 0))
+    (call $peek (i32.const 0x10010)))
+)
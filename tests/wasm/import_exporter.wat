@@ -0,0 +1,13 @@
+(module
+  (table $t (export "table") 4 10 funcref)
+  (memory $m (export "memory") 1 4)
+  (global $sp (export "__stack_pointer") (mut i32) (i32.const 1024))
+  (global $c (export "const") i32 (i32.const 7))
+
+  (func $f (result i32) (i32.const 11))
+  (elem (table $t) (i32.const 0) func $f)
+
+  (func (export "load") (param i32) (result i32) (i32.load (local.get 0)))
+  (func (export "sp") (result i32) (global.get $sp))
+  (func (export "size") (result i32) (memory.size))
+)
@@ -0,0 +1,22 @@
+(module
+  (type $ret (func (result i32)))
+  (import "env" "table" (table 2 funcref))
+  (import "env" "memory" (memory 1))
+  (import "env" "__stack_pointer" (global $sp (mut i32)))
+  (import "env" "const" (global $c i32))
+
+  (global $derived i32 (global.get $c))
+
+  (func $g (result i32) (i32.const 22))
+  (elem (i32.const 1) func $g)
+
+  (func (export "store") (param i32 i32) (i32.store (local.get 0) (local.get 1)))
+  (func (export "push") (result i32)
+    (global.set $sp (i32.sub (global.get $sp) (i32.const 16)))
+    (global.get $sp)
+  )
+  (func (export "call") (param i32) (result i32) (call_indirect (type $ret) (local.get 0)))
+  (func (export "derived") (result i32) (global.get $derived))
+  (func (export "grow") (param i32) (result i32) (memory.grow (local.get 0)))
+  (func (export "table-size") (result i32) (table.size))
+)
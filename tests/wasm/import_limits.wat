@@ -0,0 +1,4 @@
+(module
+  (import "env" "memory" (memory 2 4))
+  (func (export "size") (result i32) (memory.size))
+)
@@ -0,0 +1,6 @@
+(module
+  (import "app" "sum" (func $sum (param i32 i32) (result i32)))
+  (func (export "triple") (param i32) (result i32)
+    (call $sum (local.get 0) (call $sum (local.get 0) (local.get 0)))
+  )
+)
@@ -0,0 +1,15 @@
+(module
+  (memory (export "memory") 1)
+  (global $count (mut i32) (i32.const 0))
+  (global $base i32 (i32.const 100))
+
+  (func (export "add") (param i32 i32) (result i32)
+    (i32.add (local.get 0) (local.get 1))
+  )
+  (func (export "bump") (result i32)
+    (global.set $count (i32.add (global.get $count) (i32.const 1)))
+    (i32.store (i32.const 0) (global.get $count))
+    (i32.add (global.get $base) (global.get $count))
+  )
+  (func (export "count") (result i32) (global.get $count))
+)
@@ -0,0 +1,19 @@
+(module
+  (import "lib" "add" (func $add (param i32 i32) (result i32)))
+  (import "lib" "bump" (func $bump (result i32)))
+  (import "lib" "memory" (memory 1))
+  (global $own (mut i32) (i32.const 5))
+
+  (func (export "sum") (param i32 i32) (result i32)
+    (call $add (local.get 0) (local.get 1))
+  )
+  (func (export "sum-tail") (param i32 i32) (result i32)
+    (return_call $add (local.get 0) (local.get 1))
+  )
+  (func (export "bump-twice") (result i32)
+    (drop (call $bump))
+    (call $bump)
+  )
+  (func (export "own") (result i32) (global.get $own))
+  (func (export "load") (result i32) (i32.load (i32.const 0)))
+)
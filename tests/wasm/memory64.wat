@@ -0,0 +1,42 @@
+(module
+  (memory $m i64 1 3)
+  (memory $small 1)
+  (data (memory $m) (i64.const 0x100) "\2a\00\00\00\00\00\00\00")
+  (data $passive "wasm64")
+
+  (func (export "load") (param i64) (result i64)
+    (i64.load (local.get 0)))
+
+  (func (export "load8") (param i64) (result i32)
+    (i32.load8_u (local.get 0)))
+
+  (func (export "store") (param i64 i64)
+    (i64.store (local.get 0) (local.get 1)))
+
+  (func (export "load-offset") (param i64) (result i32)
+    (i32.load offset=0x10000 (local.get 0)))
+
+  (func (export "size") (result i64)
+    (memory.size))
+
+  (func (export "grow") (param i64) (result i64)
+    (memory.grow (local.get 0)))
+
+  (func (export "fill") (param i64 i32 i64)
+    (memory.fill (local.get 0) (local.get 1) (local.get 2)))
+
+  (func (export "copy") (param i64 i64 i64)
+    (memory.copy (local.get 0) (local.get 1) (local.get 2)))
+
+  (func (export "copy-to-small") (param i32 i64 i32)
+    (memory.copy $small $m (local.get 0) (local.get 1) (local.get 2)))
+
+  (func (export "load-small") (param i32) (result i32)
+    (i32.load $small (local.get 0)))
+
+  (func (export "init") (param i64)
+    (memory.init $passive (local.get 0) (i32.const 0) (i32.const 6)))
+
+  (func (export "simd-lane") (param i64) (result i32)
+    (i32x4.extract_lane 0 (v128.load (local.get 0))))
+)
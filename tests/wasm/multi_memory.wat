@@ -0,0 +1,43 @@
+(module
+  (memory $m0 1)
+  (memory $m1 1 2)
+  (data (memory $m1) (i32.const 16) "\2a\00\00\00")
+  (data $passive "hello")
+
+  (func (export "load0") (param i32) (result i32)
+    (i32.load $m0 (local.get 0)))
+
+  (func (export "load1") (param i32) (result i32)
+    (i32.load $m1 (local.get 0)))
+
+  (func (export "load8-1") (param i32) (result i32)
+    (i32.load8_u $m1 (local.get 0)))
+
+  (func (export "store1") (param i32 i32)
+    (i32.store $m1 (local.get 0) (local.get 1)))
+
+  (func (export "store0") (param i32 i32)
+    (i32.store $m0 (local.get 0) (local.get 1)))
+
+  (func (export "size1") (result i32)
+    (memory.size $m1))
+
+  (func (export "grow1") (param i32) (result i32)
+    (memory.grow $m1 (local.get 0)))
+
+  (func (export "fill1") (param i32 i32 i32)
+    (memory.fill $m1 (local.get 0) (local.get 1) (local.get 2)))
+
+  (func (export "init1") (param i32)
+    (memory.init $m1 $passive (local.get 0) (i32.const 0) (i32.const 5)))
+
+  (func (export "copy0to1") (param i32 i32 i32)
+    (memory.copy $m1 $m0 (local.get 0) (local.get 1) (local.get 2)))
+
+  (func (export "copy1to1") (param i32 i32 i32)
+    (memory.copy $m1 $m1 (local.get 0) (local.get 1) (local.get 2)))
+
+  (func (export "simd-roundtrip1") (param i32 i32) (result i32)
+    (v128.store $m1 (local.get 1) (v128.load $m0 (local.get 0)))
+    (i32x4.extract_lane 3 (v128.load $m1 (local.get 1))))
+)
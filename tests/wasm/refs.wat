@@ -0,0 +1,45 @@
+(module
+  (import "env" "yield" (func $yield))
+  (type $unary (func (param i32) (result i32)))
+  (table 4 funcref)
+  (elem (i32.const 0) $double $square)
+  (global $op (export "op") (mut funcref) (ref.func $square))
+
+  (func $double (export "double") (param i32) (result i32)
+    (i32.mul (local.get 0) (i32.const 2)))
+  (func $square (param i32) (result i32)
+    (i32.mul (local.get 0) (local.get 0)))
+
+  ;; Keeps function `$which` of the table in a local and a register across
+  ;; the yield, then stores each copy in slots 2 and 3 and sums their results.
+  (func (export "apply") (param $x i32) (param $which i32) (result i32)
+    (local $f funcref)
+    (local.set $f (table.get (local.get $which)))
+    (i32.const 2)
+    (local.get $f)
+    (call $yield)
+    (table.set)
+    (table.set (i32.const 3) (local.get $f))
+    (i32.add
+      (call_indirect (type $unary) (local.get $x) (i32.const 2))
+      (call_indirect (type $unary) (local.get $x) (i32.const 3))))
+
+  ;; Yields in a callee whose funcref local is still null, then calls
+  ;; function `$which` of the table through it.
+  (func $apply-in-callee (param $x i32) (param $which i32) (result i32)
+    (local $f funcref)
+    (call $yield)
+    (local.set $f (table.get (local.get $which)))
+    (table.set (i32.const 2) (local.get $f))
+    (call_indirect (type $unary) (local.get $x) (i32.const 2)))
+
+  (func (export "apply-in-callee") (param i32 i32) (result i32)
+    (call $apply-in-callee (local.get 0) (local.get 1)))
+
+  ;; Keeps the handle in a register across the yield and returns it along
+  ;; with the copy in the local.
+  (func (export "keep") (param $h externref) (result externref externref)
+    (local.get $h)
+    (call $yield)
+    (local.get $h))
+)
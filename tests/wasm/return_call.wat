@@ -0,0 +1,79 @@
+(module
+  ;; Auxiliary definitions
+  (type $over-i32 (func (param i32) (result i32)))
+  (type $over-i64 (func (param i64) (result i64)))
+  (type $i64-i64 (func (param i64 i64) (result i64)))
+
+  (func $const-i32 (result i32) (i32.const 0x132))
+  (func $const-i64 (result i64) (i64.const 0x164))
+  (func $id-i32 (param i32) (result i32) (local.get 0))
+  (func $i32-i64 (param i32 i64) (result i64) (local.get 1))
+
+  (table funcref (elem $id-i32 $fac-acc $even $odd $const-i32))
+
+  ;; Typing
+  (func (export "type-i32") (result i32) (return_call $const-i32))
+  (func (export "type-i64") (result i64) (return_call $const-i64))
+  (func (export "type-first-i32") (result i32) (return_call $id-i32 (i32.const 32)))
+  (func (export "type-second-i64") (result i64)
+    (return_call $i32-i64 (i32.const 32) (i64.const 64))
+  )
+
+  ;; Recursion
+  (func $fac-acc (export "fac-acc") (type $i64-i64)
+    (if (result i64) (i64.eqz (local.get 0))
+      (then (local.get 1))
+      (else
+        (return_call $fac-acc
+          (i64.sub (local.get 0) (i64.const 1))
+          (i64.mul (local.get 0) (local.get 1))
+        )
+      )
+    )
+  )
+
+  (func $count (export "count") (param i64) (result i64)
+    (if (result i64) (i64.eqz (local.get 0))
+      (then (local.get 0))
+      (else (return_call $count (i64.sub (local.get 0) (i64.const 1))))
+    )
+  )
+
+  (func $even (export "even") (type $over-i64)
+    (if (result i64) (i64.eqz (local.get 0))
+      (then (i64.const 44))
+      (else (return_call $odd (i64.sub (local.get 0) (i64.const 1))))
+    )
+  )
+  (func $odd (export "odd") (type $over-i64)
+    (if (result i64) (i64.eqz (local.get 0))
+      (then (i64.const 99))
+      (else (return_call $even (i64.sub (local.get 0) (i64.const 1))))
+    )
+  )
+
+  ;; Tail call from a frame that has a non-tail caller
+  (func (export "nested") (param i64) (result i64)
+    (i64.add (call $even (local.get 0)) (i64.const 1))
+  )
+
+  ;; Indirect
+  (func (export "dispatch-i32") (param i32 i32) (result i32)
+    (return_call_indirect (type $over-i32) (local.get 1) (local.get 0))
+  )
+  (func (export "dispatch-fac") (param i64) (result i64)
+    (return_call_indirect (type $i64-i64)
+      (local.get 0) (i64.const 1) (i32.const 1)
+    )
+  )
+  (func $even-indirect (export "even-indirect") (param i64) (result i64)
+    (if (result i64) (i64.eqz (local.get 0))
+      (then (i64.const 44))
+      (else
+        (return_call_indirect (type $over-i64)
+          (i64.sub (local.get 0) (i64.const 1)) (i32.const 3)
+        )
+      )
+    )
+  )
+)
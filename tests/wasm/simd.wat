@@ -0,0 +1,90 @@
+(module
+  (memory 1)
+  (data (i32.const 0) "\01\02\03\04\05\06\07\08\f8\f9\fa\fb\fc\fd\fe\ff")
+  (global $g (mut v128) (v128.const i32x4 0 0 0 0))
+
+  (func (export "v128.const") (result v128)
+    (v128.const i32x4 1 2 3 4))
+
+  ;; Lane-wise arithmetic
+  (func (export "i32x4.add") (param v128 v128) (result v128)
+    (i32x4.add (local.get 0) (local.get 1)))
+  (func (export "i8x16.add_sat_u") (param v128 v128) (result v128)
+    (i8x16.add_sat_u (local.get 0) (local.get 1)))
+  (func (export "i16x8.mul") (param v128 v128) (result v128)
+    (i16x8.mul (local.get 0) (local.get 1)))
+  (func (export "i64x2.sub") (param v128 v128) (result v128)
+    (i64x2.sub (local.get 0) (local.get 1)))
+  (func (export "f32x4.min") (param v128 v128) (result v128)
+    (f32x4.min (local.get 0) (local.get 1)))
+  (func (export "f64x2.mul") (param v128 v128) (result v128)
+    (f64x2.mul (local.get 0) (local.get 1)))
+  (func (export "i32x4.dot_i16x8_s") (param v128 v128) (result v128)
+    (i32x4.dot_i16x8_s (local.get 0) (local.get 1)))
+  (func (export "i16x8.narrow_i32x4_s") (param v128 v128) (result v128)
+    (i16x8.narrow_i32x4_s (local.get 0) (local.get 1)))
+  (func (export "i32x4.extend_high_i16x8_u") (param v128) (result v128)
+    (i32x4.extend_high_i16x8_u (local.get 0)))
+  (func (export "i32x4.trunc_sat_f32x4_s") (param v128) (result v128)
+    (i32x4.trunc_sat_f32x4_s (local.get 0)))
+  (func (export "i16x8.shl") (param v128 i32) (result v128)
+    (i16x8.shl (local.get 0) (local.get 1)))
+  (func (export "i32x4.gt_s") (param v128 v128) (result v128)
+    (i32x4.gt_s (local.get 0) (local.get 1)))
+
+  ;; Bitwise / tests
+  (func (export "v128.bitselect") (param v128 v128 v128) (result v128)
+    (v128.bitselect (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "v128.any_true") (param v128) (result i32)
+    (v128.any_true (local.get 0)))
+  (func (export "i32x4.all_true") (param v128) (result i32)
+    (i32x4.all_true (local.get 0)))
+  (func (export "i8x16.bitmask") (param v128) (result i32)
+    (i8x16.bitmask (local.get 0)))
+
+  ;; Shuffles and lanes
+  (func (export "i8x16.shuffle") (param v128 v128) (result v128)
+    (i8x16.shuffle 0 16 1 17 2 18 3 19 4 20 5 21 6 22 7 23 (local.get 0) (local.get 1)))
+  (func (export "i8x16.swizzle") (param v128 v128) (result v128)
+    (i8x16.swizzle (local.get 0) (local.get 1)))
+  (func (export "i32x4.splat") (param i32) (result v128)
+    (i32x4.splat (local.get 0)))
+  (func (export "i8x16.extract_lane_s") (param v128) (result i32)
+    (i8x16.extract_lane_s 15 (local.get 0)))
+  (func (export "i64x2.replace_lane") (param v128 i64) (result v128)
+    (i64x2.replace_lane 1 (local.get 0) (local.get 1)))
+  (func (export "f64x2.extract_lane") (param v128) (result f64)
+    (f64x2.extract_lane 1 (local.get 0)))
+
+  ;; Memory
+  (func (export "v128.load") (param i32) (result v128)
+    (v128.load offset=0 (local.get 0)))
+  (func (export "v128.load8x8_s") (param i32) (result v128)
+    (v128.load8x8_s (local.get 0)))
+  (func (export "v128.load32_splat") (param i32) (result v128)
+    (v128.load32_splat (local.get 0)))
+  (func (export "v128.load64_zero") (param i32) (result v128)
+    (v128.load64_zero (local.get 0)))
+  (func (export "v128.load16_lane") (param i32 v128) (result v128)
+    (v128.load16_lane 7 (local.get 0) (local.get 1)))
+  (func (export "store-load") (param i32 v128) (result v128)
+    (v128.store (local.get 0) (local.get 1))
+    (v128.load (local.get 0)))
+  (func (export "store32_lane") (param i32 v128) (result i32)
+    (v128.store32_lane 2 (local.get 0) (local.get 1))
+    (i32.load (local.get 0)))
+
+  ;; Locals, select and globals
+  (func (export "local-tee") (param v128) (result v128)
+    (local $t v128)
+    (i32x4.add (local.tee $t (local.get 0)) (local.get $t)))
+  (func (export "select") (param v128 v128 i32) (result v128)
+    (select (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "global") (param v128) (result v128)
+    (global.set $g (local.get 0))
+    (global.get $g))
+  (func $double (param v128) (result v128)
+    (i32x4.add (local.get 0) (local.get 0)))
+  (func (export "call") (param v128) (result v128)
+    (call $double (local.get 0)))
+)
@@ -0,0 +1,16 @@
+(module
+  (import "env" "yield" (func $yield))
+  (global $count (export "count") (mut i32) (i32.const 0))
+
+  (func (export "count-to") (param $n i32) (param $yield-at i32) (result i32)
+    (local $i i32)
+    (block $done
+      (loop $next
+        (br_if $done (i32.ge_u (local.get $i) (local.get $n)))
+        (if (i32.eq (local.get $i) (local.get $yield-at))
+          (then (call $yield)))
+        (global.set $count (i32.add (global.get $count) (i32.const 1)))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $next)))
+    (global.get $count))
+)
@@ -0,0 +1,17 @@
+(module
+  (memory 1)
+  (global $g (mut i32) (i32.const 0))
+
+  (func $inc
+    (global.set $g (i32.add (global.get $g) (i32.const 1)))
+  )
+  (func $start
+    (call $inc)
+    (call $inc)
+    (i32.store (i32.const 0) (i32.const 42))
+  )
+  (start $start)
+
+  (func (export "get") (result i32) (global.get $g))
+  (func (export "load") (result i32) (i32.load (i32.const 0)))
+)
@@ -0,0 +1,10 @@
+(module
+  (memory 1)
+  (func $start
+    (i32.store (i32.const 0) (i32.const 1))
+    (unreachable)
+  )
+  (start $start)
+
+  (func (export "load") (result i32) (i32.load (i32.const 0)))
+)
@@ -0,0 +1,16 @@
+(module
+  (global $count (export "count") (mut i32) (i32.const 0))
+
+  (func $inc
+    (global.set $count (i32.add (global.get $count) (i32.const 1))))
+
+  (func (export "count-to") (param $n i32) (result i32)
+    (local $i i32)
+    (block $done
+      (loop $next
+        (br_if $done (i32.ge_u (local.get $i) (local.get $n)))
+        (call $inc)
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $next)))
+    (global.get $count))
+)
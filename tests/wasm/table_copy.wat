@@ -0,0 +1,25 @@
+(module
+  (type $ret (func (result i32)))
+  (func $f1 (result i32) (i32.const 1))
+  (func $f2 (result i32) (i32.const 2))
+  (func $f3 (result i32) (i32.const 3))
+  (func $f4 (result i32) (i32.const 4))
+
+  (table $t0 10 funcref)
+  (table $t1 10 funcref)
+  (elem (table $t0) (i32.const 2) func $f3 $f1 $f4 $f1)
+  (elem (table $t1) (i32.const 6) func $f2 $f4)
+
+  (func (export "copy") (param i32 i32 i32)
+    (table.copy (local.get 0) (local.get 1) (local.get 2))
+  )
+  (func (export "copy-t1-to-t0") (param i32 i32 i32)
+    (table.copy $t0 $t1 (local.get 0) (local.get 1) (local.get 2))
+  )
+  (func (export "check-t0") (param i32) (result i32)
+    (call_indirect $t0 (type $ret) (local.get 0))
+  )
+  (func (export "check-t1") (param i32) (result i32)
+    (call_indirect $t1 (type $ret) (local.get 0))
+  )
+)
@@ -0,0 +1,24 @@
+(module
+  (table $t 0 externref)
+  (table $t16 0x10 funcref)
+  (table $tmax 0 10 funcref)
+
+  (func (export "get") (param $i i32) (result externref) (table.get $t (local.get $i)))
+  (func (export "set") (param $i i32) (param $r externref) (table.set $t (local.get $i) (local.get $r)))
+
+  (func (export "grow") (param $sz i32) (param $init externref) (result i32)
+    (table.grow $t (local.get $init) (local.get $sz))
+  )
+  (func (export "grow-abbrev") (param $sz i32) (param $init externref) (result i32)
+    (table.grow (local.get $init) (local.get $sz))
+  )
+  (func (export "size") (result i32) (table.size $t))
+
+  (func (export "grow-huge") (result i32)
+    (table.grow $t16 (ref.null func) (i32.const 0xffff_fff0))
+  )
+
+  (func (export "grow-max") (param i32) (result i32)
+    (table.grow $tmax (ref.null func) (local.get 0))
+  )
+)
@@ -0,0 +1,3 @@
+(module
+  (import "tables" "table" (table 4 funcref))
+)
@@ -0,0 +1,27 @@
+(module
+  (type $ret (func (result i32)))
+  (func $f0 (result i32) (i32.const 0))
+  (func $f1 (result i32) (i32.const 1))
+  (func $f2 (result i32) (i32.const 2))
+  (func $f3 (result i32) (i32.const 3))
+  (func $f4 (result i32) (i32.const 4))
+
+  (table $t 10 funcref)
+  (elem $exprs funcref (ref.func $f1) (ref.null func) (ref.func $f2))
+  (elem $funcs func $f3 $f4)
+  (elem $active (table $t) (i32.const 0) func $f0)
+
+  (func (export "init-exprs") (param i32 i32 i32)
+    (table.init $t $exprs (local.get 0) (local.get 1) (local.get 2))
+  )
+  (func (export "init-funcs") (param i32 i32 i32)
+    (table.init $t $funcs (local.get 0) (local.get 1) (local.get 2))
+  )
+  (func (export "init-active") (param i32 i32 i32)
+    (table.init $t $active (local.get 0) (local.get 1) (local.get 2))
+  )
+  (func (export "drop-exprs") (elem.drop $exprs))
+  (func (export "check") (param i32) (result i32)
+    (call_indirect $t (type $ret) (local.get 0))
+  )
+)
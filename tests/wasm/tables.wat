@@ -0,0 +1,23 @@
+(module
+  (import "env" "yield" (func $yield))
+  (type $unary (func (param i32) (result i32)))
+  (table $t 4 8 funcref)
+  (elem (i32.const 0) $double $square)
+
+  (func $double (param i32) (result i32)
+    (i32.mul (local.get 0) (i32.const 2)))
+  (func $square (param i32) (result i32)
+    (i32.mul (local.get 0) (local.get 0)))
+
+  ;; Clears slot 0, copies `$square` to slot 3 and grows the table by two
+  ;; more copies before the yield; afterwards calls slot 3 and adds the
+  ;; table size.
+  (func (export "mutate") (param $x i32) (result i32)
+    (table.set (i32.const 0) (ref.null func))
+    (table.set (i32.const 3) (table.get (i32.const 1)))
+    (drop (table.grow (table.get (i32.const 1)) (i32.const 2)))
+    (call $yield)
+    (i32.add
+      (call_indirect (type $unary) (local.get $x) (i32.const 3))
+      (table.size)))
+)
@@ -0,0 +1,31 @@
+(module
+  (type $i32-i32 (func (param i32) (result i32)))
+
+  (func $id (type $i32-i32) (local.get 0))
+
+  ;; Slot 1 is left uninitialized (null)
+  (table 3 funcref)
+  (elem (i32.const 0) $id)
+  (elem (i32.const 2) $id)
+
+  (func (export "unreachable")
+    (unreachable))
+
+  (func (export "unreachable-in-block") (result i32)
+    (block (result i32)
+      (drop (i32.const 1))
+      (unreachable)))
+
+  (func (export "unreachable-after-call") (result i32)
+    (drop (call $id (i32.const 7)))
+    (unreachable))
+
+  (func (export "call-indirect") (param i32) (result i32)
+    (call_indirect (type $i32-i32) (i32.const 42) (local.get 0)))
+
+  (func (export "i32.div_s") (param i32 i32) (result i32)
+    (i32.div_s (local.get 0) (local.get 1)))
+
+  (func (export "i64.rem_u") (param i64 i64) (result i64)
+    (i64.rem_u (local.get 0) (local.get 1)))
+)